@@ -0,0 +1,332 @@
+use fishing_shared::types::{
+    AlertRule, AlertRuleInput, AuthResponse, BiteWindows, CatchRecord, FavoriteSpot,
+    FavoriteSpotInput, FishSpecies, ForecastResult, Language, LoginRequest, NewCatchRecord,
+    ProfileUpdate, PushPublicKey, PushSubscription, RefreshRequest, RegionInfo, RegisterRequest,
+    Regulation, TripPlan, TripPlanRequest, UserProfile, UserResponse, ValidationRequest,
+    ValidationResult, WaterBody,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use urlencoding::encode;
+
+use crate::transport::{decode, expect_success, Method, Transport};
+use crate::ApiError;
+
+/// Client for one API server.
+///
+/// Cheap to clone; clones share the native connection pool.
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    base_url: String,
+    /// Access token sent as `Authorization: Bearer`
+    token: Option<String>,
+    /// Language for server messages, sent as `Accept-Language`
+    language: Option<Language>,
+    transport: Transport,
+}
+
+impl ApiClient {
+    /// Client for the server at `base_url`, e.g. `http://localhost:8080`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let mut base_url = base_url.into();
+        while base_url.ends_with('/') {
+            base_url.pop();
+        }
+        Self {
+            base_url,
+            token: None,
+            language: None,
+            transport: Transport::default(),
+        }
+    }
+
+    /// Server this client talks to, without a trailing slash.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Same client, authenticated with an access token
+    pub fn with_token(&self, token: &str) -> Self {
+        Self {
+            token: Some(token.to_string()),
+            ..self.clone()
+        }
+    }
+
+    /// Whether requests carry an access token
+    pub fn is_authenticated(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Same client, asking for server messages in `language`
+    pub fn with_language(&self, language: Language) -> Self {
+        Self {
+            language: Some(language),
+            ..self.clone()
+        }
+    }
+
+    /// Language requested for server messages, if any
+    pub fn language(&self) -> Option<Language> {
+        self.language
+    }
+
+    /// Health check
+    pub async fn health_check(&self) -> Result<(), ApiError> {
+        self.get::<serde_json::Value>("/api/v1/health").await.map(drop)
+    }
+
+    /// Bite forecast for coordinates, optionally for one species
+    pub async fn get_forecast(&self, lat: f64, lon: f64, fish: Option<&str>) -> Result<ForecastResult, ApiError> {
+        let mut path = format!("/api/v1/forecast?lat={lat}&lon={lon}");
+        if let Some(fish) = fish {
+            path.push_str(&format!("&fish={}", encode(fish)));
+        }
+        self.get(&path).await
+    }
+
+    /// Current and next best bite windows at coordinates
+    pub async fn get_bite_windows(&self, lat: f64, lon: f64) -> Result<BiteWindows, ApiError> {
+        self.get(&format!("/api/v1/forecast/windows?lat={lat}&lon={lon}")).await
+    }
+
+    /// Best spot/time windows of a trip across candidate spots
+    pub async fn plan_trip(&self, request: &TripPlanRequest) -> Result<TripPlan, ApiError> {
+        self.send(Method::Post, "/api/v1/forecast/trip", request).await
+    }
+
+    /// Leaflet URL template for bite probability map tiles
+    pub fn forecast_tiles_url(&self) -> String {
+        format!("{}/api/v1/forecast/tiles/{{z}}/{{x}}/{{y}}.png", self.base_url)
+    }
+
+    /// Country at coordinates
+    pub async fn detect_country(&self, lat: f64, lon: f64) -> Result<RegionInfo, ApiError> {
+        self.get(&format!("/api/v1/region/detect?lat={lat}&lon={lon}")).await
+    }
+
+    /// Water bodies near coordinates
+    pub async fn get_water_bodies(&self, lat: f64, lon: f64, radius_km: f64) -> Result<Vec<WaterBody>, ApiError> {
+        self.get(&format!("/api/v1/water-bodies?lat={lat}&lon={lon}&radius_km={radius_km}"))
+            .await
+    }
+
+    /// Fish species of a country (all with `None`), named in `language`
+    pub async fn get_fish_species(&self, country: Option<&str>, language: &str) -> Result<Vec<FishSpecies>, ApiError> {
+        self.get(&fish_path(country, language)).await
+    }
+
+    /// Species with a name, synonym or scientific name containing `query`
+    pub async fn search_fish_species(
+        &self,
+        query: &str,
+        country: Option<&str>,
+        language: &str,
+    ) -> Result<Vec<FishSpecies>, ApiError> {
+        self.get(&format!("{}&q={}", fish_path(country, language), encode(query)))
+            .await
+    }
+
+    /// Regulations of a country, optionally for one species
+    pub async fn get_regulations(&self, country: &str, fish: Option<&str>) -> Result<Vec<Regulation>, ApiError> {
+        let mut path = format!("/api/v1/regulations?country={}", encode(country));
+        if let Some(fish) = fish {
+            path.push_str(&format!("&fish={}", encode(fish)));
+        }
+        self.get(&path).await
+    }
+
+    /// Check a catch against the regulations
+    pub async fn validate_catch(&self, request: &ValidationRequest) -> Result<ValidationResult, ApiError> {
+        self.send(Method::Post, "/api/v1/regulations/validate", request).await
+    }
+
+    /// Save a new catch
+    pub async fn save_catch(&self, catch: &NewCatchRecord) -> Result<CatchRecord, ApiError> {
+        self.send(Method::Post, "/api/v1/catches", catch).await
+    }
+
+    /// The signed-in user's catches, newest first; `user_id` is their own id
+    pub async fn get_catches(&self, user_id: &str, limit: i64, offset: i64) -> Result<Vec<CatchRecord>, ApiError> {
+        self.get(&format!(
+            "/api/v1/catches?user_id={}&limit={limit}&offset={offset}",
+            encode(user_id)
+        ))
+        .await
+    }
+
+    /// Public catches near coordinates
+    pub async fn get_nearby_catches(&self, lat: f64, lon: f64, radius_km: f64) -> Result<Vec<CatchRecord>, ApiError> {
+        self.get(&format!("/api/v1/catches/nearby?lat={lat}&lon={lon}&radius_km={radius_km}"))
+            .await
+    }
+
+    /// Create an account and log in
+    pub async fn register(&self, request: &RegisterRequest) -> Result<AuthResponse, ApiError> {
+        self.send(Method::Post, "/api/v1/auth/register", request).await
+    }
+
+    /// Log in with email and password
+    pub async fn login(&self, email: &str, password: &str) -> Result<AuthResponse, ApiError> {
+        let request = LoginRequest {
+            email: email.to_string(),
+            password: password.to_string(),
+        };
+        self.send(Method::Post, "/api/v1/auth/login", &request).await
+    }
+
+    /// Swap a refresh token for a new token pair
+    pub async fn refresh(&self, refresh_token: &str) -> Result<AuthResponse, ApiError> {
+        let request = RefreshRequest {
+            refresh_token: refresh_token.to_string(),
+        };
+        self.send(Method::Post, "/api/v1/auth/refresh", &request).await
+    }
+
+    /// End the session a refresh token belongs to
+    pub async fn logout(&self, refresh_token: &str) -> Result<(), ApiError> {
+        let request = RefreshRequest {
+            refresh_token: refresh_token.to_string(),
+        };
+        let body = serde_json::to_string(&request).map_err(|e| ApiError::Decode(e.to_string()))?;
+        expect_success(self.request(Method::Post, "/api/v1/auth/logout", Some(body)).await?)
+    }
+
+    /// Log out one of the user's sessions, e.g. a lost phone
+    pub async fn revoke_session(&self, session_id: &str) -> Result<(), ApiError> {
+        let path = format!("/api/v1/auth/sessions/{}", encode(session_id));
+        expect_success(self.request(Method::Delete, &path, None).await?)
+    }
+
+    /// The account the token belongs to
+    pub async fn me(&self) -> Result<UserResponse, ApiError> {
+        self.get("/api/v1/auth/me").await
+    }
+
+    /// Get the current user's profile and preferences
+    pub async fn get_profile(&self) -> Result<UserProfile, ApiError> {
+        self.get("/api/v1/profile").await
+    }
+
+    /// Change some of the current user's preferences
+    pub async fn update_profile(&self, update: &ProfileUpdate) -> Result<UserProfile, ApiError> {
+        self.send(Method::Patch, "/api/v1/profile", update).await
+    }
+
+    /// The current user's favorite spots, ordered by name
+    pub async fn get_favorites(&self) -> Result<Vec<FavoriteSpot>, ApiError> {
+        self.get("/api/v1/favorites").await
+    }
+
+    /// Save a favorite spot
+    pub async fn create_favorite(&self, spot: &FavoriteSpotInput) -> Result<FavoriteSpot, ApiError> {
+        self.send(Method::Post, "/api/v1/favorites", spot).await
+    }
+
+    /// Replace one of the current user's favorite spots
+    pub async fn update_favorite(&self, id: &str, spot: &FavoriteSpotInput) -> Result<FavoriteSpot, ApiError> {
+        self.send(Method::Put, &format!("/api/v1/favorites/{}", encode(id)), spot).await
+    }
+
+    /// Delete one of the current user's favorite spots
+    pub async fn delete_favorite(&self, id: &str) -> Result<(), ApiError> {
+        let path = format!("/api/v1/favorites/{}", encode(id));
+        expect_success(self.request(Method::Delete, &path, None).await?)
+    }
+
+    /// The current user's bite alert rules, oldest first
+    pub async fn get_alerts(&self) -> Result<Vec<AlertRule>, ApiError> {
+        self.get("/api/v1/alerts").await
+    }
+
+    /// Create a bite alert rule for one of the current user's favorite spots
+    pub async fn create_alert(&self, rule: &AlertRuleInput) -> Result<AlertRule, ApiError> {
+        self.send(Method::Post, "/api/v1/alerts", rule).await
+    }
+
+    /// Replace one of the current user's bite alert rules
+    pub async fn update_alert(&self, id: &str, rule: &AlertRuleInput) -> Result<AlertRule, ApiError> {
+        self.send(Method::Put, &format!("/api/v1/alerts/{}", encode(id)), rule).await
+    }
+
+    /// Delete one of the current user's bite alert rules
+    pub async fn delete_alert(&self, id: &str) -> Result<(), ApiError> {
+        let path = format!("/api/v1/alerts/{}", encode(id));
+        expect_success(self.request(Method::Delete, &path, None).await?)
+    }
+
+    /// VAPID key to subscribe browsers with; 404 when the server doesn't send push notifications
+    pub async fn get_push_public_key(&self) -> Result<PushPublicKey, ApiError> {
+        self.get("/api/v1/push/public-key").await
+    }
+
+    /// Send the current user's bite alerts to a browser push subscription
+    pub async fn subscribe_push(&self, subscription: &PushSubscription) -> Result<(), ApiError> {
+        let body = serde_json::to_string(subscription).map_err(|e| ApiError::Decode(e.to_string()))?;
+        expect_success(self.request(Method::Post, "/api/v1/push/subscriptions", Some(body)).await?)
+    }
+
+    /// Stop sending bite alerts to a browser push subscription
+    pub async fn unsubscribe_push(&self, endpoint: &str) -> Result<(), ApiError> {
+        let path = format!("/api/v1/push/subscriptions?endpoint={}", encode(endpoint));
+        expect_success(self.request(Method::Delete, &path, None).await?)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ApiError> {
+        decode(self.request(Method::Get, path, None).await?)
+    }
+
+    async fn send<T: DeserializeOwned>(&self, method: Method, path: &str, body: &impl Serialize) -> Result<T, ApiError> {
+        let body = serde_json::to_string(body).map_err(|e| ApiError::Decode(e.to_string()))?;
+        decode(self.request(method, path, Some(body)).await?)
+    }
+
+    async fn request(&self, method: Method, path: &str, body: Option<String>) -> Result<crate::transport::Response, ApiError> {
+        let url = format!("{}{}", self.base_url, path);
+        let language = self.language.map(|language| language.code());
+        self.transport.send(method, &url, self.token.as_deref(), language, body).await
+    }
+}
+
+/// `/fish` path for a language and optional country.
+fn fish_path(country: Option<&str>, language: &str) -> String {
+    match country {
+        Some(country) => format!("/api/v1/fish?country={}&language={}", encode(country), encode(language)),
+        None => format!("/api/v1/fish?language={}", encode(language)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_is_normalized() {
+        let client = ApiClient::new("https://api.example.com/");
+
+        assert_eq!(client.base_url(), "https://api.example.com");
+        assert_eq!(
+            client.forecast_tiles_url(),
+            "https://api.example.com/api/v1/forecast/tiles/{z}/{x}/{y}.png"
+        );
+    }
+
+    #[test]
+    fn test_with_token_keeps_server() {
+        let client = ApiClient::new("http://localhost:8080");
+        let authenticated = client.with_token("abc");
+
+        assert!(!client.is_authenticated());
+        assert!(authenticated.is_authenticated());
+        assert_eq!(authenticated.base_url(), client.base_url());
+    }
+
+    #[test]
+    fn test_with_language_keeps_token() {
+        let client = ApiClient::new("http://localhost:8080").with_token("abc");
+        let localized = client.with_language(Language::De);
+
+        assert_eq!(client.language(), None);
+        assert_eq!(localized.language(), Some(Language::De));
+        assert!(localized.is_authenticated());
+    }
+}
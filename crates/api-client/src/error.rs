@@ -0,0 +1,71 @@
+use fishing_shared::types::ApiErrorBody;
+use thiserror::Error;
+
+/// Why a request failed.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ApiError {
+    /// No response arrived, e.g. the server is unreachable.
+    #[error("network error: {0}")]
+    Network(String),
+    /// The API answered with an error body.
+    #[error("{}", .body.error)]
+    Api { status: u16, body: Box<ApiErrorBody> },
+    /// Something other than the API answered with an error status, e.g. a proxy.
+    #[error("HTTP {0}")]
+    Status(u16),
+    /// The response did not match the contract.
+    #[error("unexpected response: {0}")]
+    Decode(String),
+}
+
+impl ApiError {
+    /// HTTP status of the response, if there was one.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Api { status, .. } => Some(*status),
+            Self::Status(status) => Some(*status),
+            Self::Network(_) | Self::Decode(_) => None,
+        }
+    }
+
+    /// Stable error code from the API, e.g. `invalid_credentials`.
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Self::Api { body, .. } => Some(&body.code),
+            _ => None,
+        }
+    }
+
+    /// Error for a non-success response.
+    pub(crate) fn from_response(status: u16, body: &str) -> Self {
+        match serde_json::from_str(body) {
+            Ok(body) => Self::Api { status, body },
+            Err(_) => Self::Status(status),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_body_is_kept() {
+        let err = ApiError::from_response(
+            401,
+            r#"{"error":"Wrong email or password","code":"invalid_credentials","request_id":"r-1"}"#,
+        );
+
+        assert_eq!(err.status(), Some(401));
+        assert_eq!(err.code(), Some("invalid_credentials"));
+        assert_eq!(err.to_string(), "Wrong email or password");
+    }
+
+    #[test]
+    fn test_foreign_error_page_keeps_status() {
+        let err = ApiError::from_response(502, "<html>Bad Gateway</html>");
+
+        assert_eq!(err, ApiError::Status(502));
+        assert_eq!(err.code(), None);
+    }
+}
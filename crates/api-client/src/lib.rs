@@ -0,0 +1,17 @@
+//! Typed client for the Fishing Forecast HTTP API.
+//!
+//! Requests and responses are the [`fishing_shared::types`] the backend
+//! serves, so the frontend, tools and tests all speak one contract. Pick a
+//! transport with a feature: `wasm` sends requests with the browser's fetch
+//! (gloo-net), `native` with reqwest. With both enabled, reqwest is used
+//! everywhere except in a wasm32 build.
+
+#[cfg(not(any(feature = "wasm", feature = "native")))]
+compile_error!("fishing-api-client needs the `wasm` or the `native` feature");
+
+mod client;
+mod error;
+mod transport;
+
+pub use client::ApiClient;
+pub use error::ApiError;
@@ -0,0 +1,139 @@
+//! The one place that performs HTTP; everything else works on strings.
+//!
+//! `gloo` is used for wasm32 builds with the `wasm` feature, and for native
+//! builds when `native` is off (the frontend's host-side check and test
+//! builds, which never send requests). `reqwest` is used everywhere else.
+
+use crate::ApiError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Method {
+    Get,
+    Post,
+    Patch,
+    Put,
+    Delete,
+}
+
+/// Status and body of a response.
+pub(crate) struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+#[cfg(all(feature = "wasm", any(target_arch = "wasm32", not(feature = "native"))))]
+mod imp {
+    use gloo_net::http::{Request, RequestBuilder};
+
+    use super::{Method, Response};
+    use crate::ApiError;
+
+    #[derive(Debug, Clone, Default)]
+    pub(crate) struct Transport {}
+
+    impl Transport {
+        pub async fn send(
+            &self,
+            method: Method,
+            url: &str,
+            token: Option<&str>,
+            language: Option<&str>,
+            body: Option<String>,
+        ) -> Result<Response, ApiError> {
+            let mut builder: RequestBuilder = match method {
+                Method::Get => Request::get(url),
+                Method::Post => Request::post(url),
+                Method::Patch => Request::patch(url),
+                Method::Put => Request::put(url),
+                Method::Delete => Request::delete(url),
+            };
+            if let Some(token) = token {
+                builder = builder.header("Authorization", &format!("Bearer {token}"));
+            }
+            if let Some(language) = language {
+                builder = builder.header("Accept-Language", language);
+            }
+            let request = match body {
+                Some(body) => builder
+                    .header("Content-Type", "application/json")
+                    .body(body)
+                    .map_err(|e| ApiError::Network(e.to_string()))?,
+                None => builder.build().map_err(|e| ApiError::Network(e.to_string()))?,
+            };
+
+            let response = request.send().await.map_err(|e| ApiError::Network(e.to_string()))?;
+            let status = response.status();
+            let body = response.text().await.map_err(|e| ApiError::Network(e.to_string()))?;
+            Ok(Response { status, body })
+        }
+    }
+}
+
+#[cfg(all(feature = "native", not(all(feature = "wasm", target_arch = "wasm32"))))]
+mod imp {
+    use super::{Method, Response};
+    use crate::ApiError;
+
+    #[derive(Debug, Clone, Default)]
+    pub(crate) struct Transport {
+        http: reqwest::Client,
+    }
+
+    impl Transport {
+        pub async fn send(
+            &self,
+            method: Method,
+            url: &str,
+            token: Option<&str>,
+            language: Option<&str>,
+            body: Option<String>,
+        ) -> Result<Response, ApiError> {
+            let mut request = match method {
+                Method::Get => self.http.get(url),
+                Method::Post => self.http.post(url),
+                Method::Patch => self.http.patch(url),
+                Method::Put => self.http.put(url),
+                Method::Delete => self.http.delete(url),
+            };
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            if let Some(language) = language {
+                request = request.header(reqwest::header::ACCEPT_LANGUAGE, language);
+            }
+            if let Some(body) = body {
+                request = request.header("Content-Type", "application/json").body(body);
+            }
+
+            let response = request.send().await.map_err(|e| ApiError::Network(e.to_string()))?;
+            let status = response.status().as_u16();
+            let body = response.text().await.map_err(|e| ApiError::Network(e.to_string()))?;
+            Ok(Response { status, body })
+        }
+    }
+}
+
+pub(crate) use imp::Transport;
+
+/// Turn a response into `T`, or into the API error it carries.
+pub(crate) fn decode<T: serde::de::DeserializeOwned>(response: Response) -> Result<T, ApiError> {
+    if !response.is_success() {
+        return Err(ApiError::from_response(response.status, &response.body));
+    }
+    serde_json::from_str(&response.body).map_err(|e| ApiError::Decode(e.to_string()))
+}
+
+/// Like [`decode`] for responses without a body, such as `204 No Content`.
+pub(crate) fn expect_success(response: Response) -> Result<(), ApiError> {
+    if response.is_success() {
+        Ok(())
+    } else {
+        Err(ApiError::from_response(response.status, &response.body))
+    }
+}
@@ -0,0 +1,13 @@
+-- Environmental snapshot captured by the server when a catch is saved.
+-- Temperature, pressure and moon phase reuse the existing weather_temp,
+-- weather_pressure and moon_phase columns.
+ALTER TABLE catches ADD COLUMN weather_time TIMESTAMPTZ;
+ALTER TABLE catches ADD COLUMN pressure_change_3h_hpa DOUBLE PRECISION;
+ALTER TABLE catches ADD COLUMN pressure_trend TEXT;
+ALTER TABLE catches ADD COLUMN wind_speed_ms DOUBLE PRECISION;
+ALTER TABLE catches ADD COLUMN wind_gust_ms DOUBLE PRECISION;
+ALTER TABLE catches ADD COLUMN wind_direction_deg DOUBLE PRECISION;
+ALTER TABLE catches ADD COLUMN precipitation_mm DOUBLE PRECISION;
+ALTER TABLE catches ADD COLUMN moon_illumination DOUBLE PRECISION;
+ALTER TABLE catches ADD COLUMN sun_elevation_deg DOUBLE PRECISION;
+ALTER TABLE catches ADD COLUMN sun_azimuth_deg DOUBLE PRECISION;
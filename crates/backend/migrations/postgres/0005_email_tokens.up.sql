@@ -0,0 +1,14 @@
+-- Email verification and password reset. Tokens are single use and stored
+-- as SHA-256 hashes; `purpose` is 'verify_email' or 'reset_password'.
+ALTER TABLE users ADD COLUMN email_verified_at TIMESTAMPTZ;
+
+CREATE TABLE email_tokens (
+    token_hash TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    purpose TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_email_tokens_user_id ON email_tokens(user_id);
@@ -0,0 +1,9 @@
+-- Preferences edited through the profile API. `favorite_species` is a JSON
+-- array of fish species ids.
+ALTER TABLE users ADD COLUMN length_unit TEXT NOT NULL DEFAULT 'cm';
+ALTER TABLE users ADD COLUMN weight_unit TEXT NOT NULL DEFAULT 'kg';
+ALTER TABLE users ADD COLUMN temperature_unit TEXT NOT NULL DEFAULT 'C';
+ALTER TABLE users ADD COLUMN pressure_unit TEXT NOT NULL DEFAULT 'hPa';
+ALTER TABLE users ADD COLUMN home_region TEXT;
+ALTER TABLE users ADD COLUMN favorite_species TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE users ADD COLUMN default_privacy TEXT NOT NULL DEFAULT 'private';
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS change_history;
+ALTER TABLE users DROP COLUMN role;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS fish_regions;
+DROP TABLE IF EXISTS fish_names;
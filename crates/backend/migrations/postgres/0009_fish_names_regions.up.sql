@@ -0,0 +1,24 @@
+-- Common names of each species per language. The primary name is the one
+-- shown; the others are synonyms and regional names that search also matches.
+CREATE TABLE fish_names (
+    id TEXT PRIMARY KEY,
+    fish_id TEXT NOT NULL REFERENCES fish_species(id) ON DELETE CASCADE,
+    language TEXT NOT NULL,
+    common_name TEXT NOT NULL,
+    is_primary BOOLEAN NOT NULL DEFAULT FALSE,
+    UNIQUE (fish_id, language, common_name)
+);
+
+CREATE INDEX idx_fish_names_language ON fish_names(language);
+
+-- Countries where a species lives.
+CREATE TABLE fish_regions (
+    id TEXT PRIMARY KEY,
+    fish_id TEXT NOT NULL REFERENCES fish_species(id) ON DELETE CASCADE,
+    country_code TEXT NOT NULL,
+    abundance TEXT,
+    notes TEXT,
+    UNIQUE (fish_id, country_code)
+);
+
+CREATE INDEX idx_fish_regions_country ON fish_regions(country_code);
@@ -0,0 +1,6 @@
+-- Emails are looked up ignoring case, and one address has one account
+-- however it is spelled. Accounts whose emails differ only in case make
+-- this migration fail rather than merging them silently; list them with
+--   SELECT LOWER(email), COUNT(*) FROM users GROUP BY LOWER(email) HAVING COUNT(*) > 1;
+-- and merge or rename them before upgrading.
+CREATE UNIQUE INDEX idx_users_email_lower ON users(LOWER(email));
@@ -0,0 +1 @@
+ALTER TABLE catches DROP COLUMN privacy;
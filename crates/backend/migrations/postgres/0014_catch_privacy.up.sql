@@ -0,0 +1,8 @@
+-- Who can see a catch. Catches logged so far take their owner's default
+-- privacy; ones without a known owner stay public, as nearby catches have
+-- always shown them.
+ALTER TABLE catches ADD COLUMN privacy TEXT NOT NULL DEFAULT 'private';
+UPDATE catches SET privacy = COALESCE(
+    (SELECT users.default_privacy FROM users WHERE users.id = catches.user_id),
+    'public'
+);
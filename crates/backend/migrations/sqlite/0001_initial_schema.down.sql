@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS regulations;
+DROP TABLE IF EXISTS fish_species;
+DROP TABLE IF EXISTS water_bodies;
+DROP TABLE IF EXISTS catches;
+DROP TABLE IF EXISTS users;
@@ -0,0 +1,66 @@
+-- Initial schema. Uses IF NOT EXISTS so databases created before
+-- versioned migrations are adopted without changes.
+
+CREATE TABLE IF NOT EXISTS users (
+    id TEXT PRIMARY KEY,
+    email TEXT UNIQUE,
+    password_hash TEXT NOT NULL,
+    country_code TEXT DEFAULT 'UA',
+    language TEXT DEFAULT 'uk',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS catches (
+    id TEXT PRIMARY KEY,
+    user_id TEXT,
+    location_lat REAL NOT NULL,
+    location_lon REAL NOT NULL,
+    caught_at TIMESTAMP NOT NULL,
+    fish_species TEXT,
+    weight_kg REAL,
+    length_cm REAL,
+    bait_used TEXT,
+    weather_temp REAL,
+    weather_pressure REAL,
+    moon_phase REAL,
+    notes TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS water_bodies (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    description TEXT,
+    location_lat REAL NOT NULL,
+    location_lon REAL NOT NULL,
+    water_type TEXT,
+    country_code TEXT
+);
+
+CREATE TABLE IF NOT EXISTS fish_species (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    name_uk TEXT NOT NULL,
+    name_en TEXT,
+    scientific_name TEXT,
+    best_season TEXT,
+    preferred_bait TEXT,
+    min_temp REAL,
+    max_temp REAL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS regulations (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    region_code TEXT NOT NULL,
+    fish_species TEXT,
+    min_size_cm REAL,
+    max_catch_per_day INTEGER,
+    season_start TEXT,
+    season_end TEXT,
+    restrictions TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_catches_user_id ON catches(user_id);
+
+CREATE INDEX IF NOT EXISTS idx_catches_location ON catches(location_lat, location_lon);
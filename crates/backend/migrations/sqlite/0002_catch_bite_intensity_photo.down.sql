@@ -0,0 +1,2 @@
+ALTER TABLE catches DROP COLUMN photo_url;
+ALTER TABLE catches DROP COLUMN bite_intensity;
@@ -0,0 +1,3 @@
+-- Columns from the shared CatchRecord that the initial schema lacked.
+ALTER TABLE catches ADD COLUMN bite_intensity INTEGER;
+ALTER TABLE catches ADD COLUMN photo_url TEXT;
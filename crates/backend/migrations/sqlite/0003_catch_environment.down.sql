@@ -0,0 +1,10 @@
+ALTER TABLE catches DROP COLUMN sun_azimuth_deg;
+ALTER TABLE catches DROP COLUMN sun_elevation_deg;
+ALTER TABLE catches DROP COLUMN moon_illumination;
+ALTER TABLE catches DROP COLUMN precipitation_mm;
+ALTER TABLE catches DROP COLUMN wind_direction_deg;
+ALTER TABLE catches DROP COLUMN wind_gust_ms;
+ALTER TABLE catches DROP COLUMN wind_speed_ms;
+ALTER TABLE catches DROP COLUMN pressure_trend;
+ALTER TABLE catches DROP COLUMN pressure_change_3h_hpa;
+ALTER TABLE catches DROP COLUMN weather_time;
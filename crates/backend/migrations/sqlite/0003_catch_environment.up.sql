@@ -0,0 +1,13 @@
+-- Environmental snapshot captured by the server when a catch is saved.
+-- Temperature, pressure and moon phase reuse the existing weather_temp,
+-- weather_pressure and moon_phase columns.
+ALTER TABLE catches ADD COLUMN weather_time TIMESTAMP;
+ALTER TABLE catches ADD COLUMN pressure_change_3h_hpa REAL;
+ALTER TABLE catches ADD COLUMN pressure_trend TEXT;
+ALTER TABLE catches ADD COLUMN wind_speed_ms REAL;
+ALTER TABLE catches ADD COLUMN wind_gust_ms REAL;
+ALTER TABLE catches ADD COLUMN wind_direction_deg REAL;
+ALTER TABLE catches ADD COLUMN precipitation_mm REAL;
+ALTER TABLE catches ADD COLUMN moon_illumination REAL;
+ALTER TABLE catches ADD COLUMN sun_elevation_deg REAL;
+ALTER TABLE catches ADD COLUMN sun_azimuth_deg REAL;
@@ -0,0 +1,7 @@
+ALTER TABLE users DROP COLUMN default_privacy;
+ALTER TABLE users DROP COLUMN favorite_species;
+ALTER TABLE users DROP COLUMN home_region;
+ALTER TABLE users DROP COLUMN pressure_unit;
+ALTER TABLE users DROP COLUMN temperature_unit;
+ALTER TABLE users DROP COLUMN weight_unit;
+ALTER TABLE users DROP COLUMN length_unit;
@@ -0,0 +1,17 @@
+-- Account roles; `admin` may curate species, regulations and water bodies.
+ALTER TABLE users ADD COLUMN role TEXT NOT NULL DEFAULT 'user';
+
+-- Audit trail of admin edits to reference data. `before_data` and
+-- `after_data` hold the row as JSON (absent on create and delete).
+CREATE TABLE change_history (
+    id TEXT PRIMARY KEY,
+    entity_type TEXT NOT NULL,
+    entity_id TEXT NOT NULL,
+    action TEXT NOT NULL,
+    changed_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    changed_at TIMESTAMP NOT NULL,
+    before_data TEXT,
+    after_data TEXT
+);
+
+CREATE INDEX idx_change_history_entity ON change_history(entity_type, entity_id);
@@ -0,0 +1 @@
+ALTER TABLE users DROP COLUMN wind_speed_unit;
@@ -0,0 +1,2 @@
+-- Wind speed unit, added to the profile preferences.
+ALTER TABLE users ADD COLUMN wind_speed_unit TEXT NOT NULL DEFAULT 'm/s';
@@ -0,0 +1,15 @@
+-- Places users saved to check their forecast again.
+CREATE TABLE favorite_spots (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    location_lat REAL NOT NULL,
+    location_lon REAL NOT NULL,
+    water_body_id TEXT REFERENCES water_bodies(id) ON DELETE SET NULL,
+    -- JSON array of fish species ids
+    target_species TEXT NOT NULL DEFAULT '[]',
+    notes TEXT,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_favorite_spots_user_id ON favorite_spots(user_id);
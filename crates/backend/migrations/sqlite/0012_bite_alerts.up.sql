@@ -0,0 +1,29 @@
+-- When to notify users about a good bite at their favorite spots.
+CREATE TABLE alert_rules (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    favorite_spot_id TEXT NOT NULL REFERENCES favorite_spots(id) ON DELETE CASCADE,
+    species TEXT REFERENCES fish_species(id) ON DELETE SET NULL,
+    min_probability REAL NOT NULL,
+    start_hour INTEGER NOT NULL,
+    end_hour INTEGER NOT NULL,
+    utc_offset_minutes INTEGER NOT NULL DEFAULT 0,
+    -- rising, steady or falling; NULL for any
+    pressure_trend TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    last_notified_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_alert_rules_user_id ON alert_rules(user_id);
+
+-- Browsers that accept Web Push notifications, one row per endpoint.
+CREATE TABLE push_subscriptions (
+    endpoint TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    p256dh TEXT NOT NULL,
+    auth TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_push_subscriptions_user_id ON push_subscriptions(user_id);
@@ -0,0 +1,451 @@
+//! The HTTP application: shared state and the one router every deployment
+//! mode serves.
+
+use std::sync::Arc;
+
+use axum::{
+    http::HeaderValue,
+    routing::{delete, get, post, put},
+    Router,
+};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+use crate::{
+    config::{Config, CorsOrigins},
+    db, error, openapi,
+    routes::{
+        self,
+        auth::{
+            confirm_email_handler, confirm_password_reset_handler, identities_handler,
+            login_handler, logout_handler, me_handler, oidc_authorize_handler,
+            oidc_callback_handler, oidc_providers_handler, refresh_handler, register_handler,
+            request_email_verification_handler, request_password_reset_handler,
+            revoke_session_handler, sessions_handler,
+        },
+        alerts::{
+            create_alert_handler, delete_alert_handler, list_alerts_handler,
+            push_public_key_handler, subscribe_push_handler, unsubscribe_push_handler,
+            update_alert_handler,
+        },
+        catches::{get_catches_handler, get_nearby_catches_handler, save_catch_handler},
+        favorites::{
+            create_favorite_handler, delete_favorite_handler, list_favorites_handler,
+            update_favorite_handler,
+        },
+        fish::fish_handler,
+        forecast::{
+            bite_windows_handler, detailed_forecast_handler, feature_importance_handler,
+            forecast_grid_handler, forecast_handler, forecast_tile_handler, trip_plan_handler,
+        },
+        health::health_handler,
+        profile::{get_profile_handler, update_profile_handler},
+        region::region_detect_handler,
+        regulations::{regulations_handler, regulations_validate_handler},
+        water_bodies::water_bodies_handler,
+    },
+    services::{
+        self, http_client::build_http_client, oidc::OidcConfig, rate_limit::RateLimitLayer,
+    },
+};
+
+/// Shared application state for handlers.
+#[derive(Clone)]
+pub struct AppState {
+    pub config: Arc<Config>,
+    pub http: reqwest::Client,
+    pub db: Arc<dyn db::Repository>,
+    pub mailer: Arc<dyn services::mailer::Mailer>,
+    pub notifier: Arc<dyn services::notifier::Notifier>,
+    pub oidc: Arc<OidcConfig>,
+    pub login_lockout: Arc<services::rate_limit::LoginLockout>,
+}
+
+impl AppState {
+    /// Connect to the database and set up the services `config` asks for.
+    pub async fn from_config(config: Config) -> anyhow::Result<Self> {
+        let http = build_http_client()?;
+        Ok(Self {
+            db: db::connect(&config.database_url).await?,
+            mailer: services::mailer::from_config(&config.mail)?,
+            notifier: services::notifier::from_config(&config.push)?,
+            http,
+            oidc: Arc::new(config.oidc.clone()),
+            login_lockout: Arc::default(),
+            config: Arc::new(config),
+        })
+    }
+
+    /// State for handler tests: a SQLite database in `dir`, the default
+    /// config, and mail and push notifications that are only logged.
+    #[cfg(test)]
+    pub async fn for_tests(dir: &tempfile::TempDir) -> Self {
+        let url = format!("sqlite://{}", dir.path().join("test.db").display());
+        Self {
+            config: Arc::default(),
+            http: reqwest::Client::new(),
+            db: db::connect(&url).await.unwrap(),
+            mailer: Arc::new(services::mailer::LogMailer),
+            notifier: Arc::new(services::notifier::LogNotifier),
+            oidc: Arc::default(),
+            login_lockout: Arc::default(),
+        }
+    }
+}
+
+/// Every API route plus the rate limit, request context and CORS layers.
+pub fn router(state: AppState) -> Router {
+    let config = state.config.clone();
+    let features = config.features;
+
+    let mut app = Router::new()
+        // Auth routes (public)
+        .route("/api/v1/auth/login", post(login_handler))
+        .route("/api/v1/auth/me", get(me_handler))
+        .route("/api/v1/auth/refresh", post(refresh_handler))
+        .route("/api/v1/auth/logout", post(logout_handler))
+        .route("/api/v1/auth/sessions", get(sessions_handler))
+        .route("/api/v1/auth/sessions/:id", delete(revoke_session_handler))
+        .route("/api/v1/auth/verify-email/request", post(request_email_verification_handler))
+        .route("/api/v1/auth/verify-email/confirm", post(confirm_email_handler))
+        .route("/api/v1/auth/password-reset/request", post(request_password_reset_handler))
+        .route("/api/v1/auth/password-reset/confirm", post(confirm_password_reset_handler))
+        .route("/api/v1/auth/oidc/providers", get(oidc_providers_handler))
+        .route("/api/v1/auth/oidc/:provider/authorize", get(oidc_authorize_handler))
+        .route("/api/v1/auth/oidc/:provider/callback", post(oidc_callback_handler))
+        .route("/api/v1/auth/identities", get(identities_handler))
+        // Profile (authenticated)
+        .route("/api/v1/profile", get(get_profile_handler).patch(update_profile_handler))
+        // Favorite spots (authenticated)
+        .route("/api/v1/favorites", get(list_favorites_handler).post(create_favorite_handler))
+        .route("/api/v1/favorites/:id", put(update_favorite_handler).delete(delete_favorite_handler))
+        // Bite alerts & push subscriptions (authenticated, except the public key)
+        .route("/api/v1/alerts", get(list_alerts_handler).post(create_alert_handler))
+        .route("/api/v1/alerts/:id", put(update_alert_handler).delete(delete_alert_handler))
+        .route("/api/v1/push/public-key", get(push_public_key_handler))
+        .route("/api/v1/push/subscriptions", post(subscribe_push_handler).delete(unsubscribe_push_handler))
+        // Health, API description & Forecast (public)
+        .route("/api/v1/health", get(health_handler))
+        .route("/api/v1/openapi.json", get(openapi::openapi_handler))
+        .route("/api/v1/docs", get(openapi::swagger_ui_handler))
+        .route("/api/v1/forecast", get(forecast_handler))
+        .route("/api/v1/forecast/detailed", get(detailed_forecast_handler))
+        .route("/api/v1/forecast/importance", get(feature_importance_handler))
+        .route("/api/v1/forecast/windows", get(bite_windows_handler))
+        .route("/api/v1/forecast/trip", post(trip_plan_handler))
+        // Region & Fish (public)
+        .route("/api/v1/region/detect", get(region_detect_handler))
+        .route("/api/v1/fish", get(fish_handler))
+        // Regulations (public)
+        .route("/api/v1/regulations", get(regulations_handler))
+        .route("/api/v1/regulations/validate", post(regulations_validate_handler))
+        // Catches: saving takes an optional token, listing a user's catches
+        // requires theirs, nearby catches are public
+        .route("/api/v1/catches", post(save_catch_handler).get(get_catches_handler))
+        .route("/api/v1/catches/nearby", get(get_nearby_catches_handler))
+        // Water bodies (public)
+        .route("/api/v1/water-bodies", get(water_bodies_handler));
+
+    if features.registration {
+        app = app.route("/api/v1/auth/register", post(register_handler));
+    }
+    if features.forecast_tiles {
+        app = app
+            .route("/api/v1/forecast/grid", get(forecast_grid_handler))
+            .route("/api/v1/forecast/tiles/:z/:x/:y", get(forecast_tile_handler));
+    }
+    if features.admin_api {
+        // Admin (admin role)
+        app = app.nest("/api/v1/admin", routes::admin::router());
+    }
+
+    app.with_state(state)
+        .layer(RateLimitLayer::new(config.rate_limit.clone()))
+        .layer(axum::middleware::from_fn(error::request_context))
+        .layer(cors(&config.cors_origins))
+}
+
+fn cors(origins: &CorsOrigins) -> CorsLayer {
+    let allow_origin = match origins {
+        CorsOrigins::Any => AllowOrigin::from(Any),
+        CorsOrigins::List(origins) => {
+            AllowOrigin::list(origins.iter().filter_map(|o| HeaderValue::from_str(o).ok()))
+        }
+    };
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(Any)
+        .allow_headers(Any)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use fishing_api_client::{ApiClient, ApiError};
+    use fishing_shared::types::{
+        AlertRuleInput, AuthResponse, CatchPrivacy, FavoriteSpotInput, Language, NewCatchRecord, ProfileUpdate,
+        PushSubscription, PushSubscriptionKeys, RegisterRequest, Role, TripPlanRequest, TripSpot, ValidationRequest,
+    };
+    use tower::ServiceExt;
+
+    async fn app(config: Config) -> (Router, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            database_url: format!("sqlite://{}", dir.path().join("app.db").display()),
+            ..config
+        };
+        let state = AppState::from_config(config).await.unwrap();
+        (router(state), dir)
+    }
+
+    async fn status(app: &Router, method: &str, uri: &str, origin: Option<&str>) -> (StatusCode, Option<HeaderValue>) {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(origin) = origin {
+            request = request.header(header::ORIGIN, origin);
+        }
+        let response = app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let allowed = response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).cloned();
+        (response.status(), allowed)
+    }
+
+    #[tokio::test]
+    async fn test_router_serves_every_feature_by_default() {
+        let (app, _dir) = app(Config::default()).await;
+
+        assert_eq!(status(&app, "GET", "/api/v1/health", None).await.0, StatusCode::OK);
+        assert_eq!(status(&app, "GET", "/api/v1/fish", None).await.0, StatusCode::OK);
+        assert_eq!(status(&app, "GET", "/api/v1/openapi.json", None).await.0, StatusCode::OK);
+        assert_eq!(status(&app, "GET", "/api/v1/admin/history", None).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&app, "GET", "/api/v1/forecast/grid", None).await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(status(&app, "POST", "/api/v1/auth/register", None).await.0, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let (_, allowed) = status(&app, "GET", "/api/v1/health", Some("https://anywhere.example")).await;
+        assert_eq!(allowed.unwrap(), "*");
+    }
+
+    #[tokio::test]
+    async fn test_router_honours_config() {
+        let config = Config {
+            cors_origins: CorsOrigins::List(vec!["https://fish.example.com".into()]),
+            features: crate::config::Features {
+                registration: false,
+                admin_api: false,
+                forecast_tiles: false,
+            },
+            ..Config::default()
+        };
+        let (app, _dir) = app(config).await;
+
+        assert_eq!(status(&app, "GET", "/api/v1/health", None).await.0, StatusCode::OK);
+        assert_eq!(status(&app, "GET", "/api/v1/admin/history", None).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(status(&app, "GET", "/api/v1/forecast/grid", None).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(status(&app, "POST", "/api/v1/auth/register", None).await.0, StatusCode::NOT_FOUND);
+
+        let (_, allowed) = status(&app, "GET", "/api/v1/health", Some("https://fish.example.com")).await;
+        assert_eq!(allowed.unwrap(), "https://fish.example.com");
+        let (_, allowed) = status(&app, "GET", "/api/v1/health", Some("https://evil.example")).await;
+        assert!(allowed.is_none());
+    }
+
+    // The tests below drive the real router through the client the
+    // frontend uses, so a change on either side of the contract fails here.
+
+    /// Serve the full router on a free port and return a client for it.
+    async fn serve() -> (ApiClient, tempfile::TempDir) {
+        crate::services::auth::init_jwt_secret().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState {
+            // Weather lookups fail fast so saved catches carry only astronomy.
+            http: reqwest::Client::builder()
+                .proxy(reqwest::Proxy::all("http://127.0.0.1:9").unwrap())
+                .build()
+                .unwrap(),
+            ..AppState::for_tests(&dir).await
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let service = router(state).into_make_service_with_connect_info::<std::net::SocketAddr>();
+        tokio::spawn(async move { axum::serve(listener, service).await });
+        (ApiClient::new(format!("http://{addr}/")), dir)
+    }
+
+    /// Register `email` and return a client signed in as them.
+    async fn sign_up(api: &ApiClient, email: &str) -> (ApiClient, AuthResponse) {
+        let registered = api
+            .register(&RegisterRequest {
+                email: email.into(),
+                password: "correct horse".into(),
+                country_code: Some("PL".into()),
+                language: None,
+            })
+            .await
+            .unwrap();
+        (api.with_token(&registered.token), registered)
+    }
+
+    #[tokio::test]
+    async fn test_client_reads_public_data() {
+        let (api, _dir) = serve().await;
+
+        api.health_check().await.unwrap();
+        assert!(!api.get_fish_species(Some("UA"), "uk").await.unwrap().is_empty());
+        assert_eq!(api.search_fish_species("Hecht", None, "pl").await.unwrap()[0].name, "Szczupak");
+        api.get_regulations("UA", None).await.unwrap();
+        api.get_water_bodies(50.45, 30.52, 10.0).await.unwrap();
+        let verdict = api
+            .validate_catch(&ValidationRequest {
+                country_code: "UA".into(),
+                fish_species: None,
+                size_cm: Some(30.0),
+                date: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(verdict.allowed, verdict.errors.is_empty());
+
+        assert_eq!(api.get_bite_windows(52.23, 21.05).await.unwrap_err().status(), Some(502), "weather is offline");
+        let mut trip = TripPlanRequest {
+            spots: vec![TripSpot { name: "Vistula bend".into(), lat: 52.23, lon: 21.05 }],
+            from: chrono::Utc::now(),
+            to: chrono::Utc::now() + chrono::Duration::days(2),
+            origin: None,
+        };
+        assert_eq!(api.plan_trip(&trip).await.unwrap_err().status(), Some(502), "weather is offline");
+        trip.spots.clear();
+        assert_eq!(api.plan_trip(&trip).await.unwrap_err().code(), Some("validation_failed"));
+    }
+
+    #[tokio::test]
+    async fn test_client_signs_in_and_out() {
+        let (api, _dir) = serve().await;
+        let (_, registered) = sign_up(&api, "client@example.com").await;
+        assert_eq!(registered.token_type, "Bearer");
+        assert_eq!(registered.user.role, Role::User);
+
+        let err = api.login("client@example.com", "wrong password").await.unwrap_err();
+        assert_eq!(err.status(), Some(401));
+        assert_eq!(err.code(), Some("invalid_credentials"));
+        let err = api.with_language(Language::Pl).login("client@example.com", "wrong password").await.unwrap_err();
+        assert_eq!(err.to_string(), "Nieprawidłowy e-mail lub hasło", "message follows Accept-Language");
+        assert!(matches!(api.get_profile().await, Err(ApiError::Api { status: 401, .. })));
+
+        let login = api.login("client@example.com", "correct horse").await.unwrap();
+        assert_eq!(api.with_token(&login.token).me().await.unwrap().email, "client@example.com");
+
+        let refreshed = api.refresh(&login.refresh_token).await.unwrap();
+        api.logout(&refreshed.refresh_token).await.unwrap();
+        assert_eq!(api.refresh(&refreshed.refresh_token).await.unwrap_err().status(), Some(401));
+    }
+
+    #[tokio::test]
+    async fn test_client_updates_profile() {
+        let (api, _dir) = serve().await;
+        let (user, _) = sign_up(&api, "client@example.com").await;
+
+        let profile = user
+            .update_profile(&ProfileUpdate { home_region: Some("PL-MZ".into()), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(profile.country_code, "PL");
+        assert_eq!(user.get_profile().await.unwrap(), profile);
+    }
+
+    #[tokio::test]
+    async fn test_client_manages_favorites_and_alerts() {
+        let (api, _dir) = serve().await;
+        let (user, _) = sign_up(&api, "client@example.com").await;
+
+        let mut spot = FavoriteSpotInput {
+            name: "Vistula bend".into(),
+            lat: 52.23,
+            lon: 21.05,
+            water_body_id: None,
+            target_species: vec!["zander".into()],
+            notes: None,
+        };
+        let favorite = user.create_favorite(&spot).await.unwrap();
+        spot.notes = Some("Evenings".into());
+        let favorite = user.update_favorite(&favorite.id, &spot).await.unwrap();
+        assert_eq!(user.get_favorites().await.unwrap(), vec![favorite.clone()]);
+        let mut rule = AlertRuleInput {
+            favorite_spot_id: favorite.id.clone(),
+            species: Some("zander".into()),
+            min_probability: 0.6,
+            start_hour: 18,
+            end_hour: 22,
+            utc_offset_minutes: 120,
+            pressure_trend: None,
+            enabled: true,
+        };
+        let alert = user.create_alert(&rule).await.unwrap();
+        rule.enabled = false;
+        let alert = user.update_alert(&alert.id, &rule).await.unwrap();
+        assert_eq!(user.get_alerts().await.unwrap(), vec![alert.clone()]);
+        user.delete_alert(&alert.id).await.unwrap();
+        assert_eq!(user.delete_alert(&alert.id).await.unwrap_err().status(), Some(404));
+        user.delete_favorite(&favorite.id).await.unwrap();
+        assert_eq!(user.delete_favorite(&favorite.id).await.unwrap_err().status(), Some(404));
+    }
+
+    #[tokio::test]
+    async fn test_client_manages_push_subscriptions() {
+        let (api, _dir) = serve().await;
+        let (user, _) = sign_up(&api, "client@example.com").await;
+
+        assert_eq!(api.get_push_public_key().await.unwrap_err().status(), Some(404), "no VAPID key configured");
+        let subscription = PushSubscription {
+            endpoint: "https://updates.push.services.mozilla.com/wpush/v2/client".into(),
+            keys: PushSubscriptionKeys {
+                p256dh: "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4".into(),
+                auth: "BTBZMqHH6r4Tts7J_aSIgg".into(),
+            },
+        };
+        user.subscribe_push(&subscription).await.unwrap();
+        user.unsubscribe_push(&subscription.endpoint).await.unwrap();
+        assert_eq!(user.unsubscribe_push(&subscription.endpoint).await.unwrap_err().status(), Some(404));
+    }
+
+    #[tokio::test]
+    async fn test_client_saves_catches() {
+        let (api, _dir) = serve().await;
+        let (user, registered) = sign_up(&api, "client@example.com").await;
+
+        let new_catch = NewCatchRecord {
+            id: None,
+            user_id: registered.user.id.clone(),
+            lat: 52.23,
+            lon: 21.01,
+            caught_at: chrono::Utc::now(),
+            fish_species: "pike".into(),
+            weight_kg: Some(2.5),
+            length_cm: None,
+            bait_used: None,
+            bite_intensity: Some(4),
+            weather_temp: None,
+            weather_pressure: None,
+            moon_phase: None,
+            notes: None,
+            photo_url: None,
+            privacy: None,
+        };
+        let saved = user.save_catch(&new_catch).await.unwrap();
+        assert_eq!(user.get_catches(&registered.user.id, 10, 0).await.unwrap(), vec![saved.clone()]);
+
+        // New catches take the profile's default privacy; only public ones are nearby
+        assert_eq!(saved.privacy, CatchPrivacy::Private);
+        assert!(api.get_nearby_catches(52.23, 21.01, 1.0).await.unwrap().is_empty());
+        let update = ProfileUpdate { default_privacy: Some(CatchPrivacy::Public), ..Default::default() };
+        user.update_profile(&update).await.unwrap();
+        let shared = user
+            .save_catch(&NewCatchRecord { caught_at: chrono::Utc::now(), ..new_catch })
+            .await
+            .unwrap();
+        assert_eq!(shared.privacy, CatchPrivacy::Public);
+        assert_eq!(api.get_nearby_catches(52.23, 21.01, 1.0).await.unwrap(), vec![shared]);
+    }
+}
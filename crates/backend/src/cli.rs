@@ -0,0 +1,94 @@
+//! Command-line subcommands run instead of the HTTP server.
+//!
+//! ```text
+//! fishing-backend migrate status
+//! fishing-backend migrate up
+//! fishing-backend migrate down [STEPS]
+//! fishing-backend role <EMAIL> <user|admin>
+//! fishing-backend vapid-keys
+//! ```
+
+use crate::db::{self, migrations::MigrationState};
+use crate::models::Role;
+use crate::services::notifier::VapidKey;
+
+const MIGRATE_USAGE: &str = "usage: fishing-backend migrate <status|up|down [STEPS]>";
+
+const ROLE_USAGE: &str = "usage: fishing-backend role <EMAIL> <user|admin>";
+
+/// Run `migrate <action>` against `database_url`.
+pub async fn migrate(args: &[String], database_url: &str) -> anyhow::Result<()> {
+    let repository = db::open(database_url).await?;
+
+    match args.first().map(String::as_str) {
+        Some("status") | None => {
+            let statuses = repository.migration_status().await?;
+            println!("Database backend: {}", repository.backend());
+            for status in statuses {
+                let state = match status.state {
+                    MigrationState::Applied => "applied",
+                    MigrationState::Pending => "pending",
+                    MigrationState::ChecksumMismatch => "MODIFIED",
+                    MigrationState::Unknown => "UNKNOWN",
+                };
+                let applied_at = status
+                    .applied_at
+                    .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+                    .unwrap_or_default();
+                println!("{:>5}  {:<8}  {:<40}  {}", status.version, state, status.name, applied_at);
+            }
+        }
+        Some("up") => {
+            let applied = repository.migrate_up().await?;
+            if applied.is_empty() {
+                println!("Database is up to date");
+            }
+            for migration in applied {
+                println!("Applied {}", migration.name);
+            }
+        }
+        Some("down") => {
+            let steps = match args.get(1) {
+                Some(steps) => steps
+                    .parse::<usize>()
+                    .map_err(|_| anyhow::anyhow!("invalid step count '{}'\n{}", steps, MIGRATE_USAGE))?,
+                None => 1,
+            };
+            let reverted = repository.migrate_down(steps).await?;
+            if reverted.is_empty() {
+                println!("Nothing to revert");
+            }
+            for migration in reverted {
+                println!("Reverted {}", migration.name);
+            }
+        }
+        Some(other) => anyhow::bail!("unknown migrate action '{}'\n{}", other, MIGRATE_USAGE),
+    }
+
+    Ok(())
+}
+
+/// Run `role <email> <role>`: grant or revoke admin rights. The new role
+/// applies from the user's next token refresh.
+pub async fn role(args: &[String], database_url: &str) -> anyhow::Result<()> {
+    let [email, role] = args else {
+        anyhow::bail!(ROLE_USAGE);
+    };
+    let role = Role::parse(role).ok_or_else(|| anyhow::anyhow!("unknown role '{}'\n{}", role, ROLE_USAGE))?;
+
+    let repository = db::open(database_url).await?;
+    if !repository.set_user_role(email, role).await? {
+        anyhow::bail!("no user with email '{}'", email);
+    }
+
+    println!("{} is now {}", email, role.as_str());
+    Ok(())
+}
+
+/// Run `vapid-keys`: print a fresh key pair for Web Push notifications.
+pub fn vapid_keys() -> anyhow::Result<()> {
+    let key = VapidKey::generate();
+    println!("VAPID_PRIVATE_KEY={}", key.private_key());
+    println!("# public key, served at /api/v1/push/public-key: {}", key.public_key());
+    Ok(())
+}
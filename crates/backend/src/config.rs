@@ -0,0 +1,393 @@
+//! Server configuration.
+//!
+//! Everything the server reads at startup lives in [`Config`]. It is loaded
+//! once from the environment (optionally layered over a `CONFIG_FILE` in
+//! `.env` format), validated as a whole, and then handed to
+//! [`crate::app::router`] so every deployment mode builds the same app.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result};
+use reqwest::Url;
+
+use crate::services::{
+    alerts::AlertConfig,
+    auth,
+    mailer::MailConfig,
+    notifier::PushConfig,
+    oidc::OidcConfig,
+    rate_limit::RateLimitConfig,
+};
+
+const DEFAULT_PORT: u16 = 8080;
+const DEFAULT_APP_BASE_URL: &str = "http://127.0.0.1:3001";
+const DEFAULT_WEATHER_FORECAST_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const DEFAULT_WEATHER_ARCHIVE_URL: &str = "https://archive-api.open-meteo.com/v1/archive";
+const DEFAULT_GEOCODING_URL: &str = "https://nominatim.openstreetmap.org/reverse";
+
+static UPSTREAM: OnceLock<UpstreamUrls> = OnceLock::new();
+
+/// Validated server configuration.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// `BIND_ADDR`, or `0.0.0.0:$PORT`.
+    pub bind_addr: SocketAddr,
+    /// `DATABASE_URL`: `sqlite:` or `postgres(ql)://`.
+    pub database_url: String,
+    /// `CORS_ALLOWED_ORIGINS`: `*` or a comma-separated list of origins.
+    pub cors_origins: CorsOrigins,
+    /// `JWT_SECRET`; only optional in development.
+    pub jwt_secret: String,
+    /// `APP_ENV` is `development`/`dev`; debug builds default to it.
+    pub development: bool,
+    /// `APP_BASE_URL`: frontend address used in emailed and OIDC redirect links.
+    pub app_base_url: String,
+    pub upstream: UpstreamUrls,
+    pub features: Features,
+    pub mail: MailConfig,
+    pub push: PushConfig,
+    pub alerts: AlertConfig,
+    pub oidc: OidcConfig,
+    pub rate_limit: RateLimitConfig,
+}
+
+/// Origins allowed to call the API from a browser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorsOrigins {
+    Any,
+    List(Vec<String>),
+}
+
+/// Third-party services the backend calls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpstreamUrls {
+    /// `WEATHER_FORECAST_URL`: Open-Meteo compatible forecast endpoint.
+    pub weather_forecast: String,
+    /// `WEATHER_ARCHIVE_URL`: Open-Meteo compatible archive endpoint.
+    pub weather_archive: String,
+    /// `GEOCODING_URL`: Nominatim compatible reverse geocoding endpoint.
+    pub geocoding: String,
+}
+
+impl Default for UpstreamUrls {
+    fn default() -> Self {
+        Self {
+            weather_forecast: DEFAULT_WEATHER_FORECAST_URL.to_string(),
+            weather_archive: DEFAULT_WEATHER_ARCHIVE_URL.to_string(),
+            geocoding: DEFAULT_GEOCODING_URL.to_string(),
+        }
+    }
+}
+
+/// Optional parts of the API, each switched by `FEATURE_<NAME>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Features {
+    /// `FEATURE_REGISTRATION`: password sign-up.
+    pub registration: bool,
+    /// `FEATURE_ADMIN_API`: `/api/v1/admin`.
+    pub admin_api: bool,
+    /// `FEATURE_FORECAST_TILES`: the forecast grid and map tiles.
+    pub forecast_tiles: bool,
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Self {
+            registration: true,
+            admin_api: true,
+            forecast_tiles: true,
+        }
+    }
+}
+
+impl Default for Config {
+    /// Development settings with an in-memory database; used by tests.
+    fn default() -> Self {
+        Self {
+            bind_addr: SocketAddr::from(([0, 0, 0, 0], DEFAULT_PORT)),
+            database_url: "sqlite::memory:".to_string(),
+            cors_origins: CorsOrigins::Any,
+            jwt_secret: auth::resolve_jwt_secret(None, true).unwrap_or_default(),
+            development: true,
+            app_base_url: DEFAULT_APP_BASE_URL.to_string(),
+            upstream: UpstreamUrls::default(),
+            features: Features::default(),
+            mail: MailConfig::default(),
+            push: PushConfig::default(),
+            alerts: AlertConfig::default(),
+            oidc: OidcConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Read the configuration from the environment.
+    ///
+    /// If `CONFIG_FILE` names a file, its `KEY=value` lines fill in
+    /// whatever the environment leaves unset.
+    pub fn load() -> Result<Self> {
+        Self::from_lookup(environment()?)
+    }
+
+    /// Read only `DATABASE_URL`, from the same places as [`Config::load`].
+    /// The command-line tools need nothing else, so they work without the
+    /// server's other settings.
+    pub fn load_database_url() -> Result<String> {
+        let lookup = environment()?;
+        parse_database_url(lookup("DATABASE_URL").map(|v| v.trim().to_string()).filter(|v| !v.is_empty()))
+            .map_err(anyhow::Error::msg)
+    }
+
+    /// Build and validate the configuration from `lookup`, reporting every
+    /// invalid setting at once.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name: &str| lookup(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let mut problems = Vec::new();
+        let defaults = Self::default();
+
+        let development = match var("APP_ENV") {
+            Some(env) => matches!(env.as_str(), "development" | "dev"),
+            None => cfg!(debug_assertions),
+        };
+
+        let bind_addr = match (var("BIND_ADDR"), var("PORT")) {
+            (Some(addr), _) => addr
+                .parse()
+                .map_err(|_| problems.push(format!("BIND_ADDR {:?} is not a host:port address", addr)))
+                .ok(),
+            (None, Some(port)) => port
+                .parse::<u16>()
+                .map(|port| SocketAddr::from(([0, 0, 0, 0], port)))
+                .map_err(|_| problems.push(format!("PORT {:?} is not a port number", port)))
+                .ok(),
+            (None, None) => Some(defaults.bind_addr),
+        };
+
+        let database_url = parse_database_url(var("DATABASE_URL")).map_err(|problem| problems.push(problem)).ok();
+
+        let cors_origins = match var("CORS_ALLOWED_ORIGINS") {
+            None => CorsOrigins::Any,
+            Some(value) if value == "*" => CorsOrigins::Any,
+            Some(value) => {
+                let mut origins = Vec::new();
+                for origin in value.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+                    match parse_origin(origin) {
+                        Some(origin) => origins.push(origin),
+                        None => problems.push(format!(
+                            "CORS_ALLOWED_ORIGINS entry {:?} is not an http(s) origin",
+                            origin
+                        )),
+                    }
+                }
+                CorsOrigins::List(origins)
+            }
+        };
+
+        let jwt_secret = auth::resolve_jwt_secret(var("JWT_SECRET"), development)
+            .map_err(|err| problems.push(err.to_string()))
+            .unwrap_or_default();
+
+        let mut url = |name: &str, default: &str| match var(name) {
+            None => default.to_string(),
+            Some(value) if is_http_url(&value) => value.trim_end_matches('/').to_string(),
+            Some(value) => {
+                problems.push(format!("{} {:?} is not an http(s) URL", name, value));
+                default.to_string()
+            }
+        };
+        let app_base_url = url("APP_BASE_URL", DEFAULT_APP_BASE_URL);
+        let upstream = UpstreamUrls {
+            weather_forecast: url("WEATHER_FORECAST_URL", DEFAULT_WEATHER_FORECAST_URL),
+            weather_archive: url("WEATHER_ARCHIVE_URL", DEFAULT_WEATHER_ARCHIVE_URL),
+            geocoding: url("GEOCODING_URL", DEFAULT_GEOCODING_URL),
+        };
+
+        let mut flag = |name: &str| match var(name).map(|v| v.to_ascii_lowercase()) {
+            None => true,
+            Some(value) => match value.as_str() {
+                "1" | "true" | "yes" | "on" => true,
+                "0" | "false" | "no" | "off" => false,
+                _ => {
+                    problems.push(format!("{} must be true or false, got {:?}", name, value));
+                    true
+                }
+            },
+        };
+        let features = Features {
+            registration: flag("FEATURE_REGISTRATION"),
+            admin_api: flag("FEATURE_ADMIN_API"),
+            forecast_tiles: flag("FEATURE_FORECAST_TILES"),
+        };
+
+        let mail = MailConfig::from_lookup(var)
+            .map_err(|err| problems.push(format!("{:#}", err)))
+            .unwrap_or_default();
+        let push = PushConfig::from_lookup(var)
+            .map_err(|err| problems.push(format!("{:#}", err)))
+            .unwrap_or_default();
+        let alerts = AlertConfig::from_lookup(var)
+            .map_err(|err| problems.push(format!("{:#}", err)))
+            .unwrap_or_default();
+        let oidc = OidcConfig::from_lookup(var, &app_base_url)
+            .map_err(|err| problems.push(format!("{:#}", err)))
+            .unwrap_or_default();
+        let rate_limit = RateLimitConfig::from_lookup(var)
+            .map_err(|err| problems.push(format!("{:#}", err)))
+            .unwrap_or_default();
+
+        if !problems.is_empty() {
+            anyhow::bail!("Invalid configuration:\n  - {}", problems.join("\n  - "));
+        }
+
+        Ok(Self {
+            bind_addr: bind_addr.unwrap_or(defaults.bind_addr),
+            database_url: database_url.unwrap_or_default(),
+            cors_origins,
+            jwt_secret,
+            development,
+            app_base_url,
+            upstream,
+            features,
+            mail,
+            push,
+            alerts,
+            oidc,
+            rate_limit,
+        })
+    }
+
+    /// Publish the settings read outside of request handlers (JWT signing,
+    /// upstream clients). Later calls keep the first values.
+    pub fn install_globals(&self) {
+        auth::set_jwt_secret(self.jwt_secret.clone());
+        let _ = UPSTREAM.set(self.upstream.clone());
+    }
+}
+
+/// Upstream endpoints from the installed config, or the public defaults.
+pub fn upstream() -> &'static UpstreamUrls {
+    UPSTREAM.get_or_init(UpstreamUrls::default)
+}
+
+/// The environment, falling back to the `KEY=value` lines of `CONFIG_FILE`.
+fn environment() -> Result<impl Fn(&str) -> Option<String>> {
+    let mut file = HashMap::new();
+    if let Some(path) = std::env::var("CONFIG_FILE").ok().filter(|p| !p.is_empty()) {
+        let entries = dotenvy::from_path_iter(&path)
+            .with_context(|| format!("Cannot read CONFIG_FILE {}", path))?;
+        for entry in entries {
+            let (key, value) = entry.with_context(|| format!("Invalid line in {}", path))?;
+            file.insert(key, value);
+        }
+    }
+
+    Ok(move |name: &str| std::env::var(name).ok().or_else(|| file.get(name).cloned()))
+}
+
+fn parse_database_url(value: Option<String>) -> Result<String, String> {
+    match value {
+        Some(url) if is_database_url(&url) => Ok(url),
+        Some(_) => Err("DATABASE_URL must start with sqlite:, postgres:// or postgresql://".to_string()),
+        None => Err("DATABASE_URL is not set".to_string()),
+    }
+}
+
+fn is_database_url(url: &str) -> bool {
+    url.starts_with("sqlite:") || url.starts_with("postgres://") || url.starts_with("postgresql://")
+}
+
+fn is_http_url(value: &str) -> bool {
+    Url::parse(value).is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+}
+
+/// `scheme://host[:port]` without a path, as browsers send in `Origin`.
+fn parse_origin(value: &str) -> Option<String> {
+    let url = Url::parse(value).ok()?;
+    let bare = url.path() == "/" && url.query().is_none() && url.fragment().is_none();
+    (is_http_url(value) && bare).then(|| url.origin().ascii_serialization())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(vars: &[(&str, &str)]) -> Result<Config> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        Config::from_lookup(|name| vars.get(name).map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn test_defaults_for_development() {
+        let config = load(&[("APP_ENV", "development"), ("DATABASE_URL", "sqlite://dev.db")]).unwrap();
+
+        assert_eq!(config.bind_addr, "0.0.0.0:8080".parse().unwrap());
+        assert_eq!(config.cors_origins, CorsOrigins::Any);
+        assert_eq!(config.upstream, UpstreamUrls::default());
+        assert_eq!(config.features, Features::default());
+        assert!(!config.jwt_secret.is_empty());
+    }
+
+    #[test]
+    fn test_reads_every_setting() {
+        let secret = "s".repeat(40);
+        let config = load(&[
+            ("APP_ENV", "production"),
+            ("DATABASE_URL", "postgres://db/fishing"),
+            ("JWT_SECRET", &secret),
+            ("BIND_ADDR", "127.0.0.1:9000"),
+            ("PORT", "1"),
+            ("CORS_ALLOWED_ORIGINS", "https://fish.example.com, http://localhost:3001/"),
+            ("APP_BASE_URL", "https://fish.example.com/"),
+            ("WEATHER_FORECAST_URL", "http://meteo.internal/v1/forecast"),
+            ("FEATURE_ADMIN_API", "off"),
+            ("FEATURE_REGISTRATION", "0"),
+        ])
+        .unwrap();
+
+        assert!(!config.development);
+        assert_eq!(config.bind_addr, "127.0.0.1:9000".parse().unwrap(), "BIND_ADDR wins over PORT");
+        assert_eq!(
+            config.cors_origins,
+            CorsOrigins::List(vec!["https://fish.example.com".into(), "http://localhost:3001".into()])
+        );
+        assert_eq!(config.app_base_url, "https://fish.example.com");
+        assert_eq!(config.upstream.weather_forecast, "http://meteo.internal/v1/forecast");
+        assert_eq!(config.upstream.geocoding, DEFAULT_GEOCODING_URL);
+        assert!(!config.features.admin_api);
+        assert!(!config.features.registration);
+        assert!(config.features.forecast_tiles);
+        assert_eq!(config.jwt_secret, secret);
+    }
+
+    #[test]
+    fn test_reports_every_problem() {
+        let err = load(&[
+            ("APP_ENV", "production"),
+            ("DATABASE_URL", "mysql://db"),
+            ("PORT", "eighty"),
+            ("CORS_ALLOWED_ORIGINS", "https://ok.example.com,https://bad.example.com/path"),
+            ("GEOCODING_URL", "ftp://geo"),
+            ("FEATURE_OIDC", "maybe"),
+            ("FEATURE_ADMIN_API", "maybe"),
+            ("RATE_LIMIT_AUTH_IP", "lots"),
+        ])
+        .unwrap_err()
+        .to_string();
+
+        for needle in [
+            "DATABASE_URL",
+            "PORT",
+            "https://bad.example.com/path",
+            "GEOCODING_URL",
+            "FEATURE_ADMIN_API",
+            "JWT_SECRET",
+            "lots",
+        ] {
+            assert!(err.contains(needle), "{needle} missing from: {err}");
+        }
+        assert!(!err.contains("FEATURE_OIDC"), "unknown settings are ignored");
+    }
+}
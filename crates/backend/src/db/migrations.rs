@@ -0,0 +1,239 @@
+//! Versioned schema migrations.
+//!
+//! Each backend has its own numbered scripts under `migrations/<backend>/`,
+//! embedded at compile time. Applied versions are recorded with the SHA-256
+//! checksum of their `up` script in the `schema_version` table, so editing a
+//! migration after it shipped is detected instead of silently ignored.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// A single migration with its forward and reverse scripts.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+impl Migration {
+    /// Hex-encoded SHA-256 of the `up` script.
+    pub fn checksum(&self) -> String {
+        Sha256::digest(self.up.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+macro_rules! migration {
+    ($backend:literal, $version:literal, $name:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!("../../migrations/", $backend, "/", $name, ".up.sql")),
+            down: include_str!(concat!("../../migrations/", $backend, "/", $name, ".down.sql")),
+        }
+    };
+}
+
+const SQLITE_MIGRATIONS: &[Migration] = &[
+    migration!("sqlite", 1, "0001_initial_schema"),
+    migration!("sqlite", 2, "0002_catch_bite_intensity_photo"),
+    migration!("sqlite", 3, "0003_catch_environment"),
+    migration!("sqlite", 4, "0004_sessions"),
+    migration!("sqlite", 5, "0005_email_tokens"),
+    migration!("sqlite", 6, "0006_oidc"),
+    migration!("sqlite", 7, "0007_user_preferences"),
+    migration!("sqlite", 8, "0008_roles_change_history"),
+    migration!("sqlite", 9, "0009_fish_names_regions"),
+    migration!("sqlite", 10, "0010_wind_speed_unit"),
+    migration!("sqlite", 11, "0011_favorite_spots"),
+    migration!("sqlite", 12, "0012_bite_alerts"),
+    migration!("sqlite", 13, "0013_users_email_lower"),
+    migration!("sqlite", 14, "0014_catch_privacy"),
+];
+
+const POSTGRES_MIGRATIONS: &[Migration] = &[
+    migration!("postgres", 1, "0001_initial_schema"),
+    migration!("postgres", 2, "0002_catch_bite_intensity_photo"),
+    migration!("postgres", 3, "0003_catch_environment"),
+    migration!("postgres", 4, "0004_sessions"),
+    migration!("postgres", 5, "0005_email_tokens"),
+    migration!("postgres", 6, "0006_oidc"),
+    migration!("postgres", 7, "0007_user_preferences"),
+    migration!("postgres", 8, "0008_roles_change_history"),
+    migration!("postgres", 9, "0009_fish_names_regions"),
+    migration!("postgres", 10, "0010_wind_speed_unit"),
+    migration!("postgres", 11, "0011_favorite_spots"),
+    migration!("postgres", 12, "0012_bite_alerts"),
+    migration!("postgres", 13, "0013_users_email_lower"),
+    migration!("postgres", 14, "0014_catch_privacy"),
+];
+
+/// Migrations for a repository backend, ordered by version.
+pub fn for_backend(backend: &str) -> &'static [Migration] {
+    match backend {
+        "postgres" => POSTGRES_MIGRATIONS,
+        _ => SQLITE_MIGRATIONS,
+    }
+}
+
+/// DDL for the table that tracks applied migrations.
+pub(crate) fn schema_version_ddl(backend: &str) -> &'static str {
+    match backend {
+        "postgres" => {
+            "CREATE TABLE IF NOT EXISTS schema_version (
+                version BIGINT PRIMARY KEY,
+                name TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                applied_at TIMESTAMPTZ NOT NULL
+            )"
+        }
+        _ => {
+            "CREATE TABLE IF NOT EXISTS schema_version (
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                applied_at TIMESTAMP NOT NULL
+            )"
+        }
+    }
+}
+
+/// Row of the `schema_version` table.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: DateTime<Utc>,
+}
+
+/// State of one migration relative to the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the script has changed since.
+    ChecksumMismatch,
+    /// Applied by a newer build; no script for it here.
+    Unknown,
+}
+
+/// Status line reported by `migrate status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: String,
+    pub state: MigrationState,
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+/// Compare the known migrations with the rows in `schema_version`.
+pub fn reconcile(migrations: &[Migration], applied: &[AppliedMigration]) -> Vec<MigrationStatus> {
+    let mut statuses: Vec<MigrationStatus> = migrations
+        .iter()
+        .map(|migration| {
+            let row = applied.iter().find(|a| a.version == migration.version);
+            let state = match row {
+                None => MigrationState::Pending,
+                Some(a) if a.checksum == migration.checksum() => MigrationState::Applied,
+                Some(_) => MigrationState::ChecksumMismatch,
+            };
+            MigrationStatus {
+                version: migration.version,
+                name: migration.name.to_string(),
+                state,
+                applied_at: row.map(|a| a.applied_at),
+            }
+        })
+        .collect();
+
+    for row in applied {
+        if !migrations.iter().any(|m| m.version == row.version) {
+            statuses.push(MigrationStatus {
+                version: row.version,
+                name: row.name.clone(),
+                state: MigrationState::Unknown,
+                applied_at: Some(row.applied_at),
+            });
+        }
+    }
+
+    statuses.sort_by_key(|s| s.version);
+    statuses
+}
+
+/// Refuse to migrate a database whose history does not match this build.
+pub fn ensure_consistent(statuses: &[MigrationStatus]) -> anyhow::Result<()> {
+    for status in statuses {
+        match status.state {
+            MigrationState::ChecksumMismatch => anyhow::bail!(
+                "migration {} ({}) was modified after it was applied",
+                status.version,
+                status.name
+            ),
+            MigrationState::Unknown => anyhow::bail!(
+                "database has migration {} ({}) which this build does not know; upgrade the backend",
+                status.version,
+                status.name
+            ),
+            MigrationState::Applied | MigrationState::Pending => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn applied(migration: &Migration, checksum: String) -> AppliedMigration {
+        AppliedMigration {
+            version: migration.version,
+            name: migration.name.to_string(),
+            checksum,
+            applied_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_backends_share_versions() {
+        let sqlite: Vec<_> = SQLITE_MIGRATIONS.iter().map(|m| (m.version, m.name)).collect();
+        let postgres: Vec<_> = POSTGRES_MIGRATIONS.iter().map(|m| (m.version, m.name)).collect();
+        assert_eq!(sqlite, postgres);
+
+        for pair in SQLITE_MIGRATIONS.windows(2) {
+            assert!(pair[0].version < pair[1].version, "versions must increase");
+        }
+        for migration in SQLITE_MIGRATIONS {
+            assert!(migration.name.starts_with(&format!("{:04}_", migration.version)));
+        }
+    }
+
+    #[test]
+    fn test_reconcile_detects_drift() {
+        let first = SQLITE_MIGRATIONS[0];
+
+        let statuses = reconcile(SQLITE_MIGRATIONS, &[]);
+        assert!(statuses.iter().all(|s| s.state == MigrationState::Pending));
+        assert!(ensure_consistent(&statuses).is_ok());
+
+        let statuses = reconcile(SQLITE_MIGRATIONS, &[applied(&first, first.checksum())]);
+        assert_eq!(statuses[0].state, MigrationState::Applied);
+
+        let statuses = reconcile(SQLITE_MIGRATIONS, &[applied(&first, "edited".to_string())]);
+        assert_eq!(statuses[0].state, MigrationState::ChecksumMismatch);
+        assert!(ensure_consistent(&statuses).is_err());
+
+        let mut future = applied(&first, String::new());
+        future.version = 9999;
+        let statuses = reconcile(SQLITE_MIGRATIONS, &[future]);
+        assert_eq!(statuses.last().unwrap().state, MigrationState::Unknown);
+        assert!(ensure_consistent(&statuses).is_err());
+    }
+}
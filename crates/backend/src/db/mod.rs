@@ -1,186 +1,1050 @@
+pub mod migrations;
+pub mod postgres;
 pub mod queries;
+pub mod sqlite;
 
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
-#[cfg(feature = "shuttle")]
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use std::sync::Arc;
 
-/// Create a SQLite database connection pool for development.
-#[cfg(not(feature = "shuttle"))]
-pub async fn create_pool(database_url: &str) -> anyhow::Result<SqlitePool> {
-    let pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(&format!("{}?mode=rwc", database_url))
-        .await?;
+use async_trait::async_trait;
+use uuid::Uuid;
 
-    // Ensure UTF-8 encoding
-    sqlx::query("PRAGMA encoding = 'UTF-8'")
-        .execute(&pool)
-        .await?;
+use crate::db::migrations::{Migration, MigrationStatus};
+use crate::models::{
+    AlertRuleDb, CatchRecordDb, ChangeHistoryDb, EmailTokenPurpose, FavoriteSpotDb, FishItemDb, FishSpeciesDb, NewEmailToken,
+    NewSession, NewUser, NewUserIdentity, OidcLoginDb, PushSubscriptionDb, RefreshTokenDb, RegulationDb, Role,
+    SessionDb, UserDb, UserIdentityDb, UserPreferencesDb, WaterBodyDb,
+};
+use fishing_shared::types::{EnvironmentSnapshot, NewCatchRecord};
 
-    // Initialize schema if needed
-    initialize_schema(&pool).await?;
+pub use postgres::PgRepository;
+pub use sqlite::SqliteRepository;
 
-    tracing::info!("Database schema initialized (SQLite)");
+/// Storage operations used by the handlers and services.
+///
+/// Implemented for SQLite (local development) and PostgreSQL (production);
+/// the backend is chosen from the `DATABASE_URL` scheme at startup.
+#[async_trait]
+pub trait Repository: Send + Sync {
+    /// Name of the database backend, for logs and diagnostics.
+    fn backend(&self) -> &'static str;
 
-    Ok(pool)
+    /// Compare the embedded migrations with the `schema_version` table.
+    async fn migration_status(&self) -> anyhow::Result<Vec<MigrationStatus>>;
+
+    /// Apply all pending migrations, each in its own transaction.
+    async fn migrate_up(&self) -> anyhow::Result<Vec<Migration>>;
+
+    /// Revert the last `steps` applied migrations, newest first.
+    async fn migrate_down(&self, steps: usize) -> anyhow::Result<Vec<Migration>>;
+
+    /// Insert the reference fish species, their names and regions into
+    /// empty tables, so admin edits are not undone on the next start.
+    async fn seed_fish_species(&self) -> anyhow::Result<()>;
+
+    /// Insert a new catch record with its environmental snapshot and return
+    /// the saved row, or `None` when a catch with the client's id already
+    /// exists.
+    async fn insert_catch(
+        &self,
+        record: &NewCatchRecord,
+        environment: Option<&EnvironmentSnapshot>,
+    ) -> anyhow::Result<Option<CatchRecordDb>>;
+
+    /// Get a catch by ID.
+    async fn get_catch(&self, id: &str) -> anyhow::Result<Option<CatchRecordDb>>;
+
+    /// Get catches by user ID with pagination.
+    async fn get_catches_by_user(
+        &self,
+        user_id: &str,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<CatchRecordDb>>;
+
+    /// Get nearby catches within a radius in kilometers.
+    async fn get_nearby_catches(
+        &self,
+        lat: f64,
+        lon: f64,
+        radius_km: f64,
+    ) -> anyhow::Result<Vec<CatchRecordDb>>;
+
+    /// Get nearby water bodies within a radius in kilometers.
+    async fn get_nearby_water_bodies(
+        &self,
+        lat: f64,
+        lon: f64,
+        radius_km: f64,
+    ) -> anyhow::Result<Vec<WaterBodyDb>>;
+
+    /// Get regulations for a country and optional fish species.
+    async fn get_regulations(
+        &self,
+        country_code: &str,
+        fish_species_id: Option<Uuid>,
+    ) -> anyhow::Result<Vec<RegulationDb>>;
+
+    /// Fish named in `language` (falling back to English, then Ukrainian),
+    /// limited to those living in `country_code` or not yet assigned to any
+    /// country and, with `search`, to those with a name, synonym or
+    /// scientific name containing it.
+    async fn get_fish_species(
+        &self,
+        country_code: Option<&str>,
+        language: &str,
+        search: Option<&str>,
+    ) -> anyhow::Result<Vec<FishItemDb>>;
+
+    /// Find a user by email address, ignoring case.
+    async fn find_user_by_email(&self, email: &str) -> anyhow::Result<Option<UserDb>>;
+
+    /// Find a user by ID.
+    async fn find_user_by_id(&self, id: &str) -> anyhow::Result<Option<UserDb>>;
+
+    /// Insert a new user account.
+    async fn create_user(&self, user: &NewUser) -> anyhow::Result<()>;
+
+    /// Start a login session with its first refresh token.
+    async fn create_session(&self, session: &NewSession, refresh_token_hash: &str) -> anyhow::Result<()>;
+
+    /// Find a refresh token (used or not) together with its session.
+    async fn find_refresh_token(&self, token_hash: &str) -> anyhow::Result<Option<RefreshTokenDb>>;
+
+    /// Mark `old_hash` used and issue `new_hash` in the same session.
+    ///
+    /// Returns `false` if `old_hash` was already used, so concurrent refreshes
+    /// with one token cannot both succeed.
+    async fn rotate_refresh_token(&self, old_hash: &str, new_hash: &str) -> anyhow::Result<bool>;
+
+    /// Sessions of a user that have not been revoked, newest first.
+    async fn list_sessions(&self, user_id: &str) -> anyhow::Result<Vec<SessionDb>>;
+
+    /// Revoke one of a user's sessions; returns `false` if it does not exist
+    /// or belongs to someone else.
+    async fn revoke_session(&self, user_id: &str, session_id: &str) -> anyhow::Result<bool>;
+
+    /// Store an emailed token, invalidating the user's earlier unused tokens
+    /// for the same purpose.
+    async fn create_email_token(&self, token: &NewEmailToken) -> anyhow::Result<()>;
+
+    /// Mark an unused, unexpired token as used and return its user ID.
+    async fn consume_email_token(
+        &self,
+        token_hash: &str,
+        purpose: EmailTokenPurpose,
+    ) -> anyhow::Result<Option<String>>;
+
+    /// Record that the user confirmed their email address.
+    async fn mark_email_verified(&self, user_id: &str) -> anyhow::Result<()>;
+
+    /// Replace the user's password hash and revoke all of their sessions.
+    async fn reset_password(&self, user_id: &str, password_hash: &str) -> anyhow::Result<()>;
+
+    /// Overwrite a user's preference columns; returns `false` if the user
+    /// does not exist.
+    async fn update_user_preferences(
+        &self,
+        user_id: &str,
+        preferences: &UserPreferencesDb,
+    ) -> anyhow::Result<bool>;
+
+    /// Remember a started OIDC login; expired ones are cleaned up here.
+    async fn create_oidc_login(&self, login: &OidcLoginDb) -> anyhow::Result<()>;
+
+    /// Remove and return a pending OIDC login, so each `state` works once.
+    async fn take_oidc_login(&self, state: &str) -> anyhow::Result<Option<OidcLoginDb>>;
+
+    /// Find the link for an external identity.
+    async fn find_user_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> anyhow::Result<Option<UserIdentityDb>>;
+
+    /// Link an external identity to a user; fails if it is already linked.
+    async fn link_user_identity(&self, identity: &NewUserIdentity) -> anyhow::Result<()>;
+
+    /// External identities linked to a user, oldest first.
+    async fn list_user_identities(&self, user_id: &str) -> anyhow::Result<Vec<UserIdentityDb>>;
+
+    /// Set the role of the user with `email` (ignoring case); returns `false`
+    /// if there is none.
+    async fn set_user_role(&self, email: &str, role: Role) -> anyhow::Result<bool>;
+
+    /// Every fish species row, ordered by id.
+    async fn list_fish_species_records(&self) -> anyhow::Result<Vec<FishSpeciesDb>>;
+
+    /// Whether a fish species with this id exists.
+    async fn fish_species_exists(&self, id: &str) -> anyhow::Result<bool>;
+
+    /// Name of one species in `language`, with the fallbacks of
+    /// [`get_fish_species`](Self::get_fish_species).
+    async fn fish_species_name(&self, id: &str, language: &str) -> anyhow::Result<Option<String>>;
+
+    /// Insert a species and record the change; returns `false` if the id is taken.
+    async fn create_fish_species(&self, species: &FishSpeciesDb, changed_by: &str) -> anyhow::Result<bool>;
+
+    /// Overwrite a species and record the change; returns `false` if it does not exist.
+    async fn update_fish_species(&self, species: &FishSpeciesDb, changed_by: &str) -> anyhow::Result<bool>;
+
+    /// Delete a species and record the change; returns `false` if it does not exist.
+    async fn delete_fish_species(&self, id: &str, changed_by: &str) -> anyhow::Result<bool>;
+
+    /// Every regulation, ordered by region and species.
+    async fn list_regulations(&self) -> anyhow::Result<Vec<RegulationDb>>;
+
+    /// Insert a regulation and record the change; returns `false` if the id is taken.
+    async fn create_regulation(&self, regulation: &RegulationDb, changed_by: &str) -> anyhow::Result<bool>;
+
+    /// Overwrite a regulation and record the change; returns `false` if it does not exist.
+    async fn update_regulation(&self, regulation: &RegulationDb, changed_by: &str) -> anyhow::Result<bool>;
+
+    /// Delete a regulation and record the change; returns `false` if it does not exist.
+    async fn delete_regulation(&self, id: &str, changed_by: &str) -> anyhow::Result<bool>;
+
+    /// Every water body, ordered by name.
+    async fn list_water_bodies(&self) -> anyhow::Result<Vec<WaterBodyDb>>;
+
+    /// Insert a water body and record the change; returns `false` if the id is taken.
+    async fn create_water_body(&self, water_body: &WaterBodyDb, changed_by: &str) -> anyhow::Result<bool>;
+
+    /// Overwrite a water body and record the change; returns `false` if it does not exist.
+    async fn update_water_body(&self, water_body: &WaterBodyDb, changed_by: &str) -> anyhow::Result<bool>;
+
+    /// Delete a water body and record the change; returns `false` if it does not exist.
+    async fn delete_water_body(&self, id: &str, changed_by: &str) -> anyhow::Result<bool>;
+
+    /// Latest reference data changes, newest first, optionally for one
+    /// entity type or row.
+    async fn list_change_history(
+        &self,
+        entity_type: Option<&str>,
+        entity_id: Option<&str>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<ChangeHistoryDb>>;
+
+    /// Whether a water body with this id exists.
+    async fn water_body_exists(&self, id: &str) -> anyhow::Result<bool>;
+
+    /// A user's favorite spots, ordered by name.
+    async fn list_favorite_spots(&self, user_id: &str) -> anyhow::Result<Vec<FavoriteSpotDb>>;
+
+    /// One of a user's favorite spots.
+    async fn get_favorite_spot(&self, user_id: &str, id: &str) -> anyhow::Result<Option<FavoriteSpotDb>>;
+
+    /// Insert a favorite spot.
+    async fn create_favorite_spot(&self, spot: &FavoriteSpotDb) -> anyhow::Result<()>;
+
+    /// Overwrite a favorite spot; returns `false` if it does not exist or
+    /// belongs to someone else.
+    async fn update_favorite_spot(&self, spot: &FavoriteSpotDb) -> anyhow::Result<bool>;
+
+    /// Delete a favorite spot; returns `false` if it does not exist or
+    /// belongs to someone else.
+    async fn delete_favorite_spot(&self, user_id: &str, id: &str) -> anyhow::Result<bool>;
+
+    /// A user's alert rules, oldest first.
+    async fn list_alert_rules(&self, user_id: &str) -> anyhow::Result<Vec<AlertRuleDb>>;
+
+    /// Enabled alert rules of every user, for the alert scheduler.
+    async fn list_enabled_alert_rules(&self) -> anyhow::Result<Vec<AlertRuleDb>>;
+
+    /// One of a user's alert rules.
+    async fn get_alert_rule(&self, user_id: &str, id: &str) -> anyhow::Result<Option<AlertRuleDb>>;
+
+    /// Insert an alert rule.
+    async fn create_alert_rule(&self, rule: &AlertRuleDb) -> anyhow::Result<()>;
+
+    /// Overwrite an alert rule except `last_notified_at`; returns `false` if
+    /// it does not exist or belongs to someone else.
+    async fn update_alert_rule(&self, rule: &AlertRuleDb) -> anyhow::Result<bool>;
+
+    /// Delete an alert rule; returns `false` if it does not exist or belongs
+    /// to someone else.
+    async fn delete_alert_rule(&self, user_id: &str, id: &str) -> anyhow::Result<bool>;
+
+    /// Record when an alert rule last sent a notification.
+    async fn mark_alert_notified(&self, id: &str, at: chrono::DateTime<chrono::Utc>) -> anyhow::Result<()>;
+
+    /// Store a push subscription, replacing any earlier one for its endpoint.
+    async fn save_push_subscription(&self, subscription: &PushSubscriptionDb) -> anyhow::Result<()>;
+
+    /// A user's push subscriptions.
+    async fn list_push_subscriptions(&self, user_id: &str) -> anyhow::Result<Vec<PushSubscriptionDb>>;
+
+    /// Delete a push subscription; returns `false` if it does not exist or
+    /// belongs to someone else.
+    async fn delete_push_subscription(&self, user_id: &str, endpoint: &str) -> anyhow::Result<bool>;
 }
 
-/// Create a PostgreSQL database connection pool for production (Shuttle).
-#[cfg(feature = "shuttle")]
-pub async fn create_pg_pool(database_url: &str) -> PgPool {
-    let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .connect(database_url)
-        .await
-        .expect("Failed to connect to PostgreSQL");
+/// Open the database named by `database_url` without touching its schema.
+///
+/// `postgres://` and `postgresql://` URLs use PostgreSQL, anything else is
+/// treated as a SQLite database path.
+pub async fn open(database_url: &str) -> anyhow::Result<Arc<dyn Repository>> {
+    let repository: Arc<dyn Repository> =
+        if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+            Arc::new(PgRepository::connect(database_url).await?)
+        } else {
+            Arc::new(SqliteRepository::connect(database_url).await?)
+        };
 
-    tracing::info!("Connected to PostgreSQL");
+    Ok(repository)
+}
+
+/// Open the database, apply pending migrations and seed reference data.
+pub async fn connect(database_url: &str) -> anyhow::Result<Arc<dyn Repository>> {
+    let repository = open(database_url).await?;
+
+    let applied = repository.migrate_up().await?;
+    repository.seed_fish_species().await?;
+
+    tracing::info!(
+        "Database ready ({}, {} migration(s) applied)",
+        repository.backend(),
+        applied.len()
+    );
+
+    Ok(repository)
+}
+
+/// Seed row: (id, name_uk, name_en, scientific_name, best_season, preferred_bait, min_temp, max_temp).
+pub(crate) type FishSpeciesSeed = (&'static str, &'static str, &'static str, &'static str, &'static str, &'static str, f64, f64);
+
+/// Fish species seeded into every new database.
+pub(crate) const FISH_SPECIES_SEED: &[FishSpeciesSeed] = &[
+    ("pike", "Щука", "Pike", "Esox lucius", "all_year", "spoon", 5.0, 22.0),
+    ("crucian", "Карась", "Crucian carp", "Carassius carassius", "summer", "worm", 15.0, 28.0),
+    ("perch", "Окунь", "Perch", "Perca fluviatilis", "all_year", "minnow", 8.0, 25.0),
+    ("bream", "Лящ", "Bream", "Abramis brama", "all_year", "corn", 10.0, 26.0),
+    ("zander", "Судак", "Zander", "Sander lucioperca", "all_year", "fish", 12.0, 24.0),
+    ("carp", "Короп", "Carp", "Cyprinus carpio", "summer", "boilie", 18.0, 30.0),
+    ("catfish", "Сом", "Catfish", "Silurus glanis", "summer", "fish", 18.0, 28.0),
+    ("roach", "Плітка", "Roach", "Rutilus rutilus", "all_year", "maggot", 8.0, 24.0),
+    ("tench", "Лин", "Tench", "Tinca tinca", "summer", "worm", 15.0, 26.0),
+    ("asp", "Жерех", "Asp", "Aspius aspius", "spring", "spoon", 10.0, 22.0),
+    ("burbot", "Минь", "Burbot", "Lota lota", "winter", "lamprey", 0.0, 15.0),
+    ("ide", "Язь", "Ide", "Leuciscus idus", "spring", "worm", 8.0, 22.0),
+];
+
+/// Seed row: (fish_id, Polish, German, French) primary names; Ukrainian
+/// and English ones live in `fish_species`.
+pub(crate) type FishNamesSeed = (&'static str, &'static str, &'static str, &'static str);
+
+/// Names shown for the seeded species in the other supported languages.
+pub(crate) const FISH_NAMES_SEED: &[FishNamesSeed] = &[
+    ("pike", "Szczupak", "Hecht", "Brochet"),
+    ("crucian", "Karaś", "Karausche", "Carassin"),
+    ("perch", "Okoń", "Flussbarsch", "Perche"),
+    ("bream", "Leszcz", "Brachse", "Brème"),
+    ("zander", "Sandacz", "Zander", "Sandre"),
+    ("carp", "Karp", "Karpfen", "Carpe"),
+    ("catfish", "Sum", "Wels", "Silure"),
+    ("roach", "Płoć", "Rotauge", "Gardon"),
+    ("tench", "Lin", "Schleie", "Tanche"),
+    ("asp", "Boleń", "Rapfen", "Aspe"),
+    ("burbot", "Miętus", "Quappe", "Lotte"),
+    ("ide", "Jaź", "Aland", "Ide mélanote"),
+];
 
-    pool
+/// Seed row: (fish_id, language, synonym).
+pub(crate) const FISH_SYNONYMS_SEED: &[(&str, &str, &str)] = &[
+    ("pike", "en", "Northern pike"),
+    ("perch", "en", "European perch"),
+    ("perch", "en", "Redfin perch"),
+    ("perch", "de", "Barsch"),
+    ("bream", "en", "Common bream"),
+    ("bream", "de", "Blei"),
+    ("bream", "de", "Brassen"),
+    ("zander", "en", "Pikeperch"),
+    ("carp", "en", "Common carp"),
+    ("carp", "uk", "Сазан"),
+    ("catfish", "en", "Wels catfish"),
+    ("catfish", "en", "Sheatfish"),
+    ("catfish", "de", "Waller"),
+    ("roach", "uk", "Плотва"),
+    ("roach", "de", "Plötze"),
+    ("asp", "de", "Schied"),
+    ("burbot", "uk", "Налим"),
+    ("burbot", "en", "Eelpout"),
+    ("burbot", "de", "Aalrutte"),
+    ("ide", "en", "Orfe"),
+    ("ide", "de", "Orfe"),
+];
+
+/// Seed row: (fish_id, countries where it is common, countries where it is rare).
+pub(crate) type FishRegionsSeed = (&'static str, &'static [&'static str], &'static [&'static str]);
+
+/// Where the seeded species live.
+pub(crate) const FISH_REGIONS_SEED: &[FishRegionsSeed] = &[
+    ("pike", &["UA", "PL", "DE", "FR"], &[]),
+    ("crucian", &["UA", "PL", "DE"], &["FR"]),
+    ("perch", &["UA", "PL", "DE", "FR"], &[]),
+    ("bream", &["UA", "PL", "DE", "FR"], &[]),
+    ("zander", &["UA", "PL", "DE", "FR"], &[]),
+    ("carp", &["UA", "PL", "DE", "FR"], &[]),
+    ("catfish", &["UA", "PL", "DE", "FR"], &[]),
+    ("roach", &["UA", "PL", "DE", "FR"], &[]),
+    ("tench", &["UA", "PL", "DE", "FR"], &[]),
+    ("asp", &["UA", "PL", "DE"], &[]),
+    ("burbot", &["UA", "PL", "DE"], &["FR"]),
+    ("ide", &["UA", "PL", "DE"], &["FR"]),
+];
+
+/// Whether `name` contains `search`, ignoring case.
+pub(crate) fn name_matches(name: &str, search: &str) -> bool {
+    name.to_lowercase().contains(&search.trim().to_lowercase())
 }
 
-/// Initialize database schema
-async fn initialize_schema(pool: &SqlitePool) -> anyhow::Result<()> {
-    // Create tables...
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS users (
-            id TEXT PRIMARY KEY,
-            email TEXT UNIQUE,
-            password_hash TEXT NOT NULL,
-            country_code TEXT DEFAULT 'UA',
-            language TEXT DEFAULT 'uk',
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
-
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS catches (
-            id TEXT PRIMARY KEY,
-            user_id TEXT,
-            location_lat REAL NOT NULL,
-            location_lon REAL NOT NULL,
-            caught_at TIMESTAMP NOT NULL,
-            fish_species TEXT,
-            weight_kg REAL,
-            length_cm REAL,
-            bait_used TEXT,
-            weather_temp REAL,
-            weather_pressure REAL,
-            moon_phase REAL,
-            notes TEXT,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
-
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS water_bodies (
-            id TEXT PRIMARY KEY,
-            name TEXT NOT NULL,
-            description TEXT,
-            location_lat REAL NOT NULL,
-            location_lon REAL NOT NULL,
-            water_type TEXT,
-            country_code TEXT
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
-
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS fish_species (
-            id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
-            name_uk TEXT NOT NULL,
-            name_en TEXT,
-            scientific_name TEXT,
-            best_season TEXT,
-            preferred_bait TEXT,
-            min_temp REAL,
-            max_temp REAL,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
-
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS regulations (
-            id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
-            region_code TEXT NOT NULL,
-            fish_species TEXT,
-            min_size_cm REAL,
-            max_catch_per_day INTEGER,
-            season_start TEXT,
-            season_end TEXT,
-            restrictions TEXT,
-            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
-
-    // Create indexes
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_catches_user_id ON catches(user_id)")
-        .execute(pool)
-        .await?;
-    sqlx::query("CREATE INDEX IF NOT EXISTS idx_catches_location ON catches(location_lat, location_lon)")
-        .execute(pool)
-        .await?;
-
-    // Seed fish species
-    seed_fish_species(pool).await?;
-
-    Ok(())
+/// Latitude and longitude deltas (degrees) covering `radius_km` around `lat`.
+pub(crate) fn radius_deltas(lat: f64, radius_km: f64) -> (f64, f64) {
+    let lat_delta = radius_km / 111.0;
+    let lon_delta = radius_km / (111.0 * (lat * std::f64::consts::PI / 180.0).cos());
+    (lat_delta, lon_delta)
 }
 
-/// Seed fish species data
-async fn seed_fish_species(pool: &SqlitePool) -> anyhow::Result<()> {
-    let fish_data = vec![
-        ("pike", "Щука", "Pike", "Esox lucius", "all_year", "spoon", 5.0, 22.0),
-        ("crucian", "Карась", "Crucian carp", "Carassius carassius", "summer", "worm", 15.0, 28.0),
-        ("perch", "Окунь", "Perch", "Perca fluviatilis", "all_year", "minnow", 8.0, 25.0),
-        ("bream", "Лящ", "Bream", "Abramis brama", "all_year", "corn", 10.0, 26.0),
-        ("zander", "Судак", "Zander", "Sander lucioperca", "all_year", "fish", 12.0, 24.0),
-        ("carp", "Короп", "Carp", "Cyprinus carpio", "summer", "boilie", 18.0, 30.0),
-        ("catfish", "Сом", "Catfish", "Silurus glanis", "summer", "fish", 18.0, 28.0),
-        ("roach", "Плітка", "Roach", "Rutilus rutilus", "all_year", "maggot", 8.0, 24.0),
-        ("tench", "Лин", "Tench", "Tinca tinca", "summer", "worm", 15.0, 26.0),
-        ("asp", "Жерех", "Asp", "Aspius aspius", "spring", "spoon", 10.0, 22.0),
-        ("burbot", "Минь", "Burbot", "Lota lota", "winter", "lamprey", 0.0, 15.0),
-        ("ide", "Язь", "Ide", "Leuciscus idus", "spring", "worm", 8.0, 22.0),
-    ];
-
-    for (id, name_uk, name_en, scientific_name, best_season, preferred_bait, min_temp, max_temp) in fish_data {
-        sqlx::query(
-            r#"
-            INSERT OR IGNORE INTO fish_species (id, name_uk, name_en, scientific_name, best_season, preferred_bait, min_temp, max_temp)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(id)
-        .bind(name_uk)
-        .bind(name_en)
-        .bind(scientific_name)
-        .bind(best_season)
-        .bind(preferred_bait)
-        .bind(min_temp)
-        .bind(max_temp)
-        .execute(pool)
-        .await?;
-    }
-
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fishing_shared::types::{CatchPrivacy, CatchRecord};
+
+    use std::future::Future;
+
+    /// PostgreSQL tests share one database, and `test_migrations` reverts
+    /// its whole schema, so they take turns.
+    static POSTGRES: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    /// Run `exercise` against a fresh SQLite database and, when
+    /// `TEST_DATABASE_URL` is set (e.g. to the instance from
+    /// `docker-compose.postgres.yml`), against PostgreSQL as well.
+    async fn with_repositories<F, Fut>(exercise: F)
+    where
+        F: Fn(Arc<dyn Repository>) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("sqlite://{}", dir.path().join("test.db").display());
+        let repo = connect(&url).await.unwrap();
+        assert_eq!(repo.backend(), "sqlite");
+        exercise(repo).await;
+
+        let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+            return;
+        };
+        let _turn = POSTGRES.lock().await;
+        let repo = connect(&url).await.unwrap();
+        assert_eq!(repo.backend(), "postgres");
+        exercise(repo).await;
+    }
+
+    /// A new user with a unique email.
+    async fn new_user(repo: &dyn Repository) -> NewUser {
+        let user = NewUser {
+            id: Uuid::new_v4().to_string(),
+            email: format!("angler-{}@example.com", Uuid::new_v4()),
+            password_hash: "hash".to_string(),
+            country_code: "UA".to_string(),
+            language: "uk".to_string(),
+        };
+        repo.create_user(&user).await.unwrap();
+        user
+    }
+
+    #[tokio::test]
+    async fn test_migrations() {
+        with_repositories(|repo| async move { exercise_migrations(repo.as_ref()).await }).await;
+    }
+
+    #[tokio::test]
+    async fn test_users() {
+        with_repositories(|repo| async move { exercise_users(repo.as_ref()).await }).await;
+    }
+
+    #[tokio::test]
+    async fn test_fish_names() {
+        with_repositories(|repo| async move {
+            let user = new_user(repo.as_ref()).await;
+            exercise_fish_names(repo.as_ref(), &user.id).await;
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_catches() {
+        with_repositories(|repo| async move {
+            let user = new_user(repo.as_ref()).await;
+            exercise_catches(repo.as_ref(), &user.id).await;
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_sessions() {
+        with_repositories(|repo| async move {
+            let user = new_user(repo.as_ref()).await;
+            exercise_sessions(repo.as_ref(), &user.id).await;
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_email_tokens() {
+        with_repositories(|repo| async move {
+            let user = new_user(repo.as_ref()).await;
+            exercise_email_tokens(repo.as_ref(), &user.id).await;
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_identities() {
+        with_repositories(|repo| async move {
+            let user = new_user(repo.as_ref()).await;
+            exercise_identities(repo.as_ref(), &user.id).await;
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_reference_data() {
+        with_repositories(|repo| async move {
+            let user = new_user(repo.as_ref()).await;
+            exercise_reference_data(repo.as_ref(), &user.id, &user.email).await;
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_favorite_spots() {
+        with_repositories(|repo| async move {
+            let user = new_user(repo.as_ref()).await;
+            exercise_favorite_spots(repo.as_ref(), &user.id).await;
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_alerts() {
+        with_repositories(|repo| async move {
+            let user = new_user(repo.as_ref()).await;
+            exercise_alerts(repo.as_ref(), &user.id).await;
+        })
+        .await;
+    }
+
+    async fn exercise_users(repo: &dyn Repository) {
+        let user = new_user(repo).await;
+        assert!(repo.create_user(&user).await.is_err(), "duplicate user must fail");
+
+        let by_email = repo.find_user_by_email(&user.email).await.unwrap().unwrap();
+        assert_eq!(by_email.id, user.id);
+        assert_eq!(by_email.preferences.country_code.as_deref(), Some("UA"));
+        let by_id = repo.find_user_by_id(&user.id).await.unwrap().unwrap();
+        assert_eq!(by_id.email, user.email);
+        assert!(repo.find_user_by_email("nobody@example.com").await.unwrap().is_none());
+        // Accounts stored before emails were lowercased are still found
+        let legacy = NewUser { id: Uuid::new_v4().to_string(), email: format!("Legacy-{}@Example.com", user.id), ..user.clone() };
+        repo.create_user(&legacy).await.unwrap();
+        let found = repo.find_user_by_email(&legacy.email.to_lowercase()).await.unwrap().unwrap();
+        assert_eq!(found.id, legacy.id);
+        assert!(repo.set_user_role(&legacy.email.to_uppercase(), Role::Admin).await.unwrap());
+        let respelled = NewUser { id: Uuid::new_v4().to_string(), email: legacy.email.to_lowercase(), ..user.clone() };
+        assert!(repo.create_user(&respelled).await.is_err(), "one account per address, whatever the case");
+
+        assert_eq!(by_id.preferences.length_unit, "cm");
+        let preferences = UserPreferencesDb {
+            weight_unit: "lb".to_string(),
+            wind_speed_unit: "km/h".to_string(),
+            home_region: Some("UA-32".to_string()),
+            favorite_species: r#"["pike"]"#.to_string(),
+            ..by_id.preferences.clone()
+        };
+        assert!(repo.update_user_preferences(&user.id, &preferences).await.unwrap());
+        assert!(!repo.update_user_preferences("nobody", &preferences).await.unwrap());
+        let stored = repo.find_user_by_id(&user.id).await.unwrap().unwrap().preferences;
+        assert_eq!(stored.weight_unit, "lb");
+        assert_eq!(stored.wind_speed_unit, "km/h");
+        assert_eq!(stored.home_region.as_deref(), Some("UA-32"));
+        assert_eq!(stored.favorite_species, r#"["pike"]"#);
+    }
+
+    async fn exercise_fish_names(repo: &dyn Repository, admin_id: &str) {
+        let name_of = |species: &[FishItemDb], id: &str| species.iter().find(|s| s.id == id).map(|s| s.name.clone());
+
+        let species = repo.get_fish_species(Some("UA"), "uk", None).await.unwrap();
+        assert!(species.len() >= FISH_SPECIES_SEED.len());
+        assert_eq!(name_of(&species, "pike").as_deref(), Some("Щука"));
+        let species = repo.get_fish_species(None, "pl", None).await.unwrap();
+        assert_eq!(name_of(&species, "pike").as_deref(), Some("Szczupak"));
+        assert_eq!(name_of(&species, "burbot").as_deref(), Some("Miętus"));
+        assert_eq!(repo.fish_species_name("burbot", "pl").await.unwrap().as_deref(), Some("Miętus"));
+        assert_eq!(repo.fish_species_name("kraken", "pl").await.unwrap(), None);
+
+        // A species without a translation falls back to English
+        let mut roach = repo.list_fish_species_records().await.unwrap().into_iter().find(|s| s.id == "roach").unwrap();
+        roach.id = format!("roach-{}", Uuid::new_v4());
+        repo.create_fish_species(&roach, admin_id).await.unwrap();
+        let species = repo.get_fish_species(None, "fr", None).await.unwrap();
+        assert_eq!(name_of(&species, &roach.id).as_deref(), Some("Roach"));
+        assert_eq!(name_of(&species, "roach").as_deref(), Some("Gardon"));
+        assert_eq!(repo.fish_species_name(&roach.id, "fr").await.unwrap().as_deref(), Some("Roach"));
+
+        // Only species living in the country, plus those without any regions yet
+        let french = repo.get_fish_species(Some("FR"), "fr", None).await.unwrap();
+        assert!(name_of(&french, "pike").is_some());
+        assert!(name_of(&french, "asp").is_none());
+        assert!(name_of(&french, &roach.id).is_some());
+        let american = repo.get_fish_species(Some("US"), "en", None).await.unwrap();
+        assert!(name_of(&american, "pike").is_none());
+        assert!(name_of(&american, &roach.id).is_some());
+
+        // Search matches names and synonyms in any language, ignoring case
+        let found = repo.get_fish_species(None, "en", Some("налим")).await.unwrap();
+        assert_eq!(found.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), ["burbot"]);
+        assert_eq!(found[0].name, "Burbot");
+        let found = repo.get_fish_species(Some("PL"), "pl", Some("WALLER")).await.unwrap();
+        assert_eq!(found.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["Sum"]);
+        let found = repo.get_fish_species(None, "de", Some("lucius")).await.unwrap();
+        assert_eq!(found.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["Hecht"]);
+        assert!(repo.get_fish_species(None, "en", Some("shark")).await.unwrap().is_empty());
+
+        repo.delete_fish_species(&roach.id, admin_id).await.unwrap();
+    }
+
+    fn with_id(record: &NewCatchRecord, id: &str) -> CatchRecord {
+        CatchRecord {
+            id: id.to_string(),
+            user_id: record.user_id.clone(),
+            lat: record.lat,
+            lon: record.lon,
+            caught_at: record.caught_at,
+            fish_species: record.fish_species.clone(),
+            weight_kg: record.weight_kg,
+            length_cm: record.length_cm,
+            bait_used: record.bait_used.clone(),
+            bite_intensity: record.bite_intensity,
+            weather_temp: record.weather_temp,
+            weather_pressure: record.weather_pressure,
+            moon_phase: record.moon_phase,
+            notes: record.notes.clone(),
+            photo_url: record.photo_url.clone(),
+            environment: None,
+            privacy: record.privacy.unwrap_or_default(),
+        }
+    }
+
+    /// Every catch field must survive insert and both read paths unchanged.
+    async fn exercise_catches(repo: &dyn Repository, user_id: &str) {
+        let full = NewCatchRecord {
+            id: None,
+            user_id: user_id.to_string(),
+            lat: 50.4501,
+            lon: 30.5234,
+            caught_at: "2026-05-14T05:30:00Z".parse().unwrap(),
+            fish_species: "pike".to_string(),
+            weight_kg: Some(2.45),
+            length_cm: Some(61.5),
+            bait_used: Some("spoon".to_string()),
+            bite_intensity: Some(4),
+            weather_temp: Some(17.8),
+            weather_pressure: Some(1013.2),
+            moon_phase: Some(0.62),
+            notes: Some("Біля очерету".to_string()),
+            photo_url: Some("https://example.com/pike.jpg".to_string()),
+            privacy: Some(CatchPrivacy::Public),
+        };
+        let minimal = NewCatchRecord {
+            caught_at: "2026-05-13T18:00:00Z".parse().unwrap(),
+            fish_species: "perch".to_string(),
+            weight_kg: None,
+            length_cm: None,
+            bait_used: None,
+            bite_intensity: None,
+            weather_temp: None,
+            weather_pressure: None,
+            moon_phase: None,
+            notes: None,
+            photo_url: None,
+            privacy: Some(CatchPrivacy::Private),
+            ..full.clone()
+        };
+
+        let mut expected = Vec::new();
+        for record in [&full, &minimal] {
+            let saved = CatchRecord::from(repo.insert_catch(record, None).await.unwrap().unwrap());
+            assert!(Uuid::parse_str(&saved.id).is_ok());
+            assert_eq!(saved, with_id(record, &saved.id));
+            expected.push(saved);
+        }
+
+        let by_user: Vec<CatchRecord> = repo
+            .get_catches_by_user(user_id, 50, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(CatchRecord::from)
+            .collect();
+        assert_eq!(by_user, expected, "newest first, every field intact");
+
+        let nearby: Vec<CatchRecord> = repo
+            .get_nearby_catches(50.45, 30.52, 5.0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(CatchRecord::from)
+            .filter(|c| c.user_id == user_id)
+            .collect();
+        assert_eq!(nearby, expected[..1], "only public catches");
+
+        let paged = repo.get_catches_by_user(user_id, 1, 1).await.unwrap();
+        assert_eq!(paged.len(), 1);
+        assert_eq!(paged[0].id, expected[1].id);
+
+        let environment = EnvironmentSnapshot {
+            weather_time: Some("2026-05-12T09:00:00Z".parse().unwrap()),
+            temperature_c: full.weather_temp,
+            pressure_hpa: full.weather_pressure,
+            pressure_change_3h_hpa: Some(-1.6),
+            pressure_trend: Some(fishing_shared::types::PressureTrend::Falling),
+            wind_speed_ms: Some(4.2),
+            wind_gust_ms: Some(8.9),
+            wind_direction_deg: Some(225.0),
+            precipitation_mm: Some(0.0),
+            moon_phase: full.moon_phase.unwrap(),
+            moon_illumination: 0.86,
+            sun_elevation_deg: 41.3,
+            sun_azimuth_deg: 128.7,
+        };
+        let with_environment = NewCatchRecord {
+            caught_at: "2026-05-12T09:10:00Z".parse().unwrap(),
+            ..full.clone()
+        };
+        let saved = CatchRecord::from(
+            repo.insert_catch(&with_environment, Some(&environment)).await.unwrap().unwrap(),
+        );
+        assert_eq!(saved.environment.as_ref(), Some(&environment));
+        let listed = repo.get_catches_by_user(user_id, 50, 0).await.unwrap();
+        let listed = CatchRecord::from(listed.into_iter().find(|c| c.id == saved.id).unwrap());
+        assert_eq!(listed, saved);
+
+        // A client-chosen id is kept, and sending it again stores nothing.
+        let client_id = Uuid::new_v4().to_string();
+        let replayed = NewCatchRecord {
+            id: Some(client_id.clone()),
+            caught_at: "2026-05-11T07:00:00Z".parse().unwrap(),
+            ..full.clone()
+        };
+        let first = CatchRecord::from(repo.insert_catch(&replayed, None).await.unwrap().unwrap());
+        assert_eq!(first, with_id(&replayed, &client_id));
+        assert!(repo.insert_catch(&replayed, None).await.unwrap().is_none());
+        let stored = CatchRecord::from(repo.get_catch(&client_id).await.unwrap().unwrap());
+        assert_eq!(stored, first);
+        assert!(repo.get_catch(&Uuid::new_v4().to_string()).await.unwrap().is_none());
+    }
+
+    async fn exercise_email_tokens(repo: &dyn Repository, user_id: &str) {
+        let token = |hash: &str, purpose, expires_in: chrono::Duration| NewEmailToken {
+            token_hash: hash.to_string(),
+            user_id: user_id.to_string(),
+            purpose,
+            expires_at: chrono::Utc::now() + expires_in,
+        };
+        let hour = chrono::Duration::hours(1);
+        let verify = EmailTokenPurpose::VerifyEmail;
+        let reset = EmailTokenPurpose::ResetPassword;
+        let old = format!("hash-{}", Uuid::new_v4());
+        let new = format!("hash-{}", Uuid::new_v4());
+        let expired = format!("hash-{}", Uuid::new_v4());
+
+        repo.create_email_token(&token(&old, verify, hour)).await.unwrap();
+        repo.create_email_token(&token(&new, verify, hour)).await.unwrap();
+        assert_eq!(repo.consume_email_token(&old, verify).await.unwrap(), None, "superseded");
+        assert_eq!(repo.consume_email_token(&new, reset).await.unwrap(), None, "wrong purpose");
+        assert_eq!(repo.consume_email_token(&new, verify).await.unwrap().as_deref(), Some(user_id));
+        assert_eq!(repo.consume_email_token(&new, verify).await.unwrap(), None, "single use");
+
+        repo.create_email_token(&token(&expired, reset, -hour)).await.unwrap();
+        assert_eq!(repo.consume_email_token(&expired, reset).await.unwrap(), None, "expired");
+
+        assert!(repo.find_user_by_id(user_id).await.unwrap().unwrap().email_verified_at.is_none());
+        repo.mark_email_verified(user_id).await.unwrap();
+        let verified_at = repo.find_user_by_id(user_id).await.unwrap().unwrap().email_verified_at;
+        assert!(verified_at.is_some());
+        repo.mark_email_verified(user_id).await.unwrap();
+        let again = repo.find_user_by_id(user_id).await.unwrap().unwrap().email_verified_at;
+        assert_eq!(again, verified_at, "first verification time is kept");
+
+        let session = NewSession {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            user_agent: None,
+            ip_address: None,
+            expires_at: chrono::Utc::now() + chrono::Duration::days(1),
+        };
+        repo.create_session(&session, &format!("hash-{}", Uuid::new_v4())).await.unwrap();
+        repo.reset_password(user_id, "new-hash").await.unwrap();
+        assert_eq!(repo.find_user_by_id(user_id).await.unwrap().unwrap().password_hash, "new-hash");
+        assert!(repo.list_sessions(user_id).await.unwrap().is_empty(), "reset revokes sessions");
+    }
+
+    async fn exercise_identities(repo: &dyn Repository, user_id: &str) {
+        let login = OidcLoginDb {
+            state: format!("state-{}", Uuid::new_v4()),
+            provider: "mock".to_string(),
+            code_verifier: "verifier".to_string(),
+            nonce: "nonce".to_string(),
+            redirect_uri: "http://localhost/cb".to_string(),
+            expires_at: chrono::Utc::now() + chrono::Duration::minutes(10),
+        };
+        repo.create_oidc_login(&login).await.unwrap();
+        let taken = repo.take_oidc_login(&login.state).await.unwrap().unwrap();
+        assert_eq!((taken.code_verifier.as_str(), taken.nonce.as_str()), ("verifier", "nonce"));
+        assert!(repo.take_oidc_login(&login.state).await.unwrap().is_none(), "single use");
+
+        let subject = format!("sub-{}", Uuid::new_v4());
+        assert!(repo.find_user_identity("mock", &subject).await.unwrap().is_none());
+        let identity = NewUserIdentity {
+            provider: "mock".to_string(),
+            subject: subject.clone(),
+            user_id: user_id.to_string(),
+            email: Some("angler@idp.example".to_string()),
+        };
+        repo.link_user_identity(&identity).await.unwrap();
+        assert!(repo.link_user_identity(&identity).await.is_err(), "linked once");
+        let found = repo.find_user_identity("mock", &subject).await.unwrap().unwrap();
+        assert_eq!(found.user_id, user_id);
+        assert_eq!(repo.list_user_identities(user_id).await.unwrap().len(), 1);
+    }
+
+    /// Admin writes to reference data leave one history entry each.
+    async fn exercise_reference_data(repo: &dyn Repository, user_id: &str, email: &str) {
+        assert!(repo.set_user_role(email, Role::Admin).await.unwrap());
+        assert_eq!(repo.find_user_by_id(user_id).await.unwrap().unwrap().role(), Role::Admin);
+        assert!(!repo.set_user_role("nobody@example.com", Role::Admin).await.unwrap());
+
+        let mut lake = WaterBodyDb {
+            id: Uuid::new_v4().to_string(),
+            name: "Lake".to_string(),
+            description: None,
+            location_lat: 50.45,
+            location_lon: 30.52,
+            water_type: Some("lake".to_string()),
+            country_code: Some("UA".to_string()),
+        };
+        assert!(repo.create_water_body(&lake, user_id).await.unwrap());
+        assert!(!repo.create_water_body(&lake, user_id).await.unwrap(), "id taken");
+        lake.description = Some("Reed beds on the north shore".to_string());
+        assert!(repo.update_water_body(&lake, user_id).await.unwrap());
+        let nearby = repo.get_nearby_water_bodies(50.45, 30.52, 1.0).await.unwrap();
+        assert!(nearby.iter().any(|w| w.id == lake.id && w.description == lake.description));
+
+        let regulation = RegulationDb {
+            id: Uuid::new_v4().to_string(),
+            region_code: "UA-32".to_string(),
+            fish_species: Some("pike".to_string()),
+            min_size_cm: Some(35.0),
+            max_catch_per_day: Some(5),
+            season_start: Some("03-01".to_string()),
+            season_end: Some("04-30".to_string()),
+            restrictions: None,
+        };
+        assert!(repo.create_regulation(&regulation, user_id).await.unwrap());
+        assert!(repo.list_regulations().await.unwrap().iter().any(|r| r.id == regulation.id));
+        assert!(repo.delete_regulation(&regulation.id, user_id).await.unwrap());
+        assert!(!repo.delete_regulation(&regulation.id, user_id).await.unwrap());
+
+        let mut species = repo.list_fish_species_records().await.unwrap().remove(0);
+        assert!(repo.fish_species_exists(&species.id).await.unwrap());
+        species.id = format!("species-{}", Uuid::new_v4());
+        assert!(!repo.fish_species_exists(&species.id).await.unwrap());
+        assert!(!repo.update_fish_species(&species, user_id).await.unwrap(), "missing row");
+        assert!(repo.create_fish_species(&species, user_id).await.unwrap());
+        assert!(repo.delete_fish_species(&species.id, user_id).await.unwrap());
+        assert!(repo.delete_water_body(&lake.id, user_id).await.unwrap());
+
+        let history = repo.list_change_history(Some("water_bodies"), Some(&lake.id), 10).await.unwrap();
+        let actions: Vec<_> = history.iter().map(|h| h.action.as_str()).collect();
+        assert_eq!(actions, ["delete", "update", "create"]);
+        assert!(history[1].before_data.as_deref().is_some_and(|json| !json.contains("Reed beds")));
+        assert!(history[1].after_data.as_deref().is_some_and(|json| json.contains("Reed beds")));
+        assert!(history.iter().all(|h| h.changed_by.as_deref() == Some(user_id)));
+        assert_eq!(repo.list_change_history(None, Some(&regulation.id), 10).await.unwrap().len(), 2);
+        assert_eq!(repo.list_change_history(Some("fish_species"), Some(&species.id), 1).await.unwrap().len(), 1);
+
+        repo.get_nearby_water_bodies(50.45, 30.52, 10.0).await.unwrap();
+        repo.get_regulations("UA", None).await.unwrap();
+    }
+
+    /// Spots are only visible to and changeable by their owner.
+    async fn exercise_favorite_spots(repo: &dyn Repository, user_id: &str) {
+        let lake = WaterBodyDb {
+            id: Uuid::new_v4().to_string(),
+            name: "Lake".to_string(),
+            description: None,
+            location_lat: 50.45,
+            location_lon: 30.52,
+            water_type: Some("lake".to_string()),
+            country_code: Some("UA".to_string()),
+        };
+        assert!(!repo.water_body_exists(&lake.id).await.unwrap());
+        repo.create_water_body(&lake, user_id).await.unwrap();
+        assert!(repo.water_body_exists(&lake.id).await.unwrap());
+
+        let mut spot = FavoriteSpotDb {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            name: "North shore".to_string(),
+            location_lat: 50.46,
+            location_lon: 30.52,
+            water_body_id: Some(lake.id.clone()),
+            target_species: r#"["pike"]"#.to_string(),
+            notes: None,
+            created_at: chrono::Utc::now(),
+        };
+        repo.create_favorite_spot(&spot).await.unwrap();
+        let listed = repo.list_favorite_spots(user_id).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].target_species, r#"["pike"]"#);
+        assert!(repo.list_favorite_spots("someone-else").await.unwrap().is_empty());
+
+        spot.notes = Some("Reeds".to_string());
+        assert!(repo.update_favorite_spot(&spot).await.unwrap());
+        let stranger = FavoriteSpotDb { user_id: "someone-else".to_string(), ..spot.clone() };
+        assert!(!repo.update_favorite_spot(&stranger).await.unwrap());
+        let stored = repo.get_favorite_spot(user_id, &spot.id).await.unwrap().unwrap();
+        assert_eq!(stored.notes.as_deref(), Some("Reeds"));
+        assert!(repo.get_favorite_spot("someone-else", &spot.id).await.unwrap().is_none());
+
+        // Removing the water body keeps the spot
+        repo.delete_water_body(&lake.id, user_id).await.unwrap();
+        let stored = repo.get_favorite_spot(user_id, &spot.id).await.unwrap().unwrap();
+        assert_eq!(stored.water_body_id, None);
+
+        assert!(!repo.delete_favorite_spot("someone-else", &spot.id).await.unwrap());
+        assert!(repo.delete_favorite_spot(user_id, &spot.id).await.unwrap());
+        assert!(repo.list_favorite_spots(user_id).await.unwrap().is_empty());
+    }
+
+    async fn exercise_alerts(repo: &dyn Repository, user_id: &str) {
+        let spot = FavoriteSpotDb {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            name: "Dam".to_string(),
+            location_lat: 50.46,
+            location_lon: 30.52,
+            water_body_id: None,
+            target_species: "[]".to_string(),
+            notes: None,
+            created_at: chrono::Utc::now(),
+        };
+        repo.create_favorite_spot(&spot).await.unwrap();
+
+        let mut rule = AlertRuleDb {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            favorite_spot_id: spot.id.clone(),
+            species: Some("pike".to_string()),
+            min_probability: 0.7,
+            start_hour: 20,
+            end_hour: 6,
+            utc_offset_minutes: 120,
+            pressure_trend: Some("falling".to_string()),
+            enabled: true,
+            last_notified_at: None,
+            created_at: chrono::Utc::now(),
+        };
+        repo.create_alert_rule(&rule).await.unwrap();
+        let listed = repo.list_alert_rules(user_id).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!((listed[0].start_hour, listed[0].end_hour), (20, 6));
+        assert_eq!(listed[0].pressure_trend.as_deref(), Some("falling"));
+        let is_enabled = |rules: Vec<AlertRuleDb>, id: &str| rules.iter().any(|r| r.id == id);
+        assert!(is_enabled(repo.list_enabled_alert_rules().await.unwrap(), &rule.id));
+
+        let notified = chrono::Utc::now();
+        repo.mark_alert_notified(&rule.id, notified).await.unwrap();
+        rule.enabled = false;
+        rule.min_probability = 0.8;
+        assert!(repo.update_alert_rule(&rule).await.unwrap());
+        let stranger = AlertRuleDb { user_id: "someone-else".to_string(), ..rule.clone() };
+        assert!(!repo.update_alert_rule(&stranger).await.unwrap());
+        let stored = repo.get_alert_rule(user_id, &rule.id).await.unwrap().unwrap();
+        assert_eq!(stored.min_probability, 0.8);
+        assert!(stored.last_notified_at.is_some_and(|at| (at - notified).num_seconds().abs() < 1));
+        assert!(!is_enabled(repo.list_enabled_alert_rules().await.unwrap(), &rule.id));
+        assert!(repo.get_alert_rule("someone-else", &rule.id).await.unwrap().is_none());
+
+        let mut subscription = PushSubscriptionDb {
+            endpoint: format!("https://push.example.com/{}", Uuid::new_v4()),
+            user_id: user_id.to_string(),
+            p256dh: "key".to_string(),
+            auth: "secret".to_string(),
+            created_at: chrono::Utc::now(),
+        };
+        repo.save_push_subscription(&subscription).await.unwrap();
+        subscription.auth = "rotated".to_string();
+        repo.save_push_subscription(&subscription).await.unwrap();
+        let listed = repo.list_push_subscriptions(user_id).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].auth, "rotated");
+        assert!(!repo.delete_push_subscription("someone-else", &subscription.endpoint).await.unwrap());
+        assert!(repo.delete_push_subscription(user_id, &subscription.endpoint).await.unwrap());
+        assert!(repo.list_push_subscriptions(user_id).await.unwrap().is_empty());
+
+        assert!(!repo.delete_alert_rule("someone-else", &rule.id).await.unwrap());
+        assert!(repo.delete_alert_rule(user_id, &rule.id).await.unwrap());
+
+        // Deleting the spot takes its rules along
+        repo.create_alert_rule(&rule).await.unwrap();
+        repo.delete_favorite_spot(user_id, &spot.id).await.unwrap();
+        assert!(repo.list_alert_rules(user_id).await.unwrap().is_empty());
+    }
+
+    async fn exercise_sessions(repo: &dyn Repository, user_id: &str) {
+        let session = NewSession {
+            id: Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            user_agent: Some("test-agent".to_string()),
+            ip_address: None,
+            expires_at: chrono::Utc::now() + chrono::Duration::days(1),
+        };
+        let first = format!("hash-{}", Uuid::new_v4());
+        let second = format!("hash-{}", Uuid::new_v4());
+        repo.create_session(&session, &first).await.unwrap();
+
+        let token = repo.find_refresh_token(&first).await.unwrap().unwrap();
+        assert_eq!((token.session_id.as_str(), token.user_id.as_str()), (session.id.as_str(), user_id));
+        assert!(token.used_at.is_none() && token.revoked_at.is_none());
+
+        assert!(repo.rotate_refresh_token(&first, &second).await.unwrap());
+        assert!(!repo.rotate_refresh_token(&first, "hash-again").await.unwrap(), "single use");
+        assert!(repo.find_refresh_token(&first).await.unwrap().unwrap().used_at.is_some());
+        assert_eq!(repo.find_refresh_token(&second).await.unwrap().unwrap().session_id, session.id);
+
+        assert_eq!(repo.list_sessions(user_id).await.unwrap().len(), 1);
+        assert!(!repo.revoke_session("someone-else", &session.id).await.unwrap());
+        assert!(repo.revoke_session(user_id, &session.id).await.unwrap());
+        assert!(repo.list_sessions(user_id).await.unwrap().is_empty());
+        assert!(repo.find_refresh_token(&second).await.unwrap().unwrap().revoked_at.is_some());
+    }
+
+    /// Revert everything, check status, then migrate back up.
+    async fn exercise_migrations(repo: &dyn Repository) {
+        let all = migrations::for_backend(repo.backend());
+
+        let reverted = repo.migrate_down(all.len()).await.unwrap();
+        assert_eq!(reverted.len(), all.len());
+        let status = repo.migration_status().await.unwrap();
+        assert!(status.iter().all(|s| s.state == migrations::MigrationState::Pending));
+
+        let applied = repo.migrate_up().await.unwrap();
+        assert_eq!(applied.len(), all.len());
+        assert!(repo.migrate_up().await.unwrap().is_empty(), "up must be idempotent");
+        let status = repo.migration_status().await.unwrap();
+        assert!(status
+            .iter()
+            .all(|s| s.state == migrations::MigrationState::Applied && s.applied_at.is_some()));
+
+        repo.seed_fish_species().await.unwrap();
+    }
 }
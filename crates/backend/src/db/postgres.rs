@@ -0,0 +1,25 @@
+use sqlx::postgres::{PgPool, PgPoolOptions};
+
+use super::queries::impl_repository;
+
+/// PostgreSQL-backed repository for production deployments.
+#[derive(Clone)]
+pub struct PgRepository {
+    pool: PgPool,
+}
+
+impl PgRepository {
+    /// Connect to PostgreSQL.
+    pub async fn connect(database_url: &str) -> anyhow::Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .connect(database_url)
+            .await?;
+
+        tracing::info!("Connected to PostgreSQL");
+
+        Ok(Self { pool })
+    }
+}
+
+impl_repository!(PgRepository, "postgres");
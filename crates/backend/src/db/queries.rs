@@ -1,207 +1,1670 @@
-use sqlx::SqlitePool;
-use uuid::Uuid;
-
-use crate::models::{CatchRecordDb, FishItemDb, NewCatchRecord, RegulationDb, WaterBodyDb};
-
-/// Insert a new catch record and return the saved row.
-pub async fn insert_catch(
-    pool: &SqlitePool,
-    record: &NewCatchRecord,
-) -> anyhow::Result<CatchRecordDb> {
-    let id = Uuid::new_v4();
-    let now = chrono::Utc::now();
-
-    sqlx::query(
-        r#"
-        INSERT INTO catches (
-            id, user_id, location_lat, location_lon, caught_at,
-            fish_species, weight_kg, length_cm, bait_used, weather_temp,
-            weather_pressure, moon_phase, notes, created_at
-        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-        "#,
-    )
-    .bind(id.to_string())
-    .bind(record.user_id.to_string())
-    .bind(record.lat)
-    .bind(record.lon)
-    .bind(&record.caught_at)
-    .bind(&record.fish_species)
-    .bind(record.weight)
-    .bind(record.length)
-    .bind(&record.bait_used)
-    .bind(record.weather_temp)
-    .bind(record.weather_pressure)
-    .bind(record.moon_phase)
-    .bind(&record.notes)
-    .bind(now)
-    .execute(pool)
-    .await?;
-
-    Ok(CatchRecordDb {
-        id,
-        user_id: record.user_id,
-        lat: record.lat,
-        lon: record.lon,
-        caught_at: record.caught_at,
-        fish_species: record.fish_species.clone(),
-        weight: record.weight,
-        length: record.length,
-        bait_used: record.bait_used.clone(),
-        weather_temp: record.weather_temp,
-        weather_pressure: record.weather_pressure,
-        moon_phase: record.moon_phase,
-        notes: record.notes.clone(),
-    })
-}
+//! Queries shared by the SQLite and PostgreSQL repositories.
+//!
+//! Statements stick to the SQL understood by both databases: `$N`
+//! placeholders, ids and timestamps generated in Rust, and
+//! `ON CONFLICT ... DO NOTHING` instead of dialect-specific upserts.
 
-/// Get catches by user ID with pagination.
-pub async fn get_catches_by_user(
-    pool: &SqlitePool,
-    user_id: Uuid,
-    limit: i64,
-    offset: i64,
-) -> anyhow::Result<Vec<CatchRecordDb>> {
-    let rows = sqlx::query_as::<_, CatchRecordDb>(
-        r#"
-        SELECT id, user_id, location_lat, location_lon, caught_at,
-               fish_species, weight_kg, bait_used, notes
-        FROM catches
-        WHERE user_id = ?
-        ORDER BY caught_at DESC
-        LIMIT ? OFFSET ?
-        "#,
-    )
-    .bind(user_id.to_string())
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(pool)
-    .await?;
-
-    Ok(rows)
-}
+/// Columns selected into [`CatchRecordDb`](crate::models::CatchRecordDb).
+pub(crate) const CATCH_COLUMNS: &str = "id, user_id, location_lat, location_lon, caught_at, \
+    fish_species, weight_kg, length_cm, bait_used, bite_intensity, \
+    weather_temp, weather_pressure, moon_phase, notes, photo_url, \
+    weather_time, pressure_change_3h_hpa, pressure_trend, wind_speed_ms, wind_gust_ms, \
+    wind_direction_deg, precipitation_mm, moon_illumination, sun_elevation_deg, sun_azimuth_deg, privacy";
 
-/// Get nearby catches within a radius in kilometers.
-pub async fn get_nearby_catches(
-    pool: &SqlitePool,
-    lat: f64,
-    lon: f64,
-    radius_km: f64,
-) -> anyhow::Result<Vec<CatchRecordDb>> {
-    let lat_delta = radius_km / 111.0;
-    let lon_delta = radius_km / (111.0 * (lat * std::f64::consts::PI / 180.0).cos());
-
-    let rows = sqlx::query_as::<_, CatchRecordDb>(
-        r#"
-        SELECT id, user_id, location_lat, location_lon, caught_at,
-               fish_species, weight_kg, bait_used, notes
-        FROM catches
-        WHERE location_lat BETWEEN ? AND ?
-          AND location_lon BETWEEN ? AND ?
-        ORDER BY caught_at DESC
-        LIMIT 100
-        "#,
-    )
-    .bind(lat - lat_delta)
-    .bind(lat + lat_delta)
-    .bind(lon - lon_delta)
-    .bind(lon + lon_delta)
-    .fetch_all(pool)
-    .await?;
-
-    Ok(rows)
-}
+/// Columns selected into [`UserDb`](crate::models::UserDb).
+pub(crate) const USER_COLUMNS: &str = "id, email, password_hash, role, country_code, language, created_at, \
+    email_verified_at, length_unit, weight_unit, temperature_unit, pressure_unit, \
+    wind_speed_unit, home_region, favorite_species, default_privacy";
 
-/// Get nearby water bodies within a radius in kilometers.
-pub async fn get_nearby_water_bodies(
-    pool: &SqlitePool,
-    lat: f64,
-    lon: f64,
-    radius_km: f64,
-) -> anyhow::Result<Vec<WaterBodyDb>> {
-    let lat_delta = radius_km / 111.0;
-    let lon_delta = radius_km / (111.0 * (lat * std::f64::consts::PI / 180.0).cos());
-
-    let rows = sqlx::query_as::<_, WaterBodyDb>(
-        r#"
-        SELECT id, name, location_lat, location_lon, water_type
-        FROM water_bodies
-        WHERE location_lat BETWEEN ? AND ?
-          AND location_lon BETWEEN ? AND ?
-        ORDER BY
-            (location_lat - ?) * (location_lat - ?) +
-            (location_lon - ?) * (location_lon - ?) ASC
-        LIMIT 200
-        "#,
-    )
-    .bind(lat - lat_delta)
-    .bind(lat + lat_delta)
-    .bind(lon - lon_delta)
-    .bind(lon + lon_delta)
-    .bind(lat)
-    .bind(lat)
-    .bind(lon)
-    .bind(lon)
-    .fetch_all(pool)
-    .await?;
-
-    Ok(rows)
-}
+/// Columns selected into [`FishSpeciesDb`](crate::models::FishSpeciesDb).
+pub(crate) const FISH_SPECIES_COLUMNS: &str =
+    "id, name_uk, name_en, scientific_name, best_season, preferred_bait, min_temp, max_temp";
 
-/// Get regulations for a country and optional fish species.
-pub async fn get_regulations(
-    pool: &SqlitePool,
-    country_code: &str,
-    fish_species_id: Option<Uuid>,
-) -> anyhow::Result<Vec<RegulationDb>> {
-    let rows = if let Some(_fish_id) = fish_species_id {
-        sqlx::query_as::<_, RegulationDb>(
-            r#"
-            SELECT id, region_code, fish_species, min_size_cm, max_catch_per_day,
-                   season_start, season_end, restrictions
-            FROM regulations
-            WHERE region_code = ?
-            "#,
-        )
-        .bind(country_code)
-        .fetch_all(pool)
-        .await?
-    } else {
-        sqlx::query_as::<_, RegulationDb>(
+/// Columns selected into [`RegulationDb`](crate::models::RegulationDb).
+pub(crate) const REGULATION_COLUMNS: &str = "id, region_code, fish_species, min_size_cm, \
+    max_catch_per_day, season_start, season_end, restrictions";
+
+/// Columns selected into [`WaterBodyDb`](crate::models::WaterBodyDb).
+pub(crate) const WATER_BODY_COLUMNS: &str =
+    "id, name, description, location_lat, location_lon, water_type, country_code";
+
+/// Columns selected into [`FavoriteSpotDb`](crate::models::FavoriteSpotDb).
+pub(crate) const FAVORITE_SPOT_COLUMNS: &str = "id, user_id, name, location_lat, location_lon, \
+    water_body_id, target_species, notes, created_at";
+
+/// Columns selected into [`AlertRuleDb`](crate::models::AlertRuleDb).
+pub(crate) const ALERT_RULE_COLUMNS: &str = "id, user_id, favorite_spot_id, species, min_probability, \
+    start_hour, end_hour, utc_offset_minutes, pressure_trend, enabled, last_notified_at, created_at";
+
+/// Columns selected into [`PushSubscriptionDb`](crate::models::PushSubscriptionDb).
+pub(crate) const PUSH_SUBSCRIPTION_COLUMNS: &str = "endpoint, user_id, p256dh, auth, created_at";
+
+/// Append a `change_history` row on the open transaction `$tx`.
+///
+/// `$before` and `$after` are `Option<&T>` of the row, stored as JSON.
+macro_rules! record_change {
+    ($tx:expr, $entity_type:expr, $entity_id:expr, $action:expr, $changed_by:expr, $before:expr, $after:expr) => {
+        sqlx::query(
             r#"
-            SELECT id, region_code, fish_species, min_size_cm, max_catch_per_day,
-                   season_start, season_end, restrictions
-            FROM regulations
-            WHERE region_code = ?
+            INSERT INTO change_history
+                (id, entity_type, entity_id, action, changed_by, changed_at, before_data, after_data)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             "#,
         )
-        .bind(country_code)
-        .fetch_all(pool)
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind($entity_type)
+        .bind($entity_id)
+        .bind($action.as_str())
+        .bind($changed_by)
+        .bind(chrono::Utc::now())
+        .bind($before.map(serde_json::to_string).transpose()?)
+        .bind($after.map(serde_json::to_string).transpose()?)
+        .execute(&mut *$tx)
         .await?
     };
-
-    Ok(rows)
 }
 
-/// Get localized fish list for a country and language.
-pub async fn get_fish_species(
-    pool: &SqlitePool,
-    _country_code: &str,
-    _language: &str,
-) -> anyhow::Result<Vec<FishItemDb>> {
-    tracing::debug!("Querying fish_species table");
-    
-    // Query fish_species table directly
-    let rows = sqlx::query_as::<_, FishItemDb>(
-        r#"
-        SELECT id, name_uk as name, scientific_name
-        FROM fish_species
-        ORDER BY name_uk
-        "#,
-    )
-    .fetch_all(pool)
-    .await?;
-
-    tracing::debug!("Found {} fish species", rows.len());
-    
-    Ok(rows)
+/// Implement [`Repository`](crate::db::Repository) for a repository type
+/// with a `pool` field, using the shared queries.
+macro_rules! impl_repository {
+    ($repo:ty, $backend:literal) => {
+        impl $repo {
+            /// Read `schema_version`, creating it on first use.
+            async fn applied_migrations(
+                &self,
+            ) -> anyhow::Result<Vec<$crate::db::migrations::AppliedMigration>> {
+                sqlx::query($crate::db::migrations::schema_version_ddl($backend))
+                    .execute(&self.pool)
+                    .await?;
+
+                let rows = sqlx::query_as::<_, $crate::db::migrations::AppliedMigration>(
+                    "SELECT version, name, checksum, applied_at FROM schema_version ORDER BY version",
+                )
+                .fetch_all(&self.pool)
+                .await?;
+
+                Ok(rows)
+            }
+
+            /// Insert the reference names and regions into empty tables,
+            /// skipping species an admin has deleted.
+            async fn seed_fish_names_and_regions(&self) -> anyhow::Result<()> {
+                let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM fish_names")
+                    .fetch_one(&self.pool)
+                    .await?;
+                if count == 0 {
+                    let primary = $crate::db::FISH_NAMES_SEED.iter().flat_map(|(id, pl, de, fr)| {
+                        [(*id, "pl", *pl, true), (*id, "de", *de, true), (*id, "fr", *fr, true)]
+                    });
+                    let synonyms = $crate::db::FISH_SYNONYMS_SEED
+                        .iter()
+                        .map(|(id, language, name)| (*id, *language, *name, false));
+                    for (fish_id, language, name, is_primary) in primary.chain(synonyms) {
+                        sqlx::query(
+                            r#"
+                            INSERT INTO fish_names (id, fish_id, language, common_name, is_primary)
+                            SELECT $1, $2, $3, $4, $5 WHERE EXISTS (SELECT 1 FROM fish_species WHERE id = $2)
+                            ON CONFLICT DO NOTHING
+                            "#,
+                        )
+                        .bind(uuid::Uuid::new_v4().to_string())
+                        .bind(fish_id)
+                        .bind(language)
+                        .bind(name)
+                        .bind(is_primary)
+                        .execute(&self.pool)
+                        .await?;
+                    }
+                }
+
+                let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM fish_regions")
+                    .fetch_one(&self.pool)
+                    .await?;
+                if count == 0 {
+                    for (fish_id, common, rare) in $crate::db::FISH_REGIONS_SEED {
+                        let countries = common
+                            .iter()
+                            .map(|country| (*country, "common"))
+                            .chain(rare.iter().map(|country| (*country, "rare")));
+                        for (country_code, abundance) in countries {
+                            sqlx::query(
+                                r#"
+                                INSERT INTO fish_regions (id, fish_id, country_code, abundance)
+                                SELECT $1, $2, $3, $4 WHERE EXISTS (SELECT 1 FROM fish_species WHERE id = $2)
+                                ON CONFLICT DO NOTHING
+                                "#,
+                            )
+                            .bind(uuid::Uuid::new_v4().to_string())
+                            .bind(fish_id)
+                            .bind(country_code)
+                            .bind(abundance)
+                            .execute(&self.pool)
+                            .await?;
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl $crate::db::Repository for $repo {
+            fn backend(&self) -> &'static str {
+                $backend
+            }
+
+            async fn migration_status(
+                &self,
+            ) -> anyhow::Result<Vec<$crate::db::migrations::MigrationStatus>> {
+                let applied = self.applied_migrations().await?;
+                Ok($crate::db::migrations::reconcile(
+                    $crate::db::migrations::for_backend($backend),
+                    &applied,
+                ))
+            }
+
+            async fn migrate_up(&self) -> anyhow::Result<Vec<$crate::db::migrations::Migration>> {
+                let statuses = self.migration_status().await?;
+                $crate::db::migrations::ensure_consistent(&statuses)?;
+
+                let mut applied = Vec::new();
+                for migration in $crate::db::migrations::for_backend($backend) {
+                    let pending = statuses.iter().any(|s| {
+                        s.version == migration.version
+                            && s.state == $crate::db::migrations::MigrationState::Pending
+                    });
+                    if !pending {
+                        continue;
+                    }
+
+                    let mut tx = self.pool.begin().await?;
+                    sqlx::Executor::execute(&mut *tx, migration.up)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("migration {} failed: {}", migration.name, e))?;
+                    sqlx::query(
+                        "INSERT INTO schema_version (version, name, checksum, applied_at) VALUES ($1, $2, $3, $4)",
+                    )
+                    .bind(migration.version)
+                    .bind(migration.name)
+                    .bind(migration.checksum())
+                    .bind(chrono::Utc::now())
+                    .execute(&mut *tx)
+                    .await?;
+                    tx.commit().await?;
+
+                    tracing::info!("Applied migration {}", migration.name);
+                    applied.push(*migration);
+                }
+
+                Ok(applied)
+            }
+
+            async fn migrate_down(
+                &self,
+                steps: usize,
+            ) -> anyhow::Result<Vec<$crate::db::migrations::Migration>> {
+                let statuses = self.migration_status().await?;
+                $crate::db::migrations::ensure_consistent(&statuses)?;
+
+                let mut reverted = Vec::new();
+                let applied = statuses
+                    .iter()
+                    .rev()
+                    .filter(|s| s.state == $crate::db::migrations::MigrationState::Applied)
+                    .take(steps);
+                for status in applied {
+                    let Some(migration) = $crate::db::migrations::for_backend($backend)
+                        .iter()
+                        .find(|m| m.version == status.version)
+                    else {
+                        continue;
+                    };
+
+                    let mut tx = self.pool.begin().await?;
+                    sqlx::Executor::execute(&mut *tx, migration.down)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("reverting {} failed: {}", migration.name, e))?;
+                    sqlx::query("DELETE FROM schema_version WHERE version = $1")
+                        .bind(migration.version)
+                        .execute(&mut *tx)
+                        .await?;
+                    tx.commit().await?;
+
+                    tracing::info!("Reverted migration {}", migration.name);
+                    reverted.push(*migration);
+                }
+
+                Ok(reverted)
+            }
+
+            async fn seed_fish_species(&self) -> anyhow::Result<()> {
+                let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM fish_species")
+                    .fetch_one(&self.pool)
+                    .await?;
+                if count > 0 {
+                    return self.seed_fish_names_and_regions().await;
+                }
+
+                for (id, name_uk, name_en, scientific_name, best_season, preferred_bait, min_temp, max_temp) in
+                    $crate::db::FISH_SPECIES_SEED
+                {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO fish_species (id, name_uk, name_en, scientific_name, best_season, preferred_bait, min_temp, max_temp)
+                        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                        ON CONFLICT (id) DO NOTHING
+                        "#,
+                    )
+                    .bind(id)
+                    .bind(name_uk)
+                    .bind(name_en)
+                    .bind(scientific_name)
+                    .bind(best_season)
+                    .bind(preferred_bait)
+                    .bind(min_temp)
+                    .bind(max_temp)
+                    .execute(&self.pool)
+                    .await?;
+                }
+
+                self.seed_fish_names_and_regions().await
+            }
+
+            async fn insert_catch(
+                &self,
+                record: &fishing_shared::types::NewCatchRecord,
+                environment: Option<&fishing_shared::types::EnvironmentSnapshot>,
+            ) -> anyhow::Result<Option<$crate::models::CatchRecordDb>> {
+                let sql = format!(
+                    r#"
+                    INSERT INTO catches (
+                        id, user_id, location_lat, location_lon, caught_at,
+                        fish_species, weight_kg, length_cm, bait_used, bite_intensity,
+                        weather_temp, weather_pressure, moon_phase, notes, photo_url,
+                        weather_time, pressure_change_3h_hpa, pressure_trend, wind_speed_ms, wind_gust_ms,
+                        wind_direction_deg, precipitation_mm, moon_illumination, sun_elevation_deg, sun_azimuth_deg,
+                        privacy, created_at
+                    ) VALUES (
+                        $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
+                        $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27
+                    )
+                    ON CONFLICT (id) DO NOTHING
+                    RETURNING {}
+                    "#,
+                    $crate::db::queries::CATCH_COLUMNS
+                );
+
+                let row = sqlx::query_as::<_, $crate::models::CatchRecordDb>(&sql)
+                    .bind(record.id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string()))
+                    // Anonymous catches have no owner
+                    .bind(Some(record.user_id.as_str()).filter(|id| !id.is_empty()))
+                    .bind(record.lat)
+                    .bind(record.lon)
+                    .bind(record.caught_at)
+                    .bind(&record.fish_species)
+                    .bind(record.weight_kg)
+                    .bind(record.length_cm)
+                    .bind(&record.bait_used)
+                    .bind(record.bite_intensity.map(i32::from))
+                    .bind(record.weather_temp)
+                    .bind(record.weather_pressure)
+                    .bind(record.moon_phase)
+                    .bind(&record.notes)
+                    .bind(&record.photo_url)
+                    .bind(environment.and_then(|e| e.weather_time))
+                    .bind(environment.and_then(|e| e.pressure_change_3h_hpa))
+                    .bind(environment.and_then(|e| e.pressure_trend).map(|t| t.as_str()))
+                    .bind(environment.and_then(|e| e.wind_speed_ms))
+                    .bind(environment.and_then(|e| e.wind_gust_ms))
+                    .bind(environment.and_then(|e| e.wind_direction_deg))
+                    .bind(environment.and_then(|e| e.precipitation_mm))
+                    .bind(environment.map(|e| e.moon_illumination))
+                    .bind(environment.map(|e| e.sun_elevation_deg))
+                    .bind(environment.map(|e| e.sun_azimuth_deg))
+                    .bind(record.privacy.unwrap_or_default().as_str())
+                    .bind(chrono::Utc::now())
+                    // Step to completion: SQLite only commits an INSERT ... RETURNING
+                    // once the statement finishes, which fetch_one does not wait for.
+                    .fetch_all(&self.pool)
+                    .await?
+                    .pop();
+
+                Ok(row)
+            }
+
+            async fn get_catch(
+                &self,
+                id: &str,
+            ) -> anyhow::Result<Option<$crate::models::CatchRecordDb>> {
+                let sql = format!(
+                    "SELECT {} FROM catches WHERE id = $1",
+                    $crate::db::queries::CATCH_COLUMNS
+                );
+
+                let row = sqlx::query_as::<_, $crate::models::CatchRecordDb>(&sql)
+                    .bind(id)
+                    .fetch_optional(&self.pool)
+                    .await?;
+
+                Ok(row)
+            }
+
+            async fn get_catches_by_user(
+                &self,
+                user_id: &str,
+                limit: i64,
+                offset: i64,
+            ) -> anyhow::Result<Vec<$crate::models::CatchRecordDb>> {
+                let sql = format!(
+                    r#"
+                    SELECT {}
+                    FROM catches
+                    WHERE user_id = $1
+                    ORDER BY caught_at DESC
+                    LIMIT $2 OFFSET $3
+                    "#,
+                    $crate::db::queries::CATCH_COLUMNS
+                );
+
+                let rows = sqlx::query_as::<_, $crate::models::CatchRecordDb>(&sql)
+                    .bind(user_id)
+                    .bind(limit)
+                    .bind(offset)
+                    .fetch_all(&self.pool)
+                    .await?;
+
+                Ok(rows)
+            }
+
+            async fn get_nearby_catches(
+                &self,
+                lat: f64,
+                lon: f64,
+                radius_km: f64,
+            ) -> anyhow::Result<Vec<$crate::models::CatchRecordDb>> {
+                let (lat_delta, lon_delta) = $crate::db::radius_deltas(lat, radius_km);
+
+                let sql = format!(
+                    r#"
+                    SELECT {}
+                    FROM catches
+                    WHERE location_lat BETWEEN $1 AND $2
+                      AND location_lon BETWEEN $3 AND $4
+                      AND privacy = 'public'
+                    ORDER BY caught_at DESC
+                    LIMIT 100
+                    "#,
+                    $crate::db::queries::CATCH_COLUMNS
+                );
+
+                let rows = sqlx::query_as::<_, $crate::models::CatchRecordDb>(&sql)
+                    .bind(lat - lat_delta)
+                    .bind(lat + lat_delta)
+                    .bind(lon - lon_delta)
+                    .bind(lon + lon_delta)
+                    .fetch_all(&self.pool)
+                    .await?;
+
+                Ok(rows)
+            }
+
+            async fn get_nearby_water_bodies(
+                &self,
+                lat: f64,
+                lon: f64,
+                radius_km: f64,
+            ) -> anyhow::Result<Vec<$crate::models::WaterBodyDb>> {
+                let (lat_delta, lon_delta) = $crate::db::radius_deltas(lat, radius_km);
+
+                let sql = format!(
+                    r#"
+                    SELECT {}
+                    FROM water_bodies
+                    WHERE location_lat BETWEEN $1 AND $2
+                      AND location_lon BETWEEN $3 AND $4
+                    ORDER BY
+                        (location_lat - $5) * (location_lat - $5) +
+                        (location_lon - $6) * (location_lon - $6) ASC
+                    LIMIT 200
+                    "#,
+                    $crate::db::queries::WATER_BODY_COLUMNS
+                );
+                let rows = sqlx::query_as::<_, $crate::models::WaterBodyDb>(&sql)
+                .bind(lat - lat_delta)
+                .bind(lat + lat_delta)
+                .bind(lon - lon_delta)
+                .bind(lon + lon_delta)
+                .bind(lat)
+                .bind(lon)
+                .fetch_all(&self.pool)
+                .await?;
+
+                Ok(rows)
+            }
+
+            async fn get_regulations(
+                &self,
+                country_code: &str,
+                _fish_species_id: Option<uuid::Uuid>,
+            ) -> anyhow::Result<Vec<$crate::models::RegulationDb>> {
+                let sql = format!(
+                    "SELECT {} FROM regulations WHERE region_code = $1",
+                    $crate::db::queries::REGULATION_COLUMNS
+                );
+                let rows = sqlx::query_as::<_, $crate::models::RegulationDb>(&sql)
+                .bind(country_code)
+                .fetch_all(&self.pool)
+                .await?;
+
+                Ok(rows)
+            }
+
+            async fn get_fish_species(
+                &self,
+                country_code: Option<&str>,
+                language: &str,
+                search: Option<&str>,
+            ) -> anyhow::Result<Vec<$crate::models::FishItemDb>> {
+                tracing::debug!("Querying fish_species table");
+
+                let mut rows = sqlx::query_as::<_, $crate::models::FishItemDb>(
+                    r#"
+                    SELECT s.id,
+                        COALESCE(
+                            (SELECT n.common_name FROM fish_names n
+                             WHERE n.fish_id = s.id AND n.language = $1 AND n.is_primary
+                             ORDER BY n.common_name LIMIT 1),
+                            CASE WHEN $1 = 'uk' THEN s.name_uk END,
+                            s.name_en,
+                            s.name_uk
+                        ) AS name,
+                        s.scientific_name
+                    FROM fish_species s
+                    WHERE $2 IS NULL
+                        OR NOT EXISTS (SELECT 1 FROM fish_regions r WHERE r.fish_id = s.id)
+                        OR EXISTS (SELECT 1 FROM fish_regions r WHERE r.fish_id = s.id AND r.country_code = $2)
+                    ORDER BY name
+                    "#,
+                )
+                .bind(language)
+                .bind(country_code)
+                .fetch_all(&self.pool)
+                .await?;
+
+                if let Some(search) = search {
+                    // Matched here rather than with LIKE: SQLite only folds ASCII case
+                    let names: Vec<(String, String)> = sqlx::query_as(
+                        r#"
+                        SELECT fish_id, common_name FROM fish_names
+                        UNION ALL SELECT id, name_uk FROM fish_species
+                        UNION ALL SELECT id, name_en FROM fish_species WHERE name_en IS NOT NULL
+                        UNION ALL SELECT id, scientific_name FROM fish_species WHERE scientific_name IS NOT NULL
+                        "#,
+                    )
+                    .fetch_all(&self.pool)
+                    .await?;
+                    let matching: std::collections::HashSet<String> = names
+                        .into_iter()
+                        .filter(|(_, name)| $crate::db::name_matches(name, search))
+                        .map(|(id, _)| id)
+                        .collect();
+                    rows.retain(|row| matching.contains(&row.id));
+                }
+
+                tracing::debug!("Found {} fish species", rows.len());
+
+                Ok(rows)
+            }
+
+            async fn find_user_by_email(
+                &self,
+                email: &str,
+            ) -> anyhow::Result<Option<$crate::models::UserDb>> {
+                let sql = format!(
+                    "SELECT {} FROM users WHERE LOWER(email) = LOWER($1)",
+                    $crate::db::queries::USER_COLUMNS
+                );
+                let user = sqlx::query_as::<_, $crate::models::UserDb>(&sql)
+                .bind(email)
+                .fetch_optional(&self.pool)
+                .await?;
+
+                Ok(user)
+            }
+
+            async fn find_user_by_id(
+                &self,
+                id: &str,
+            ) -> anyhow::Result<Option<$crate::models::UserDb>> {
+                let sql = format!(
+                    "SELECT {} FROM users WHERE id = $1",
+                    $crate::db::queries::USER_COLUMNS
+                );
+                let user = sqlx::query_as::<_, $crate::models::UserDb>(&sql)
+                .bind(id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+                Ok(user)
+            }
+
+            async fn create_user(&self, user: &$crate::models::NewUser) -> anyhow::Result<()> {
+                sqlx::query(
+                    "INSERT INTO users (id, email, password_hash, country_code, language, created_at) VALUES ($1, $2, $3, $4, $5, $6)",
+                )
+                .bind(&user.id)
+                .bind(&user.email)
+                .bind(&user.password_hash)
+                .bind(&user.country_code)
+                .bind(&user.language)
+                .bind(chrono::Utc::now())
+                .execute(&self.pool)
+                .await?;
+
+                Ok(())
+            }
+
+            async fn create_session(
+                &self,
+                session: &$crate::models::NewSession,
+                refresh_token_hash: &str,
+            ) -> anyhow::Result<()> {
+                let now = chrono::Utc::now();
+                let mut tx = self.pool.begin().await?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO sessions (id, user_id, user_agent, ip_address, created_at, last_used_at, expires_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
+                    "#,
+                )
+                .bind(&session.id)
+                .bind(&session.user_id)
+                .bind(&session.user_agent)
+                .bind(&session.ip_address)
+                .bind(now)
+                .bind(now)
+                .bind(session.expires_at)
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query("INSERT INTO refresh_tokens (token_hash, session_id, created_at) VALUES ($1, $2, $3)")
+                    .bind(refresh_token_hash)
+                    .bind(&session.id)
+                    .bind(now)
+                    .execute(&mut *tx)
+                    .await?;
+
+                tx.commit().await?;
+                Ok(())
+            }
+
+            async fn find_refresh_token(
+                &self,
+                token_hash: &str,
+            ) -> anyhow::Result<Option<$crate::models::RefreshTokenDb>> {
+                let token = sqlx::query_as::<_, $crate::models::RefreshTokenDb>(
+                    r#"
+                    SELECT t.token_hash, t.session_id, t.used_at, s.user_id, s.expires_at, s.revoked_at
+                    FROM refresh_tokens t
+                    JOIN sessions s ON s.id = t.session_id
+                    WHERE t.token_hash = $1
+                    "#,
+                )
+                .bind(token_hash)
+                .fetch_optional(&self.pool)
+                .await?;
+
+                Ok(token)
+            }
+
+            async fn rotate_refresh_token(&self, old_hash: &str, new_hash: &str) -> anyhow::Result<bool> {
+                let now = chrono::Utc::now();
+                let mut tx = self.pool.begin().await?;
+
+                let marked = sqlx::query(
+                    "UPDATE refresh_tokens SET used_at = $1 WHERE token_hash = $2 AND used_at IS NULL",
+                )
+                .bind(now)
+                .bind(old_hash)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+                if marked != 1 {
+                    return Ok(false);
+                }
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO refresh_tokens (token_hash, session_id, created_at)
+                    SELECT $1, session_id, $2 FROM refresh_tokens WHERE token_hash = $3
+                    "#,
+                )
+                .bind(new_hash)
+                .bind(now)
+                .bind(old_hash)
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query(
+                    "UPDATE sessions SET last_used_at = $1 WHERE id = (SELECT session_id FROM refresh_tokens WHERE token_hash = $2)",
+                )
+                .bind(now)
+                .bind(old_hash)
+                .execute(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+                Ok(true)
+            }
+
+            async fn list_sessions(
+                &self,
+                user_id: &str,
+            ) -> anyhow::Result<Vec<$crate::models::SessionDb>> {
+                let sessions = sqlx::query_as::<_, $crate::models::SessionDb>(
+                    r#"
+                    SELECT id, user_id, user_agent, ip_address, created_at, last_used_at, expires_at, revoked_at
+                    FROM sessions
+                    WHERE user_id = $1 AND revoked_at IS NULL
+                    ORDER BY created_at DESC
+                    "#,
+                )
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await?;
+
+                Ok(sessions)
+            }
+
+            async fn revoke_session(&self, user_id: &str, session_id: &str) -> anyhow::Result<bool> {
+                let revoked = sqlx::query(
+                    "UPDATE sessions SET revoked_at = $1 WHERE id = $2 AND user_id = $3 AND revoked_at IS NULL",
+                )
+                .bind(chrono::Utc::now())
+                .bind(session_id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+
+                Ok(revoked == 1)
+            }
+
+            async fn create_email_token(
+                &self,
+                token: &$crate::models::NewEmailToken,
+            ) -> anyhow::Result<()> {
+                let now = chrono::Utc::now();
+                let mut tx = self.pool.begin().await?;
+
+                sqlx::query(
+                    "UPDATE email_tokens SET used_at = $1 WHERE user_id = $2 AND purpose = $3 AND used_at IS NULL",
+                )
+                .bind(now)
+                .bind(&token.user_id)
+                .bind(token.purpose.as_str())
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO email_tokens (token_hash, user_id, purpose, created_at, expires_at)
+                    VALUES ($1, $2, $3, $4, $5)
+                    "#,
+                )
+                .bind(&token.token_hash)
+                .bind(&token.user_id)
+                .bind(token.purpose.as_str())
+                .bind(now)
+                .bind(token.expires_at)
+                .execute(&mut *tx)
+                .await?;
+
+                tx.commit().await?;
+                Ok(())
+            }
+
+            async fn consume_email_token(
+                &self,
+                token_hash: &str,
+                purpose: $crate::models::EmailTokenPurpose,
+            ) -> anyhow::Result<Option<String>> {
+                // Single statement, so two confirmations of one token cannot
+                // both succeed. `fetch_all` steps the statement to completion
+                // on SQLite (see `insert_catch`).
+                let mut rows: Vec<(String,)> = sqlx::query_as(
+                    r#"
+                    UPDATE email_tokens SET used_at = $1
+                    WHERE token_hash = $2 AND purpose = $3 AND used_at IS NULL AND expires_at > $1
+                    RETURNING user_id
+                    "#,
+                )
+                .bind(chrono::Utc::now())
+                .bind(token_hash)
+                .bind(purpose.as_str())
+                .fetch_all(&self.pool)
+                .await?;
+
+                Ok(rows.pop().map(|(user_id,)| user_id))
+            }
+
+            async fn mark_email_verified(&self, user_id: &str) -> anyhow::Result<()> {
+                sqlx::query(
+                    "UPDATE users SET email_verified_at = $1 WHERE id = $2 AND email_verified_at IS NULL",
+                )
+                .bind(chrono::Utc::now())
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+
+                Ok(())
+            }
+
+            async fn reset_password(&self, user_id: &str, password_hash: &str) -> anyhow::Result<()> {
+                let mut tx = self.pool.begin().await?;
+
+                sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2")
+                    .bind(password_hash)
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                sqlx::query("UPDATE sessions SET revoked_at = $1 WHERE user_id = $2 AND revoked_at IS NULL")
+                    .bind(chrono::Utc::now())
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                tx.commit().await?;
+                Ok(())
+            }
+
+            async fn update_user_preferences(
+                &self,
+                user_id: &str,
+                preferences: &$crate::models::UserPreferencesDb,
+            ) -> anyhow::Result<bool> {
+                let updated = sqlx::query(
+                    r#"
+                    UPDATE users SET
+                        country_code = $1, language = $2, length_unit = $3, weight_unit = $4,
+                        temperature_unit = $5, pressure_unit = $6, wind_speed_unit = $7,
+                        home_region = $8, favorite_species = $9, default_privacy = $10
+                    WHERE id = $11
+                    "#,
+                )
+                .bind(&preferences.country_code)
+                .bind(&preferences.language)
+                .bind(&preferences.length_unit)
+                .bind(&preferences.weight_unit)
+                .bind(&preferences.temperature_unit)
+                .bind(&preferences.pressure_unit)
+                .bind(&preferences.wind_speed_unit)
+                .bind(&preferences.home_region)
+                .bind(&preferences.favorite_species)
+                .bind(&preferences.default_privacy)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+
+                Ok(updated == 1)
+            }
+
+            async fn create_oidc_login(&self, login: &$crate::models::OidcLoginDb) -> anyhow::Result<()> {
+                let now = chrono::Utc::now();
+
+                sqlx::query("DELETE FROM oidc_logins WHERE expires_at < $1")
+                    .bind(now)
+                    .execute(&self.pool)
+                    .await?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO oidc_logins (state, provider, code_verifier, nonce, redirect_uri, created_at, expires_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
+                    "#,
+                )
+                .bind(&login.state)
+                .bind(&login.provider)
+                .bind(&login.code_verifier)
+                .bind(&login.nonce)
+                .bind(&login.redirect_uri)
+                .bind(now)
+                .bind(login.expires_at)
+                .execute(&self.pool)
+                .await?;
+
+                Ok(())
+            }
+
+            async fn take_oidc_login(
+                &self,
+                state: &str,
+            ) -> anyhow::Result<Option<$crate::models::OidcLoginDb>> {
+                // `fetch_all` steps the statement to completion on SQLite
+                // (see `insert_catch`).
+                let mut rows = sqlx::query_as::<_, $crate::models::OidcLoginDb>(
+                    r#"
+                    DELETE FROM oidc_logins WHERE state = $1
+                    RETURNING state, provider, code_verifier, nonce, redirect_uri, expires_at
+                    "#,
+                )
+                .bind(state)
+                .fetch_all(&self.pool)
+                .await?;
+
+                Ok(rows.pop())
+            }
+
+            async fn find_user_identity(
+                &self,
+                provider: &str,
+                subject: &str,
+            ) -> anyhow::Result<Option<$crate::models::UserIdentityDb>> {
+                let identity = sqlx::query_as::<_, $crate::models::UserIdentityDb>(
+                    r#"
+                    SELECT provider, subject, user_id, email, created_at
+                    FROM user_identities
+                    WHERE provider = $1 AND subject = $2
+                    "#,
+                )
+                .bind(provider)
+                .bind(subject)
+                .fetch_optional(&self.pool)
+                .await?;
+
+                Ok(identity)
+            }
+
+            async fn link_user_identity(
+                &self,
+                identity: &$crate::models::NewUserIdentity,
+            ) -> anyhow::Result<()> {
+                sqlx::query(
+                    r#"
+                    INSERT INTO user_identities (provider, subject, user_id, email, created_at)
+                    VALUES ($1, $2, $3, $4, $5)
+                    "#,
+                )
+                .bind(&identity.provider)
+                .bind(&identity.subject)
+                .bind(&identity.user_id)
+                .bind(&identity.email)
+                .bind(chrono::Utc::now())
+                .execute(&self.pool)
+                .await?;
+
+                Ok(())
+            }
+
+            async fn list_user_identities(
+                &self,
+                user_id: &str,
+            ) -> anyhow::Result<Vec<$crate::models::UserIdentityDb>> {
+                let identities = sqlx::query_as::<_, $crate::models::UserIdentityDb>(
+                    r#"
+                    SELECT provider, subject, user_id, email, created_at
+                    FROM user_identities
+                    WHERE user_id = $1
+                    ORDER BY created_at
+                    "#,
+                )
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await?;
+
+                Ok(identities)
+            }
+
+            async fn set_user_role(&self, email: &str, role: $crate::models::Role) -> anyhow::Result<bool> {
+                let updated = sqlx::query("UPDATE users SET role = $1 WHERE LOWER(email) = LOWER($2)")
+                    .bind(role.as_str())
+                    .bind(email)
+                    .execute(&self.pool)
+                    .await?
+                    .rows_affected();
+
+                Ok(updated == 1)
+            }
+
+            async fn list_fish_species_records(&self) -> anyhow::Result<Vec<$crate::models::FishSpeciesDb>> {
+                let sql = format!(
+                    "SELECT {} FROM fish_species ORDER BY id",
+                    $crate::db::queries::FISH_SPECIES_COLUMNS
+                );
+                let rows = sqlx::query_as::<_, $crate::models::FishSpeciesDb>(&sql)
+                    .fetch_all(&self.pool)
+                    .await?;
+
+                Ok(rows)
+            }
+
+            async fn fish_species_exists(&self, id: &str) -> anyhow::Result<bool> {
+                let row: Option<(String,)> = sqlx::query_as("SELECT id FROM fish_species WHERE id = $1")
+                    .bind(id)
+                    .fetch_optional(&self.pool)
+                    .await?;
+
+                Ok(row.is_some())
+            }
+
+            async fn fish_species_name(&self, id: &str, language: &str) -> anyhow::Result<Option<String>> {
+                let row: Option<(String,)> = sqlx::query_as(
+                    r#"
+                    SELECT COALESCE(
+                            (SELECT n.common_name FROM fish_names n
+                             WHERE n.fish_id = s.id AND n.language = $2 AND n.is_primary
+                             ORDER BY n.common_name LIMIT 1),
+                            CASE WHEN $2 = 'uk' THEN s.name_uk END,
+                            s.name_en,
+                            s.name_uk
+                        )
+                    FROM fish_species s
+                    WHERE s.id = $1
+                    "#,
+                )
+                .bind(id)
+                .bind(language)
+                .fetch_optional(&self.pool)
+                .await?;
+
+                Ok(row.map(|(name,)| name))
+            }
+
+            async fn create_fish_species(
+                &self,
+                species: &$crate::models::FishSpeciesDb,
+                changed_by: &str,
+            ) -> anyhow::Result<bool> {
+                let mut tx = self.pool.begin().await?;
+
+                let inserted = sqlx::query(
+                    r#"
+                    INSERT INTO fish_species (id, name_uk, name_en, scientific_name, best_season, preferred_bait, min_temp, max_temp, created_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                    ON CONFLICT (id) DO NOTHING
+                    "#,
+                )
+                .bind(&species.id)
+                .bind(&species.name_uk)
+                .bind(&species.name_en)
+                .bind(&species.scientific_name)
+                .bind(&species.best_season)
+                .bind(&species.preferred_bait)
+                .bind(species.min_temp)
+                .bind(species.max_temp)
+                .bind(chrono::Utc::now())
+                .execute(&mut *tx)
+                .await?
+                .rows_affected()
+                    == 1;
+
+                if inserted {
+                    $crate::db::queries::record_change!(
+                        tx,
+                        "fish_species",
+                        &species.id,
+                        $crate::models::ChangeAction::Create,
+                        changed_by,
+                        None::<&$crate::models::FishSpeciesDb>,
+                        Some(species)
+                    );
+                }
+
+                tx.commit().await?;
+                Ok(inserted)
+            }
+
+            async fn update_fish_species(
+                &self,
+                species: &$crate::models::FishSpeciesDb,
+                changed_by: &str,
+            ) -> anyhow::Result<bool> {
+                let mut tx = self.pool.begin().await?;
+
+                let sql = format!("SELECT {} FROM fish_species WHERE id = $1", $crate::db::queries::FISH_SPECIES_COLUMNS);
+                let Some(before) = sqlx::query_as::<_, $crate::models::FishSpeciesDb>(&sql)
+                    .bind(&species.id)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                else {
+                    return Ok(false);
+                };
+
+                sqlx::query(
+                    r#"
+                    UPDATE fish_species SET
+                        name_uk = $1, name_en = $2, scientific_name = $3, best_season = $4, preferred_bait = $5, min_temp = $6, max_temp = $7
+                    WHERE id = $8
+                    "#,
+                )
+                .bind(&species.name_uk)
+                .bind(&species.name_en)
+                .bind(&species.scientific_name)
+                .bind(&species.best_season)
+                .bind(&species.preferred_bait)
+                .bind(species.min_temp)
+                .bind(species.max_temp)
+                .bind(&species.id)
+                .execute(&mut *tx)
+                .await?;
+
+                $crate::db::queries::record_change!(
+                    tx,
+                    "fish_species",
+                    &species.id,
+                    $crate::models::ChangeAction::Update,
+                    changed_by,
+                    Some(&before),
+                    Some(species)
+                );
+
+                tx.commit().await?;
+                Ok(true)
+            }
+
+            async fn delete_fish_species(&self, id: &str, changed_by: &str) -> anyhow::Result<bool> {
+                let mut tx = self.pool.begin().await?;
+
+                let sql = format!("SELECT {} FROM fish_species WHERE id = $1", $crate::db::queries::FISH_SPECIES_COLUMNS);
+                let Some(before) = sqlx::query_as::<_, $crate::models::FishSpeciesDb>(&sql)
+                    .bind(id)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                else {
+                    return Ok(false);
+                };
+
+                sqlx::query("DELETE FROM fish_species WHERE id = $1")
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                $crate::db::queries::record_change!(
+                    tx,
+                    "fish_species",
+                    id,
+                    $crate::models::ChangeAction::Delete,
+                    changed_by,
+                    Some(&before),
+                    None::<&$crate::models::FishSpeciesDb>
+                );
+
+                tx.commit().await?;
+                Ok(true)
+            }
+
+            async fn list_regulations(&self) -> anyhow::Result<Vec<$crate::models::RegulationDb>> {
+                let sql = format!(
+                    "SELECT {} FROM regulations ORDER BY region_code, fish_species, id",
+                    $crate::db::queries::REGULATION_COLUMNS
+                );
+                let rows = sqlx::query_as::<_, $crate::models::RegulationDb>(&sql)
+                    .fetch_all(&self.pool)
+                    .await?;
+
+                Ok(rows)
+            }
+
+            async fn create_regulation(
+                &self,
+                regulation: &$crate::models::RegulationDb,
+                changed_by: &str,
+            ) -> anyhow::Result<bool> {
+                let mut tx = self.pool.begin().await?;
+
+                let inserted = sqlx::query(
+                    r#"
+                    INSERT INTO regulations (id, region_code, fish_species, min_size_cm, max_catch_per_day, season_start, season_end, restrictions, created_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                    ON CONFLICT (id) DO NOTHING
+                    "#,
+                )
+                .bind(&regulation.id)
+                .bind(&regulation.region_code)
+                .bind(&regulation.fish_species)
+                .bind(regulation.min_size_cm)
+                .bind(regulation.max_catch_per_day)
+                .bind(&regulation.season_start)
+                .bind(&regulation.season_end)
+                .bind(&regulation.restrictions)
+                .bind(chrono::Utc::now())
+                .execute(&mut *tx)
+                .await?
+                .rows_affected()
+                    == 1;
+
+                if inserted {
+                    $crate::db::queries::record_change!(
+                        tx,
+                        "regulations",
+                        &regulation.id,
+                        $crate::models::ChangeAction::Create,
+                        changed_by,
+                        None::<&$crate::models::RegulationDb>,
+                        Some(regulation)
+                    );
+                }
+
+                tx.commit().await?;
+                Ok(inserted)
+            }
+
+            async fn update_regulation(
+                &self,
+                regulation: &$crate::models::RegulationDb,
+                changed_by: &str,
+            ) -> anyhow::Result<bool> {
+                let mut tx = self.pool.begin().await?;
+
+                let sql = format!("SELECT {} FROM regulations WHERE id = $1", $crate::db::queries::REGULATION_COLUMNS);
+                let Some(before) = sqlx::query_as::<_, $crate::models::RegulationDb>(&sql)
+                    .bind(&regulation.id)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                else {
+                    return Ok(false);
+                };
+
+                sqlx::query(
+                    r#"
+                    UPDATE regulations SET
+                        region_code = $1, fish_species = $2, min_size_cm = $3, max_catch_per_day = $4, season_start = $5, season_end = $6, restrictions = $7
+                    WHERE id = $8
+                    "#,
+                )
+                .bind(&regulation.region_code)
+                .bind(&regulation.fish_species)
+                .bind(regulation.min_size_cm)
+                .bind(regulation.max_catch_per_day)
+                .bind(&regulation.season_start)
+                .bind(&regulation.season_end)
+                .bind(&regulation.restrictions)
+                .bind(&regulation.id)
+                .execute(&mut *tx)
+                .await?;
+
+                $crate::db::queries::record_change!(
+                    tx,
+                    "regulations",
+                    &regulation.id,
+                    $crate::models::ChangeAction::Update,
+                    changed_by,
+                    Some(&before),
+                    Some(regulation)
+                );
+
+                tx.commit().await?;
+                Ok(true)
+            }
+
+            async fn delete_regulation(&self, id: &str, changed_by: &str) -> anyhow::Result<bool> {
+                let mut tx = self.pool.begin().await?;
+
+                let sql = format!("SELECT {} FROM regulations WHERE id = $1", $crate::db::queries::REGULATION_COLUMNS);
+                let Some(before) = sqlx::query_as::<_, $crate::models::RegulationDb>(&sql)
+                    .bind(id)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                else {
+                    return Ok(false);
+                };
+
+                sqlx::query("DELETE FROM regulations WHERE id = $1")
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                $crate::db::queries::record_change!(
+                    tx,
+                    "regulations",
+                    id,
+                    $crate::models::ChangeAction::Delete,
+                    changed_by,
+                    Some(&before),
+                    None::<&$crate::models::RegulationDb>
+                );
+
+                tx.commit().await?;
+                Ok(true)
+            }
+
+            async fn list_water_bodies(&self) -> anyhow::Result<Vec<$crate::models::WaterBodyDb>> {
+                let sql = format!(
+                    "SELECT {} FROM water_bodies ORDER BY name, id",
+                    $crate::db::queries::WATER_BODY_COLUMNS
+                );
+                let rows = sqlx::query_as::<_, $crate::models::WaterBodyDb>(&sql)
+                    .fetch_all(&self.pool)
+                    .await?;
+
+                Ok(rows)
+            }
+
+            async fn create_water_body(
+                &self,
+                water_body: &$crate::models::WaterBodyDb,
+                changed_by: &str,
+            ) -> anyhow::Result<bool> {
+                let mut tx = self.pool.begin().await?;
+
+                let inserted = sqlx::query(
+                    r#"
+                    INSERT INTO water_bodies (id, name, description, location_lat, location_lon, water_type, country_code)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
+                    ON CONFLICT (id) DO NOTHING
+                    "#,
+                )
+                .bind(&water_body.id)
+                .bind(&water_body.name)
+                .bind(&water_body.description)
+                .bind(water_body.location_lat)
+                .bind(water_body.location_lon)
+                .bind(&water_body.water_type)
+                .bind(&water_body.country_code)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected()
+                    == 1;
+
+                if inserted {
+                    $crate::db::queries::record_change!(
+                        tx,
+                        "water_bodies",
+                        &water_body.id,
+                        $crate::models::ChangeAction::Create,
+                        changed_by,
+                        None::<&$crate::models::WaterBodyDb>,
+                        Some(water_body)
+                    );
+                }
+
+                tx.commit().await?;
+                Ok(inserted)
+            }
+
+            async fn update_water_body(
+                &self,
+                water_body: &$crate::models::WaterBodyDb,
+                changed_by: &str,
+            ) -> anyhow::Result<bool> {
+                let mut tx = self.pool.begin().await?;
+
+                let sql = format!("SELECT {} FROM water_bodies WHERE id = $1", $crate::db::queries::WATER_BODY_COLUMNS);
+                let Some(before) = sqlx::query_as::<_, $crate::models::WaterBodyDb>(&sql)
+                    .bind(&water_body.id)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                else {
+                    return Ok(false);
+                };
+
+                sqlx::query(
+                    r#"
+                    UPDATE water_bodies SET
+                        name = $1, description = $2, location_lat = $3, location_lon = $4, water_type = $5, country_code = $6
+                    WHERE id = $7
+                    "#,
+                )
+                .bind(&water_body.name)
+                .bind(&water_body.description)
+                .bind(water_body.location_lat)
+                .bind(water_body.location_lon)
+                .bind(&water_body.water_type)
+                .bind(&water_body.country_code)
+                .bind(&water_body.id)
+                .execute(&mut *tx)
+                .await?;
+
+                $crate::db::queries::record_change!(
+                    tx,
+                    "water_bodies",
+                    &water_body.id,
+                    $crate::models::ChangeAction::Update,
+                    changed_by,
+                    Some(&before),
+                    Some(water_body)
+                );
+
+                tx.commit().await?;
+                Ok(true)
+            }
+
+            async fn delete_water_body(&self, id: &str, changed_by: &str) -> anyhow::Result<bool> {
+                let mut tx = self.pool.begin().await?;
+
+                let sql = format!("SELECT {} FROM water_bodies WHERE id = $1", $crate::db::queries::WATER_BODY_COLUMNS);
+                let Some(before) = sqlx::query_as::<_, $crate::models::WaterBodyDb>(&sql)
+                    .bind(id)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                else {
+                    return Ok(false);
+                };
+
+                sqlx::query("DELETE FROM water_bodies WHERE id = $1")
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                $crate::db::queries::record_change!(
+                    tx,
+                    "water_bodies",
+                    id,
+                    $crate::models::ChangeAction::Delete,
+                    changed_by,
+                    Some(&before),
+                    None::<&$crate::models::WaterBodyDb>
+                );
+
+                tx.commit().await?;
+                Ok(true)
+            }
+
+            async fn list_change_history(
+                &self,
+                entity_type: Option<&str>,
+                entity_id: Option<&str>,
+                limit: i64,
+            ) -> anyhow::Result<Vec<$crate::models::ChangeHistoryDb>> {
+                let rows = sqlx::query_as::<_, $crate::models::ChangeHistoryDb>(
+                    r#"
+                    SELECT id, entity_type, entity_id, action, changed_by, changed_at, before_data, after_data
+                    FROM change_history
+                    WHERE entity_type = COALESCE($1, entity_type)
+                      AND entity_id = COALESCE($2, entity_id)
+                    ORDER BY changed_at DESC
+                    LIMIT $3
+                    "#,
+                )
+                .bind(entity_type)
+                .bind(entity_id)
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?;
+
+                Ok(rows)
+            }
+
+            async fn water_body_exists(&self, id: &str) -> anyhow::Result<bool> {
+                let row: Option<(String,)> = sqlx::query_as("SELECT id FROM water_bodies WHERE id = $1")
+                    .bind(id)
+                    .fetch_optional(&self.pool)
+                    .await?;
+
+                Ok(row.is_some())
+            }
+
+            async fn list_favorite_spots(
+                &self,
+                user_id: &str,
+            ) -> anyhow::Result<Vec<$crate::models::FavoriteSpotDb>> {
+                let sql = format!(
+                    "SELECT {} FROM favorite_spots WHERE user_id = $1 ORDER BY name, id",
+                    $crate::db::queries::FAVORITE_SPOT_COLUMNS
+                );
+                let rows = sqlx::query_as::<_, $crate::models::FavoriteSpotDb>(&sql)
+                    .bind(user_id)
+                    .fetch_all(&self.pool)
+                    .await?;
+
+                Ok(rows)
+            }
+
+            async fn get_favorite_spot(
+                &self,
+                user_id: &str,
+                id: &str,
+            ) -> anyhow::Result<Option<$crate::models::FavoriteSpotDb>> {
+                let sql = format!(
+                    "SELECT {} FROM favorite_spots WHERE id = $1 AND user_id = $2",
+                    $crate::db::queries::FAVORITE_SPOT_COLUMNS
+                );
+                let row = sqlx::query_as::<_, $crate::models::FavoriteSpotDb>(&sql)
+                    .bind(id)
+                    .bind(user_id)
+                    .fetch_optional(&self.pool)
+                    .await?;
+
+                Ok(row)
+            }
+
+            async fn create_favorite_spot(&self, spot: &$crate::models::FavoriteSpotDb) -> anyhow::Result<()> {
+                sqlx::query(
+                    r#"
+                    INSERT INTO favorite_spots
+                        (id, user_id, name, location_lat, location_lon, water_body_id, target_species, notes, created_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                    "#,
+                )
+                .bind(&spot.id)
+                .bind(&spot.user_id)
+                .bind(&spot.name)
+                .bind(spot.location_lat)
+                .bind(spot.location_lon)
+                .bind(&spot.water_body_id)
+                .bind(&spot.target_species)
+                .bind(&spot.notes)
+                .bind(spot.created_at)
+                .execute(&self.pool)
+                .await?;
+
+                Ok(())
+            }
+
+            async fn update_favorite_spot(&self, spot: &$crate::models::FavoriteSpotDb) -> anyhow::Result<bool> {
+                let updated = sqlx::query(
+                    r#"
+                    UPDATE favorite_spots
+                    SET name = $1, location_lat = $2, location_lon = $3, water_body_id = $4,
+                        target_species = $5, notes = $6
+                    WHERE id = $7 AND user_id = $8
+                    "#,
+                )
+                .bind(&spot.name)
+                .bind(spot.location_lat)
+                .bind(spot.location_lon)
+                .bind(&spot.water_body_id)
+                .bind(&spot.target_species)
+                .bind(&spot.notes)
+                .bind(&spot.id)
+                .bind(&spot.user_id)
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+
+                Ok(updated == 1)
+            }
+
+            async fn delete_favorite_spot(&self, user_id: &str, id: &str) -> anyhow::Result<bool> {
+                let deleted = sqlx::query("DELETE FROM favorite_spots WHERE id = $1 AND user_id = $2")
+                    .bind(id)
+                    .bind(user_id)
+                    .execute(&self.pool)
+                    .await?
+                    .rows_affected();
+
+                Ok(deleted == 1)
+            }
+
+            async fn list_alert_rules(&self, user_id: &str) -> anyhow::Result<Vec<$crate::models::AlertRuleDb>> {
+                let sql = format!(
+                    "SELECT {} FROM alert_rules WHERE user_id = $1 ORDER BY created_at, id",
+                    $crate::db::queries::ALERT_RULE_COLUMNS
+                );
+                let rows = sqlx::query_as::<_, $crate::models::AlertRuleDb>(&sql)
+                    .bind(user_id)
+                    .fetch_all(&self.pool)
+                    .await?;
+
+                Ok(rows)
+            }
+
+            async fn list_enabled_alert_rules(&self) -> anyhow::Result<Vec<$crate::models::AlertRuleDb>> {
+                let sql = format!(
+                    "SELECT {} FROM alert_rules WHERE enabled = $1 ORDER BY created_at, id",
+                    $crate::db::queries::ALERT_RULE_COLUMNS
+                );
+                let rows = sqlx::query_as::<_, $crate::models::AlertRuleDb>(&sql)
+                    .bind(true)
+                    .fetch_all(&self.pool)
+                    .await?;
+
+                Ok(rows)
+            }
+
+            async fn get_alert_rule(
+                &self,
+                user_id: &str,
+                id: &str,
+            ) -> anyhow::Result<Option<$crate::models::AlertRuleDb>> {
+                let sql = format!(
+                    "SELECT {} FROM alert_rules WHERE id = $1 AND user_id = $2",
+                    $crate::db::queries::ALERT_RULE_COLUMNS
+                );
+                let row = sqlx::query_as::<_, $crate::models::AlertRuleDb>(&sql)
+                    .bind(id)
+                    .bind(user_id)
+                    .fetch_optional(&self.pool)
+                    .await?;
+
+                Ok(row)
+            }
+
+            async fn create_alert_rule(&self, rule: &$crate::models::AlertRuleDb) -> anyhow::Result<()> {
+                sqlx::query(
+                    r#"
+                    INSERT INTO alert_rules
+                        (id, user_id, favorite_spot_id, species, min_probability, start_hour, end_hour,
+                         utc_offset_minutes, pressure_trend, enabled, last_notified_at, created_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                    "#,
+                )
+                .bind(&rule.id)
+                .bind(&rule.user_id)
+                .bind(&rule.favorite_spot_id)
+                .bind(&rule.species)
+                .bind(rule.min_probability)
+                .bind(rule.start_hour)
+                .bind(rule.end_hour)
+                .bind(rule.utc_offset_minutes)
+                .bind(&rule.pressure_trend)
+                .bind(rule.enabled)
+                .bind(rule.last_notified_at)
+                .bind(rule.created_at)
+                .execute(&self.pool)
+                .await?;
+
+                Ok(())
+            }
+
+            async fn update_alert_rule(&self, rule: &$crate::models::AlertRuleDb) -> anyhow::Result<bool> {
+                let updated = sqlx::query(
+                    r#"
+                    UPDATE alert_rules
+                    SET favorite_spot_id = $1, species = $2, min_probability = $3, start_hour = $4,
+                        end_hour = $5, utc_offset_minutes = $6, pressure_trend = $7, enabled = $8
+                    WHERE id = $9 AND user_id = $10
+                    "#,
+                )
+                .bind(&rule.favorite_spot_id)
+                .bind(&rule.species)
+                .bind(rule.min_probability)
+                .bind(rule.start_hour)
+                .bind(rule.end_hour)
+                .bind(rule.utc_offset_minutes)
+                .bind(&rule.pressure_trend)
+                .bind(rule.enabled)
+                .bind(&rule.id)
+                .bind(&rule.user_id)
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+
+                Ok(updated == 1)
+            }
+
+            async fn delete_alert_rule(&self, user_id: &str, id: &str) -> anyhow::Result<bool> {
+                let deleted = sqlx::query("DELETE FROM alert_rules WHERE id = $1 AND user_id = $2")
+                    .bind(id)
+                    .bind(user_id)
+                    .execute(&self.pool)
+                    .await?
+                    .rows_affected();
+
+                Ok(deleted == 1)
+            }
+
+            async fn mark_alert_notified(&self, id: &str, at: chrono::DateTime<chrono::Utc>) -> anyhow::Result<()> {
+                sqlx::query("UPDATE alert_rules SET last_notified_at = $1 WHERE id = $2")
+                    .bind(at)
+                    .bind(id)
+                    .execute(&self.pool)
+                    .await?;
+
+                Ok(())
+            }
+
+            async fn save_push_subscription(
+                &self,
+                subscription: &$crate::models::PushSubscriptionDb,
+            ) -> anyhow::Result<()> {
+                // The endpoint moves to whoever subscribed with it last
+                let mut tx = self.pool.begin().await?;
+                sqlx::query("DELETE FROM push_subscriptions WHERE endpoint = $1")
+                    .bind(&subscription.endpoint)
+                    .execute(&mut *tx)
+                    .await?;
+                sqlx::query(
+                    r#"
+                    INSERT INTO push_subscriptions (endpoint, user_id, p256dh, auth, created_at)
+                    VALUES ($1, $2, $3, $4, $5)
+                    "#,
+                )
+                .bind(&subscription.endpoint)
+                .bind(&subscription.user_id)
+                .bind(&subscription.p256dh)
+                .bind(&subscription.auth)
+                .bind(subscription.created_at)
+                .execute(&mut *tx)
+                .await?;
+                tx.commit().await?;
+
+                Ok(())
+            }
+
+            async fn list_push_subscriptions(
+                &self,
+                user_id: &str,
+            ) -> anyhow::Result<Vec<$crate::models::PushSubscriptionDb>> {
+                let sql = format!(
+                    "SELECT {} FROM push_subscriptions WHERE user_id = $1 ORDER BY created_at, endpoint",
+                    $crate::db::queries::PUSH_SUBSCRIPTION_COLUMNS
+                );
+                let rows = sqlx::query_as::<_, $crate::models::PushSubscriptionDb>(&sql)
+                    .bind(user_id)
+                    .fetch_all(&self.pool)
+                    .await?;
+
+                Ok(rows)
+            }
+
+            async fn delete_push_subscription(&self, user_id: &str, endpoint: &str) -> anyhow::Result<bool> {
+                let deleted = sqlx::query("DELETE FROM push_subscriptions WHERE endpoint = $1 AND user_id = $2")
+                    .bind(endpoint)
+                    .bind(user_id)
+                    .execute(&self.pool)
+                    .await?
+                    .rows_affected();
+
+                Ok(deleted == 1)
+            }
+        }
+    };
 }
+
+pub(crate) use impl_repository;
+pub(crate) use record_change;
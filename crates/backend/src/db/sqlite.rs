@@ -0,0 +1,28 @@
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+
+use super::queries::impl_repository;
+
+/// SQLite-backed repository for local development.
+#[derive(Clone)]
+pub struct SqliteRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteRepository {
+    /// Open (creating if needed) a SQLite database.
+    pub async fn connect(database_url: &str) -> anyhow::Result<Self> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect(&format!("{}?mode=rwc", database_url))
+            .await?;
+
+        // Ensure UTF-8 encoding
+        sqlx::query("PRAGMA encoding = 'UTF-8'")
+            .execute(&pool)
+            .await?;
+
+        Ok(Self { pool })
+    }
+}
+
+impl_repository!(SqliteRepository, "sqlite");
@@ -0,0 +1,596 @@
+//! API errors and the per-request context used to render them.
+//!
+//! Handlers return [`ApiError`]; services keep returning `anyhow::Result`
+//! and can put an `ApiError` inside it to pick the response, anything else
+//! becomes `internal_error` with the cause logged but not sent. Every error
+//! response has the [`ApiErrorBody`] shape with a stable `code` (listed in
+//! `docs/API.md`), a message localized from `Accept-Language` and the
+//! request ID.
+
+use std::time::Duration;
+
+use axum::{
+    body::to_bytes,
+    extract::Request,
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use fishing_shared::types::{ApiErrorBody, FieldError, Language};
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Header carrying the request ID in both directions.
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest request ID accepted from a client.
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+/// Largest body of a non-`ApiError` error response kept as `detail`.
+const MAX_DETAIL_BYTES: usize = 4096;
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+/// Error returned by handlers.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    /// Every field that failed validation.
+    #[error("{}", .0.iter().map(|f| f.message.as_str()).collect::<Vec<_>>().join("; "))]
+    Validation(Vec<FieldError>),
+    #[error("{0}")]
+    Unauthorized(String),
+    #[error("access token expired")]
+    TokenExpired,
+    #[error("invalid email or password")]
+    InvalidCredentials,
+    /// An emailed or one-time token that is unknown, used or expired.
+    #[error("invalid or expired token")]
+    InvalidToken,
+    #[error("{0}")]
+    Forbidden(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Conflict(String),
+    #[error("email already registered")]
+    EmailTaken,
+    #[error("too many requests")]
+    RateLimited { retry_after: Duration },
+    #[error("too many failed logins")]
+    AccountLocked { retry_after: Duration },
+    /// A service the API depends on (weather, geocoding, identity provider)
+    /// failed.
+    #[error("upstream service failed: {0:#}")]
+    Upstream(anyhow::Error),
+    #[error("{0:#}")]
+    Internal(anyhow::Error),
+    /// Error response built outside the handlers, e.g. by an extractor.
+    #[error("{detail}")]
+    Status { status: StatusCode, detail: String },
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        match err.downcast::<ApiError>() {
+            Ok(api_error) => api_error,
+            Err(err) => ApiError::Internal(err),
+        }
+    }
+}
+
+/// Stable machine-readable error codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    BadRequest,
+    ValidationFailed,
+    Unauthorized,
+    TokenExpired,
+    InvalidCredentials,
+    InvalidToken,
+    Forbidden,
+    NotFound,
+    MethodNotAllowed,
+    Conflict,
+    EmailTaken,
+    PayloadTooLarge,
+    UnsupportedMediaType,
+    UnprocessableEntity,
+    RateLimited,
+    AccountLocked,
+    InternalError,
+    UpstreamUnavailable,
+    ServiceUnavailable,
+}
+
+impl ErrorCode {
+    pub const ALL: &'static [ErrorCode] = &[
+        Self::BadRequest,
+        Self::ValidationFailed,
+        Self::Unauthorized,
+        Self::TokenExpired,
+        Self::InvalidCredentials,
+        Self::InvalidToken,
+        Self::Forbidden,
+        Self::NotFound,
+        Self::MethodNotAllowed,
+        Self::Conflict,
+        Self::EmailTaken,
+        Self::PayloadTooLarge,
+        Self::UnsupportedMediaType,
+        Self::UnprocessableEntity,
+        Self::RateLimited,
+        Self::AccountLocked,
+        Self::InternalError,
+        Self::UpstreamUnavailable,
+        Self::ServiceUnavailable,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BadRequest => "bad_request",
+            Self::ValidationFailed => "validation_failed",
+            Self::Unauthorized => "unauthorized",
+            Self::TokenExpired => "token_expired",
+            Self::InvalidCredentials => "invalid_credentials",
+            Self::InvalidToken => "invalid_token",
+            Self::Forbidden => "forbidden",
+            Self::NotFound => "not_found",
+            Self::MethodNotAllowed => "method_not_allowed",
+            Self::Conflict => "conflict",
+            Self::EmailTaken => "email_taken",
+            Self::PayloadTooLarge => "payload_too_large",
+            Self::UnsupportedMediaType => "unsupported_media_type",
+            Self::UnprocessableEntity => "unprocessable_entity",
+            Self::RateLimited => "rate_limited",
+            Self::AccountLocked => "account_locked",
+            Self::InternalError => "internal_error",
+            Self::UpstreamUnavailable => "upstream_unavailable",
+            Self::ServiceUnavailable => "service_unavailable",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::BadRequest | Self::ValidationFailed | Self::InvalidToken => StatusCode::BAD_REQUEST,
+            Self::Unauthorized | Self::TokenExpired | Self::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            Self::Forbidden => StatusCode::FORBIDDEN,
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            Self::Conflict | Self::EmailTaken => StatusCode::CONFLICT,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::UnprocessableEntity => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::RateLimited | Self::AccountLocked => StatusCode::TOO_MANY_REQUESTS,
+            Self::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::UpstreamUnavailable => StatusCode::BAD_GATEWAY,
+            Self::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Code for an error response that did not come from an [`ApiError`].
+    fn for_status(status: StatusCode) -> Self {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|code| code.status() == status)
+            .unwrap_or(if status.is_server_error() {
+                Self::InternalError
+            } else {
+                Self::BadRequest
+            })
+    }
+
+    /// Message shown to people.
+    pub fn message(&self, language: Language) -> &'static str {
+        use Language::{De, En, Fr, Pl, Uk};
+
+        match (self, language) {
+            (Self::BadRequest, Uk) => "Некоректний запит",
+            (Self::BadRequest, En) => "Invalid request",
+            (Self::BadRequest, Pl) => "Nieprawidłowe żądanie",
+            (Self::BadRequest, De) => "Ungültige Anfrage",
+            (Self::BadRequest, Fr) => "Requête invalide",
+
+            (Self::ValidationFailed, Uk) => "Деякі поля заповнено некоректно",
+            (Self::ValidationFailed, En) => "Some fields are invalid",
+            (Self::ValidationFailed, Pl) => "Niektóre pola są nieprawidłowe",
+            (Self::ValidationFailed, De) => "Einige Felder sind ungültig",
+            (Self::ValidationFailed, Fr) => "Certains champs sont invalides",
+
+            (Self::Unauthorized, Uk) => "Потрібно увійти",
+            (Self::Unauthorized, En) => "Sign-in required",
+            (Self::Unauthorized, Pl) => "Wymagane logowanie",
+            (Self::Unauthorized, De) => "Anmeldung erforderlich",
+            (Self::Unauthorized, Fr) => "Connexion requise",
+
+            (Self::TokenExpired, Uk) => "Термін дії токена доступу минув",
+            (Self::TokenExpired, En) => "Access token expired",
+            (Self::TokenExpired, Pl) => "Token dostępu wygasł",
+            (Self::TokenExpired, De) => "Zugriffstoken abgelaufen",
+            (Self::TokenExpired, Fr) => "Le jeton d'accès a expiré",
+
+            (Self::InvalidCredentials, Uk) => "Невірний email або пароль",
+            (Self::InvalidCredentials, En) => "Invalid email or password",
+            (Self::InvalidCredentials, Pl) => "Nieprawidłowy e-mail lub hasło",
+            (Self::InvalidCredentials, De) => "E-Mail oder Passwort ist falsch",
+            (Self::InvalidCredentials, Fr) => "E-mail ou mot de passe incorrect",
+
+            (Self::InvalidToken, Uk) => "Посилання недійсне або застаріле",
+            (Self::InvalidToken, En) => "The link is invalid or has expired",
+            (Self::InvalidToken, Pl) => "Link jest nieprawidłowy lub wygasł",
+            (Self::InvalidToken, De) => "Der Link ist ungültig oder abgelaufen",
+            (Self::InvalidToken, Fr) => "Le lien est invalide ou a expiré",
+
+            (Self::Forbidden, Uk) => "Недостатньо прав для цієї дії",
+            (Self::Forbidden, En) => "You are not allowed to do this",
+            (Self::Forbidden, Pl) => "Brak uprawnień do tej operacji",
+            (Self::Forbidden, De) => "Dafür fehlt die Berechtigung",
+            (Self::Forbidden, Fr) => "Vous n'avez pas l'autorisation de faire cela",
+
+            (Self::NotFound, Uk) => "Не знайдено",
+            (Self::NotFound, En) => "Not found",
+            (Self::NotFound, Pl) => "Nie znaleziono",
+            (Self::NotFound, De) => "Nicht gefunden",
+            (Self::NotFound, Fr) => "Introuvable",
+
+            (Self::MethodNotAllowed, Uk) => "Метод не підтримується",
+            (Self::MethodNotAllowed, En) => "Method not allowed",
+            (Self::MethodNotAllowed, Pl) => "Metoda niedozwolona",
+            (Self::MethodNotAllowed, De) => "Methode nicht erlaubt",
+            (Self::MethodNotAllowed, Fr) => "Méthode non autorisée",
+
+            (Self::Conflict, Uk) => "Такий запис уже існує",
+            (Self::Conflict, En) => "This already exists",
+            (Self::Conflict, Pl) => "Taki wpis już istnieje",
+            (Self::Conflict, De) => "Eintrag existiert bereits",
+            (Self::Conflict, Fr) => "Cet élément existe déjà",
+
+            (Self::EmailTaken, Uk) => "Цей email уже зареєстровано",
+            (Self::EmailTaken, En) => "This email is already registered",
+            (Self::EmailTaken, Pl) => "Ten e-mail jest już zarejestrowany",
+            (Self::EmailTaken, De) => "Diese E-Mail-Adresse ist bereits registriert",
+            (Self::EmailTaken, Fr) => "Cette adresse e-mail est déjà utilisée",
+
+            (Self::PayloadTooLarge, Uk) => "Запит завеликий",
+            (Self::PayloadTooLarge, En) => "Request is too large",
+            (Self::PayloadTooLarge, Pl) => "Żądanie jest zbyt duże",
+            (Self::PayloadTooLarge, De) => "Anfrage ist zu groß",
+            (Self::PayloadTooLarge, Fr) => "Requête trop volumineuse",
+
+            (Self::UnsupportedMediaType, Uk) => "Непідтримуваний формат даних",
+            (Self::UnsupportedMediaType, En) => "Unsupported content type",
+            (Self::UnsupportedMediaType, Pl) => "Nieobsługiwany format danych",
+            (Self::UnsupportedMediaType, De) => "Nicht unterstütztes Datenformat",
+            (Self::UnsupportedMediaType, Fr) => "Format de données non pris en charge",
+
+            (Self::UnprocessableEntity, Uk) => "Не вдалося обробити дані запиту",
+            (Self::UnprocessableEntity, En) => "The request data could not be processed",
+            (Self::UnprocessableEntity, Pl) => "Nie udało się przetworzyć danych żądania",
+            (Self::UnprocessableEntity, De) => "Die Anfragedaten konnten nicht verarbeitet werden",
+            (Self::UnprocessableEntity, Fr) => "Les données de la requête n'ont pas pu être traitées",
+
+            (Self::RateLimited, Uk) => "Забагато запитів, спробуйте пізніше",
+            (Self::RateLimited, En) => "Too many requests, try again later",
+            (Self::RateLimited, Pl) => "Zbyt wiele żądań, spróbuj później",
+            (Self::RateLimited, De) => "Zu viele Anfragen, bitte später erneut versuchen",
+            (Self::RateLimited, Fr) => "Trop de requêtes, réessayez plus tard",
+
+            (Self::AccountLocked, Uk) => "Забагато невдалих спроб входу, спробуйте пізніше",
+            (Self::AccountLocked, En) => "Too many failed sign-in attempts, try again later",
+            (Self::AccountLocked, Pl) => "Zbyt wiele nieudanych prób logowania, spróbuj później",
+            (Self::AccountLocked, De) => "Zu viele fehlgeschlagene Anmeldeversuche, bitte später erneut versuchen",
+            (Self::AccountLocked, Fr) => "Trop de tentatives de connexion échouées, réessayez plus tard",
+
+            (Self::InternalError, Uk) => "Сталася помилка на сервері",
+            (Self::InternalError, En) => "Something went wrong on our side",
+            (Self::InternalError, Pl) => "Wystąpił błąd serwera",
+            (Self::InternalError, De) => "Interner Serverfehler",
+            (Self::InternalError, Fr) => "Erreur interne du serveur",
+
+            (Self::UpstreamUnavailable, Uk) => "Зовнішній сервіс недоступний, спробуйте пізніше",
+            (Self::UpstreamUnavailable, En) => "An external service is unavailable, try again later",
+            (Self::UpstreamUnavailable, Pl) => "Usługa zewnętrzna jest niedostępna, spróbuj później",
+            (Self::UpstreamUnavailable, De) => "Ein externer Dienst ist nicht erreichbar, bitte später erneut versuchen",
+            (Self::UpstreamUnavailable, Fr) => "Un service externe est indisponible, réessayez plus tard",
+
+            (Self::ServiceUnavailable, Uk) => "Сервіс тимчасово недоступний",
+            (Self::ServiceUnavailable, En) => "Service temporarily unavailable",
+            (Self::ServiceUnavailable, Pl) => "Usługa jest chwilowo niedostępna",
+            (Self::ServiceUnavailable, De) => "Dienst vorübergehend nicht verfügbar",
+            (Self::ServiceUnavailable, Fr) => "Service temporairement indisponible",
+        }
+    }
+}
+
+impl ApiError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::BadRequest(_) => ErrorCode::BadRequest,
+            Self::Validation(_) => ErrorCode::ValidationFailed,
+            Self::Unauthorized(_) => ErrorCode::Unauthorized,
+            Self::TokenExpired => ErrorCode::TokenExpired,
+            Self::InvalidCredentials => ErrorCode::InvalidCredentials,
+            Self::InvalidToken => ErrorCode::InvalidToken,
+            Self::Forbidden(_) => ErrorCode::Forbidden,
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::Conflict(_) => ErrorCode::Conflict,
+            Self::EmailTaken => ErrorCode::EmailTaken,
+            Self::RateLimited { .. } => ErrorCode::RateLimited,
+            Self::AccountLocked { .. } => ErrorCode::AccountLocked,
+            Self::Upstream(_) => ErrorCode::UpstreamUnavailable,
+            Self::Internal(_) => ErrorCode::InternalError,
+            Self::Status { status, .. } => ErrorCode::for_status(*status),
+        }
+    }
+
+    /// Untranslated specifics safe to show to the client.
+    fn detail(&self) -> Option<String> {
+        match self {
+            Self::BadRequest(detail)
+            | Self::Unauthorized(detail)
+            | Self::Forbidden(detail)
+            | Self::NotFound(detail)
+            | Self::Conflict(detail)
+            | Self::Status { detail, .. } => Some(detail.clone()).filter(|d| !d.is_empty()),
+            Self::Validation(_) => Some(self.to_string()),
+            _ => None,
+        }
+    }
+
+    fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after } | Self::AccountLocked { retry_after } => Some(*retry_after),
+            _ => None,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let context = RequestContext::current();
+        let code = self.code();
+
+        match &self {
+            Self::Internal(err) => tracing::error!("Internal error: {:#}", err),
+            Self::Upstream(err) => tracing::warn!("Upstream failure: {:#}", err),
+            _ => {}
+        }
+
+        // Whole seconds, rounded up so a retry right on time succeeds.
+        let retry_after = self
+            .retry_after()
+            .map(|d| (d.as_secs() + u64::from(d.subsec_nanos() > 0)).max(1));
+        let body = ApiErrorBody {
+            error: code.message(context.language).to_string(),
+            code: code.as_str().to_string(),
+            detail: self.detail(),
+            request_id: context.request_id,
+            retry_after,
+            fields: match self {
+                Self::Validation(fields) => fields,
+                _ => Vec::new(),
+            },
+        };
+
+        let mut response = (code.status(), Json(body)).into_response();
+        if let Some(seconds) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response.extensions_mut().insert(Rendered);
+        response
+    }
+}
+
+/// Marks responses rendered from an [`ApiError`].
+#[derive(Debug, Clone, Copy)]
+struct Rendered;
+
+/// Request details needed to render errors.
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub request_id: Option<String>,
+    pub language: Language,
+}
+
+tokio::task_local! {
+    static REQUEST_CONTEXT: RequestContext;
+}
+
+impl RequestContext {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let request_id = headers
+            .get(&REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|id| is_valid_request_id(id))
+            .map(str::to_string)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let language = headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(negotiate_language)
+            .unwrap_or(Language::En);
+
+        Self {
+            request_id: Some(request_id),
+            language,
+        }
+    }
+
+    /// Context of the request being handled; English without a request ID
+    /// outside [`request_context`].
+    pub fn current() -> Self {
+        REQUEST_CONTEXT
+            .try_with(Clone::clone)
+            .unwrap_or(Self {
+                request_id: None,
+                language: Language::En,
+            })
+    }
+}
+
+/// Middleware assigning request IDs (taken from `X-Request-Id` when the
+/// client sends a sane one) and turning every error response into the
+/// [`ApiErrorBody`] shape.
+pub async fn request_context(request: Request, next: Next) -> Response {
+    let context = RequestContext::from_headers(request.headers());
+    let request_id = context.request_id.clone().unwrap_or_default();
+    let span = tracing::info_span!("request", request_id = %request_id);
+
+    let mut response = REQUEST_CONTEXT
+        .scope(context, async move {
+            let response = next.run(request).await;
+            normalize_error(response).await
+        })
+        .instrument(span)
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER.clone(), value);
+    }
+    response
+}
+
+/// Re-render error responses that did not come from an [`ApiError`], such
+/// as extractor rejections and unknown routes, keeping their text as detail.
+async fn normalize_error(response: Response) -> Response {
+    let status = response.status();
+    if !(status.is_client_error() || status.is_server_error())
+        || response.extensions().get::<Rendered>().is_some()
+    {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let detail = to_bytes(body, MAX_DETAIL_BYTES)
+        .await
+        .map(|bytes| String::from_utf8_lossy(&bytes).trim().to_string())
+        .unwrap_or_default();
+
+    let mut normalized = ApiError::Status { status, detail }.into_response();
+    *normalized.status_mut() = status;
+    for (name, value) in parts.headers.iter() {
+        if name != header::CONTENT_TYPE && name != header::CONTENT_LENGTH {
+            normalized.headers_mut().insert(name.clone(), value.clone());
+        }
+    }
+    normalized
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Best supported language in an `Accept-Language` header.
+pub fn negotiate_language(header: &str) -> Option<Language> {
+    let mut ranges: Vec<(f32, Language)> = header
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            let primary = tag.split('-').next()?.to_ascii_lowercase();
+            let language = Language::from_code(&primary)?;
+            (quality > 0.0).then_some((quality, language))
+        })
+        .collect();
+
+    // Stable sort keeps the client's order among equal weights.
+    ranges.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranges.first().map(|(_, language)| *language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_negotiate_language() {
+        assert_eq!(negotiate_language("uk-UA,uk;q=0.9,en;q=0.8"), Some(Language::Uk));
+        assert_eq!(negotiate_language("ru;q=0.9, de;q=0.5, en;q=0.7"), Some(Language::En));
+        assert_eq!(negotiate_language("fr-CA"), Some(Language::Fr));
+        assert_eq!(negotiate_language("pl;q=0, es"), None);
+        assert_eq!(negotiate_language(""), None);
+    }
+
+    #[test]
+    fn test_every_code_has_distinct_name_and_message() {
+        let mut names: Vec<_> = ErrorCode::ALL.iter().map(ErrorCode::as_str).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), ErrorCode::ALL.len());
+        for code in ErrorCode::ALL {
+            assert_ne!(code.message(Language::Uk), code.message(Language::En), "{:?}", code);
+        }
+    }
+
+    #[test]
+    fn test_anyhow_errors_keep_their_api_error() {
+        let err = anyhow::Error::from(ApiError::NotFound("Session not found".into()));
+        assert_eq!(ApiError::from(err).code(), ErrorCode::NotFound);
+
+        let err = anyhow::anyhow!("connection refused");
+        assert_eq!(ApiError::from(err).code(), ErrorCode::InternalError);
+    }
+
+    #[tokio::test]
+    async fn test_error_responses_are_localized_and_carry_request_id() {
+        async fn failing() -> ApiResult<&'static str> {
+            Err(ApiError::Internal(anyhow::anyhow!("database password is hunter2")))
+        }
+        let app = Router::new()
+            .route("/fail", get(failing))
+            .layer(middleware::from_fn(request_context));
+        let call = |uri: &str, headers: &[(&str, &str)]| {
+            let mut request = axum::http::Request::get(uri);
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+        let body = |response: Response| async move {
+            let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<ApiErrorBody>(&bytes).unwrap()
+        };
+
+        let response = call("/fail", &[("accept-language", "uk"), ("x-request-id", "trace-42")]).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.headers()[&REQUEST_ID_HEADER], "trace-42");
+        let error = body(response).await;
+        assert_eq!(error.code, "internal_error");
+        assert_eq!(error.error, "Сталася помилка на сервері");
+        assert_eq!(error.request_id.as_deref(), Some("trace-42"));
+        assert_eq!(error.detail, None, "internal causes stay in the log");
+
+        // Responses from outside the handlers get the same shape.
+        let response = call("/missing", &[("x-request-id", "not a valid id")]).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let generated = response.headers()[&REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        assert!(Uuid::parse_str(&generated).is_ok());
+        let error = body(response).await;
+        assert_eq!((error.code.as_str(), error.error.as_str()), ("not_found", "Not found"));
+        assert_eq!(error.request_id, Some(generated));
+
+        let response = call("/fail", &[]).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let response = app
+            .clone()
+            .oneshot(axum::http::Request::post("/fail").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert!(response.headers().contains_key(header::ALLOW));
+        assert_eq!(body(response).await.code, "method_not_allowed");
+    }
+}
@@ -0,0 +1,62 @@
+//! Extractors that validate what they deserialize.
+//!
+//! `ValidJson<T>` and `ValidQuery<T>` behave like `Json<T>` and `Query<T>`,
+//! then run `T`'s [`Validate`] rules and reject the request with
+//! `validation_failed` listing every bad field.
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, FromRequestParts, Query, Request},
+    http::request::Parts,
+    Json,
+};
+use fishing_shared::validation::Validate;
+use serde::de::DeserializeOwned;
+
+use crate::error::ApiError;
+
+/// JSON body that passed validation.
+#[derive(Debug, Clone)]
+pub struct ValidJson<T>(pub T);
+
+/// Query string that passed validation.
+#[derive(Debug, Clone)]
+pub struct ValidQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(|rejection| rejected(rejection.status(), rejection.body_text()))?;
+        value.check().map_err(ApiError::Validation)?;
+        Ok(Self(value))
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ValidQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|rejection| rejected(rejection.status(), rejection.body_text()))?;
+        value.check().map_err(ApiError::Validation)?;
+        Ok(Self(value))
+    }
+}
+
+fn rejected(status: axum::http::StatusCode, detail: String) -> ApiError {
+    ApiError::Status { status, detail }
+}
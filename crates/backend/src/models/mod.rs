@@ -1,55 +1,131 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use utoipa::ToSchema;
+use fishing_shared::types::{
+    AlertRule, CatchPrivacy, CatchRecord, EnvironmentSnapshot, FavoriteSpot, FishSpecies, PressureTrend, Regulation,
+    WaterBody,
+};
 
-/// Catch record stored in the database.
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub use fishing_shared::types::Role;
+
+/// Row of the `catches` table; field names match the columns.
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct CatchRecordDb {
-    pub id: Uuid,
-    pub user_id: Uuid,
-    pub lat: f64,
-    pub lon: f64,
+    pub id: String,
+    pub user_id: Option<String>,
+    pub location_lat: f64,
+    pub location_lon: f64,
     pub caught_at: DateTime<Utc>,
-    pub fish_species: String,
-    pub weight: Option<f64>,
-    pub length: Option<f64>,
-    pub bait_used: String,
+    pub fish_species: Option<String>,
+    pub weight_kg: Option<f64>,
+    pub length_cm: Option<f64>,
+    pub bait_used: Option<String>,
+    pub bite_intensity: Option<i32>,
     pub weather_temp: Option<f64>,
     pub weather_pressure: Option<f64>,
     pub moon_phase: Option<f64>,
     pub notes: Option<String>,
+    pub photo_url: Option<String>,
+    pub weather_time: Option<DateTime<Utc>>,
+    pub pressure_change_3h_hpa: Option<f64>,
+    pub pressure_trend: Option<String>,
+    pub wind_speed_ms: Option<f64>,
+    pub wind_gust_ms: Option<f64>,
+    pub wind_direction_deg: Option<f64>,
+    pub precipitation_mm: Option<f64>,
+    pub moon_illumination: Option<f64>,
+    pub sun_elevation_deg: Option<f64>,
+    pub sun_azimuth_deg: Option<f64>,
+    pub privacy: String,
 }
 
-/// New catch record payload (before insert).
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NewCatchRecord {
-    pub user_id: Uuid,
-    pub lat: f64,
-    pub lon: f64,
-    pub caught_at: DateTime<Utc>,
-    pub fish_species: String,
-    pub weight: Option<f64>,
-    pub length: Option<f64>,
-    pub bait_used: String,
-    pub weather_temp: Option<f64>,
-    pub weather_pressure: Option<f64>,
-    pub moon_phase: Option<f64>,
-    pub notes: Option<String>,
+impl CatchRecordDb {
+    /// Snapshot columns, present once the server has captured them.
+    ///
+    /// Temperature, pressure and moon phase share the catch's own columns.
+    fn environment(&self) -> Option<EnvironmentSnapshot> {
+        let (Some(moon_illumination), Some(sun_elevation_deg), Some(sun_azimuth_deg)) =
+            (self.moon_illumination, self.sun_elevation_deg, self.sun_azimuth_deg)
+        else {
+            return None;
+        };
+
+        Some(EnvironmentSnapshot {
+            weather_time: self.weather_time,
+            temperature_c: self.weather_temp,
+            pressure_hpa: self.weather_pressure,
+            pressure_change_3h_hpa: self.pressure_change_3h_hpa,
+            pressure_trend: self.pressure_trend.as_deref().and_then(PressureTrend::parse),
+            wind_speed_ms: self.wind_speed_ms,
+            wind_gust_ms: self.wind_gust_ms,
+            wind_direction_deg: self.wind_direction_deg,
+            precipitation_mm: self.precipitation_mm,
+            moon_phase: self.moon_phase.unwrap_or_default(),
+            moon_illumination,
+            sun_elevation_deg,
+            sun_azimuth_deg,
+        })
+    }
+}
+
+impl From<CatchRecordDb> for CatchRecord {
+    fn from(row: CatchRecordDb) -> Self {
+        let environment = row.environment();
+        Self {
+            id: row.id,
+            user_id: row.user_id.unwrap_or_default(),
+            lat: row.location_lat,
+            lon: row.location_lon,
+            caught_at: row.caught_at,
+            fish_species: row.fish_species.unwrap_or_default(),
+            weight_kg: row.weight_kg,
+            length_cm: row.length_cm,
+            bait_used: row.bait_used,
+            bite_intensity: row.bite_intensity.and_then(|v| u8::try_from(v).ok()),
+            weather_temp: row.weather_temp,
+            weather_pressure: row.weather_pressure,
+            moon_phase: row.moon_phase,
+            notes: row.notes,
+            photo_url: row.photo_url,
+            environment,
+            privacy: CatchPrivacy::parse(&row.privacy).unwrap_or_default(),
+        }
+    }
 }
 
 /// Water body stored in the database.
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct WaterBodyDb {
+    /// Generated on create when left empty.
+    #[serde(default)]
     pub id: String,
     pub name: String,
+    pub description: Option<String>,
     pub location_lat: f64,
     pub location_lon: f64,
     pub water_type: Option<String>,
+    pub country_code: Option<String>,
+}
+
+impl From<WaterBodyDb> for WaterBody {
+    fn from(row: WaterBodyDb) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            description: row.description,
+            location_lat: row.location_lat,
+            location_lon: row.location_lon,
+            water_type: row.water_type,
+            country_code: row.country_code,
+        }
+    }
 }
 
 /// Fishing regulation row for API responses.
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct RegulationDb {
+    /// Generated on create when left empty.
+    #[serde(default)]
     pub id: String,
     pub region_code: String,
     pub fish_species: Option<String>,
@@ -60,10 +136,296 @@ pub struct RegulationDb {
     pub restrictions: Option<String>,
 }
 
+impl From<RegulationDb> for Regulation {
+    fn from(row: RegulationDb) -> Self {
+        Self {
+            id: row.id,
+            region_code: row.region_code,
+            fish_species: row.fish_species,
+            min_size_cm: row.min_size_cm,
+            max_catch_per_day: row.max_catch_per_day,
+            season_start: row.season_start,
+            season_end: row.season_end,
+            restrictions: row.restrictions,
+        }
+    }
+}
+
 /// Fish item for localized lists.
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct FishItemDb {
     pub id: String,
     pub name: String,
     pub scientific_name: String,
 }
+
+impl From<FishItemDb> for FishSpecies {
+    fn from(row: FishItemDb) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            scientific_name: row.scientific_name,
+        }
+    }
+}
+
+/// Full `fish_species` row, as curated by admins.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct FishSpeciesDb {
+    /// Slug such as `pike`; generated on create when left empty.
+    #[serde(default)]
+    pub id: String,
+    pub name_uk: String,
+    pub name_en: Option<String>,
+    pub scientific_name: Option<String>,
+    pub best_season: Option<String>,
+    pub preferred_bait: Option<String>,
+    pub min_temp: Option<f64>,
+    pub max_temp: Option<f64>,
+}
+
+/// User account stored in the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UserDb {
+    pub id: String,
+    pub email: String,
+    pub password_hash: String,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+    pub email_verified_at: Option<DateTime<Utc>>,
+    #[sqlx(flatten)]
+    pub preferences: UserPreferencesDb,
+}
+
+impl UserDb {
+    /// Parsed `role`; unknown values grant nothing.
+    pub fn role(&self) -> Role {
+        Role::parse(&self.role).unwrap_or_default()
+    }
+}
+
+/// Preference columns of `users`, as stored.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UserPreferencesDb {
+    pub country_code: Option<String>,
+    pub language: Option<String>,
+    pub length_unit: String,
+    pub weight_unit: String,
+    pub temperature_unit: String,
+    pub pressure_unit: String,
+    pub wind_speed_unit: String,
+    pub home_region: Option<String>,
+    /// JSON array of fish species ids.
+    pub favorite_species: String,
+    pub default_privacy: String,
+}
+
+/// New user account (before insert).
+#[derive(Debug, Clone)]
+pub struct NewUser {
+    pub id: String,
+    pub email: String,
+    pub password_hash: String,
+    pub country_code: String,
+    pub language: String,
+}
+
+/// Login session stored in the database.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct SessionDb {
+    pub id: String,
+    pub user_id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// New session payload (before insert).
+#[derive(Debug, Clone)]
+pub struct NewSession {
+    pub id: String,
+    pub user_id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Refresh token joined with its session.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RefreshTokenDb {
+    pub token_hash: String,
+    pub session_id: String,
+    pub used_at: Option<DateTime<Utc>>,
+    pub user_id: String,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// What an emailed token authorizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailTokenPurpose {
+    VerifyEmail,
+    ResetPassword,
+}
+
+impl EmailTokenPurpose {
+    /// Value stored in `email_tokens.purpose`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::VerifyEmail => "verify_email",
+            Self::ResetPassword => "reset_password",
+        }
+    }
+}
+
+/// New emailed token (before insert); only the hash is stored.
+#[derive(Debug, Clone)]
+pub struct NewEmailToken {
+    pub token_hash: String,
+    pub user_id: String,
+    pub purpose: EmailTokenPurpose,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// External identity linked to a user.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct UserIdentityDb {
+    pub provider: String,
+    pub subject: String,
+    pub user_id: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// New identity link (before insert).
+#[derive(Debug, Clone)]
+pub struct NewUserIdentity {
+    pub provider: String,
+    pub subject: String,
+    pub user_id: String,
+    pub email: Option<String>,
+}
+
+/// Row of the `favorite_spots` table.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct FavoriteSpotDb {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub location_lat: f64,
+    pub location_lon: f64,
+    pub water_body_id: Option<String>,
+    /// JSON array of fish species ids.
+    pub target_species: String,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<FavoriteSpotDb> for FavoriteSpot {
+    fn from(row: FavoriteSpotDb) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            lat: row.location_lat,
+            lon: row.location_lon,
+            water_body_id: row.water_body_id,
+            target_species: serde_json::from_str(&row.target_species).unwrap_or_default(),
+            notes: row.notes,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// Row of the `alert_rules` table.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AlertRuleDb {
+    pub id: String,
+    pub user_id: String,
+    pub favorite_spot_id: String,
+    pub species: Option<String>,
+    pub min_probability: f64,
+    pub start_hour: i32,
+    pub end_hour: i32,
+    pub utc_offset_minutes: i32,
+    pub pressure_trend: Option<String>,
+    pub enabled: bool,
+    pub last_notified_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AlertRuleDb> for AlertRule {
+    fn from(row: AlertRuleDb) -> Self {
+        Self {
+            id: row.id,
+            favorite_spot_id: row.favorite_spot_id,
+            species: row.species,
+            min_probability: row.min_probability,
+            start_hour: row.start_hour.clamp(0, 23) as u8,
+            end_hour: row.end_hour.clamp(0, 23) as u8,
+            utc_offset_minutes: row.utc_offset_minutes,
+            pressure_trend: row.pressure_trend.as_deref().and_then(PressureTrend::parse),
+            enabled: row.enabled,
+            last_notified_at: row.last_notified_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// Row of the `push_subscriptions` table.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct PushSubscriptionDb {
+    pub endpoint: String,
+    pub user_id: String,
+    pub p256dh: String,
+    pub auth: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Pending OIDC login between the redirect to the provider and the callback.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct OidcLoginDb {
+    pub state: String,
+    pub provider: String,
+    pub code_verifier: String,
+    pub nonce: String,
+    pub redirect_uri: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// What an admin did to a reference data row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl ChangeAction {
+    /// Value stored in `change_history.action`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// Entry of the `change_history` audit trail.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ChangeHistoryDb {
+    pub id: String,
+    /// Table the row belongs to, e.g. `fish_species`.
+    pub entity_type: String,
+    pub entity_id: String,
+    pub action: String,
+    pub changed_by: Option<String>,
+    pub changed_at: DateTime<Utc>,
+    /// Row as JSON before the change; `None` on create.
+    pub before_data: Option<String>,
+    /// Row as JSON after the change; `None` on delete.
+    pub after_data: Option<String>,
+}
@@ -0,0 +1,270 @@
+//! OpenAPI 3 description of the HTTP API, generated from the handlers'
+//! `#[utoipa::path]` annotations and the request/response types.
+//!
+//! Served at `/api/v1/openapi.json`, browsable at `/api/v1/docs`. A copy is
+//! kept in `docs/openapi.json`; the snapshot test below fails when the two
+//! differ, so API changes show up in review.
+
+use axum::{
+    response::{Html, IntoResponse},
+    Json,
+};
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::{models, routes, services};
+use fishing_ml_engine as ml;
+use fishing_shared::types as shared;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Fishing Forecast API",
+        description = "Bite forecasts, catch logging and fishing reference data. \
+            Every error response has the `ApiErrorBody` shape.",
+    ),
+    paths(
+        routes::health::health_handler,
+        routes::auth::register_handler,
+        routes::auth::login_handler,
+        routes::auth::me_handler,
+        routes::auth::refresh_handler,
+        routes::auth::logout_handler,
+        routes::auth::sessions_handler,
+        routes::auth::revoke_session_handler,
+        routes::auth::request_email_verification_handler,
+        routes::auth::confirm_email_handler,
+        routes::auth::request_password_reset_handler,
+        routes::auth::confirm_password_reset_handler,
+        routes::auth::oidc_providers_handler,
+        routes::auth::oidc_authorize_handler,
+        routes::auth::oidc_callback_handler,
+        routes::auth::identities_handler,
+        routes::profile::get_profile_handler,
+        routes::profile::update_profile_handler,
+        routes::favorites::list_favorites_handler,
+        routes::favorites::create_favorite_handler,
+        routes::favorites::update_favorite_handler,
+        routes::favorites::delete_favorite_handler,
+        routes::alerts::list_alerts_handler,
+        routes::alerts::create_alert_handler,
+        routes::alerts::update_alert_handler,
+        routes::alerts::delete_alert_handler,
+        routes::alerts::push_public_key_handler,
+        routes::alerts::subscribe_push_handler,
+        routes::alerts::unsubscribe_push_handler,
+        routes::forecast::forecast_handler,
+        routes::forecast::detailed_forecast_handler,
+        routes::forecast::feature_importance_handler,
+        routes::forecast::bite_windows_handler,
+        routes::forecast::trip_plan_handler,
+        routes::forecast::forecast_grid_handler,
+        routes::forecast::forecast_tile_handler,
+        routes::region::region_detect_handler,
+        routes::fish::fish_handler,
+        routes::regulations::regulations_handler,
+        routes::regulations::regulations_validate_handler,
+        routes::catches::save_catch_handler,
+        routes::catches::get_catches_handler,
+        routes::catches::get_nearby_catches_handler,
+        routes::water_bodies::water_bodies_handler,
+        routes::admin::fish_species::list,
+        routes::admin::fish_species::create,
+        routes::admin::fish_species::update,
+        routes::admin::fish_species::delete,
+        routes::admin::regulations::list,
+        routes::admin::regulations::create,
+        routes::admin::regulations::update,
+        routes::admin::regulations::delete,
+        routes::admin::water_bodies::list,
+        routes::admin::water_bodies::create,
+        routes::admin::water_bodies::update,
+        routes::admin::water_bodies::delete,
+        routes::admin::history_handler,
+    ),
+    components(schemas(
+        shared::ApiErrorBody,
+        shared::FieldError,
+        shared::CatchRecord,
+        shared::NewCatchRecord,
+        shared::EnvironmentSnapshot,
+        shared::PressureTrend,
+        shared::ForecastResult,
+        shared::ForecastSource,
+        shared::ForecastFactors,
+        shared::BaitRecommendation,
+        shared::BiteWindow,
+        shared::BiteWindows,
+        shared::TripPlanRequest,
+        shared::TripSpot,
+        shared::TripOrigin,
+        shared::TripPlan,
+        shared::TripWindow,
+        shared::TripSpotForecast,
+        shared::HourlyProbability,
+        shared::FavoriteSpot,
+        shared::FavoriteSpotInput,
+        shared::AlertRule,
+        shared::AlertRuleInput,
+        shared::PushSubscription,
+        shared::PushSubscriptionKeys,
+        shared::PushPublicKey,
+        shared::WeatherCurrent,
+        shared::RegionInfo,
+        shared::Language,
+        shared::UserProfile,
+        shared::ProfileUpdate,
+        shared::WaterBody,
+        shared::FishSpecies,
+        shared::Regulation,
+        shared::ValidationRequest,
+        shared::ValidationResult,
+        shared::Role,
+        shared::LoginRequest,
+        shared::RegisterRequest,
+        shared::RefreshRequest,
+        shared::AuthResponse,
+        shared::UserResponse,
+        shared::CatchPrivacy,
+        shared::UnitPreferences,
+        shared::LengthUnit,
+        shared::WeightUnit,
+        shared::TemperatureUnit,
+        shared::PressureUnit,
+        shared::WindSpeedUnit,
+        ml::PredictionResult,
+        ml::FactorScore,
+        ml::PredictionRecommendation,
+        ml::FeatureImportance,
+        models::FishSpeciesDb,
+        models::RegulationDb,
+        models::WaterBodyDb,
+        services::auth::VerifyEmailRequest,
+        services::auth::PasswordResetRequest,
+        services::auth::PasswordResetConfirm,
+        services::auth::SessionResponse,
+        services::oidc::ProviderResponse,
+        services::oidc::AuthorizeResponse,
+        services::oidc::CallbackRequest,
+        services::oidc::IdentityResponse,
+        services::admin::ChangeHistoryResponse,
+    )),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "health", description = "Liveness"),
+        (name = "auth", description = "Accounts, sessions and external identity providers"),
+        (name = "profile", description = "The current user's preferences"),
+        (name = "favorites", description = "The current user's saved spots"),
+        (name = "alerts", description = "Bite alert rules and Web Push subscriptions"),
+        (name = "forecast", description = "Bite forecasts and map layers"),
+        (name = "catches", description = "Catch log"),
+        (name = "reference", description = "Regions, fish species, regulations and water bodies"),
+        (name = "admin", description = "Reference data maintenance; admin role only"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the `bearer` scheme the authenticated paths refer to.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// The OpenAPI document
+/// GET /api/v1/openapi.json
+pub async fn openapi_handler() -> impl IntoResponse {
+    Json(ApiDoc::openapi())
+}
+
+/// Swagger UI for the OpenAPI document
+/// GET /api/v1/docs
+pub async fn swagger_ui_handler() -> Html<&'static str> {
+    Html(SWAGGER_UI_PAGE)
+}
+
+const SWAGGER_UI_PAGE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Fishing Forecast API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5.17.14/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5.17.14/swagger-ui-bundle.js" crossorigin></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/api/v1/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"##;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn snapshot_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../docs/openapi.json")
+    }
+
+    /// Run with `UPDATE_OPENAPI=1` to accept an intended API change.
+    #[test]
+    fn test_spec_matches_snapshot() {
+        let spec = ApiDoc::openapi().to_pretty_json().unwrap() + "\n";
+
+        if std::env::var_os("UPDATE_OPENAPI").is_some() {
+            std::fs::write(snapshot_path(), &spec).unwrap();
+            return;
+        }
+
+        let snapshot = std::fs::read_to_string(snapshot_path()).unwrap_or_default();
+        assert!(
+            snapshot == spec,
+            "the OpenAPI spec no longer matches docs/openapi.json; if the API change is \
+             intended, run `UPDATE_OPENAPI=1 cargo test -p fishing-backend openapi` and commit the result"
+        );
+    }
+
+    #[test]
+    fn test_spec_describes_errors_and_auth() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        let login = &spec["paths"]["/api/v1/auth/login"]["post"];
+        assert_eq!(login["requestBody"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/LoginRequest");
+        assert_eq!(login["responses"]["401"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/ApiErrorBody");
+        assert_eq!(spec["paths"]["/api/v1/profile"]["get"]["security"][0]["bearer"], serde_json::json!([]));
+        assert_eq!(spec["components"]["securitySchemes"]["bearer"]["scheme"], "bearer");
+        assert!(spec["paths"]["/api/v1/admin/regulations/{id}"]["put"].is_object());
+
+        let mut ids: Vec<&str> = spec["paths"]
+            .as_object()
+            .unwrap()
+            .values()
+            .flat_map(|path| path.as_object().unwrap().values())
+            .map(|operation| operation["operationId"].as_str().unwrap())
+            .collect();
+        let total = ids.len();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), total, "operation ids must be unique");
+        assert_eq!(
+            spec["paths"]["/api/v1/regulations"]["get"]["responses"]["200"]["content"]["application/json"]["schema"]["items"]["$ref"],
+            "#/components/schemas/Regulation"
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::StatusCode,
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    models::{FishSpeciesDb, RegulationDb, Role, WaterBodyDb},
+    routes::auth::authenticate,
+    services::{
+        admin::{self, AdminEntity, ChangeHistoryResponse},
+        auth::Claims,
+    },
+    AppState,
+};
+
+/// Query parameters for the change history.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HistoryQuery {
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Admin API, nested under `/api/v1/admin`; every route requires the
+/// admin role.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/fish-species",
+            get(list_handler::<FishSpeciesDb>).post(create_handler::<FishSpeciesDb>),
+        )
+        .route(
+            "/fish-species/:id",
+            put(update_handler::<FishSpeciesDb>).delete(delete_handler::<FishSpeciesDb>),
+        )
+        .route(
+            "/regulations",
+            get(list_handler::<RegulationDb>).post(create_handler::<RegulationDb>),
+        )
+        .route(
+            "/regulations/:id",
+            put(update_handler::<RegulationDb>).delete(delete_handler::<RegulationDb>),
+        )
+        .route(
+            "/water-bodies",
+            get(list_handler::<WaterBodyDb>).post(create_handler::<WaterBodyDb>),
+        )
+        .route(
+            "/water-bodies/:id",
+            put(update_handler::<WaterBodyDb>).delete(delete_handler::<WaterBodyDb>),
+        )
+        .route("/history", get(history_handler))
+        .route_layer(middleware::from_fn(require_admin))
+}
+
+/// Reject requests without an admin access token; passes the claims on
+/// as a request extension.
+pub async fn require_admin(mut request: Request, next: Next) -> Response {
+    let claims = match authenticate(request.headers()) {
+        Ok(c) => c,
+        Err(err) => return err.into_response(),
+    };
+
+    if claims.role != Role::Admin {
+        return ApiError::Forbidden("Admin role required".into()).into_response();
+    }
+
+    request.extensions_mut().insert(claims);
+    next.run(request).await
+}
+
+/// List every row
+/// GET /api/v1/admin/{fish-species,regulations,water-bodies}
+pub async fn list_handler<T: AdminEntity>(State(state): State<AppState>) -> ApiResult<Json<Vec<T>>> {
+    Ok(Json(T::list(state.db.as_ref()).await?))
+}
+
+/// Create a row; the id is generated unless given
+/// POST /api/v1/admin/{fish-species,regulations,water-bodies}
+pub async fn create_handler<T: AdminEntity>(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(row): Json<T>,
+) -> ApiResult<(StatusCode, Json<T>)> {
+    let row = row.prepare().map_err(ApiError::Validation)?;
+
+    if row.create(state.db.as_ref(), &claims.sub).await? {
+        Ok((StatusCode::CREATED, Json(row)))
+    } else {
+        Err(ApiError::Conflict(format!("{} {} already exists", T::LABEL, row.id())))
+    }
+}
+
+/// Replace a row
+/// PUT /api/v1/admin/{fish-species,regulations,water-bodies}/:id
+pub async fn update_handler<T: AdminEntity>(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(mut row): Json<T>,
+) -> ApiResult<Json<T>> {
+    row.set_id(id);
+    let row = row.prepare().map_err(ApiError::Validation)?;
+
+    if row.update(state.db.as_ref(), &claims.sub).await? {
+        Ok(Json(row))
+    } else {
+        Err(ApiError::NotFound(format!("{} not found", T::LABEL)))
+    }
+}
+
+/// Delete a row
+/// DELETE /api/v1/admin/{fish-species,regulations,water-bodies}/:id
+pub async fn delete_handler<T: AdminEntity>(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    if T::delete(state.db.as_ref(), &id, &claims.sub).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::NotFound(format!("{} not found", T::LABEL)))
+    }
+}
+
+/// Latest changes to reference data, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/history",
+    tag = "admin",
+    params(HistoryQuery),
+    responses(
+        (status = 200, description = "Latest changes, newest first", body = [ChangeHistoryResponse]),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+        (status = 403, description = "Admin role required", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn history_handler(
+    State(state): State<AppState>,
+    Query(query): Query<HistoryQuery>,
+) -> ApiResult<Json<Vec<ChangeHistoryResponse>>> {
+    let limit = query
+        .limit
+        .unwrap_or(admin::DEFAULT_HISTORY_LIMIT)
+        .clamp(1, admin::MAX_HISTORY_LIMIT);
+
+    let rows = state
+        .db
+        .list_change_history(query.entity_type.as_deref(), query.entity_id.as_deref(), limit)
+        .await?;
+    Ok(Json(rows.into_iter().map(Into::into).collect()))
+}
+
+/// OpenAPI descriptions of the generic CRUD handlers for one entity, in a
+/// module named after its collection; `#[utoipa::path]` cannot describe a
+/// generic function directly.
+macro_rules! crud_docs {
+    ($module:ident, $entity:ident, $collection:literal, $item:literal) => {
+        #[allow(dead_code)]
+        pub mod $module {
+            /// List every row
+            #[utoipa::path(
+                get,
+                operation_id = concat!("list_", stringify!($module)),
+                path = $collection,
+                tag = "admin",
+                responses(
+                    (status = 200, description = "Every row", body = [$entity]),
+                    (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+                    (status = 403, description = "Admin role required", body = ApiErrorBody),
+                ),
+                security(("bearer" = []))
+            )]
+            pub async fn list() {}
+
+            /// Create a row; the id is generated unless given
+            #[utoipa::path(
+                post,
+                operation_id = concat!("create_", stringify!($module)),
+                path = $collection,
+                tag = "admin",
+                request_body = $entity,
+                responses(
+                    (status = 201, description = "Created row", body = $entity),
+                    (status = 400, description = "Invalid fields", body = ApiErrorBody),
+                    (status = 403, description = "Admin role required", body = ApiErrorBody),
+                    (status = 409, description = "Id already taken", body = ApiErrorBody),
+                ),
+                security(("bearer" = []))
+            )]
+            pub async fn create() {}
+
+            /// Replace a row
+            #[utoipa::path(
+                put,
+                operation_id = concat!("update_", stringify!($module)),
+                path = $item,
+                tag = "admin",
+                params(("id" = String, Path, description = "Row id")),
+                request_body = $entity,
+                responses(
+                    (status = 200, description = "Updated row", body = $entity),
+                    (status = 400, description = "Invalid fields", body = ApiErrorBody),
+                    (status = 403, description = "Admin role required", body = ApiErrorBody),
+                    (status = 404, description = "No such row", body = ApiErrorBody),
+                ),
+                security(("bearer" = []))
+            )]
+            pub async fn update() {}
+
+            /// Delete a row
+            #[utoipa::path(
+                delete,
+                operation_id = concat!("delete_", stringify!($module)),
+                path = $item,
+                tag = "admin",
+                params(("id" = String, Path, description = "Row id")),
+                responses(
+                    (status = 204, description = "Deleted"),
+                    (status = 403, description = "Admin role required", body = ApiErrorBody),
+                    (status = 404, description = "No such row", body = ApiErrorBody),
+                ),
+                security(("bearer" = []))
+            )]
+            pub async fn delete() {}
+        }
+    };
+}
+
+crud_docs!(fish_species, FishSpeciesDb, "/api/v1/admin/fish-species", "/api/v1/admin/fish-species/{id}");
+crud_docs!(regulations, RegulationDb, "/api/v1/admin/regulations", "/api/v1/admin/regulations/{id}");
+crud_docs!(water_bodies, WaterBodyDb, "/api/v1/admin/water-bodies", "/api/v1/admin/water-bodies/{id}");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+        routing::post,
+    };
+    use tower::ServiceExt;
+
+    use crate::routes::auth::{refresh_handler, register_handler};
+    use crate::services::auth;
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {}", token));
+        }
+        let body = body.map(|b| Body::from(b.to_string())).unwrap_or_else(Body::empty);
+        let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_admin_species_crud_with_history() {
+        auth::init_jwt_secret().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::for_tests(&dir).await;
+        let db = state.db.clone();
+        let app = Router::new()
+            .route("/register", post(register_handler))
+            .route("/refresh", post(refresh_handler))
+            .nest("/admin", router())
+            .with_state(state);
+
+        let credentials = serde_json::json!({ "email": "curator@example.com", "password": "s3cret-pass" });
+        let (_, registered) = call(&app, "POST", "/register", None, Some(credentials)).await;
+        assert_eq!(registered["user"]["role"], "user");
+
+        let (status, _) = call(&app, "GET", "/admin/fish-species", None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = call(&app, "GET", "/admin/fish-species", registered["token"].as_str(), None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // The role is read from the database when tokens are refreshed.
+        assert!(db.set_user_role("curator@example.com", Role::Admin).await.unwrap());
+        let refresh = serde_json::json!({ "refresh_token": registered["refresh_token"] });
+        let (_, refreshed) = call(&app, "POST", "/refresh", None, Some(refresh)).await;
+        assert_eq!(refreshed["user"]["role"], "admin");
+        let token = refreshed["token"].as_str();
+
+        let grayling = serde_json::json!({
+            "id": "grayling",
+            "name_uk": "Харіус",
+            "name_en": "Grayling",
+            "best_season": "autumn",
+            "min_temp": 4.0,
+            "max_temp": 18.0,
+        });
+        let (status, created) = call(&app, "POST", "/admin/fish-species", token, Some(grayling.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["id"], "grayling");
+        let (status, _) = call(&app, "POST", "/admin/fish-species", token, Some(grayling.clone())).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = call(&app, "POST", "/admin/fish-species", token, Some(serde_json::json!({ "name_uk": " " }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let mut renamed = grayling.clone();
+        renamed["name_en"] = "European grayling".into();
+        let (status, updated) = call(&app, "PUT", "/admin/fish-species/grayling", token, Some(renamed.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated["name_en"], "European grayling");
+        let (status, _) = call(&app, "PUT", "/admin/fish-species/kraken", token, Some(renamed)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (_, listed) = call(&app, "GET", "/admin/fish-species", token, None).await;
+        assert!(listed.as_array().unwrap().iter().any(|s| s["id"] == "grayling"));
+
+        let (status, _) = call(&app, "DELETE", "/admin/fish-species/grayling", token, None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(&app, "DELETE", "/admin/fish-species/grayling", token, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let uri = "/admin/history?entity_type=fish_species&entity_id=grayling";
+        let (status, history) = call(&app, "GET", uri, token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let actions: Vec<_> = history.as_array().unwrap().iter().map(|e| e["action"].clone()).collect();
+        assert_eq!(actions, ["delete", "update", "create"]);
+        assert_eq!(history[1]["before"]["name_en"], "Grayling");
+        assert_eq!(history[1]["after"]["name_en"], "European grayling");
+        assert_eq!(history[0]["after"], serde_json::Value::Null);
+        assert_eq!(history[2]["changed_by"], registered["user"]["id"]);
+    }
+}
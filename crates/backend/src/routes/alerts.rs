@@ -0,0 +1,387 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use fishing_shared::types::{AlertRule, AlertRuleInput, PushPublicKey, PushSubscription};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::{
+    error::{ApiError, ApiResult},
+    extract::ValidJson,
+    models::PushSubscriptionDb,
+    routes::auth::authenticate,
+    services::{alerts, notifier},
+    AppState,
+};
+
+/// List the current user's bite alert rules
+#[utoipa::path(
+    get,
+    path = "/api/v1/alerts",
+    tag = "alerts",
+    responses(
+        (status = 200, description = "Alert rules, oldest first", body = [AlertRule]),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn list_alerts_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> ApiResult<Json<Vec<AlertRule>>> {
+    let claims = authenticate(&headers)?;
+
+    Ok(Json(alerts::list(&state, &claims.sub).await?))
+}
+
+/// Create a bite alert rule for one of the user's favorite spots
+#[utoipa::path(
+    post,
+    path = "/api/v1/alerts",
+    tag = "alerts",
+    request_body = AlertRuleInput,
+    responses(
+        (status = 201, description = "Saved rule", body = AlertRule),
+        (status = 400, description = "Invalid fields", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn create_alert_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(input): Json<AlertRuleInput>,
+) -> ApiResult<(StatusCode, Json<AlertRule>)> {
+    let claims = authenticate(&headers)?;
+
+    let problems = alerts::validate_input(&state, &claims.sub, &input).await?;
+    if !problems.is_empty() {
+        return Err(ApiError::Validation(problems));
+    }
+
+    let rule = alerts::create(&state, &claims.sub, input).await?;
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Replace a bite alert rule
+#[utoipa::path(
+    put,
+    path = "/api/v1/alerts/{id}",
+    tag = "alerts",
+    params(("id" = String, Path, description = "Rule id")),
+    request_body = AlertRuleInput,
+    responses(
+        (status = 200, description = "Updated rule", body = AlertRule),
+        (status = 400, description = "Invalid fields", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+        (status = 404, description = "No such rule of the current user", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn update_alert_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(input): Json<AlertRuleInput>,
+) -> ApiResult<Json<AlertRule>> {
+    let claims = authenticate(&headers)?;
+
+    let problems = alerts::validate_input(&state, &claims.sub, &input).await?;
+    if !problems.is_empty() {
+        return Err(ApiError::Validation(problems));
+    }
+
+    alerts::update(&state, &claims.sub, &id, input)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound("Alert rule not found".into()))
+}
+
+/// Delete a bite alert rule
+#[utoipa::path(
+    delete,
+    path = "/api/v1/alerts/{id}",
+    tag = "alerts",
+    params(("id" = String, Path, description = "Rule id")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+        (status = 404, description = "No such rule of the current user", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn delete_alert_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let claims = authenticate(&headers)?;
+
+    if state.db.delete_alert_rule(&claims.sub, &id).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::NotFound("Alert rule not found".into()))
+    }
+}
+
+/// The VAPID public key browsers subscribe to push notifications with
+#[utoipa::path(
+    get,
+    path = "/api/v1/push/public-key",
+    tag = "alerts",
+    responses(
+        (status = 200, description = "Application server key", body = PushPublicKey),
+        (status = 404, description = "Push notifications are not configured", body = ApiErrorBody),
+    )
+)]
+pub async fn push_public_key_handler(State(state): State<AppState>) -> ApiResult<Json<PushPublicKey>> {
+    let key = state
+        .config
+        .push
+        .vapid_key
+        .as_ref()
+        .ok_or_else(|| ApiError::NotFound("Push notifications are not configured".into()))?;
+
+    Ok(Json(PushPublicKey {
+        public_key: key.public_key(),
+    }))
+}
+
+/// Send bite alerts to this browser
+#[utoipa::path(
+    post,
+    path = "/api/v1/push/subscriptions",
+    tag = "alerts",
+    request_body = PushSubscription,
+    responses(
+        (status = 204, description = "Subscribed"),
+        (status = 400, description = "Invalid fields", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn subscribe_push_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    ValidJson(subscription): ValidJson<PushSubscription>,
+) -> ApiResult<StatusCode> {
+    let claims = authenticate(&headers)?;
+    notifier::check_keys(&subscription.keys).map_err(ApiError::Validation)?;
+
+    state
+        .db
+        .save_push_subscription(&PushSubscriptionDb {
+            endpoint: subscription.endpoint,
+            user_id: claims.sub,
+            p256dh: subscription.keys.p256dh,
+            auth: subscription.keys.auth,
+            created_at: chrono::Utc::now(),
+        })
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Browser to stop sending bite alerts to.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct UnsubscribeQuery {
+    /// Endpoint of the push subscription
+    pub endpoint: String,
+}
+
+/// Stop sending bite alerts to a browser
+#[utoipa::path(
+    delete,
+    path = "/api/v1/push/subscriptions",
+    tag = "alerts",
+    params(UnsubscribeQuery),
+    responses(
+        (status = 204, description = "Unsubscribed"),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+        (status = 404, description = "No such subscription of the current user", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn unsubscribe_push_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<UnsubscribeQuery>,
+) -> ApiResult<StatusCode> {
+    let claims = authenticate(&headers)?;
+
+    if state.db.delete_push_subscription(&claims.sub, &query.endpoint).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::NotFound("Push subscription not found".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+        routing::{get, post, put},
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use crate::config::Config;
+    use crate::routes::auth::register_handler;
+    use crate::routes::favorites::create_favorite_handler;
+    use crate::services::notifier::{PushConfig, VapidKey};
+    use crate::services::auth;
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {}", token));
+        }
+        let body = body.map(|b| Body::from(b.to_string())).unwrap_or_else(Body::empty);
+        let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    async fn register(app: &Router, email: &str) -> String {
+        let credentials = serde_json::json!({ "email": email, "password": "s3cret-pass" });
+        let (_, registered) = call(app, "POST", "/register", None, Some(credentials)).await;
+        registered["token"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_alert_rules_and_push_subscriptions() {
+        auth::init_jwt_secret().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let key = VapidKey::generate();
+        let config = Config {
+            push: PushConfig {
+                vapid_key: Some(key.clone()),
+                ..PushConfig::default()
+            },
+            ..Config::default()
+        };
+        let state = AppState { config: Arc::new(config), ..AppState::for_tests(&dir).await };
+        let app = Router::new()
+            .route("/register", post(register_handler))
+            .route("/favorites", post(create_favorite_handler))
+            .route("/alerts", get(list_alerts_handler).post(create_alert_handler))
+            .route("/alerts/:id", put(update_alert_handler).delete(delete_alert_handler))
+            .route("/push/public-key", get(push_public_key_handler))
+            .route("/push/subscriptions", post(subscribe_push_handler).delete(unsubscribe_push_handler))
+            .with_state(state);
+        let owner = register(&app, "alerts@example.com").await;
+        let stranger = register(&app, "other-alerts@example.com").await;
+
+        let (status, body) = call(&app, "GET", "/push/public-key", None, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["public_key"], key.public_key());
+
+        let spot = serde_json::json!({ "name": "Dam", "lat": 50.46, "lon": 30.52 });
+        let (_, spot) = call(&app, "POST", "/favorites", Some(&owner), Some(spot)).await;
+        let rule = serde_json::json!({
+            "favorite_spot_id": spot["id"],
+            "species": "pike",
+            "min_probability": 0.7,
+            "start_hour": 5,
+            "end_hour": 9,
+            "utc_offset_minutes": 180,
+            "pressure_trend": "falling",
+        });
+        let (status, created) = call(&app, "POST", "/alerts", Some(&owner), Some(rule.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["enabled"], true);
+        assert_eq!(created["pressure_trend"], "falling");
+        assert_eq!(created["last_notified_at"], serde_json::Value::Null);
+        let id = created["id"].as_str().unwrap();
+
+        // Only the owner's spots can carry alerts
+        let (status, body) = call(&app, "POST", "/alerts", Some(&stranger), Some(rule.clone())).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["fields"][0]["field"], "favorite_spot_id");
+
+        let invalid = serde_json::json!({
+            "favorite_spot_id": spot["id"],
+            "species": "kraken",
+            "min_probability": 1.5,
+            "start_hour": 24,
+        });
+        let (status, body) = call(&app, "POST", "/alerts", Some(&owner), Some(invalid)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let fields: Vec<_> = body["fields"].as_array().unwrap().iter().map(|f| f["field"].as_str().unwrap()).collect();
+        assert_eq!(fields, ["min_probability", "start_hour", "species"]);
+
+        let uri = format!("/alerts/{id}");
+        let mut paused = rule.clone();
+        paused["enabled"] = false.into();
+        paused["pressure_trend"] = serde_json::Value::Null;
+        let (status, updated) = call(&app, "PUT", &uri, Some(&owner), Some(paused.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated["enabled"], false);
+        assert_eq!(updated["pressure_trend"], serde_json::Value::Null);
+        assert_eq!(updated["created_at"], created["created_at"]);
+        let (status, _) = call(&app, "PUT", &uri, Some(&stranger), Some(paused)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (_, listed) = call(&app, "GET", "/alerts", Some(&owner), None).await;
+        assert_eq!(listed, serde_json::json!([updated]));
+        let (_, listed) = call(&app, "GET", "/alerts", Some(&stranger), None).await;
+        assert_eq!(listed, serde_json::json!([]));
+
+        let subscription = serde_json::json!({
+            "endpoint": "https://fcm.googleapis.com/fcm/send/abc",
+            "keys": { "p256dh": "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4", "auth": "BTBZMqHH6r4Tts7J_aSIgg" },
+        });
+        let (status, _) = call(&app, "POST", "/push/subscriptions", Some(&owner), Some(subscription)).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let fields = |body: &serde_json::Value| -> Vec<String> {
+            body["fields"].as_array().unwrap().iter().map(|f| f["field"].as_str().unwrap().to_string()).collect()
+        };
+        let insecure = serde_json::json!({
+            "endpoint": "http://fcm.googleapis.com/fcm/send/abc",
+            "keys": { "p256dh": "", "auth": "BTBZMqHH6r4Tts7J_aSIgg" },
+        });
+        let (status, body) = call(&app, "POST", "/push/subscriptions", Some(&owner), Some(insecure)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(fields(&body), ["keys.p256dh", "endpoint"]);
+        for endpoint in ["https://127.0.0.1/send", "https://push.example.com/send", "https://fcm.googleapis.com:8443/send"] {
+            let subscription = serde_json::json!({ "endpoint": endpoint, "keys": { "p256dh": "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4", "auth": "BTBZMqHH6r4Tts7J_aSIgg" } });
+            let (status, body) = call(&app, "POST", "/push/subscriptions", Some(&owner), Some(subscription)).await;
+            assert_eq!((status, fields(&body)), (StatusCode::BAD_REQUEST, vec!["endpoint".to_string()]), "{endpoint}");
+        }
+        let malformed = serde_json::json!({
+            "endpoint": "https://fcm.googleapis.com/fcm/send/abc",
+            "keys": { "p256dh": "BCVxsr7N", "auth": "not base64!" },
+        });
+        let (status, body) = call(&app, "POST", "/push/subscriptions", Some(&owner), Some(malformed)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(fields(&body), ["keys.p256dh", "keys.auth"]);
+
+        let unsubscribe = "/push/subscriptions?endpoint=https%3A%2F%2Ffcm.googleapis.com%2Ffcm%2Fsend%2Fabc";
+        let (status, _) = call(&app, "DELETE", unsubscribe, Some(&stranger), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = call(&app, "DELETE", unsubscribe, Some(&owner), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (status, _) = call(&app, "DELETE", &uri, Some(&stranger), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = call(&app, "DELETE", &uri, Some(&owner), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(&app, "GET", "/alerts", None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
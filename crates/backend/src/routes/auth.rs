@@ -1,77 +1,378 @@
-use axum::{extract::State, response::IntoResponse, Json};
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
 
-use crate::{services::auth::{self, LoginRequest, RegisterRequest}, AppState};
+use crate::{
+    error::{ApiError, ApiResult},
+    extract::ValidJson,
+    services::auth::{
+        self, AuthResponse, Claims, ClientInfo, LoginRequest, PasswordResetConfirm,
+        PasswordResetRequest, RefreshRequest, RegisterRequest, SessionResponse, UserResponse,
+        VerifyEmailRequest,
+    },
+    services::oidc::{self, AuthorizeResponse, CallbackRequest, IdentityResponse, ProviderResponse},
+    AppState,
+};
 
 /// Register new user
-/// POST /api/v1/auth/register
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "Account created and logged in", body = AuthResponse),
+        (status = 400, description = "Invalid fields", body = ApiErrorBody),
+        (status = 409, description = "Email already registered", body = ApiErrorBody),
+    )
+)]
 pub async fn register_handler(
     State(state): State<AppState>,
-    Json(request): Json<RegisterRequest>,
-) -> impl IntoResponse {
-    match auth::register(&state, request).await {
-        Ok(response) => (axum::http::StatusCode::CREATED, Json(response)).into_response(),
-        Err(err) => {
-            let body = serde_json::json!({ "error": err.to_string() });
-            (axum::http::StatusCode::BAD_REQUEST, Json(body)).into_response()
-        }
-    }
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<RegisterRequest>,
+) -> ApiResult<(StatusCode, Json<AuthResponse>)> {
+    let response = auth::register(&state, request, client_info(&headers)).await?;
+    Ok((StatusCode::CREATED, Json(response)))
 }
 
 /// Login user
-/// POST /api/v1/auth/login
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Logged in", body = AuthResponse),
+        (status = 401, description = "Wrong email or password", body = ApiErrorBody),
+        (status = 429, description = "Account locked after failed attempts", body = ApiErrorBody),
+    )
+)]
 pub async fn login_handler(
     State(state): State<AppState>,
-    Json(request): Json<LoginRequest>,
-) -> impl IntoResponse {
-    match auth::login(&state, request).await {
-        Ok(response) => (axum::http::StatusCode::OK, Json(response)).into_response(),
-        Err(err) => {
-            let body = serde_json::json!({ "error": err.to_string() });
-            (axum::http::StatusCode::UNAUTHORIZED, Json(body)).into_response()
-        }
-    }
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<LoginRequest>,
+) -> ApiResult<Json<AuthResponse>> {
+    Ok(Json(auth::login(&state, request, client_info(&headers)).await?))
 }
 
 /// Get current user profile
-/// GET /api/v1/auth/me
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/me",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Current user", body = UserResponse),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn me_handler(
     State(state): State<AppState>,
-    headers: axum::http::HeaderMap,
-) -> impl IntoResponse {
-    // Extract token from Authorization header
-    let token = match extract_token(&headers) {
-        Some(t) => t,
-        None => {
-            let body = serde_json::json!({ "error": "Missing authorization header" });
-            return (axum::http::StatusCode::UNAUTHORIZED, Json(body)).into_response();
-        }
-    };
-    
-    // Validate token
-    let claims = match auth::validate_token(&token) {
-        Ok(c) => c,
-        Err(err) => {
-            let body = serde_json::json!({ "error": err.to_string() });
-            return (axum::http::StatusCode::UNAUTHORIZED, Json(body)).into_response();
-        }
+    headers: HeaderMap,
+) -> ApiResult<Json<UserResponse>> {
+    let claims = authenticate(&headers)?;
+
+    auth::get_user_by_id(&state, &claims.sub)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound("User not found".into()))
+}
+
+/// Exchange a refresh token for a new token pair
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    tag = "auth",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "New token pair", body = AuthResponse),
+        (status = 401, description = "Refresh token invalid, expired or reused", body = ApiErrorBody),
+    )
+)]
+pub async fn refresh_handler(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<RefreshRequest>,
+) -> ApiResult<Json<AuthResponse>> {
+    Ok(Json(auth::refresh(&state, request).await?))
+}
+
+/// Revoke the session of a refresh token
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    tag = "auth",
+    request_body = RefreshRequest,
+    responses(
+        (status = 204, description = "Session revoked"),
+    )
+)]
+pub async fn logout_handler(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<RefreshRequest>,
+) -> ApiResult<StatusCode> {
+    auth::logout(&state, request).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List active sessions of the current user
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/sessions",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Active sessions", body = [SessionResponse]),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn sessions_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> ApiResult<Json<Vec<SessionResponse>>> {
+    let claims = authenticate(&headers)?;
+    Ok(Json(auth::list_sessions(&state, &claims).await?))
+}
+
+/// Revoke one of the current user's sessions
+#[utoipa::path(
+    delete,
+    path = "/api/v1/auth/sessions/{id}",
+    tag = "auth",
+    params(("id" = String, Path, description = "Session id")),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+        (status = 404, description = "No such session", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn revoke_session_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let claims = authenticate(&headers)?;
+
+    if auth::revoke_session(&state, &claims, &session_id).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::NotFound("Session not found".into()))
+    }
+}
+
+/// Send a new verification email to the current user
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify-email/request",
+    tag = "auth",
+    responses(
+        (status = 202, description = "Verification email sent"),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn request_email_verification_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> ApiResult<StatusCode> {
+    let claims = authenticate(&headers)?;
+    auth::request_email_verification(&state, &claims).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Confirm an email address
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify-email/confirm",
+    tag = "auth",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 200, description = "Email verified", body = UserResponse),
+        (status = 400, description = "Token invalid or expired", body = ApiErrorBody),
+    )
+)]
+pub async fn confirm_email_handler(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<VerifyEmailRequest>,
+) -> ApiResult<Json<UserResponse>> {
+    Ok(Json(auth::confirm_email(&state, request).await?))
+}
+
+/// Email a password reset link
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/password-reset/request",
+    tag = "auth",
+    request_body = PasswordResetRequest,
+    responses(
+        (status = 202, description = "Reset link sent if the account exists"),
+    )
+)]
+pub async fn request_password_reset_handler(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<PasswordResetRequest>,
+) -> ApiResult<StatusCode> {
+    auth::request_password_reset(&state, request).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Set a new password with a reset token
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/password-reset/confirm",
+    tag = "auth",
+    request_body = PasswordResetConfirm,
+    responses(
+        (status = 204, description = "Password changed; all sessions revoked"),
+        (status = 400, description = "Token invalid or expired", body = ApiErrorBody),
+    )
+)]
+pub async fn confirm_password_reset_handler(
+    State(state): State<AppState>,
+    ValidJson(request): ValidJson<PasswordResetConfirm>,
+) -> ApiResult<StatusCode> {
+    auth::confirm_password_reset(&state, request).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the configured OIDC providers
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/oidc/providers",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Configured providers", body = [ProviderResponse]),
+    )
+)]
+pub async fn oidc_providers_handler(State(state): State<AppState>) -> Json<Vec<ProviderResponse>> {
+    let providers = state
+        .oidc
+        .providers()
+        .iter()
+        .map(|p| ProviderResponse {
+            id: p.id.clone(),
+            name: p.name.clone(),
+        })
+        .collect();
+
+    Json(providers)
+}
+
+/// Start an OIDC login
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/oidc/{provider}/authorize",
+    tag = "auth",
+    params(("provider" = String, Path, description = "Provider id")),
+    responses(
+        (status = 200, description = "Provider login URL", body = AuthorizeResponse),
+        (status = 404, description = "Unknown provider", body = ApiErrorBody),
+        (status = 502, description = "Provider unreachable", body = ApiErrorBody),
+    )
+)]
+pub async fn oidc_authorize_handler(
+    State(state): State<AppState>,
+    Path(provider_id): Path<String>,
+) -> ApiResult<Json<AuthorizeResponse>> {
+    let provider = state
+        .oidc
+        .provider(&provider_id)
+        .ok_or_else(|| ApiError::NotFound("Unknown identity provider".into()))?;
+
+    oidc::authorize(&state, provider)
+        .await
+        .map(Json)
+        .map_err(ApiError::Upstream)
+}
+
+/// Finish an OIDC login; with a bearer token, link the identity instead
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/oidc/{provider}/callback",
+    tag = "auth",
+    params(("provider" = String, Path, description = "Provider id")),
+    request_body = CallbackRequest,
+    responses(
+        (status = 200, description = "Logged in or identity linked", body = AuthResponse),
+        (status = 401, description = "Login state or provider response rejected", body = ApiErrorBody),
+        (status = 404, description = "Unknown provider", body = ApiErrorBody),
+        (status = 409, description = "Identity or email belongs to another account", body = ApiErrorBody),
+    ),
+    security((), ("bearer" = []))
+)]
+pub async fn oidc_callback_handler(
+    State(state): State<AppState>,
+    Path(provider_id): Path<String>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<CallbackRequest>,
+) -> ApiResult<Json<AuthResponse>> {
+    let provider = state
+        .oidc
+        .provider(&provider_id)
+        .ok_or_else(|| ApiError::NotFound("Unknown identity provider".into()))?;
+    let link_to = authenticate_if_present(&headers)?;
+
+    let response = oidc::callback(&state, provider, request, link_to.as_ref(), client_info(&headers)).await?;
+    Ok(Json(response))
+}
+
+/// External identities linked to the current user
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/identities",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Linked identities", body = [IdentityResponse]),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn identities_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> ApiResult<Json<Vec<IdentityResponse>>> {
+    let claims = authenticate(&headers)?;
+    Ok(Json(oidc::list_identities(&state, &claims).await?))
+}
+
+/// Validate the bearer token
+pub(crate) fn authenticate(headers: &HeaderMap) -> ApiResult<Claims> {
+    let token = extract_token(headers)
+        .ok_or_else(|| ApiError::Unauthorized("Missing authorization header".into()))?;
+
+    Ok(auth::validate_token(&token)?)
+}
+
+/// Validate the bearer token if one was sent; anonymous requests give `None`
+pub(crate) fn authenticate_if_present(headers: &HeaderMap) -> ApiResult<Option<Claims>> {
+    match extract_token(headers) {
+        Some(_) => authenticate(headers).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Client details recorded on new sessions
+fn client_info(headers: &HeaderMap) -> ClientInfo {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
     };
-    
-    // Get user
-    match auth::get_user_by_id(&state, &claims.sub).await {
-        Ok(Some(user)) => (axum::http::StatusCode::OK, Json(user)).into_response(),
-        Ok(None) => {
-            let body = serde_json::json!({ "error": "User not found" });
-            (axum::http::StatusCode::NOT_FOUND, Json(body)).into_response()
-        }
-        Err(err) => {
-            let body = serde_json::json!({ "error": err.to_string() });
-            (axum::http::StatusCode::INTERNAL_SERVER_ERROR, Json(body)).into_response()
-        }
+
+    ClientInfo {
+        user_agent: header("user-agent"),
+        ip_address: header("x-forwarded-for")
+            .and_then(|v| v.split(',').next().map(|ip| ip.trim().to_string()))
+            .or_else(|| header("x-real-ip")),
     }
 }
 
 /// Extract JWT token from Authorization header
-fn extract_token(headers: &axum::http::HeaderMap) -> Option<String> {
+fn extract_token(headers: &HeaderMap) -> Option<String> {
     headers
         .get(axum::http::header::AUTHORIZATION)?
         .to_str()
@@ -79,3 +380,266 @@ fn extract_token(headers: &axum::http::HeaderMap) -> Option<String> {
         .filter(|s| s.starts_with("Bearer "))
         .map(|s| s.trim_start_matches("Bearer ").to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+        routing::{delete, get, post},
+        Router,
+    };
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    use crate::services::mailer::FileMailer;
+
+    async fn test_app() -> (Router, tempfile::TempDir) {
+        auth::init_jwt_secret().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState {
+            mailer: Arc::new(FileMailer::new(dir.path().join("outbox"))),
+            ..AppState::for_tests(&dir).await
+        };
+        let app = Router::new()
+            .route("/register", post(register_handler))
+            .route("/login", post(login_handler))
+            .route("/me", get(me_handler))
+            .route("/refresh", post(refresh_handler))
+            .route("/logout", post(logout_handler))
+            .route("/sessions", get(sessions_handler))
+            .route("/sessions/:id", delete(revoke_session_handler))
+            .route("/verify-email/request", post(request_email_verification_handler))
+            .route("/verify-email/confirm", post(confirm_email_handler))
+            .route("/password-reset/request", post(request_password_reset_handler))
+            .route("/password-reset/confirm", post(confirm_password_reset_handler))
+            .with_state(state);
+        (app, dir)
+    }
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .header("user-agent", "test-agent");
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {}", token));
+        }
+        let body = body.map(|b| Body::from(b.to_string())).unwrap_or_else(Body::empty);
+        let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+        (status, json)
+    }
+
+    /// Remove the emails sent so far and return the tokens in their links.
+    fn take_mailed_tokens(dir: &tempfile::TempDir) -> Vec<(String, String)> {
+        let outbox = dir.path().join("outbox");
+        let Ok(entries) = std::fs::read_dir(&outbox) else {
+            return Vec::new();
+        };
+        entries
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let text = std::fs::read_to_string(&path).unwrap();
+                std::fs::remove_file(path).unwrap();
+                let to = text.lines().next().unwrap().trim_start_matches("To: ").to_string();
+                let token = text.split("?token=").nth(1).unwrap().lines().next().unwrap().to_string();
+                (to, token)
+            })
+            .collect()
+    }
+
+    fn refresh_body(token: &serde_json::Value) -> Option<serde_json::Value> {
+        Some(serde_json::json!({ "refresh_token": token }))
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rotation_sessions_and_logout() {
+        let (app, _dir) = test_app().await;
+        let credentials = serde_json::json!({ "email": "angler@example.com", "password": "s3cret-pass" });
+
+        let (status, registered) = call(&app, "POST", "/register", None, Some(credentials.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(registered["token_type"], "Bearer");
+        let (status, taken) = call(&app, "POST", "/register", None, Some(credentials.clone())).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(taken["code"], "email_taken");
+        let first_refresh = registered["refresh_token"].clone();
+
+        // Rotation: the new pair works, the old refresh token does not.
+        let (status, rotated) = call(&app, "POST", "/refresh", None, refresh_body(&first_refresh)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_ne!(rotated["refresh_token"], first_refresh);
+        let (status, me) = call(&app, "GET", "/me", rotated["token"].as_str(), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(me["email"], "angler@example.com");
+
+        // Reusing a spent token revokes the session, so the rotated one dies too.
+        let (status, _) = call(&app, "POST", "/refresh", None, refresh_body(&first_refresh)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = call(&app, "POST", "/refresh", None, refresh_body(&rotated["refresh_token"])).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // A fresh login shows up as the current session.
+        let (status, login) = call(&app, "POST", "/login", None, Some(credentials.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, other) = call(&app, "POST", "/login", None, Some(credentials)).await;
+        let access = login["token"].as_str().unwrap();
+        let (status, sessions) = call(&app, "GET", "/sessions", Some(access), None).await;
+        assert_eq!(status, StatusCode::OK);
+        let sessions = sessions.as_array().unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions.iter().filter(|s| s["current"] == true).count(), 1);
+        assert_eq!(sessions[0]["user_agent"], "test-agent");
+
+        // Revoke the other session by id, then log out of this one.
+        let other_id = sessions.iter().find(|s| s["current"] == false).unwrap()["id"].clone();
+        let uri = format!("/sessions/{}", other_id.as_str().unwrap());
+        let (status, _) = call(&app, "DELETE", &uri, Some(access), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(&app, "DELETE", &uri, Some(access), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = call(&app, "POST", "/refresh", None, refresh_body(&other["refresh_token"])).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = call(&app, "POST", "/logout", None, refresh_body(&login["refresh_token"])).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(&app, "POST", "/refresh", None, refresh_body(&login["refresh_token"])).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (_, sessions) = call(&app, "GET", "/sessions", Some(access), None).await;
+        assert_eq!(sessions.as_array().unwrap().len(), 0);
+
+        let (status, _) = call(&app, "GET", "/sessions", None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_email_verification() {
+        let (app, dir) = test_app().await;
+        let credentials = serde_json::json!({ "email": "new@example.com", "password": "s3cret-pass" });
+
+        let (status, registered) = call(&app, "POST", "/register", None, Some(credentials)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(registered["user"]["email_verified"], false);
+        let access = registered["token"].as_str().unwrap();
+
+        // Asking again replaces the link sent at registration.
+        let first = take_mailed_tokens(&dir);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].0, "new@example.com");
+        let (status, _) = call(&app, "POST", "/verify-email/request", Some(access), None).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let second = take_mailed_tokens(&dir);
+        assert_eq!(second.len(), 1);
+
+        let confirm = |token: &str| Some(serde_json::json!({ "token": token }));
+        let (status, _) = call(&app, "POST", "/verify-email/confirm", None, confirm(&first[0].1)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "superseded token");
+        let (status, user) = call(&app, "POST", "/verify-email/confirm", None, confirm(&second[0].1)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(user["email_verified"], true);
+        let (status, _) = call(&app, "POST", "/verify-email/confirm", None, confirm(&second[0].1)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "single use");
+
+        let (_, me) = call(&app, "GET", "/me", Some(access), None).await;
+        assert_eq!(me["email_verified"], true);
+        let (status, _) = call(&app, "POST", "/verify-email/request", Some(access), None).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(take_mailed_tokens(&dir).is_empty(), "already verified");
+
+        let (status, _) = call(&app, "POST", "/verify-email/request", None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_password_reset() {
+        let (app, dir) = test_app().await;
+        let old = serde_json::json!({ "email": "reset@example.com", "password": "old-password" });
+        let new = serde_json::json!({ "email": "reset@example.com", "password": "new-password" });
+
+        let (_, registered) = call(&app, "POST", "/register", None, Some(old.clone())).await;
+        take_mailed_tokens(&dir);
+
+        // Unknown addresses get the same answer and no email.
+        let request = |email: &str| Some(serde_json::json!({ "email": email }));
+        let (status, _) = call(&app, "POST", "/password-reset/request", None, request("nobody@example.com")).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(take_mailed_tokens(&dir).is_empty());
+
+        let (status, _) = call(&app, "POST", "/password-reset/request", None, request("reset@example.com")).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let mailed = take_mailed_tokens(&dir);
+        assert_eq!(mailed.len(), 1);
+
+        let confirm = |token: &str| Some(serde_json::json!({ "token": token, "new_password": "new-password" }));
+        let (status, _) = call(&app, "POST", "/password-reset/confirm", None, confirm("bogus")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = call(&app, "POST", "/password-reset/confirm", None, confirm(&mailed[0].1)).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(&app, "POST", "/password-reset/confirm", None, confirm(&mailed[0].1)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "single use");
+
+        // Existing sessions end; only the new password logs in.
+        let (status, _) = call(&app, "POST", "/refresh", None, refresh_body(&registered["refresh_token"])).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = call(&app, "POST", "/login", None, Some(old)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, login) = call(&app, "POST", "/login", None, Some(new)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(login["user"]["email_verified"], true);
+    }
+
+    #[tokio::test]
+    async fn test_login_lockout() {
+        let (app, _dir) = test_app().await;
+        let attempt = Some(serde_json::json!({ "email": "Ghost@example.com", "password": "guess" }));
+
+        for _ in 0..5 {
+            let (status, _) = call(&app, "POST", "/login", None, attempt.clone()).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+        let (status, body) = call(&app, "POST", "/login", None, attempt).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["code"], "account_locked");
+        assert_eq!(body["retry_after"], 30);
+
+        // The lockout follows the account, not the spelling of the email.
+        let again = Some(serde_json::json!({ "email": "ghost@example.com ", "password": "guess" }));
+        let (status, _) = call(&app, "POST", "/login", None, again).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_emails_ignore_case_and_spaces() {
+        let (app, dir) = test_app().await;
+        let credentials = |email: &str| Some(serde_json::json!({ "email": email, "password": "s3cret-pass" }));
+
+        let (status, registered) = call(&app, "POST", "/register", None, credentials(" Mixed@Example.COM")).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(registered["user"]["email"], "mixed@example.com");
+        let (status, _) = call(&app, "POST", "/register", None, credentials("MIXED@example.com")).await;
+        assert_eq!(status, StatusCode::CONFLICT, "same account, other spelling");
+        take_mailed_tokens(&dir);
+
+        let (status, login) = call(&app, "POST", "/login", None, credentials("mixed@EXAMPLE.com ")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(login["user"]["id"], registered["user"]["id"]);
+
+        let request = Some(serde_json::json!({ "email": "Mixed@Example.com" }));
+        let (status, _) = call(&app, "POST", "/password-reset/request", None, request).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let mailed = take_mailed_tokens(&dir);
+        assert_eq!(mailed.len(), 1);
+        assert_eq!(mailed[0].0, "mixed@example.com");
+    }
+}
@@ -1,78 +1,411 @@
-use axum::{extract::Query, response::IntoResponse, Json};
+use axum::{
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use fishing_shared::types::{CatchPrivacy, CatchRecord, FieldError, NewCatchRecord};
+use fishing_shared::validation::{codes, Validate, Validator};
 use serde::Deserialize;
-use uuid::Uuid;
+use utoipa::IntoParams;
 
-use crate::{db::queries, models::NewCatchRecord, AppState};
+use crate::{
+    error::{ApiError, ApiResult},
+    extract::{ValidJson, ValidQuery},
+    routes::auth::{authenticate, authenticate_if_present},
+    services::{environment::capture_snapshot, profile},
+    AppState,
+};
 
 /// Query parameters for fetching user catches.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct CatchesQuery {
-    pub user_id: Uuid,
+    pub user_id: String,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
 
 /// Query parameters for nearby catches.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct NearbyCatchesQuery {
     pub lat: f64,
     pub lon: f64,
     pub radius_km: f64,
 }
 
-/// Save a new catch record.
+impl Validate for CatchesQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.required("user_id", &self.user_id);
+    }
+}
+
+impl Validate for NearbyCatchesQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("lat", self.lat)
+            .longitude("lon", self.lon)
+            .radius_km("radius_km", self.radius_km);
+    }
+}
+
+/// Save a new catch record with the conditions at its time and place.
+///
+/// With a bearer token the catch belongs to the signed-in user and gets their
+/// default privacy unless the payload sets one; without a token it is saved
+/// anonymously and public. The payload's `user_id` is ignored either way.
+///
+/// A catch sent again with the same client `id` is answered with the stored
+/// record instead of being saved twice, so offline clients can retry freely.
+#[utoipa::path(
+    post,
+    path = "/api/v1/catches",
+    tag = "catches",
+    request_body = NewCatchRecord,
+    responses(
+        (status = 201, description = "Saved catch with captured conditions", body = CatchRecord),
+        (status = 200, description = "Catch with this id was already saved", body = CatchRecord),
+        (status = 400, description = "Invalid fields", body = ApiErrorBody),
+        (status = 401, description = "Invalid or expired token", body = ApiErrorBody),
+        (status = 409, description = "The id belongs to another user's catch", body = ApiErrorBody),
+    ),
+    security((), ("bearer" = []))
+)]
 pub async fn save_catch_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
-    Json(payload): Json<NewCatchRecord>,
-) -> impl IntoResponse {
-    match queries::insert_catch(&state.db, &payload).await {
-        Ok(saved) => {
-            use crate::models::CatchRecordDb;
-            Json::<CatchRecordDb>(saved).into_response()
+    headers: HeaderMap,
+    ValidJson(mut payload): ValidJson<NewCatchRecord>,
+) -> ApiResult<(StatusCode, Json<CatchRecord>)> {
+    match authenticate_if_present(&headers)? {
+        Some(claims) => {
+            if payload.privacy.is_none() {
+                let profile = profile::get_profile(&state, &claims.sub).await?;
+                payload.privacy = Some(profile.map(|p| p.default_privacy).unwrap_or_default());
+            }
+            payload.user_id = claims.sub;
         }
-        Err(err) => {
-            let error_msg = err.to_string();
-            let body: serde_json::Value = serde_json::json!({"error": error_msg});
-            (axum::http::StatusCode::BAD_REQUEST, Json(body)).into_response()
+        None => {
+            payload.user_id = String::new();
+            payload.privacy = Some(CatchPrivacy::Public);
         }
     }
+    if let Some(existing) = already_saved(&state, &payload).await? {
+        return Ok((StatusCode::OK, Json(existing)));
+    }
+
+    if !state.db.fish_species_exists(&payload.fish_species).await? {
+        let message = format!("Unknown fish species: {}", payload.fish_species);
+        return Err(ApiError::Validation(vec![FieldError::new("fish_species", codes::UNKNOWN, message)]));
+    }
+
+    let environment =
+        capture_snapshot(&state.http, payload.lat, payload.lon, payload.caught_at).await;
+    payload.weather_temp = environment.temperature_c.or(payload.weather_temp);
+    payload.weather_pressure = environment.pressure_hpa.or(payload.weather_pressure);
+    payload.moon_phase = Some(environment.moon_phase);
+
+    match state.db.insert_catch(&payload, Some(&environment)).await? {
+        Some(saved) => Ok((StatusCode::CREATED, Json(CatchRecord::from(saved)))),
+        // Another request with the same id won the race.
+        None => already_saved(&state, &payload)
+            .await?
+            .map(|existing| (StatusCode::OK, Json(existing)))
+            .ok_or_else(|| ApiError::Internal(anyhow::anyhow!("catch vanished after a conflicting insert"))),
+    }
+}
+
+/// The stored catch for a replayed client id, if it is the sender's own
+/// (`payload.user_id` is the signed-in user, empty when anonymous).
+async fn already_saved(state: &AppState, payload: &NewCatchRecord) -> ApiResult<Option<CatchRecord>> {
+    let Some(id) = payload.id.as_deref() else {
+        return Ok(None);
+    };
+    match state.db.get_catch(id).await? {
+        Some(existing) if existing.user_id.as_deref().unwrap_or_default() == payload.user_id => Ok(Some(existing.into())),
+        Some(_) => Err(ApiError::Conflict(format!("Catch {} already exists", id))),
+        None => Ok(None),
+    }
 }
 
-/// Get catches for a user.
+/// Get catches of the signed-in user; `user_id` must be their own id.
+#[utoipa::path(
+    get,
+    path = "/api/v1/catches",
+    tag = "catches",
+    params(CatchesQuery),
+    responses(
+        (status = 200, description = "Catches, newest first", body = [CatchRecord]),
+        (status = 400, description = "Invalid query", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+        (status = 403, description = "Catches of another user", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
 pub async fn get_catches_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
-    Query(query): Query<CatchesQuery>,
-) -> impl IntoResponse {
+    headers: HeaderMap,
+    ValidQuery(query): ValidQuery<CatchesQuery>,
+) -> ApiResult<Json<Vec<CatchRecord>>> {
+    if authenticate(&headers)?.sub != query.user_id {
+        return Err(ApiError::Forbidden("Only your own catches can be listed".into()));
+    }
     let limit = query.limit.unwrap_or(50).clamp(1, 200);
     let offset = query.offset.unwrap_or(0).max(0);
 
-    match queries::get_catches_by_user(&state.db, query.user_id, limit, offset).await {
-        Ok(rows) => {
-            use crate::models::CatchRecordDb;
-            Json::<Vec<CatchRecordDb>>(rows).into_response()
-        }
-        Err(err) => {
-            let error_msg = err.to_string();
-            let body: serde_json::Value = serde_json::json!({"error": error_msg});
-            (axum::http::StatusCode::BAD_REQUEST, Json(body)).into_response()
-        }
-    }
+    let rows = state.db.get_catches_by_user(&query.user_id, limit, offset).await?;
+    Ok(Json(rows.into_iter().map(CatchRecord::from).collect()))
 }
 
 /// Get nearby public catches.
+#[utoipa::path(
+    get,
+    path = "/api/v1/catches/nearby",
+    tag = "catches",
+    params(NearbyCatchesQuery),
+    responses(
+        (status = 200, description = "Public catches within the radius", body = [CatchRecord]),
+        (status = 400, description = "Invalid query", body = ApiErrorBody),
+    )
+)]
 pub async fn get_nearby_catches_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
-    Query(query): Query<NearbyCatchesQuery>,
-) -> impl IntoResponse {
-    match queries::get_nearby_catches(&state.db, query.lat, query.lon, query.radius_km).await {
-        Ok(rows) => {
-            use crate::models::CatchRecordDb;
-            Json::<Vec<CatchRecordDb>>(rows).into_response()
-        }
-        Err(err) => {
-            let error_msg = err.to_string();
-            let body: serde_json::Value = serde_json::json!({"error": error_msg});
-            (axum::http::StatusCode::BAD_REQUEST, Json(body)).into_response()
-        }
+    ValidQuery(query): ValidQuery<NearbyCatchesQuery>,
+) -> ApiResult<Json<Vec<CatchRecord>>> {
+    let rows = state.db.get_nearby_catches(query.lat, query.lon, query.radius_km).await?;
+    Ok(Json(rows.into_iter().map(CatchRecord::from).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+        routing::{get, post},
+        Router,
+    };
+    use fishing_shared::types::Role;
+    use tower::ServiceExt;
+
+    use crate::services::auth;
+
+    async fn test_app() -> (Router, tempfile::TempDir) {
+        auth::init_jwt_secret().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        // Weather lookups fail fast so saved catches carry only astronomy.
+        let offline = reqwest::Client::builder()
+            .proxy(reqwest::Proxy::all("http://127.0.0.1:9").unwrap())
+            .build()
+            .unwrap();
+        let state = AppState { http: offline, ..AppState::for_tests(&dir).await };
+        let app = Router::new()
+            .route("/api/v1/catches", post(save_catch_handler).get(get_catches_handler))
+            .route("/api/v1/catches/nearby", get(get_nearby_catches_handler))
+            .with_state(state);
+        (app, dir)
+    }
+
+    async fn send(app: &Router, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    /// Sign the request in as `user` if given.
+    fn signed_in(request: axum::http::request::Builder, user: Option<&str>) -> axum::http::request::Builder {
+        let Some(user) = user else {
+            return request;
+        };
+        let email = format!("{}@example.com", user);
+        let token = auth::generate_token(user, &email, Role::User, "session").unwrap();
+        request.header("authorization", format!("Bearer {}", token))
+    }
+
+    /// POST a catch, signed in as `user` if given.
+    fn post_json(body: &serde_json::Value, user: Option<&str>) -> Request<Body> {
+        let request = Request::post("/api/v1/catches").header("content-type", "application/json");
+        signed_in(request, user).body(Body::from(body.to_string())).unwrap()
+    }
+
+    /// GET a user's catches, signed in as `user` if given.
+    fn list(user_id: &str, user: Option<&str>) -> Request<Body> {
+        let request = Request::get(format!("/api/v1/catches?user_id={}", user_id));
+        signed_in(request, user).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_catch_round_trip_over_http() {
+        let (app, _dir) = test_app().await;
+        let payload = serde_json::json!({
+            "lat": 50.4501,
+            "lon": 30.5234,
+            "caught_at": "2026-05-14T05:30:00Z",
+            "fish_species": "zander",
+            "weight_kg": 1.8,
+            "length_cm": 52.0,
+            "bait_used": "jig",
+            "bite_intensity": 5,
+            "weather_temp": 14.0,
+            "weather_pressure": 1019.5,
+            "moon_phase": 0.1,
+            "notes": "Світанок",
+            "photo_url": null,
+            "privacy": "public"
+        });
+
+        let (status, saved) = send(&app, post_json(&payload, Some("angler-1"))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let saved: CatchRecord = serde_json::from_value(saved).unwrap();
+        assert_eq!(saved.user_id, "angler-1");
+        let sent: NewCatchRecord = serde_json::from_value(payload).unwrap();
+        assert_eq!(saved.fish_species, sent.fish_species);
+        assert_eq!(saved.bite_intensity, Some(5));
+
+        let environment = saved.environment.clone().expect("snapshot attached");
+        assert_eq!(environment.temperature_c, Some(14.0), "client value kept without lookup");
+        assert_eq!(environment.wind_speed_ms, None);
+        assert!(environment.sun_elevation_deg > 0.0, "05:30 UTC in May is after sunrise in Kyiv");
+        assert_eq!(saved.moon_phase, Some(environment.moon_phase));
+
+        let (status, _) = send(&app, list("angler-1", None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send(&app, list("angler-1", Some("angler-2"))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, listed) = send(&app, list("angler-1", Some("angler-1"))).await;
+        assert_eq!(status, StatusCode::OK);
+        let listed: Vec<CatchRecord> = serde_json::from_value(listed).unwrap();
+        assert_eq!(listed, vec![saved.clone()]);
+
+        let nearby = Request::get("/api/v1/catches/nearby?lat=50.45&lon=30.52&radius_km=3")
+            .body(Body::empty())
+            .unwrap();
+        let (_, found) = send(&app, nearby).await;
+        let found: Vec<CatchRecord> = serde_json::from_value(found).unwrap();
+        assert_eq!(found, vec![saved]);
+    }
+
+    #[tokio::test]
+    async fn test_catch_without_token_is_saved_anonymously() {
+        let (app, _dir) = test_app().await;
+        let payload = serde_json::json!({
+            "id": "0b7e3f52-4c1d-4f6a-8d2e-9a1b2c3d4e5f",
+            "user_id": "angler-1",
+            "lat": 50.45,
+            "lon": 30.52,
+            "caught_at": "2026-05-14T05:30:00Z",
+            "fish_species": "pike"
+        });
+
+        let (status, saved) = send(&app, post_json(&payload, None)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(saved["user_id"], "", "the payload cannot name an owner");
+        assert_eq!(saved["privacy"], "public");
+        let (status, again) = send(&app, post_json(&payload, None)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(again, saved);
+
+        // A signed-in user cannot claim the anonymous catch
+        let (status, _) = send(&app, post_json(&payload, Some("angler-1"))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // A token that was sent must be valid, so clients know to refresh it
+        let expired = Request::post("/api/v1/catches")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer not-a-token")
+            .body(Body::from(payload.to_string()))
+            .unwrap();
+        let (status, _) = send(&app, expired).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_replayed_catch_is_stored_once() {
+        let (app, _dir) = test_app().await;
+        let payload = serde_json::json!({
+            "id": "6f1c2d0e-8a4b-4c3d-9e5f-1a2b3c4d5e6f",
+            "user_id": "angler-1",
+            "lat": 50.45,
+            "lon": 30.52,
+            "caught_at": "2026-05-14T05:30:00Z",
+            "fish_species": "pike"
+        });
+
+        let (status, first) = send(&app, post_json(&payload, Some("angler-1"))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(first["id"], payload["id"]);
+
+        let (status, again) = send(&app, post_json(&payload, Some("angler-1"))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(again, first);
+
+        let (_, listed) = send(&app, list("angler-1", Some("angler-1"))).await;
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+
+        // Claiming the owner's id in the payload does not help another user
+        let (status, body) = send(&app, post_json(&payload, Some("angler-2"))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], "conflict");
+
+        let mut malformed = payload;
+        malformed["id"] = "not-a-uuid".into();
+        let (status, body) = send(&app, post_json(&malformed, Some("angler-1"))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["fields"][0]["field"], "id");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_out_of_range_bite_intensity() {
+        let (app, _dir) = test_app().await;
+        let payload = serde_json::json!({
+            "lat": 50.45,
+            "lon": 30.52,
+            "caught_at": "2026-05-14T05:30:00Z",
+            "fish_species": "pike",
+            "bite_intensity": 9
+        });
+        let (status, _) = send(&app, post_json(&payload, Some("angler-1"))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_reports_every_invalid_field() {
+        let (app, _dir) = test_app().await;
+        let payload = serde_json::json!({
+            "lat": 500.0,
+            "lon": 30.52,
+            "caught_at": "2099-01-01T00:00:00Z",
+            "fish_species": "pike",
+            "weight_kg": -2.0
+        });
+        let (status, body) = send(&app, post_json(&payload, Some("angler-1"))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "validation_failed");
+        let fields: Vec<_> = body["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| (f["field"].as_str().unwrap(), f["code"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            fields,
+            [("lat", "out_of_range"), ("caught_at", "in_future"), ("weight_kg", "not_positive")]
+        );
+
+        let unknown = serde_json::json!({
+            "lat": 50.45,
+            "lon": 30.52,
+            "caught_at": "2026-05-14T05:30:00Z",
+            "fish_species": "kraken"
+        });
+        let (status, body) = send(&app, post_json(&unknown, Some("angler-1"))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["fields"][0]["field"], "fish_species");
+        assert_eq!(body["fields"][0]["code"], "unknown");
+
+        let nearby = Request::get("/api/v1/catches/nearby?lat=50.45&lon=30.52&radius_km=1000000")
+            .body(Body::empty())
+            .unwrap();
+        let (status, body) = send(&app, nearby).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["fields"][0]["field"], "radius_km");
     }
 }
@@ -0,0 +1,231 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use fishing_shared::types::{FavoriteSpot, FavoriteSpotInput};
+
+use crate::{
+    error::{ApiError, ApiResult},
+    routes::auth::authenticate,
+    services::favorites,
+    AppState,
+};
+
+/// List the current user's favorite spots
+#[utoipa::path(
+    get,
+    path = "/api/v1/favorites",
+    tag = "favorites",
+    responses(
+        (status = 200, description = "Favorite spots, ordered by name", body = [FavoriteSpot]),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn list_favorites_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> ApiResult<Json<Vec<FavoriteSpot>>> {
+    let claims = authenticate(&headers)?;
+
+    Ok(Json(favorites::list(&state, &claims.sub).await?))
+}
+
+/// Save a favorite spot
+#[utoipa::path(
+    post,
+    path = "/api/v1/favorites",
+    tag = "favorites",
+    request_body = FavoriteSpotInput,
+    responses(
+        (status = 201, description = "Saved spot", body = FavoriteSpot),
+        (status = 400, description = "Invalid fields", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn create_favorite_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(input): Json<FavoriteSpotInput>,
+) -> ApiResult<(StatusCode, Json<FavoriteSpot>)> {
+    let claims = authenticate(&headers)?;
+
+    let problems = favorites::validate_input(&state, &input).await?;
+    if !problems.is_empty() {
+        return Err(ApiError::Validation(problems));
+    }
+
+    let spot = favorites::create(&state, &claims.sub, input).await?;
+    Ok((StatusCode::CREATED, Json(spot)))
+}
+
+/// Replace a favorite spot
+#[utoipa::path(
+    put,
+    path = "/api/v1/favorites/{id}",
+    tag = "favorites",
+    params(("id" = String, Path, description = "Spot id")),
+    request_body = FavoriteSpotInput,
+    responses(
+        (status = 200, description = "Updated spot", body = FavoriteSpot),
+        (status = 400, description = "Invalid fields", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+        (status = 404, description = "No such spot of the current user", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn update_favorite_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(input): Json<FavoriteSpotInput>,
+) -> ApiResult<Json<FavoriteSpot>> {
+    let claims = authenticate(&headers)?;
+
+    let problems = favorites::validate_input(&state, &input).await?;
+    if !problems.is_empty() {
+        return Err(ApiError::Validation(problems));
+    }
+
+    favorites::update(&state, &claims.sub, &id, input)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound("Favorite spot not found".into()))
+}
+
+/// Delete a favorite spot
+#[utoipa::path(
+    delete,
+    path = "/api/v1/favorites/{id}",
+    tag = "favorites",
+    params(("id" = String, Path, description = "Spot id")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+        (status = 404, description = "No such spot of the current user", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn delete_favorite_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> ApiResult<StatusCode> {
+    let claims = authenticate(&headers)?;
+
+    if state.db.delete_favorite_spot(&claims.sub, &id).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::NotFound("Favorite spot not found".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+        routing::{get, post, put},
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use crate::routes::auth::register_handler;
+    use crate::services::auth;
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {}", token));
+        }
+        let body = body.map(|b| Body::from(b.to_string())).unwrap_or_else(Body::empty);
+        let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    async fn register(app: &Router, email: &str) -> String {
+        let credentials = serde_json::json!({ "email": email, "password": "s3cret-pass" });
+        let (_, registered) = call(app, "POST", "/register", None, Some(credentials)).await;
+        registered["token"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_favorite_spots_round_trip() {
+        auth::init_jwt_secret().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::for_tests(&dir).await;
+        let app = Router::new()
+            .route("/register", post(register_handler))
+            .route("/favorites", get(list_favorites_handler).post(create_favorite_handler))
+            .route("/favorites/:id", put(update_favorite_handler).delete(delete_favorite_handler))
+            .with_state(state);
+        let owner = register(&app, "spots@example.com").await;
+        let stranger = register(&app, "other@example.com").await;
+
+        let spot = serde_json::json!({
+            "name": " North shore ",
+            "lat": 50.46,
+            "lon": 30.52,
+            "target_species": ["pike", "perch", "pike"],
+        });
+        let (status, created) = call(&app, "POST", "/favorites", Some(&owner), Some(spot)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["name"], "North shore");
+        assert_eq!(created["target_species"], serde_json::json!(["pike", "perch"]));
+        assert_eq!(created["water_body_id"], serde_json::Value::Null);
+        let id = created["id"].as_str().unwrap();
+
+        let (status, listed) = call(&app, "GET", "/favorites", Some(&owner), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(listed, serde_json::json!([created.clone()]));
+        let (_, listed) = call(&app, "GET", "/favorites", Some(&stranger), None).await;
+        assert_eq!(listed, serde_json::json!([]));
+
+        let uri = format!("/favorites/{id}");
+        let changed = serde_json::json!({ "name": "North shore", "lat": 50.46, "lon": 30.53, "notes": "Reeds" });
+        let (status, updated) = call(&app, "PUT", &uri, Some(&owner), Some(changed.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated["lon"], 30.53);
+        assert_eq!(updated["notes"], "Reeds");
+        assert_eq!(updated["created_at"], created["created_at"]);
+        let (status, _) = call(&app, "PUT", &uri, Some(&stranger), Some(changed)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let invalid = serde_json::json!({
+            "name": "",
+            "lat": 95.0,
+            "lon": 30.5,
+            "water_body_id": "atlantis",
+            "target_species": ["kraken"],
+        });
+        let (status, body) = call(&app, "POST", "/favorites", Some(&owner), Some(invalid)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let fields: Vec<_> = body["fields"].as_array().unwrap().iter().map(|f| f["field"].as_str().unwrap()).collect();
+        assert_eq!(fields, ["name", "lat", "water_body_id", "target_species"]);
+
+        let (status, _) = call(&app, "GET", "/favorites", None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = call(&app, "DELETE", &uri, Some(&stranger), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = call(&app, "DELETE", &uri, Some(&owner), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, listed) = call(&app, "GET", "/favorites", Some(&owner), None).await;
+        assert_eq!(listed, serde_json::json!([]));
+    }
+}
@@ -1,30 +1,126 @@
-use axum::{extract::Query, response::IntoResponse, Json};
+use axum::{http::header, response::IntoResponse, Json};
+use fishing_shared::types::{FishSpecies, Language};
+use fishing_shared::validation::{codes, Validate, Validator, MAX_NAME_LEN};
 use serde::Deserialize;
+use utoipa::IntoParams;
 
-use crate::{db::queries, AppState};
+use crate::{
+    error::{ApiResult, RequestContext},
+    extract::ValidQuery,
+    AppState,
+};
 
 /// Query parameters for fish species list.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct FishQuery {
+    /// Only species living in this country; `EU` or absent lists all
     pub country: Option<String>,
+    /// Language of the names; defaults to the `Accept-Language` one
     pub language: Option<String>,
+    /// Part of a name, synonym or scientific name in any language
+    pub q: Option<String>,
+}
+
+impl Validate for FishQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.country_code("country", self.country.as_deref());
+        let language = self.language.as_deref().map(str::to_lowercase);
+        if language.is_some_and(|code| Language::from_code(&code).is_none()) {
+            v.error("language", codes::UNKNOWN, "language must be one of: uk, en, pl, de, fr");
+        }
+        v.max_len("q", self.q.as_deref(), MAX_NAME_LEN);
+    }
 }
 
 /// Get localized fish species list.
+#[utoipa::path(
+    get,
+    path = "/api/v1/fish",
+    tag = "reference",
+    params(FishQuery),
+    responses(
+        (status = 200, description = "Fish species with localized names", body = [FishSpecies]),
+        (status = 400, description = "Invalid query", body = ApiErrorBody),
+    )
+)]
 pub async fn fish_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
-    Query(query): Query<FishQuery>,
-) -> impl IntoResponse {
-    let country = query.country.unwrap_or_else(|| "EU".to_string()).to_uppercase();
-    let language = query.language.unwrap_or_else(|| "en".to_string()).to_lowercase();
-
-    tracing::debug!("Fish handler called: country={}, language={}", country, language);
-
-    match queries::get_fish_species(&state.db, &country, &language).await {
-        Ok(rows) => Json::<Vec<_>>(rows).into_response(),
-        Err(err) => {
-            let body: serde_json::Value = serde_json::json!({"error": err.to_string() as String});
-            (axum::http::StatusCode::BAD_REQUEST, Json(body)).into_response()
-        }
+    ValidQuery(query): ValidQuery<FishQuery>,
+) -> ApiResult<impl IntoResponse> {
+    let country = query
+        .country
+        .map(|code| code.to_uppercase())
+        .filter(|code| code != "EU");
+    let language = query
+        .language
+        .and_then(|code| Language::from_code(&code.to_lowercase()))
+        .unwrap_or(RequestContext::current().language);
+    let search = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+
+    tracing::debug!("Fish handler called: country={:?}, language={}", country, language.code());
+
+    let rows = state.db.get_fish_species(country.as_deref(), language.code(), search).await?;
+    let species: Vec<FishSpecies> = rows.into_iter().map(FishSpecies::from).collect();
+    // Without `language` the names follow the negotiated one
+    Ok(([(header::VARY, "Accept-Language")], Json(species)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    async fn test_app() -> (Router, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::for_tests(&dir).await;
+        let app = Router::new()
+            .route("/api/v1/fish", get(fish_handler))
+            .layer(axum::middleware::from_fn(crate::error::request_context))
+            .with_state(state);
+        (app, dir)
+    }
+
+    async fn get_fish(app: &Router, uri: &str, accept_language: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::get(uri)
+            .header("accept-language", accept_language)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn names(body: &serde_json::Value) -> Vec<&str> {
+        body.as_array().unwrap().iter().map(|s| s["name"].as_str().unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_names_follow_language_and_country() {
+        let (app, _dir) = test_app().await;
+
+        let (status, body) = get_fish(&app, "/api/v1/fish?country=pl&q=pike", "de").await;
+        assert_eq!(status, StatusCode::OK);
+        // Zander is also known as pikeperch
+        assert_eq!(names(&body), ["Hecht", "Zander"]);
+
+        let (_, body) = get_fish(&app, "/api/v1/fish?language=fr&q=%D0%BD%D0%B0%D0%BB%D0%B8%D0%BC", "de").await;
+        assert_eq!(names(&body), ["Lotte"]);
+
+        let (_, all) = get_fish(&app, "/api/v1/fish?country=EU", "en").await;
+        let (_, french) = get_fish(&app, "/api/v1/fish?country=FR", "en").await;
+        assert!(names(&all).contains(&"Asp"));
+        assert!(!names(&french).contains(&"Asp"));
+
+        let (status, body) = get_fish(&app, "/api/v1/fish?country=POL", "en").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["fields"][0]["field"], "country");
     }
 }
@@ -1,50 +1,264 @@
-use axum::{extract::Query, response::IntoResponse, Json};
+use axum::{
+    extract::{Path, Query},
+    http::header,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use fishing_shared::types::TripPlanRequest;
+use fishing_shared::validation::{Validate, Validator, MAX_NAME_LEN};
 use serde::Deserialize;
+use utoipa::IntoParams;
 
-use crate::{services::prediction::{build_forecast_ml, get_detailed_prediction, get_feature_importance}, AppState};
+use crate::{
+    error::{ApiError, ApiResult, RequestContext},
+    extract::{ValidJson, ValidQuery},
+    services::{
+        bite_windows::bite_windows,
+        forecast_grid::{
+            evaluate_grid, BoundingBox, GridLayout, DEFAULT_GRID_RESOLUTION, TILE_SIZE_PX,
+        },
+        prediction::{build_forecast_ml, get_detailed_prediction, get_feature_importance},
+        trip_planner::plan_trip,
+    },
+    AppState,
+};
 
 /// Query parameters for forecast requests.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ForecastQuery {
     pub lat: f64,
     pub lon: f64,
     pub fish: Option<String>,
 }
 
+impl Validate for ForecastQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("lat", self.lat)
+            .longitude("lon", self.lon)
+            .max_len("fish", self.fish.as_deref(), MAX_NAME_LEN);
+    }
+}
+
 /// Get forecast for a location using ML model.
+#[utoipa::path(
+    get,
+    path = "/api/v1/forecast",
+    tag = "forecast",
+    params(ForecastQuery),
+    responses(
+        (status = 200, description = "Bite forecast", body = ForecastResult),
+        (status = 400, description = "Invalid query", body = ApiErrorBody),
+        (status = 502, description = "Weather service unavailable", body = ApiErrorBody),
+    )
+)]
 pub async fn forecast_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
-    Query(query): Query<ForecastQuery>,
-) -> impl IntoResponse {
-    match build_forecast_ml(&state.http, query.lat, query.lon, query.fish.as_deref()).await {
-        Ok(result) => Json(result).into_response(),
-        Err(err) => {
-            let message = format!("{:#}", err);
-            tracing::error!("Forecast error: {}", message);
-            let body = serde_json::json!({"error": message});
-            (axum::http::StatusCode::BAD_GATEWAY, Json(body)).into_response()
-        }
-    }
+    ValidQuery(query): ValidQuery<ForecastQuery>,
+) -> ApiResult<impl IntoResponse> {
+    let language = RequestContext::current().language;
+    build_forecast_ml(&state.http, query.lat, query.lon, query.fish.as_deref(), language)
+        .await
+        // The explanation is worded for the negotiated language
+        .map(|forecast| ([(header::VARY, "Accept-Language")], Json(forecast)))
+        .map_err(ApiError::Upstream)
 }
 
 /// Get detailed ML prediction with factor breakdown.
+#[utoipa::path(
+    get,
+    path = "/api/v1/forecast/detailed",
+    tag = "forecast",
+    params(ForecastQuery),
+    responses(
+        (status = 200, description = "Prediction with factor breakdown", body = PredictionResult),
+        (status = 400, description = "Invalid query", body = ApiErrorBody),
+        (status = 502, description = "Weather service unavailable", body = ApiErrorBody),
+    )
+)]
 pub async fn detailed_forecast_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
-    Query(query): Query<ForecastQuery>,
-) -> impl IntoResponse {
-    match get_detailed_prediction(&state.http, query.lat, query.lon).await {
-        Ok(result) => Json(result).into_response(),
-        Err(err) => {
-            let message = format!("{:#}", err);
-            tracing::error!("Detailed forecast error: {}", message);
-            let body = serde_json::json!({"error": message});
-            (axum::http::StatusCode::BAD_GATEWAY, Json(body)).into_response()
-        }
+    ValidQuery(query): ValidQuery<ForecastQuery>,
+) -> ApiResult<impl IntoResponse> {
+    get_detailed_prediction(&state.http, query.lat, query.lon)
+        .await
+        .map(Json)
+        .map_err(ApiError::Upstream)
+}
+
+/// Query parameters for bite window requests.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BiteWindowsQuery {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl Validate for BiteWindowsQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("lat", self.lat).longitude("lon", self.lon);
     }
 }
 
+/// Get the current and next best bite windows at a location.
+#[utoipa::path(
+    get,
+    path = "/api/v1/forecast/windows",
+    tag = "forecast",
+    params(BiteWindowsQuery),
+    responses(
+        (status = 200, description = "Bite windows over the next two days", body = BiteWindows),
+        (status = 400, description = "Invalid query", body = ApiErrorBody),
+        (status = 502, description = "Weather service unavailable", body = ApiErrorBody),
+    )
+)]
+pub async fn bite_windows_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    ValidQuery(query): ValidQuery<BiteWindowsQuery>,
+) -> ApiResult<impl IntoResponse> {
+    bite_windows(&state.http, query.lat, query.lon, Utc::now())
+        .await
+        .map(Json)
+        .map_err(ApiError::Upstream)
+}
+
+/// Rank the best spot/time windows of a trip across candidate spots.
+#[utoipa::path(
+    post,
+    path = "/api/v1/forecast/trip",
+    tag = "forecast",
+    request_body = TripPlanRequest,
+    responses(
+        (status = 200, description = "Ranked windows and each spot's hourly forecast", body = TripPlan),
+        (status = 400, description = "Invalid spots or time range", body = ApiErrorBody),
+        (status = 502, description = "Weather service unavailable for every spot", body = ApiErrorBody),
+    )
+)]
+pub async fn trip_plan_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    ValidJson(request): ValidJson<TripPlanRequest>,
+) -> ApiResult<impl IntoResponse> {
+    plan_trip(&state.http, &request, Utc::now())
+        .await
+        .map(Json)
+        .map_err(ApiError::Upstream)
+}
+
 /// Get ML model feature importance scores.
+#[utoipa::path(
+    get,
+    path = "/api/v1/forecast/importance",
+    tag = "forecast",
+    responses(
+        (status = 200, description = "Relative weight of each model input", body = FeatureImportance),
+    )
+)]
 pub async fn feature_importance_handler() -> impl IntoResponse {
     let importance = get_feature_importance().await;
     Json(importance).into_response()
 }
+
+/// Query parameters for forecast grid requests.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ForecastGridQuery {
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+    /// Forecast time (defaults to now)
+    pub time: Option<DateTime<Utc>>,
+    /// Cells per side of the grid
+    pub resolution: Option<usize>,
+}
+
+impl Validate for ForecastGridQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("min_lat", self.min_lat)
+            .longitude("min_lon", self.min_lon)
+            .latitude("max_lat", self.max_lat)
+            .longitude("max_lon", self.max_lon);
+    }
+}
+
+/// Query parameters for forecast tile requests.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ForecastTileQuery {
+    /// Forecast time (defaults to now)
+    pub time: Option<DateTime<Utc>>,
+}
+
+/// Get bite probability over a bounding box as a GeoJSON grid.
+#[utoipa::path(
+    get,
+    path = "/api/v1/forecast/grid",
+    tag = "forecast",
+    params(ForecastGridQuery),
+    responses(
+        (status = 200, description = "GeoJSON FeatureCollection of grid cells with a `probability` property", body = Object, content_type = "application/geo+json"),
+        (status = 400, description = "Invalid bounding box or resolution", body = ApiErrorBody),
+        (status = 502, description = "Weather service unavailable", body = ApiErrorBody),
+    )
+)]
+pub async fn forecast_grid_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    ValidQuery(query): ValidQuery<ForecastGridQuery>,
+) -> ApiResult<impl IntoResponse> {
+    let bbox = BoundingBox {
+        min_lat: query.min_lat,
+        min_lon: query.min_lon,
+        max_lat: query.max_lat,
+        max_lon: query.max_lon,
+    };
+    let resolution = query.resolution.unwrap_or(DEFAULT_GRID_RESOLUTION);
+
+    let layout = GridLayout::for_bbox(&bbox, resolution)
+        .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+
+    let grid = evaluate_grid(&state.http, layout, query.time.unwrap_or_else(Utc::now))
+        .await
+        .map_err(ApiError::Upstream)?;
+    Ok((
+        [(header::CONTENT_TYPE, "application/geo+json")],
+        Json(grid.to_geojson()),
+    ))
+}
+
+/// Get a bite probability map tile as PNG.
+#[utoipa::path(
+    get,
+    path = "/api/v1/forecast/tiles/{z}/{x}/{y}",
+    tag = "forecast",
+    params(("z" = u32, Path, description = "Zoom level"), ("x" = u32, Path, description = "Tile column"), ("y" = String, Path, description = "Tile row, optionally with `.png`"), ForecastTileQuery),
+    responses(
+        (status = 200, description = "Bite probability tile", body = Vec<u8>, content_type = "image/png"),
+        (status = 400, description = "Invalid tile coordinates", body = ApiErrorBody),
+        (status = 502, description = "Weather service unavailable", body = ApiErrorBody),
+    )
+)]
+pub async fn forecast_tile_handler(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path((z, x, y)): Path<(u32, u32, String)>,
+    Query(query): Query<ForecastTileQuery>,
+) -> ApiResult<impl IntoResponse> {
+    let layout = y
+        .trim_end_matches(".png")
+        .parse::<u32>()
+        .map_err(|_| anyhow::anyhow!("invalid tile row: {y}"))
+        .and_then(|y| GridLayout::for_tile(z, x, y))
+        .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+
+    let grid = evaluate_grid(&state.http, layout, query.time.unwrap_or_else(Utc::now))
+        .await
+        .map_err(ApiError::Upstream)?;
+    let png = grid.render_png(TILE_SIZE_PX)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/png"),
+            (header::CACHE_CONTROL, "public, max-age=900"),
+        ],
+        png,
+    ))
+}
@@ -2,6 +2,14 @@ use axum::Json;
 use serde_json::json;
 
 /// Health check endpoint.
+#[utoipa::path(
+    get,
+    path = "/api/v1/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "Server is up", body = Object, example = json!({"status": "ok"})),
+    )
+)]
 pub async fn health_handler() -> Json<serde_json::Value> {
     Json(json!({"status": "ok"}))
 }
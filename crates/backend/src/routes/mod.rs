@@ -6,3 +6,7 @@ pub mod regulations;
 pub mod catches;
 pub mod water_bodies;
 pub mod auth;
+pub mod profile;
+pub mod favorites;
+pub mod alerts;
+pub mod admin;
@@ -0,0 +1,156 @@
+use axum::{extract::State, http::HeaderMap, Json};
+use fishing_shared::types::{ProfileUpdate, UserProfile};
+
+use crate::{
+    error::{ApiError, ApiResult},
+    routes::auth::authenticate,
+    services::profile,
+    AppState,
+};
+
+/// Get the current user's profile and preferences
+#[utoipa::path(
+    get,
+    path = "/api/v1/profile",
+    tag = "profile",
+    responses(
+        (status = 200, description = "Profile and preferences", body = UserProfile),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn get_profile_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> ApiResult<Json<UserProfile>> {
+    let claims = authenticate(&headers)?;
+
+    profile::get_profile(&state, &claims.sub)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound("User not found".into()))
+}
+
+/// Change some of the current user's preferences
+#[utoipa::path(
+    patch,
+    path = "/api/v1/profile",
+    tag = "profile",
+    request_body = ProfileUpdate,
+    responses(
+        (status = 200, description = "Updated profile", body = UserProfile),
+        (status = 400, description = "Invalid fields", body = ApiErrorBody),
+        (status = 401, description = "Missing or invalid token", body = ApiErrorBody),
+    ),
+    security(("bearer" = []))
+)]
+pub async fn update_profile_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(update): Json<ProfileUpdate>,
+) -> ApiResult<Json<UserProfile>> {
+    let claims = authenticate(&headers)?;
+
+    let problems = profile::validate_update(&state, &update).await?;
+    if !problems.is_empty() {
+        return Err(ApiError::Validation(problems));
+    }
+
+    profile::update_profile(&state, &claims.sub, update)
+        .await?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound("User not found".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+        routing::{get, post},
+        Router,
+    };
+    use tower::ServiceExt;
+
+    use crate::routes::auth::register_handler;
+    use crate::services::auth;
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            request = request.header("authorization", format!("Bearer {}", token));
+        }
+        let body = body.map(|b| Body::from(b.to_string())).unwrap_or_else(Body::empty);
+        let response = app.clone().oneshot(request.body(body).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_profile_round_trip() {
+        auth::init_jwt_secret().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::for_tests(&dir).await;
+        let app = Router::new()
+            .route("/register", post(register_handler))
+            .route("/profile", get(get_profile_handler).patch(update_profile_handler))
+            .with_state(state);
+
+        let credentials = serde_json::json!({ "email": "prefs@example.com", "password": "s3cret-pass", "language": "en" });
+        let (_, registered) = call(&app, "POST", "/register", None, Some(credentials)).await;
+        let token = registered["token"].as_str();
+
+        let (status, profile) = call(&app, "GET", "/profile", token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(profile["language"], "en");
+        assert_eq!(profile["units"]["weight"], "kg");
+        assert_eq!(profile["units"]["wind"], "m/s");
+        assert_eq!(profile["default_privacy"], "private");
+        assert_eq!(profile["favorite_species"], serde_json::json!([]));
+
+        let update = serde_json::json!({
+            "units": { "length": "in", "weight": "lb", "temperature": "F", "pressure": "mmHg", "wind": "kn" },
+            "home_region": "ua-32",
+            "favorite_species": ["pike", "perch", "pike"],
+            "default_privacy": "public",
+        });
+        let (status, updated) = call(&app, "PATCH", "/profile", token, Some(update)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated["units"]["pressure"], "mmHg");
+        assert_eq!(updated["units"]["wind"], "kn");
+        assert_eq!(updated["home_region"], "UA-32");
+        assert_eq!(updated["favorite_species"], serde_json::json!(["pike", "perch"]));
+        assert_eq!(updated["language"], "en", "fields left out stay unchanged");
+
+        let (_, reloaded) = call(&app, "GET", "/profile", token, None).await;
+        assert_eq!(reloaded, updated);
+
+        let (status, body) = call(&app, "PATCH", "/profile", token, Some(serde_json::json!({ "favorite_species": ["kraken"] }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["fields"][0]["field"], "favorite_species");
+        assert_eq!(body["fields"][0]["code"], "unknown");
+        let (status, _) = call(&app, "PATCH", "/profile", token, Some(serde_json::json!({ "country_code": "Ukraine" }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = call(&app, "PATCH", "/profile", token, Some(serde_json::json!({ "language": "xx" }))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let (status, cleared) = call(&app, "PATCH", "/profile", token, Some(serde_json::json!({ "home_region": "" }))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(cleared["home_region"], serde_json::Value::Null);
+
+        let (status, _) = call(&app, "GET", "/profile", None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -1,25 +1,47 @@
-use axum::{extract::Query, response::IntoResponse, Json};
+use axum::{response::IntoResponse, Json};
+use fishing_shared::validation::{Validate, Validator};
 use serde::Deserialize;
+use utoipa::IntoParams;
 
-use crate::{services::geocoding::detect_region, AppState};
+use crate::{
+    error::{ApiError, ApiResult},
+    extract::ValidQuery,
+    services::geocoding::detect_region,
+    AppState,
+};
 
 /// Query parameters for region detection.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RegionQuery {
     pub lat: f64,
     pub lon: f64,
 }
 
+impl Validate for RegionQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("lat", self.lat).longitude("lon", self.lon);
+    }
+}
+
 /// Detect country for given coordinates.
+#[utoipa::path(
+    get,
+    path = "/api/v1/region/detect",
+    tag = "reference",
+    params(RegionQuery),
+    responses(
+        (status = 200, description = "Country at the coordinates", body = RegionInfo),
+        (status = 400, description = "Invalid query", body = ApiErrorBody),
+        (status = 502, description = "Geocoding service unavailable", body = ApiErrorBody),
+    )
+)]
 pub async fn region_detect_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
-    Query(query): Query<RegionQuery>,
-) -> impl IntoResponse {
-    match detect_region(&state.http, query.lat, query.lon).await {
-        Ok(region) => Json(region).into_response(),
-        Err(err) => {
-            let body = serde_json::json!({"error": err.to_string()});
-            (axum::http::StatusCode::BAD_GATEWAY, Json(body)).into_response()
-        }
-    }
+    ValidQuery(query): ValidQuery<RegionQuery>,
+) -> ApiResult<impl IntoResponse> {
+    detect_region(&state.http, query.lat, query.lon)
+        .await
+        .map(Json)
+        .map_err(ApiError::Upstream)
 }
@@ -1,16 +1,32 @@
-use axum::{extract::Query, response::IntoResponse, Json};
-use chrono::NaiveDate;
+use axum::Json;
+use fishing_shared::types::{Regulation, ValidationRequest, ValidationResult};
+use fishing_shared::validation::{Validate, Validator};
 use serde::{Deserialize, Serialize};
+use utoipa::IntoParams;
 use uuid::Uuid;
 
+use crate::{
+    error::ApiResult,
+    extract::{ValidJson, ValidQuery},
+};
+
 /// Query parameters for regulations lookup.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RegulationsQuery {
     pub country: String,
     pub fish: Option<String>,
 }
 
+impl Validate for RegulationsQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.required("country", &self.country)
+            .region_code("country", Some(&self.country));
+    }
+}
+
 /// Regulations response item.
+#[allow(dead_code)]
 #[derive(Debug, Serialize)]
 pub struct RegulationItem {
     pub license_required: bool,
@@ -24,69 +40,57 @@ pub struct RegulationItem {
     pub prohibited_gear: Vec<String>,
 }
 
-/// Request payload for regulations validation.
-#[derive(Debug, Deserialize)]
-pub struct ValidationRequest {
-    pub country_code: String,
-    pub fish_species: Option<String>,
-    pub size_cm: Option<f64>,
-    pub date: Option<NaiveDate>,
-}
-
-/// Validation result for a catch.
-#[derive(Debug, Serialize)]
-pub struct ValidationResult {
-    pub allowed: bool,
-    pub errors: Vec<String>,
-    pub warnings: Vec<String>,
-}
-
 /// Get regulations for a given country and optional fish.
+#[utoipa::path(
+    get,
+    path = "/api/v1/regulations",
+    tag = "reference",
+    params(RegulationsQuery),
+    responses(
+        (status = 200, description = "Regulation rows for the region", body = [Regulation]),
+        (status = 400, description = "Invalid query", body = ApiErrorBody),
+    )
+)]
 pub async fn regulations_handler(
     axum::extract::State(state): axum::extract::State<crate::AppState>,
-    Query(query): Query<RegulationsQuery>,
-) -> impl IntoResponse {
+    ValidQuery(query): ValidQuery<RegulationsQuery>,
+) -> ApiResult<Json<Vec<Regulation>>> {
     let country = query.country.to_uppercase();
     let fish_species_id = query
         .fish
         .as_ref()
         .and_then(|id| Uuid::parse_str(id).ok());
 
-    match crate::db::queries::get_regulations(&state.db, &country, fish_species_id).await {
-        Ok(rows) => {
-            // Simplified response for Phase 1 - just return raw database rows
-            Json(rows).into_response()
-        }
-        Err(err) => {
-            let error_msg = err.to_string();
-            let body: serde_json::Value = serde_json::json!({"error": error_msg});
-            (axum::http::StatusCode::BAD_REQUEST, Json(body)).into_response()
-        }
-    }
+    // Simplified response for Phase 1 - just return raw database rows
+    let rows = state.db.get_regulations(&country, fish_species_id).await?;
+    Ok(Json(rows.into_iter().map(Regulation::from).collect()))
 }
 
 /// Validate a catch against regulations.
+#[utoipa::path(
+    post,
+    path = "/api/v1/regulations/validate",
+    tag = "reference",
+    request_body = ValidationRequest,
+    responses(
+        (status = 200, description = "Whether the catch may be kept", body = ValidationResult),
+        (status = 400, description = "Invalid fields", body = ApiErrorBody),
+    )
+)]
 pub async fn regulations_validate_handler(
     axum::extract::State(state): axum::extract::State<crate::AppState>,
-    Json(payload): Json<ValidationRequest>,
-) -> impl IntoResponse {
+    ValidJson(payload): ValidJson<ValidationRequest>,
+) -> ApiResult<Json<ValidationResult>> {
     let country = payload.country_code.to_uppercase();
     let fish_species_id = payload
         .fish_species
         .as_ref()
         .and_then(|id| Uuid::parse_str(id).ok());
 
-    let regulations = match crate::db::queries::get_regulations(&state.db, &country, fish_species_id).await {
-        Ok(rows) => rows,
-        Err(err) => {
-            let error_msg = err.to_string();
-            let body: serde_json::Value = serde_json::json!({"error": error_msg});
-            return (axum::http::StatusCode::BAD_REQUEST, Json(body)).into_response();
-        }
-    };
+    let regulations = state.db.get_regulations(&country, fish_species_id).await?;
 
     let mut errors = Vec::new();
-    let mut warnings = Vec::new();
+    let warnings = Vec::new();
 
     if let Some(size_cm) = payload.size_cm {
         for rule in &regulations {
@@ -103,5 +107,5 @@ pub async fn regulations_validate_handler(
     // as those fields don't exist in current RegulationDb model
 
     let allowed = errors.is_empty();
-    Json(ValidationResult { allowed, errors, warnings }).into_response()
+    Ok(Json(ValidationResult { allowed, errors, warnings }))
 }
@@ -1,26 +1,43 @@
-use axum::{extract::Query, response::IntoResponse, Json};
+use axum::Json;
+use fishing_shared::types::WaterBody;
+use fishing_shared::validation::{Validate, Validator};
 use serde::Deserialize;
+use utoipa::IntoParams;
 
-use crate::{db::queries, AppState};
+use crate::{error::ApiResult, extract::ValidQuery, AppState};
 
 /// Query parameters for nearby water bodies.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct WaterBodiesQuery {
     pub lat: f64,
     pub lon: f64,
     pub radius_km: f64,
 }
 
+impl Validate for WaterBodiesQuery {
+    fn validate(&self, v: &mut Validator) {
+        v.latitude("lat", self.lat)
+            .longitude("lon", self.lon)
+            .radius_km("radius_km", self.radius_km);
+    }
+}
+
 /// Get nearby water bodies.
+#[utoipa::path(
+    get,
+    path = "/api/v1/water-bodies",
+    tag = "reference",
+    params(WaterBodiesQuery),
+    responses(
+        (status = 200, description = "Water bodies within the radius", body = [WaterBody]),
+        (status = 400, description = "Invalid query", body = ApiErrorBody),
+    )
+)]
 pub async fn water_bodies_handler(
     axum::extract::State(state): axum::extract::State<AppState>,
-    Query(query): Query<WaterBodiesQuery>,
-) -> impl IntoResponse {
-    match queries::get_nearby_water_bodies(&state.db, query.lat, query.lon, query.radius_km).await {
-        Ok(rows) => Json::<Vec<_>>(rows).into_response(),
-        Err(err) => {
-            let body: serde_json::Value = serde_json::json!({"error": err.to_string()});
-            (axum::http::StatusCode::BAD_REQUEST, Json(body)).into_response()
-        }
-    }
+    ValidQuery(query): ValidQuery<WaterBodiesQuery>,
+) -> ApiResult<Json<Vec<WaterBody>>> {
+    let rows = state.db.get_nearby_water_bodies(query.lat, query.lon, query.radius_km).await?;
+    Ok(Json(rows.into_iter().map(WaterBody::from).collect()))
 }
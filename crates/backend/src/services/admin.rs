@@ -0,0 +1,334 @@
+//! Curation of reference data: fish species, regulations and water bodies.
+//!
+//! Writes go through the repository, which records every change in
+//! `change_history`; this module only cleans up and checks the payloads.
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use fishing_shared::types::FieldError;
+use fishing_shared::validation::{codes, Validator};
+use serde::{de::DeserializeOwned, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::db::Repository;
+use crate::models::{ChangeHistoryDb, FishSpeciesDb, RegulationDb, WaterBodyDb};
+
+/// Seasons understood by the forecast for `best_season`.
+const SEASONS: &[&str] = &["all_year", "spring", "summer", "autumn", "winter"];
+
+/// Longest accepted id supplied by the client.
+const MAX_ID_LEN: usize = 64;
+
+/// Entries returned by the history endpoint when no `limit` is given.
+pub const DEFAULT_HISTORY_LIMIT: i64 = 50;
+
+/// Upper bound on the history `limit`.
+pub const MAX_HISTORY_LIMIT: i64 = 500;
+
+/// Entry of the change history with the row snapshots decoded.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChangeHistoryResponse {
+    pub id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub action: String,
+    pub changed_by: Option<String>,
+    pub changed_at: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+impl From<ChangeHistoryDb> for ChangeHistoryResponse {
+    fn from(row: ChangeHistoryDb) -> Self {
+        let decode = |data: Option<String>| data.and_then(|json| serde_json::from_str(&json).ok());
+        Self {
+            id: row.id,
+            entity_type: row.entity_type,
+            entity_id: row.entity_id,
+            action: row.action,
+            changed_by: row.changed_by,
+            changed_at: row.changed_at.to_rfc3339(),
+            before: decode(row.before_data),
+            after: decode(row.after_data),
+        }
+    }
+}
+
+/// A reference data table editable through the admin API.
+#[async_trait]
+pub trait AdminEntity: Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Name used in error messages.
+    const LABEL: &'static str;
+
+    fn id(&self) -> &str;
+
+    fn set_id(&mut self, id: String);
+
+    /// Normalize the payload; returns one message per problem.
+    fn prepare(self) -> Result<Self, Vec<FieldError>>;
+
+    async fn list(db: &dyn Repository) -> anyhow::Result<Vec<Self>>;
+
+    /// Returns `false` if the id is taken.
+    async fn create(&self, db: &dyn Repository, changed_by: &str) -> anyhow::Result<bool>;
+
+    /// Returns `false` if the row does not exist.
+    async fn update(&self, db: &dyn Repository, changed_by: &str) -> anyhow::Result<bool>;
+
+    /// Returns `false` if the row does not exist.
+    async fn delete(db: &dyn Repository, id: &str, changed_by: &str) -> anyhow::Result<bool>;
+}
+
+#[async_trait]
+impl AdminEntity for FishSpeciesDb {
+    const LABEL: &'static str = "Fish species";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+
+    fn prepare(self) -> Result<Self, Vec<FieldError>> {
+        prepare_species(self)
+    }
+
+    async fn list(db: &dyn Repository) -> anyhow::Result<Vec<Self>> {
+        db.list_fish_species_records().await
+    }
+
+    async fn create(&self, db: &dyn Repository, changed_by: &str) -> anyhow::Result<bool> {
+        db.create_fish_species(self, changed_by).await
+    }
+
+    async fn update(&self, db: &dyn Repository, changed_by: &str) -> anyhow::Result<bool> {
+        db.update_fish_species(self, changed_by).await
+    }
+
+    async fn delete(db: &dyn Repository, id: &str, changed_by: &str) -> anyhow::Result<bool> {
+        db.delete_fish_species(id, changed_by).await
+    }
+}
+
+#[async_trait]
+impl AdminEntity for RegulationDb {
+    const LABEL: &'static str = "Regulation";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+
+    fn prepare(self) -> Result<Self, Vec<FieldError>> {
+        prepare_regulation(self)
+    }
+
+    async fn list(db: &dyn Repository) -> anyhow::Result<Vec<Self>> {
+        db.list_regulations().await
+    }
+
+    async fn create(&self, db: &dyn Repository, changed_by: &str) -> anyhow::Result<bool> {
+        db.create_regulation(self, changed_by).await
+    }
+
+    async fn update(&self, db: &dyn Repository, changed_by: &str) -> anyhow::Result<bool> {
+        db.update_regulation(self, changed_by).await
+    }
+
+    async fn delete(db: &dyn Repository, id: &str, changed_by: &str) -> anyhow::Result<bool> {
+        db.delete_regulation(id, changed_by).await
+    }
+}
+
+#[async_trait]
+impl AdminEntity for WaterBodyDb {
+    const LABEL: &'static str = "Water body";
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.id = id;
+    }
+
+    fn prepare(self) -> Result<Self, Vec<FieldError>> {
+        prepare_water_body(self)
+    }
+
+    async fn list(db: &dyn Repository) -> anyhow::Result<Vec<Self>> {
+        db.list_water_bodies().await
+    }
+
+    async fn create(&self, db: &dyn Repository, changed_by: &str) -> anyhow::Result<bool> {
+        db.create_water_body(self, changed_by).await
+    }
+
+    async fn update(&self, db: &dyn Repository, changed_by: &str) -> anyhow::Result<bool> {
+        db.update_water_body(self, changed_by).await
+    }
+
+    async fn delete(db: &dyn Repository, id: &str, changed_by: &str) -> anyhow::Result<bool> {
+        db.delete_water_body(id, changed_by).await
+    }
+}
+
+/// Normalize a species payload; returns every rejected field.
+pub fn prepare_species(mut species: FishSpeciesDb) -> Result<FishSpeciesDb, Vec<FieldError>> {
+    let mut v = Validator::default();
+
+    species.id = prepare_id(&species.id, &mut v);
+    species.name_uk = species.name_uk.trim().to_string();
+    v.required("name_uk", &species.name_uk);
+    species.name_en = non_empty(species.name_en);
+    species.scientific_name = non_empty(species.scientific_name);
+    species.preferred_bait = non_empty(species.preferred_bait);
+    species.best_season = non_empty(species.best_season).map(|s| s.to_lowercase());
+    if let Some(season) = &species.best_season {
+        if !SEASONS.contains(&season.as_str()) {
+            v.error(
+                "best_season",
+                codes::UNKNOWN,
+                format!("best_season must be one of: {}", SEASONS.join(", ")),
+            );
+        }
+    }
+    if let (Some(min), Some(max)) = (species.min_temp, species.max_temp) {
+        if min > max {
+            v.error("min_temp", codes::OUT_OF_RANGE, "min_temp must not exceed max_temp");
+        }
+    }
+
+    v.finish().map(|()| species)
+}
+
+/// Normalize a regulation payload; returns every rejected field.
+pub fn prepare_regulation(mut regulation: RegulationDb) -> Result<RegulationDb, Vec<FieldError>> {
+    let mut v = Validator::default();
+
+    regulation.id = prepare_id(&regulation.id, &mut v);
+    regulation.region_code = regulation.region_code.trim().to_uppercase();
+    v.required("region_code", &regulation.region_code)
+        .region_code("region_code", Some(&regulation.region_code));
+    regulation.fish_species = non_empty(regulation.fish_species);
+    regulation.restrictions = non_empty(regulation.restrictions);
+    v.positive("min_size_cm", regulation.min_size_cm)
+        .range("max_catch_per_day", regulation.max_catch_per_day, 0, i32::MAX);
+    regulation.season_start = non_empty(regulation.season_start);
+    regulation.season_end = non_empty(regulation.season_end);
+    for (field, value) in [("season_start", &regulation.season_start), ("season_end", &regulation.season_end)] {
+        if value.as_deref().is_some_and(|day| !is_month_day(day)) {
+            v.error(field, codes::INVALID_FORMAT, format!("{} must be a day of the year as MM-DD", field));
+        }
+    }
+
+    v.finish().map(|()| regulation)
+}
+
+/// Normalize a water body payload; returns every rejected field.
+pub fn prepare_water_body(mut water_body: WaterBodyDb) -> Result<WaterBodyDb, Vec<FieldError>> {
+    let mut v = Validator::default();
+
+    water_body.id = prepare_id(&water_body.id, &mut v);
+    water_body.name = water_body.name.trim().to_string();
+    v.required("name", &water_body.name);
+    water_body.description = non_empty(water_body.description);
+    water_body.water_type = non_empty(water_body.water_type).map(|t| t.to_lowercase());
+    water_body.country_code = non_empty(water_body.country_code).map(|c| c.to_uppercase());
+    v.latitude("location_lat", water_body.location_lat)
+        .longitude("location_lon", water_body.location_lon)
+        .country_code("country_code", water_body.country_code.as_deref());
+
+    v.finish().map(|()| water_body)
+}
+
+/// Keep a client-supplied id if it is a plain slug, or generate one.
+fn prepare_id(id: &str, v: &mut Validator) -> String {
+    let id = id.trim();
+    if id.is_empty() {
+        return Uuid::new_v4().to_string();
+    }
+    let valid = id.len() <= MAX_ID_LEN
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        v.error(
+            "id",
+            codes::INVALID_FORMAT,
+            format!("id must be at most {} letters, digits, '-' or '_'", MAX_ID_LEN),
+        );
+    }
+    id.to_string()
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn is_month_day(value: &str) -> bool {
+    // 2000 is a leap year, so 02-29 is accepted.
+    value.len() == 5 && NaiveDate::parse_from_str(&format!("2000-{}", value), "%Y-%m-%d").is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regulation() -> RegulationDb {
+        RegulationDb {
+            id: String::new(),
+            region_code: " ua-32 ".to_string(),
+            fish_species: Some("pike".to_string()),
+            min_size_cm: Some(35.0),
+            max_catch_per_day: Some(5),
+            season_start: Some("03-01".to_string()),
+            season_end: Some(" ".to_string()),
+            restrictions: None,
+        }
+    }
+
+    #[test]
+    fn test_prepare_regulation() {
+        let prepared = prepare_regulation(regulation()).unwrap();
+        assert_eq!(prepared.region_code, "UA-32");
+        assert!(Uuid::parse_str(&prepared.id).is_ok(), "id generated when missing");
+        assert_eq!(prepared.season_end, None);
+
+        let problems = prepare_regulation(RegulationDb {
+            id: "no spaces".to_string(),
+            min_size_cm: Some(-1.0),
+            season_start: Some("02-30".to_string()),
+            ..regulation()
+        })
+        .unwrap_err();
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+    }
+
+    #[test]
+    fn test_prepare_species_checks_temperatures_and_season() {
+        let species = FishSpeciesDb {
+            id: "grayling".to_string(),
+            name_uk: "Харіус".to_string(),
+            name_en: Some("Grayling".to_string()),
+            scientific_name: Some("Thymallus thymallus".to_string()),
+            best_season: Some("Autumn".to_string()),
+            preferred_bait: None,
+            min_temp: Some(4.0),
+            max_temp: Some(18.0),
+        };
+        assert_eq!(prepare_species(species.clone()).unwrap().best_season.as_deref(), Some("autumn"));
+
+        let problems = prepare_species(FishSpeciesDb {
+            best_season: Some("monsoon".to_string()),
+            min_temp: Some(20.0),
+            ..species
+        })
+        .unwrap_err();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+    }
+}
@@ -0,0 +1,559 @@
+//! Bite alerts: rules for the user's favorite spots and the scheduler that
+//! checks them against the hourly forecast.
+//!
+//! Every [`AlertConfig::check_interval`] the scheduler looks at the next
+//! [`ALERT_HORIZON_HOURS`] at each spot with an enabled rule. The first hour
+//! within the rule's local time window that reaches its probability (and
+//! pressure trend, if set) is sent to the user's browsers through the
+//! [`Notifier`](super::notifier::Notifier). A rule that fired stays quiet for
+//! [`ALERT_COOLDOWN_HOURS`].
+//!
+//! The bite model does not tell species apart, so a rule's species only
+//! names the fish in the notification; every rule at a spot is checked
+//! against the same forecast.
+
+use std::collections::HashMap;
+use std::future::Future;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, FixedOffset, Timelike, Utc};
+use fishing_shared::types::{AlertRule, AlertRuleInput, FieldError, Language, PressureTrend};
+use fishing_shared::validation::{codes, Validate, Validator};
+use uuid::Uuid;
+
+use super::bite_windows::hourly_forecast;
+use super::notifier::{Delivery, Notification, NotificationAction};
+use crate::models::{AlertRuleDb, FavoriteSpotDb};
+use crate::AppState;
+
+/// Hours looked ahead for a good bite, including the current one.
+pub const ALERT_HORIZON_HOURS: i64 = 24;
+
+/// Least time between two notifications of one rule.
+pub const ALERT_COOLDOWN_HOURS: i64 = 12;
+
+/// Hours of history the pressure trend is taken over.
+const PRESSURE_TREND_HOURS: i64 = 3;
+
+const DEFAULT_CHECK_MINUTES: u64 = 30;
+
+/// Alert scheduler settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertConfig {
+    /// `ALERT_CHECK_MINUTES`; `0` turns the scheduler off.
+    pub check_minutes: u64,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            check_minutes: DEFAULT_CHECK_MINUTES,
+        }
+    }
+}
+
+impl AlertConfig {
+    /// Read `ALERT_CHECK_MINUTES`.
+    pub fn from_lookup(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let check_minutes = match var("ALERT_CHECK_MINUTES") {
+            Some(value) => value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("ALERT_CHECK_MINUTES {:?} is not a number of minutes", value))?,
+            None => DEFAULT_CHECK_MINUTES,
+        };
+        Ok(Self { check_minutes })
+    }
+
+    /// Time between two checks, unless the scheduler is off.
+    pub fn check_interval(&self) -> Option<std::time::Duration> {
+        (self.check_minutes > 0).then(|| std::time::Duration::from_secs(self.check_minutes * 60))
+    }
+}
+
+/// Forecast for one hour at a spot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ForecastHour {
+    /// Start of the hour.
+    pub time: DateTime<Utc>,
+    pub probability: f64,
+    /// Pressure change over the preceding three hours, where known.
+    pub pressure_trend: Option<PressureTrend>,
+}
+
+/// Check a rule before saving it, including that the spot is the user's and
+/// the species exists; returns every rejected field.
+pub async fn validate_input(state: &AppState, user_id: &str, input: &AlertRuleInput) -> Result<Vec<FieldError>> {
+    let mut v = Validator::default();
+    input.validate(&mut v);
+
+    if state.db.get_favorite_spot(user_id, &input.favorite_spot_id).await?.is_none() {
+        v.error(
+            "favorite_spot_id",
+            codes::UNKNOWN,
+            format!("Unknown favorite spot: {}", input.favorite_spot_id),
+        );
+    }
+    if let Some(id) = input.species.as_deref() {
+        if !state.db.fish_species_exists(id).await? {
+            v.error("species", codes::UNKNOWN, format!("Unknown fish species: {}", id));
+        }
+    }
+
+    Ok(v.finish().err().unwrap_or_default())
+}
+
+/// The user's rules, oldest first
+pub async fn list(state: &AppState, user_id: &str) -> Result<Vec<AlertRule>> {
+    let rows = state.db.list_alert_rules(user_id).await?;
+    Ok(rows.into_iter().map(AlertRule::from).collect())
+}
+
+/// Save a validated new rule
+pub async fn create(state: &AppState, user_id: &str, input: AlertRuleInput) -> Result<AlertRule> {
+    let row = rule_to_db(Uuid::new_v4().to_string(), user_id, None, Utc::now(), input);
+    state.db.create_alert_rule(&row).await?;
+    Ok(row.into())
+}
+
+/// Replace a rule with validated fields; returns `None` if the user has no
+/// rule with this id
+pub async fn update(
+    state: &AppState,
+    user_id: &str,
+    id: &str,
+    input: AlertRuleInput,
+) -> Result<Option<AlertRule>> {
+    let Some(existing) = state.db.get_alert_rule(user_id, id).await? else {
+        return Ok(None);
+    };
+    let row = rule_to_db(existing.id, user_id, existing.last_notified_at, existing.created_at, input);
+    if !state.db.update_alert_rule(&row).await? {
+        return Ok(None);
+    }
+    Ok(Some(row.into()))
+}
+
+fn rule_to_db(
+    id: String,
+    user_id: &str,
+    last_notified_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    input: AlertRuleInput,
+) -> AlertRuleDb {
+    AlertRuleDb {
+        id,
+        user_id: user_id.to_string(),
+        favorite_spot_id: input.favorite_spot_id,
+        species: input.species.filter(|species| !species.is_empty()),
+        min_probability: input.min_probability,
+        start_hour: input.start_hour.into(),
+        end_hour: input.end_hour.into(),
+        utc_offset_minutes: input.utc_offset_minutes,
+        pressure_trend: input.pressure_trend.map(|trend| trend.as_str().to_string()),
+        enabled: input.enabled,
+        last_notified_at,
+        created_at,
+    }
+}
+
+/// Check the rules every [`AlertConfig::check_interval`] for as long as the
+/// server runs.
+pub fn spawn_scheduler(state: AppState) {
+    let Some(interval) = state.config.alerts.check_interval() else {
+        tracing::info!("ALERT_CHECK_MINUTES is 0, bite alerts are off");
+        return;
+    };
+
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(interval);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            let now = Utc::now();
+            let http = &state.http;
+            match check_alerts(&state, now, |lat, lon| forecast_hours(http, lat, lon, now)).await {
+                Ok(0) => {}
+                Ok(sent) => tracing::info!("Sent {} bite alerts", sent),
+                Err(err) => tracing::warn!("Checking bite alerts failed: {:#}", err),
+            }
+        }
+    });
+}
+
+/// Notify the owners of enabled rules that match the forecast `forecast`
+/// gives for a spot; returns how many rules fired.
+pub async fn check_alerts<F, Fut>(state: &AppState, now: DateTime<Utc>, forecast: F) -> Result<usize>
+where
+    F: Fn(f64, f64) -> Fut,
+    Fut: Future<Output = Result<Vec<ForecastHour>>>,
+{
+    // Spots and their forecasts, fetched once per check
+    let mut spots: HashMap<String, Option<(FavoriteSpotDb, Vec<ForecastHour>)>> = HashMap::new();
+    let mut fired = 0;
+
+    for row in state.db.list_enabled_alert_rules().await? {
+        let rule = AlertRule::from(row.clone());
+        if cooling_down(&rule, now) {
+            continue;
+        }
+
+        if !spots.contains_key(&rule.favorite_spot_id) {
+            let loaded = match state.db.get_favorite_spot(&row.user_id, &rule.favorite_spot_id).await {
+                Ok(Some(spot)) => match forecast(spot.location_lat, spot.location_lon).await {
+                    Ok(hours) => Some((spot, hours)),
+                    Err(err) => {
+                        tracing::warn!("No forecast for favorite spot {}: {:#}", spot.id, err);
+                        None
+                    }
+                },
+                Ok(None) => None,
+                Err(err) => {
+                    tracing::warn!("Loading favorite spot {} failed: {:#}", rule.favorite_spot_id, err);
+                    None
+                }
+            };
+            spots.insert(rule.favorite_spot_id.clone(), loaded);
+        }
+        let Some((spot, hours)) = spots[&rule.favorite_spot_id].as_ref() else {
+            continue;
+        };
+        let Some(hour) = first_match(&rule, hours, now) else {
+            continue;
+        };
+
+        // One rule failing must not hold up the others
+        let subscriptions = match state.db.list_push_subscriptions(&row.user_id).await {
+            Ok(subscriptions) if subscriptions.is_empty() => continue,
+            Ok(subscriptions) => subscriptions,
+            Err(err) => {
+                tracing::warn!("Loading push subscriptions for alert {} failed: {:#}", rule.id, err);
+                continue;
+            }
+        };
+        let notification = match notification(state, &row.user_id, &rule, spot, &hour).await {
+            Ok(notification) => notification,
+            Err(err) => {
+                tracing::warn!("Preparing alert {} failed: {:#}", rule.id, err);
+                continue;
+            }
+        };
+
+        let mut delivered = false;
+        for subscription in &subscriptions {
+            match state.notifier.notify(subscription, &notification).await {
+                Ok(Delivery::Delivered) => delivered = true,
+                Ok(Delivery::Gone) => {
+                    let deleted = state
+                        .db
+                        .delete_push_subscription(&subscription.user_id, &subscription.endpoint)
+                        .await;
+                    if let Err(err) = deleted {
+                        tracing::warn!("Removing expired subscription {} failed: {:#}", subscription.endpoint, err);
+                    }
+                }
+                Err(err) => tracing::warn!("Bite alert to {} failed: {:#}", subscription.endpoint, err),
+            }
+        }
+        if delivered {
+            fired += 1;
+            if let Err(err) = state.db.mark_alert_notified(&rule.id, now).await {
+                tracing::warn!("Marking alert {} as sent failed: {:#}", rule.id, err);
+            }
+        }
+    }
+
+    Ok(fired)
+}
+
+/// Probabilities and pressure trends for the next [`ALERT_HORIZON_HOURS`].
+pub async fn forecast_hours(
+    http: &reqwest::Client,
+    lat: f64,
+    lon: f64,
+    now: DateTime<Utc>,
+) -> Result<Vec<ForecastHour>> {
+    let from = now - Duration::hours(PRESSURE_TREND_HOURS);
+    let hours = hourly_forecast(http, lat, lon, from, ALERT_HORIZON_HOURS + PRESSURE_TREND_HOURS).await?;
+    let pressure_at: HashMap<_, _> = hours.iter().map(|(weather, _)| (weather.time, weather.pressure_hpa)).collect();
+
+    Ok(hours
+        .iter()
+        .map(|(weather, probability)| ForecastHour {
+            time: weather.time,
+            probability: *probability,
+            pressure_trend: pressure_at
+                .get(&(weather.time - Duration::hours(PRESSURE_TREND_HOURS)))
+                .map(|earlier| PressureTrend::from_change(weather.pressure_hpa - earlier)),
+        })
+        .filter(|hour| hour.time + Duration::hours(1) > now)
+        .collect())
+}
+
+/// Whether `rule` fired less than [`ALERT_COOLDOWN_HOURS`] ago.
+fn cooling_down(rule: &AlertRule, now: DateTime<Utc>) -> bool {
+    rule.last_notified_at
+        .is_some_and(|at| now - at < Duration::hours(ALERT_COOLDOWN_HOURS))
+}
+
+/// The first hour within the horizon that satisfies `rule`.
+pub fn first_match(rule: &AlertRule, hours: &[ForecastHour], now: DateTime<Utc>) -> Option<ForecastHour> {
+    let offset = utc_offset(rule);
+    let horizon_end = now + Duration::hours(ALERT_HORIZON_HOURS);
+
+    hours
+        .iter()
+        .filter(|hour| hour.time + Duration::hours(1) > now && hour.time < horizon_end)
+        .filter(|hour| in_window(hour.time.with_timezone(&offset).hour(), rule.start_hour, rule.end_hour))
+        .filter(|hour| hour.probability >= rule.min_probability)
+        .find(|hour| rule.pressure_trend.is_none() || hour.pressure_trend == rule.pressure_trend)
+        .copied()
+}
+
+/// Whether a local hour falls in `start..end`, wrapping past midnight; equal
+/// bounds cover the whole day.
+fn in_window(hour: u32, start: u8, end: u8) -> bool {
+    let (start, end) = (u32::from(start), u32::from(end));
+    match start.cmp(&end) {
+        std::cmp::Ordering::Equal => true,
+        std::cmp::Ordering::Less => start <= hour && hour < end,
+        std::cmp::Ordering::Greater => hour >= start || hour < end,
+    }
+}
+
+fn utc_offset(rule: &AlertRule) -> FixedOffset {
+    FixedOffset::east_opt(rule.utc_offset_minutes * 60).unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+}
+
+/// The alert in the user's language.
+async fn notification(
+    state: &AppState,
+    user_id: &str,
+    rule: &AlertRule,
+    spot: &FavoriteSpotDb,
+    hour: &ForecastHour,
+) -> Result<Notification> {
+    let language = state
+        .db
+        .find_user_by_id(user_id)
+        .await?
+        .and_then(|user| user.preferences.language)
+        .and_then(|code| Language::from_code(&code))
+        .unwrap_or_default();
+    let species = match rule.species.as_deref() {
+        Some(id) => state.db.fish_species_name(id, language.code()).await?,
+        None => None,
+    };
+
+    let local = hour.time.with_timezone(&utc_offset(rule));
+    let now_local = Utc::now().with_timezone(&utc_offset(rule));
+    let time = if local.date_naive() == now_local.date_naive() {
+        local.format("%H:%M").to_string()
+    } else {
+        local.format("%d.%m %H:%M").to_string()
+    };
+    let percent = (hour.probability * 100.0).round();
+    let mut body = bite_message(language, percent, &time);
+    if let Some(species) = species {
+        body = format!("{}: {}", species, body);
+    }
+
+    Ok(Notification {
+        title: format!("🎣 {}", spot.name),
+        body,
+        url: Some(state.config.app_base_url.clone()),
+        actions: actions(language),
+    })
+}
+
+/// "Open" and "Close" buttons.
+fn actions(language: Language) -> Vec<NotificationAction> {
+    let (open, close) = match language {
+        Language::Uk => ("Відкрити", "Закрити"),
+        Language::En => ("Open", "Close"),
+        Language::Pl => ("Otwórz", "Zamknij"),
+        Language::De => ("Öffnen", "Schließen"),
+        Language::Fr => ("Ouvrir", "Fermer"),
+    };
+    [("open", open), ("close", close)]
+        .into_iter()
+        .map(|(action, title)| NotificationAction {
+            action: action.to_string(),
+            title: title.to_string(),
+        })
+        .collect()
+}
+
+fn bite_message(language: Language, percent: f64, time: &str) -> String {
+    match language {
+        Language::Uk => format!("Ймовірність клювання {}% о {}", percent, time),
+        Language::En => format!("{}% bite chance at {}", percent, time),
+        Language::Pl => format!("Szansa na branie {}% o {}", percent, time),
+        Language::De => format!("Beißwahrscheinlichkeit {} % um {}", percent, time),
+        Language::Fr => format!("Probabilité de touche {} % à {}", percent, time),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use chrono::TimeZone;
+
+    use crate::models::{NewUser, PushSubscriptionDb};
+    use crate::services::notifier::MemoryNotifier;
+
+    fn rule() -> AlertRule {
+        AlertRule {
+            id: "rule".to_string(),
+            favorite_spot_id: "spot".to_string(),
+            species: None,
+            min_probability: 0.7,
+            start_hour: 5,
+            end_hour: 9,
+            utc_offset_minutes: 180,
+            pressure_trend: None,
+            enabled: true,
+            last_notified_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn hours(start: DateTime<Utc>, values: &[(f64, Option<PressureTrend>)]) -> Vec<ForecastHour> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &(probability, pressure_trend))| ForecastHour {
+                time: start + Duration::hours(i as i64),
+                probability,
+                pressure_trend,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_first_match_respects_window_threshold_and_trend() {
+        // 00:00 UTC is 03:00 at UTC+3
+        let now = Utc.with_ymd_and_hms(2026, 5, 14, 0, 30, 0).unwrap();
+        let steady = Some(PressureTrend::Steady);
+        let falling = Some(PressureTrend::Falling);
+        let series = hours(
+            Utc.with_ymd_and_hms(2026, 5, 14, 0, 0, 0).unwrap(),
+            &[(0.9, steady), (0.9, steady), (0.6, steady), (0.75, steady), (0.8, falling), (0.9, steady)],
+        );
+
+        // 03:00 and 04:00 are before the window, 05:00 is too weak
+        let found = first_match(&rule(), &series, now).unwrap();
+        assert_eq!(found.time, series[3].time);
+
+        let falling_rule = AlertRule { pressure_trend: falling, ..rule() };
+        assert_eq!(first_match(&falling_rule, &series, now).unwrap().time, series[4].time);
+
+        let strict = AlertRule { min_probability: 0.95, ..rule() };
+        assert_eq!(first_match(&strict, &series, now), None);
+
+        // A window across midnight, local 22:00 to 04:00
+        let night = AlertRule { start_hour: 22, end_hour: 4, ..rule() };
+        assert_eq!(first_match(&night, &series, now).unwrap().time, series[0].time);
+        let all_day = AlertRule { start_hour: 0, end_hour: 0, min_probability: 0.85, ..rule() };
+        assert_eq!(first_match(&all_day, &series, now).unwrap().time, series[0].time);
+        assert!(in_window(23, 22, 4) && in_window(3, 22, 4) && !in_window(4, 22, 4));
+    }
+
+    #[tokio::test]
+    async fn test_check_alerts_notifies_once_and_drops_gone_subscriptions() {
+        let dir = tempfile::tempdir().unwrap();
+        let notifier = Arc::new(MemoryNotifier {
+            gone: vec!["https://push.example.com/old".to_string()],
+            ..Default::default()
+        });
+        let state = AppState { notifier: notifier.clone(), ..AppState::for_tests(&dir).await };
+        state
+            .db
+            .create_user(&NewUser {
+                id: "angler".to_string(),
+                email: "angler@example.com".to_string(),
+                password_hash: String::new(),
+                country_code: "UA".to_string(),
+                language: "en".to_string(),
+            })
+            .await
+            .unwrap();
+        let spot = FavoriteSpotDb {
+            id: "spot".to_string(),
+            user_id: "angler".to_string(),
+            name: "Dam".to_string(),
+            location_lat: 50.46,
+            location_lon: 30.52,
+            water_body_id: None,
+            target_species: "[]".to_string(),
+            notes: None,
+            created_at: Utc::now(),
+        };
+        state.db.create_favorite_spot(&spot).await.unwrap();
+        let input = AlertRuleInput {
+            favorite_spot_id: "spot".to_string(),
+            species: Some("pike".to_string()),
+            min_probability: 0.7,
+            start_hour: 0,
+            end_hour: 0,
+            utc_offset_minutes: 0,
+            pressure_trend: None,
+            enabled: true,
+        };
+        assert!(validate_input(&state, "angler", &input).await.unwrap().is_empty());
+        let stranger = validate_input(&state, "someone-else", &input).await.unwrap();
+        assert_eq!(stranger[0].field, "favorite_spot_id");
+        let rule = create(&state, "angler", input).await.unwrap();
+        for endpoint in ["https://push.example.com/old", "https://push.example.com/new"] {
+            state
+                .db
+                .save_push_subscription(&PushSubscriptionDb {
+                    endpoint: endpoint.to_string(),
+                    user_id: "angler".to_string(),
+                    p256dh: "key".to_string(),
+                    auth: "secret".to_string(),
+                    created_at: Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+
+        let now = Utc::now();
+        let forecast = |_lat: f64, _lon: f64| async move { Ok(hours(now, &[(0.5, None), (0.8, None)])) };
+        assert_eq!(check_alerts(&state, now, forecast).await.unwrap(), 1);
+
+        let sent = notifier.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, "https://push.example.com/new");
+        assert_eq!(sent[0].1.title, "🎣 Dam");
+        assert!(sent[0].1.body.starts_with("Pike: 80% bite chance at "), "{}", sent[0].1.body);
+        let buttons: Vec<_> = sent[0].1.actions.iter().map(|a| (a.action.as_str(), a.title.as_str())).collect();
+        assert_eq!(buttons, [("open", "Open"), ("close", "Close")]);
+        let endpoints: Vec<_> = state
+            .db
+            .list_push_subscriptions("angler")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|subscription| subscription.endpoint)
+            .collect();
+        assert_eq!(endpoints, ["https://push.example.com/new"]);
+        let stored = state.db.get_alert_rule("angler", &rule.id).await.unwrap().unwrap();
+        assert!(stored.last_notified_at.is_some());
+
+        // The rule stays quiet until the cooldown is over
+        assert_eq!(check_alerts(&state, now + Duration::hours(1), forecast).await.unwrap(), 0);
+        let later = now + Duration::hours(ALERT_COOLDOWN_HOURS);
+        assert_eq!(check_alerts(&state, later, |_, _| async move { Ok(hours(later, &[(0.9, None)])) }).await.unwrap(), 1);
+        assert_eq!(notifier.sent.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_alert_config() {
+        assert_eq!(AlertConfig::default().check_interval(), Some(std::time::Duration::from_secs(30 * 60)));
+        let off = AlertConfig::from_lookup(|_| Some("0".to_string())).unwrap();
+        assert_eq!(off.check_interval(), None);
+        assert!(AlertConfig::from_lookup(|_| Some("soon".to_string())).is_err());
+    }
+}
@@ -1,72 +1,169 @@
+use std::sync::OnceLock;
+
 use anyhow::{Context, Result};
 use bcrypt::{hash, verify, DEFAULT_COST};
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use utoipa::ToSchema;
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+pub use fishing_shared::types::{AuthResponse, LoginRequest, RefreshRequest, RegisterRequest, UserResponse};
+use fishing_shared::validation::{Validate, Validator, MAX_PASSWORD_LEN, MIN_PASSWORD_LEN};
+
+use crate::error::ApiError;
+use crate::models::{EmailTokenPurpose, NewEmailToken, NewSession, NewUser, Role, SessionDb, UserDb};
+use crate::services::mailer::Email;
 use crate::AppState;
 
+/// Lifetime of an access token.
+const ACCESS_TOKEN_TTL_MINUTES: i64 = 15;
+
+/// Lifetime of a session; refreshing does not extend it.
+const SESSION_TTL_DAYS: i64 = 30;
+
+/// Lifetime of an email verification link.
+const EMAIL_VERIFICATION_TTL_HOURS: i64 = 48;
+
+/// Lifetime of a password reset link.
+const PASSWORD_RESET_TTL_MINUTES: i64 = 60;
+
+/// Minimum secret length accepted outside development.
+const MIN_JWT_SECRET_LEN: usize = 32;
+
+const DEV_JWT_SECRET: &str = "development-secret-key-change-in-production";
+
+static JWT_SECRET: OnceLock<String> = OnceLock::new();
+
 /// JWT claims structure
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,        // User ID
     pub email: String,
+    pub sid: String,        // Session ID
+    #[serde(default)]
+    pub role: Role,         // Updated on the next refresh after a change
     pub exp: usize,         // Expiration timestamp
     pub iat: usize,         // Issued at timestamp
 }
 
-/// Login request
-#[derive(Debug, Deserialize)]
-pub struct LoginRequest {
-    pub email: String,
-    pub password: String,
+/// Where a login came from, recorded on the session.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
 }
 
-/// Register request
-#[derive(Debug, Deserialize)]
-pub struct RegisterRequest {
+/// Email verification confirmation
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+/// Password reset request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PasswordResetRequest {
     pub email: String,
-    pub password: String,
-    pub country_code: Option<String>,
-    pub language: Option<String>,
 }
 
-/// Auth response
-#[derive(Debug, Serialize)]
-pub struct AuthResponse {
+/// Password reset confirmation
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PasswordResetConfirm {
     pub token: String,
-    pub user: UserResponse,
+    pub new_password: String,
 }
 
-/// User response (without sensitive data)
-#[derive(Debug, Serialize)]
-pub struct UserResponse {
+impl Validate for VerifyEmailRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.required("token", &self.token);
+    }
+}
+
+impl Validate for PasswordResetRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.email("email", self.email.trim());
+    }
+}
+
+impl Validate for PasswordResetConfirm {
+    fn validate(&self, v: &mut Validator) {
+        v.required("token", &self.token)
+            .min_len("new_password", &self.new_password, MIN_PASSWORD_LEN)
+            .max_bytes("new_password", &self.new_password, MAX_PASSWORD_LEN);
+    }
+}
+
+/// Session entry for the session list
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionResponse {
     pub id: String,
-    pub email: String,
-    pub country_code: String,
-    pub language: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
     pub created_at: String,
+    pub last_used_at: String,
+    pub expires_at: String,
+    /// Whether this is the session of the token making the request.
+    pub current: bool,
 }
 
-/// User in database
-#[derive(Debug, FromRow)]
-struct DbUser {
-    id: String,
-    email: String,
-    password_hash: String,
-    country_code: Option<String>,
-    language: Option<String>,
-    created_at: chrono::DateTime<Utc>,
+/// Pick the JWT secret, refusing the built-in one outside development.
+pub fn resolve_jwt_secret(configured: Option<String>, development: bool) -> Result<String> {
+    match configured.filter(|s| !s.is_empty()) {
+        Some(secret) if !development && secret.len() < MIN_JWT_SECRET_LEN => Err(anyhow::anyhow!(
+            "JWT_SECRET must be at least {} characters outside development",
+            MIN_JWT_SECRET_LEN
+        )),
+        Some(secret) => Ok(secret),
+        None if development => {
+            tracing::warn!("JWT_SECRET not set, using default dev secret");
+            Ok(DEV_JWT_SECRET.to_string())
+        }
+        None => Err(anyhow::anyhow!(
+            "JWT_SECRET is not set; it is required outside development (APP_ENV)"
+        )),
+    }
 }
 
-/// Get JWT secret from environment
-fn get_jwt_secret() -> String {
-    std::env::var("JWT_SECRET").unwrap_or_else(|_| {
-        tracing::warn!("JWT_SECRET not set, using default dev secret");
-        "development-secret-key-change-in-production".to_string()
-    })
+/// Install the signing secret from the validated config; call once at
+/// startup. Later calls keep the first secret.
+pub fn set_jwt_secret(secret: String) {
+    let _ = JWT_SECRET.set(secret);
+}
+
+/// Use the development secret in tests.
+#[cfg(test)]
+pub fn init_jwt_secret() -> Result<()> {
+    set_jwt_secret(resolve_jwt_secret(None, true)?);
+    Ok(())
+}
+
+fn get_jwt_secret() -> &'static str {
+    JWT_SECRET
+        .get()
+        .expect("set_jwt_secret must be called at startup")
+}
+
+/// Generate a random refresh, verification or reset token.
+fn generate_secret_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hash a token for storage; only hashes are kept in the database.
+fn hash_secret_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// The form emails are stored and compared in: `Angler@Example.com ` and
+/// `angler@example.com` are the same account.
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
 }
 
 /// Hash password using bcrypt
@@ -96,15 +193,17 @@ pub async fn verify_password(password: &str, hash: &str) -> Result<bool> {
         .map_err(anyhow::Error::msg)
 }
 
-/// Generate JWT token
-pub fn generate_token(user_id: &str, email: &str) -> Result<String> {
+/// Generate a short-lived access token for a session
+pub fn generate_token(user_id: &str, email: &str, role: Role, session_id: &str) -> Result<String> {
     let secret = get_jwt_secret();
     let now = Utc::now();
-    let duration = Duration::hours(24 * 7); // 7 days expiry
+    let duration = Duration::minutes(ACCESS_TOKEN_TTL_MINUTES);
     
     let claims = Claims {
         sub: user_id.to_string(),
         email: email.to_string(),
+        sid: session_id.to_string(),
+        role,
         exp: (now + duration).timestamp() as usize,
         iat: now.timestamp() as usize,
     };
@@ -127,25 +226,25 @@ pub fn validate_token(token: &str) -> Result<Claims> {
         &DecodingKey::from_secret(secret.as_bytes()),
         &validation,
     )
-    .context("Invalid JWT token")
     .map(|data| data.claims)
+    .map_err(|err| match err.kind() {
+        jsonwebtoken::errors::ErrorKind::ExpiredSignature => ApiError::TokenExpired.into(),
+        _ => ApiError::Unauthorized("Invalid JWT token".into()).into(),
+    })
 }
 
 /// Register new user
 pub async fn register(
     state: &AppState,
     request: RegisterRequest,
+    client: ClientInfo,
 ) -> Result<AuthResponse> {
     // Check if user already exists
-    let existing: Option<DbUser> = sqlx::query_as(
-        "SELECT id, email, password_hash, country_code, language, created_at FROM users WHERE email = ?1"
-    )
-    .bind(&request.email)
-    .fetch_optional(&state.db)
-    .await?;
+    let email = normalize_email(&request.email);
+    let existing = state.db.find_user_by_email(&email).await?;
     
     if existing.is_some() {
-        return Err(anyhow::anyhow!("User with this email already exists"));
+        return Err(ApiError::EmailTaken.into());
     }
     
     // Hash password
@@ -156,85 +255,408 @@ pub async fn register(
     let country_code = request.country_code.unwrap_or_else(|| "UA".to_string());
     let language = request.language.unwrap_or_else(|| "uk".to_string());
     
-    sqlx::query(
-        "INSERT INTO users (id, email, password_hash, country_code, language) VALUES (?1, ?2, ?3, ?4, ?5)"
-    )
-    .bind(&user_id)
-    .bind(&request.email)
-    .bind(&password_hash)
-    .bind(&country_code)
-    .bind(&language)
-    .execute(&state.db)
-    .await
-    .context("Failed to create user")?;
-    
-    // Generate token
-    let token = generate_token(&user_id, &request.email)?;
-    
-    Ok(AuthResponse {
-        token,
-        user: UserResponse {
-            id: user_id,
-            email: request.email,
+    state
+        .db
+        .create_user(&NewUser {
+            id: user_id.clone(),
+            email,
+            password_hash,
             country_code,
             language,
-            created_at: Utc::now().to_rfc3339(),
-        },
-    })
+        })
+        .await
+        .context("Failed to create user")?;
+    
+    let user = state
+        .db
+        .find_user_by_id(&user_id)
+        .await?
+        .context("Failed to load created user")?;
+
+    // The account works before verification; a failed send can be retried
+    // through `POST /auth/verify-email/request`.
+    if let Err(err) = send_verification_email(state, &user).await {
+        tracing::warn!("Failed to send verification email to {}: {:#}", user.email, err);
+    }
+
+    start_session(state, user, client).await
 }
 
 /// Login user
 pub async fn login(
     state: &AppState,
     request: LoginRequest,
+    client: ClientInfo,
 ) -> Result<AuthResponse> {
-    // Find user
-    let user: DbUser = sqlx::query_as(
-        "SELECT id, email, password_hash, country_code, language, created_at FROM users WHERE email = ?1"
-    )
-    .bind(&request.email)
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| anyhow::anyhow!("Invalid email or password"))?;
-    
-    // Verify password
-    let valid = verify_password(&request.password, &user.password_hash).await?;
-    if !valid {
-        return Err(anyhow::anyhow!("Invalid email or password"));
+    // Locked accounts are refused before the password is checked
+    let email = normalize_email(&request.email);
+    if let Some(retry_after) = state.login_lockout.locked_for(&email) {
+        return Err(ApiError::AccountLocked { retry_after }.into());
     }
-    
-    // Generate token
-    let token = generate_token(&user.id, &user.email)?;
-    
+
+    // Find user
+    let user = state.db.find_user_by_email(&email).await?;
+
+    // Verify password (accounts created through OIDC have none)
+    let user = match user {
+        Some(user)
+            if !user.password_hash.is_empty()
+                && verify_password(&request.password, &user.password_hash).await? =>
+        {
+            user
+        }
+        // Unknown emails count too, so lockouts do not reveal which exist
+        _ => {
+            state.login_lockout.record_failure(&email);
+            return Err(ApiError::InvalidCredentials.into());
+        }
+    };
+    state.login_lockout.record_success(&email);
+
+    start_session(state, user, client).await
+}
+
+/// Open a new session and issue its first token pair
+pub(crate) async fn start_session(state: &AppState, user: UserDb, client: ClientInfo) -> Result<AuthResponse> {
+    let session_id = Uuid::new_v4().to_string();
+    let refresh_token = generate_secret_token();
+
+    state
+        .db
+        .create_session(
+            &NewSession {
+                id: session_id.clone(),
+                user_id: user.id.clone(),
+                user_agent: client.user_agent,
+                ip_address: client.ip_address,
+                expires_at: Utc::now() + Duration::days(SESSION_TTL_DAYS),
+            },
+            &hash_secret_token(&refresh_token),
+        )
+        .await
+        .context("Failed to create session")?;
+
+    auth_response(user, &session_id, refresh_token)
+}
+
+fn auth_response(user: UserDb, session_id: &str, refresh_token: String) -> Result<AuthResponse> {
+    let token = generate_token(&user.id, &user.email, user.role(), session_id)?;
+
     Ok(AuthResponse {
         token,
-        user: UserResponse {
-            id: user.id,
-            email: user.email,
-            country_code: user.country_code.unwrap_or_default(),
-            language: user.language.unwrap_or_default(),
-            created_at: user.created_at.to_rfc3339(),
-        },
+        token_type: "Bearer".to_string(),
+        expires_in: ACCESS_TOKEN_TTL_MINUTES * 60,
+        refresh_token,
+        user: user_response(user),
     })
 }
 
+fn user_response(user: UserDb) -> UserResponse {
+    let role = user.role();
+    UserResponse {
+        id: user.id,
+        email: user.email,
+        country_code: user.preferences.country_code.unwrap_or_default(),
+        language: user.preferences.language.unwrap_or_default(),
+        email_verified: user.email_verified_at.is_some(),
+        role,
+        created_at: user.created_at.to_rfc3339(),
+    }
+}
+
+/// Exchange a refresh token for a new token pair (rotation)
+///
+/// Each refresh token works once. Presenting one that was already used means
+/// it leaked, so the whole session is revoked.
+pub async fn refresh(state: &AppState, request: RefreshRequest) -> Result<AuthResponse> {
+    let old_hash = hash_secret_token(&request.refresh_token);
+    let token = state
+        .db
+        .find_refresh_token(&old_hash)
+        .await?
+        .ok_or_else(|| ApiError::Unauthorized("Invalid refresh token".into()))?;
+
+    if token.revoked_at.is_some() || token.expires_at <= Utc::now() {
+        return Err(ApiError::Unauthorized("Session expired or revoked".into()).into());
+    }
+
+    let refresh_token = generate_secret_token();
+    let rotated = token.used_at.is_none()
+        && state
+            .db
+            .rotate_refresh_token(&old_hash, &hash_secret_token(&refresh_token))
+            .await?;
+    if !rotated {
+        tracing::warn!("Refresh token reuse detected, revoking session {}", token.session_id);
+        state.db.revoke_session(&token.user_id, &token.session_id).await?;
+        return Err(ApiError::Unauthorized("Refresh token already used; session revoked".into()).into());
+    }
+
+    let user = state
+        .db
+        .find_user_by_id(&token.user_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+
+    auth_response(user, &token.session_id, refresh_token)
+}
+
+/// Revoke the session a refresh token belongs to
+pub async fn logout(state: &AppState, request: RefreshRequest) -> Result<()> {
+    if let Some(token) = state
+        .db
+        .find_refresh_token(&hash_secret_token(&request.refresh_token))
+        .await?
+    {
+        state.db.revoke_session(&token.user_id, &token.session_id).await?;
+    }
+    Ok(())
+}
+
+/// Active sessions of the token's user
+pub async fn list_sessions(state: &AppState, claims: &Claims) -> Result<Vec<SessionResponse>> {
+    let now = Utc::now();
+    let sessions = state.db.list_sessions(&claims.sub).await?;
+
+    Ok(sessions
+        .into_iter()
+        .filter(|s| s.expires_at > now)
+        .map(|s| session_response(s, &claims.sid))
+        .collect())
+}
+
+fn session_response(session: SessionDb, current_session_id: &str) -> SessionResponse {
+    let format = |time: DateTime<Utc>| time.to_rfc3339();
+    SessionResponse {
+        current: session.id == current_session_id,
+        id: session.id,
+        user_agent: session.user_agent,
+        ip_address: session.ip_address,
+        created_at: format(session.created_at),
+        last_used_at: format(session.last_used_at),
+        expires_at: format(session.expires_at),
+    }
+}
+
+/// Revoke one of the token user's sessions
+pub async fn revoke_session(state: &AppState, claims: &Claims, session_id: &str) -> Result<bool> {
+    state.db.revoke_session(&claims.sub, session_id).await
+}
+
+/// Store a new single-use token for `user` and return the plain token
+async fn issue_email_token(
+    state: &AppState,
+    user: &UserDb,
+    purpose: EmailTokenPurpose,
+    ttl: Duration,
+) -> Result<String> {
+    let token = generate_secret_token();
+    state
+        .db
+        .create_email_token(&NewEmailToken {
+            token_hash: hash_secret_token(&token),
+            user_id: user.id.clone(),
+            purpose,
+            expires_at: Utc::now() + ttl,
+        })
+        .await?;
+    Ok(token)
+}
+
+async fn send_verification_email(state: &AppState, user: &UserDb) -> Result<()> {
+    let token = issue_email_token(
+        state,
+        user,
+        EmailTokenPurpose::VerifyEmail,
+        Duration::hours(EMAIL_VERIFICATION_TTL_HOURS),
+    )
+    .await?;
+    let link = format!("{}/verify-email?token={}", state.config.app_base_url, token);
+
+    state
+        .mailer
+        .send(&verification_email(user, &link, &token))
+        .await
+}
+
+fn verification_email(user: &UserDb, link: &str, token: &str) -> Email {
+    let (subject, body) = match user.preferences.language.as_deref() {
+        Some("uk") => (
+            "Підтвердіть електронну адресу",
+            format!(
+                "Щоб підтвердити адресу для Fishing Forecast, відкрийте посилання:\n\n{}\n\n\
+                 Код підтвердження: {}\nПосилання дійсне {} годин.",
+                link, token, EMAIL_VERIFICATION_TTL_HOURS
+            ),
+        ),
+        _ => (
+            "Confirm your email address",
+            format!(
+                "To confirm your Fishing Forecast email address, open this link:\n\n{}\n\n\
+                 Confirmation code: {}\nThe link is valid for {} hours.",
+                link, token, EMAIL_VERIFICATION_TTL_HOURS
+            ),
+        ),
+    };
+
+    Email {
+        to: user.email.clone(),
+        subject: subject.to_string(),
+        body,
+    }
+}
+
+fn password_reset_email(user: &UserDb, link: &str, token: &str) -> Email {
+    let (subject, body) = match user.preferences.language.as_deref() {
+        Some("uk") => (
+            "Скидання пароля",
+            format!(
+                "Щоб встановити новий пароль для Fishing Forecast, відкрийте посилання:\n\n{}\n\n\
+                 Код скидання: {}\nПосилання дійсне {} хвилин. Якщо ви не надсилали запит, \
+                 просто проігноруйте цей лист.",
+                link, token, PASSWORD_RESET_TTL_MINUTES
+            ),
+        ),
+        _ => (
+            "Reset your password",
+            format!(
+                "To choose a new Fishing Forecast password, open this link:\n\n{}\n\n\
+                 Reset code: {}\nThe link is valid for {} minutes. If you did not ask for \
+                 a reset, you can ignore this email.",
+                link, token, PASSWORD_RESET_TTL_MINUTES
+            ),
+        ),
+    };
+
+    Email {
+        to: user.email.clone(),
+        subject: subject.to_string(),
+        body,
+    }
+}
+
+/// Send a new verification link to the token's user
+///
+/// Does nothing if the address is already verified.
+pub async fn request_email_verification(state: &AppState, claims: &Claims) -> Result<()> {
+    let user = state
+        .db
+        .find_user_by_id(&claims.sub)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+
+    if user.email_verified_at.is_none() {
+        send_verification_email(state, &user).await?;
+    }
+    Ok(())
+}
+
+/// Confirm an email address with a token from the verification email
+pub async fn confirm_email(state: &AppState, request: VerifyEmailRequest) -> Result<UserResponse> {
+    let user_id = state
+        .db
+        .consume_email_token(&hash_secret_token(&request.token), EmailTokenPurpose::VerifyEmail)
+        .await?
+        .ok_or(ApiError::InvalidToken)?;
+
+    state.db.mark_email_verified(&user_id).await?;
+
+    let user = state
+        .db
+        .find_user_by_id(&user_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("User not found".into()))?;
+    Ok(user_response(user))
+}
+
+/// Email a password reset link
+///
+/// Succeeds whether or not the address has an account, so the endpoint
+/// cannot be used to discover registered emails.
+pub async fn request_password_reset(state: &AppState, request: PasswordResetRequest) -> Result<()> {
+    let Some(user) = state.db.find_user_by_email(&normalize_email(&request.email)).await? else {
+        tracing::info!("Password reset requested for unknown email");
+        return Ok(());
+    };
+
+    let token = issue_email_token(
+        state,
+        &user,
+        EmailTokenPurpose::ResetPassword,
+        Duration::minutes(PASSWORD_RESET_TTL_MINUTES),
+    )
+    .await?;
+    let link = format!("{}/reset-password?token={}", state.config.app_base_url, token);
+
+    state
+        .mailer
+        .send(&password_reset_email(&user, &link, &token))
+        .await
+}
+
+/// Set a new password with a token from the reset email
+///
+/// All of the user's sessions are revoked. Receiving the email also proves
+/// the address, so an unverified one becomes verified.
+pub async fn confirm_password_reset(state: &AppState, request: PasswordResetConfirm) -> Result<()> {
+    let user_id = state
+        .db
+        .consume_email_token(&hash_secret_token(&request.token), EmailTokenPurpose::ResetPassword)
+        .await?
+        .ok_or(ApiError::InvalidToken)?;
+
+    let password_hash = hash_password(&request.new_password).await?;
+    state.db.reset_password(&user_id, &password_hash).await?;
+    state.db.mark_email_verified(&user_id).await?;
+    Ok(())
+}
+
 /// Get user by ID
 pub async fn get_user_by_id(
     state: &AppState,
     user_id: &str,
 ) -> Result<Option<UserResponse>> {
-    let user: Option<DbUser> = sqlx::query_as(
-        "SELECT id, email, password_hash, country_code, language, created_at FROM users WHERE id = ?1"
-    )
-    .bind(user_id)
-    .fetch_optional(&state.db)
-    .await?;
+    let user = state.db.find_user_by_id(user_id).await?;
     
-    Ok(user.map(|u| UserResponse {
-        id: u.id,
-        email: u.email,
-        country_code: u.country_code.unwrap_or_default(),
-        language: u.language.unwrap_or_default(),
-        created_at: u.created_at.to_rfc3339(),
-    }))
+    Ok(user.map(user_response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_jwt_secret() {
+        let strong = "x".repeat(MIN_JWT_SECRET_LEN);
+
+        assert_eq!(resolve_jwt_secret(None, true).unwrap(), DEV_JWT_SECRET);
+        assert!(resolve_jwt_secret(None, false).is_err(), "no secret outside dev");
+        assert!(resolve_jwt_secret(Some(String::new()), false).is_err());
+        assert!(resolve_jwt_secret(Some("short".into()), false).is_err());
+        assert_eq!(resolve_jwt_secret(Some("short".into()), true).unwrap(), "short");
+        assert_eq!(resolve_jwt_secret(Some(strong.clone()), false).unwrap(), strong);
+    }
+
+    #[test]
+    fn test_access_token_carries_session() {
+        init_jwt_secret().unwrap();
+        let token = generate_token("user-1", "angler@example.com", Role::Admin, "session-1").unwrap();
+        let claims = validate_token(&token).unwrap();
+
+        assert_eq!(claims.sub, "user-1");
+        assert_eq!(claims.sid, "session-1");
+        assert_eq!(claims.role, Role::Admin);
+        assert_eq!(claims.exp - claims.iat, (ACCESS_TOKEN_TTL_MINUTES * 60) as usize);
+        assert!(validate_token(&format!("{}x", token)).is_err());
+    }
+
+    #[test]
+    fn test_refresh_tokens_are_random_and_hashed() {
+        let a = generate_secret_token();
+        let b = generate_secret_token();
+        assert_ne!(a, b);
+        assert_eq!(a.len(), 64);
+        assert_ne!(hash_secret_token(&a), a);
+        assert_eq!(hash_secret_token(&a), hash_secret_token(&a));
+    }
 }
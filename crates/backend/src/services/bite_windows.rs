@@ -0,0 +1,172 @@
+//! Hours with a good bite over the next two days.
+//!
+//! The model is run on the hourly weather forecast for a place; runs of
+//! consecutive hours at or above [`GOOD_BITE_PROBABILITY`] form windows.
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use fishing_shared::types::{BiteWindow, BiteWindows, WeatherCurrent};
+
+use super::prediction::{features_from_weather, MODEL_REGISTRY};
+use super::weather::fetch_weather_series;
+
+/// Hours looked ahead, including the current one.
+pub const HORIZON_HOURS: i64 = 48;
+
+/// Probability from which an hour counts towards a window.
+pub const GOOD_BITE_PROBABILITY: f64 = 0.6;
+
+/// Current and next best bite windows at a place.
+pub async fn bite_windows(
+    http: &reqwest::Client,
+    lat: f64,
+    lon: f64,
+    now: DateTime<Utc>,
+) -> anyhow::Result<BiteWindows> {
+    let hours = hourly_probabilities(http, lat, lon, now, HORIZON_HOURS).await?;
+    anyhow::ensure!(!hours.is_empty(), "no hourly weather for {}, {}", lat, lon);
+    Ok(summarize(&hours, now))
+}
+
+/// Model probability for `count` hours, starting with the one containing
+/// `from`. Hours the weather service has no data for are left out.
+pub async fn hourly_probabilities(
+    http: &reqwest::Client,
+    lat: f64,
+    lon: f64,
+    from: DateTime<Utc>,
+    count: i64,
+) -> anyhow::Result<Vec<(DateTime<Utc>, f64)>> {
+    let hours = hourly_forecast(http, lat, lon, from, count).await?;
+    Ok(hours.into_iter().map(|(weather, probability)| (weather.time, probability)).collect())
+}
+
+/// Like [`hourly_probabilities`], keeping each hour's weather.
+pub async fn hourly_forecast(
+    http: &reqwest::Client,
+    lat: f64,
+    lon: f64,
+    from: DateTime<Utc>,
+    count: i64,
+) -> anyhow::Result<Vec<(WeatherCurrent, f64)>> {
+    let first = from
+        .duration_trunc(Duration::hours(1))
+        .context("invalid forecast time")?;
+    let series = fetch_weather_series(http, lat, lon, first, first + Duration::hours(count - 1))
+        .await
+        .context("weather fetch failed")?;
+
+    let model = MODEL_REGISTRY.get().await;
+    Ok(series
+        .into_iter()
+        .map(|weather| {
+            let probability = model.predict(&features_from_weather(&weather, lat));
+            (weather, probability)
+        })
+        .collect())
+}
+
+/// Windows in hourly probabilities (ordered by time), seen from `now`.
+pub fn summarize(hours: &[(DateTime<Utc>, f64)], now: DateTime<Utc>) -> BiteWindows {
+    let contains_now = |start: DateTime<Utc>, end: DateTime<Utc>| start <= now && now < end;
+    let windows = good_windows(hours);
+
+    let probability_now = hours
+        .iter()
+        .find(|(time, _)| contains_now(*time, *time + Duration::hours(1)))
+        .map(|&(_, probability)| probability)
+        .unwrap_or_default();
+    let current = windows
+        .iter()
+        .find(|window| contains_now(window.start, window.end))
+        .cloned();
+    // The earliest of equally good windows
+    let next_best = windows
+        .into_iter()
+        .filter(|window| window.start > now)
+        .fold(None::<BiteWindow>, |best, window| match best {
+            Some(best) if best.peak_probability >= window.peak_probability => Some(best),
+            _ => Some(window),
+        });
+
+    BiteWindows {
+        probability_now,
+        current,
+        next_best,
+        generated_at: Utc::now(),
+    }
+}
+
+/// Runs of consecutive hours at or above [`GOOD_BITE_PROBABILITY`], in
+/// hourly probabilities ordered by time.
+pub fn good_windows(hours: &[(DateTime<Utc>, f64)]) -> Vec<BiteWindow> {
+    let mut windows: Vec<BiteWindow> = Vec::new();
+    for &(time, probability) in hours {
+        if probability < GOOD_BITE_PROBABILITY {
+            continue;
+        }
+        match windows.last_mut() {
+            Some(window) if window.end == time => {
+                window.end = time + Duration::hours(1);
+                window.peak_probability = window.peak_probability.max(probability);
+            }
+            _ => windows.push(BiteWindow {
+                start: time,
+                end: time + Duration::hours(1),
+                peak_probability: probability,
+            }),
+        }
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn hours(start: DateTime<Utc>, probabilities: &[f64]) -> Vec<(DateTime<Utc>, f64)> {
+        probabilities
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| (start + Duration::hours(i as i64), p))
+            .collect()
+    }
+
+    #[test]
+    fn test_windows_are_runs_of_good_hours() {
+        let start = Utc.with_ymd_and_hms(2026, 5, 14, 4, 0, 0).unwrap();
+        let now = start + Duration::minutes(90);
+        let series = hours(start, &[0.5, 0.7, 0.65, 0.4, 0.3, 0.8, 0.9, 0.2, 0.9, 0.5]);
+
+        let windows = summarize(&series, now);
+        assert_eq!(windows.probability_now, 0.7);
+        let current = windows.current.unwrap();
+        assert_eq!((current.start, current.end), (start + Duration::hours(1), start + Duration::hours(3)));
+        assert_eq!(current.peak_probability, 0.7);
+
+        // 05:00-07:00 at 0.9 beats the single hour at 0.9 that comes later
+        let next = windows.next_best.unwrap();
+        assert_eq!((next.start, next.end), (start + Duration::hours(5), start + Duration::hours(7)));
+        assert_eq!(next.peak_probability, 0.9);
+    }
+
+    #[test]
+    fn test_no_windows_when_the_bite_stays_poor() {
+        let start = Utc.with_ymd_and_hms(2026, 5, 14, 12, 0, 0).unwrap();
+        let windows = summarize(&hours(start, &[0.3, 0.59, 0.4]), start);
+        assert_eq!(windows.probability_now, 0.3);
+        assert_eq!(windows.current, None);
+        assert_eq!(windows.next_best, None);
+    }
+
+    #[test]
+    fn test_missing_hours_split_windows() {
+        let start = Utc.with_ymd_and_hms(2026, 5, 14, 12, 0, 0).unwrap();
+        let mut series = hours(start, &[0.7, 0.7]);
+        series.push((start + Duration::hours(3), 0.8));
+        let windows = summarize(&series, start - Duration::hours(1));
+        assert_eq!(windows.probability_now, 0.0, "no data for the current hour");
+        assert_eq!(windows.next_best.unwrap().start, start + Duration::hours(3));
+    }
+}
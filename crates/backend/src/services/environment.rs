@@ -0,0 +1,124 @@
+use chrono::{DateTime, Duration, Utc};
+use fishing_shared::{
+    types::{EnvironmentSnapshot, PressureTrend, WeatherCurrent},
+    utils::{
+        moon::{moon_illumination, moon_phase},
+        sun::sun_position,
+    },
+};
+
+use crate::services::weather::fetch_weather_series;
+
+/// Hours of history used for the pressure trend.
+const PRESSURE_TREND_HOURS: i64 = 3;
+
+/// Look up the conditions at a catch's time and place.
+///
+/// Never fails: if the weather lookup does not succeed the snapshot still
+/// carries the astronomical values and the weather fields stay empty.
+pub async fn capture_snapshot(
+    http: &reqwest::Client,
+    lat: f64,
+    lon: f64,
+    caught_at: DateTime<Utc>,
+) -> EnvironmentSnapshot {
+    let start = caught_at - Duration::hours(PRESSURE_TREND_HOURS);
+    let series = match fetch_weather_series(http, lat, lon, start, caught_at).await {
+        Ok(series) => series,
+        Err(err) => {
+            tracing::warn!("Weather lookup for catch at {} failed: {:#}", caught_at, err);
+            Vec::new()
+        }
+    };
+
+    build_snapshot(lat, lon, caught_at, &series)
+}
+
+/// Combine hourly weather (oldest first, ending at the catch hour) with the
+/// moon and sun position at `caught_at`.
+pub fn build_snapshot(
+    lat: f64,
+    lon: f64,
+    caught_at: DateTime<Utc>,
+    series: &[WeatherCurrent],
+) -> EnvironmentSnapshot {
+    let phase = moon_phase(caught_at);
+    let sun = sun_position(lat, lon, caught_at);
+
+    let current = series.last();
+    let pressure_change = match (series.first(), current) {
+        (Some(first), Some(last)) if last.time > first.time => {
+            let hours = (last.time - first.time).num_minutes() as f64 / 60.0;
+            Some((last.pressure_hpa - first.pressure_hpa) * PRESSURE_TREND_HOURS as f64 / hours)
+        }
+        _ => None,
+    };
+
+    EnvironmentSnapshot {
+        weather_time: current.map(|w| w.time),
+        temperature_c: current.map(|w| w.temperature_c),
+        pressure_hpa: current.map(|w| w.pressure_hpa),
+        pressure_change_3h_hpa: pressure_change,
+        pressure_trend: pressure_change.map(PressureTrend::from_change),
+        wind_speed_ms: current.map(|w| w.wind_speed_ms),
+        wind_gust_ms: current.and_then(|w| w.wind_gust_ms),
+        wind_direction_deg: current.and_then(|w| w.wind_direction_deg),
+        precipitation_mm: current.and_then(|w| w.precipitation_mm),
+        moon_phase: phase,
+        moon_illumination: moon_illumination(phase),
+        sun_elevation_deg: sun.elevation_deg,
+        sun_azimuth_deg: sun.azimuth_deg,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hour(time: &str, pressure_hpa: f64) -> WeatherCurrent {
+        WeatherCurrent {
+            temperature_c: 16.0,
+            pressure_hpa,
+            wind_speed_ms: 3.5,
+            wind_gust_ms: Some(7.0),
+            wind_direction_deg: Some(240.0),
+            precipitation_mm: Some(0.2),
+            time: time.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_uses_catch_hour_and_pressure_trend() {
+        let caught_at = "2026-06-21T10:20:00Z".parse().unwrap();
+        let series = [
+            hour("2026-06-21T07:00:00Z", 1016.0),
+            hour("2026-06-21T08:00:00Z", 1015.1),
+            hour("2026-06-21T09:00:00Z", 1014.0),
+            hour("2026-06-21T10:00:00Z", 1013.5),
+        ];
+
+        let snapshot = build_snapshot(50.45, 30.52, caught_at, &series);
+        assert_eq!(snapshot.weather_time, Some("2026-06-21T10:00:00Z".parse().unwrap()));
+        assert_eq!(snapshot.pressure_hpa, Some(1013.5));
+        assert!((snapshot.pressure_change_3h_hpa.unwrap() + 2.5).abs() < 1e-9);
+        assert_eq!(snapshot.pressure_trend, Some(PressureTrend::Falling));
+        assert_eq!(snapshot.wind_gust_ms, Some(7.0));
+
+        // Kyiv just after solar noon on the June solstice: sun high, slightly
+        // west of south.
+        assert!((snapshot.sun_elevation_deg - 63.0).abs() < 1.5);
+        assert!((180.0..200.0).contains(&snapshot.sun_azimuth_deg));
+    }
+
+    #[test]
+    fn test_snapshot_without_weather_keeps_astronomy() {
+        let caught_at = "2026-06-21T22:00:00Z".parse().unwrap();
+        let snapshot = build_snapshot(50.45, 30.52, caught_at, &[]);
+
+        assert_eq!(snapshot.temperature_c, None);
+        assert_eq!(snapshot.pressure_trend, None);
+        assert!(snapshot.sun_elevation_deg < 0.0, "sun is down at night");
+        assert!((0.0..1.0).contains(&snapshot.moon_phase));
+        assert!((0.0..=1.0).contains(&snapshot.moon_illumination));
+    }
+}
@@ -0,0 +1,87 @@
+//! Favorite spots of the signed-in user.
+
+use anyhow::Result;
+use fishing_shared::types::{FavoriteSpot, FavoriteSpotInput, FieldError};
+use fishing_shared::validation::{codes, Validate, Validator};
+use uuid::Uuid;
+
+use crate::models::FavoriteSpotDb;
+use crate::AppState;
+
+/// Check a spot before saving it, including that its water body and target
+/// species exist; returns every rejected field.
+pub async fn validate_input(state: &AppState, input: &FavoriteSpotInput) -> Result<Vec<FieldError>> {
+    let mut v = Validator::default();
+    input.validate(&mut v);
+
+    if let Some(id) = input.water_body_id.as_deref() {
+        if !state.db.water_body_exists(id).await? {
+            v.error("water_body_id", codes::UNKNOWN, format!("Unknown water body: {}", id));
+        }
+    }
+    for id in &input.target_species {
+        if !state.db.fish_species_exists(id).await? {
+            v.error("target_species", codes::UNKNOWN, format!("Unknown fish species: {}", id));
+        }
+    }
+
+    Ok(v.finish().err().unwrap_or_default())
+}
+
+/// The user's spots, ordered by name
+pub async fn list(state: &AppState, user_id: &str) -> Result<Vec<FavoriteSpot>> {
+    let rows = state.db.list_favorite_spots(user_id).await?;
+    Ok(rows.into_iter().map(FavoriteSpot::from).collect())
+}
+
+/// Save a validated new spot
+pub async fn create(state: &AppState, user_id: &str, input: FavoriteSpotInput) -> Result<FavoriteSpot> {
+    let row = spot_to_db(Uuid::new_v4().to_string(), user_id, chrono::Utc::now(), input)?;
+    state.db.create_favorite_spot(&row).await?;
+    Ok(row.into())
+}
+
+/// Replace a spot with validated fields; returns `None` if the user has no
+/// spot with this id
+pub async fn update(
+    state: &AppState,
+    user_id: &str,
+    id: &str,
+    input: FavoriteSpotInput,
+) -> Result<Option<FavoriteSpot>> {
+    let Some(existing) = state.db.get_favorite_spot(user_id, id).await? else {
+        return Ok(None);
+    };
+    let row = spot_to_db(existing.id, user_id, existing.created_at, input)?;
+    if !state.db.update_favorite_spot(&row).await? {
+        return Ok(None);
+    }
+    Ok(Some(row.into()))
+}
+
+fn spot_to_db(
+    id: String,
+    user_id: &str,
+    created_at: chrono::DateTime<chrono::Utc>,
+    input: FavoriteSpotInput,
+) -> Result<FavoriteSpotDb> {
+    let mut species = Vec::with_capacity(input.target_species.len());
+    for id in input.target_species {
+        if !species.contains(&id) {
+            species.push(id);
+        }
+    }
+    let notes = input.notes.filter(|notes| !notes.trim().is_empty());
+
+    Ok(FavoriteSpotDb {
+        id,
+        user_id: user_id.to_string(),
+        name: input.name.trim().to_string(),
+        location_lat: input.lat,
+        location_lon: input.lon,
+        water_body_id: input.water_body_id,
+        target_species: serde_json::to_string(&species)?,
+        notes,
+        created_at,
+    })
+}
@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use chrono::{DateTime, DurationRound, Utc};
+use fishing_shared::{
+    types::WeatherCurrent,
+    utils::geo::{tile_x_to_lon, tile_y_to_lat},
+};
+use geojson::{Feature, FeatureCollection, Geometry, JsonObject, Value};
+use tokio::task::JoinSet;
+
+use super::{
+    prediction::{features_from_weather, MODEL_REGISTRY},
+    weather::fetch_hourly_weather,
+};
+
+/// Spacing of the lattice weather is sampled on, in degrees.
+///
+/// Cells that snap to the same lattice point share a single upstream fetch;
+/// this is close to the native resolution of the Open-Meteo models anyway.
+pub const WEATHER_SAMPLE_DEG: f64 = 0.25;
+
+/// Maximum number of distinct weather samples a single grid may need.
+pub const MAX_WEATHER_SAMPLES: usize = 64;
+
+/// Maximum number of cells per grid side.
+pub const MAX_GRID_RESOLUTION: usize = 64;
+
+/// Default number of cells per grid side.
+pub const DEFAULT_GRID_RESOLUTION: usize = 20;
+
+/// Number of cells per side evaluated for a map tile.
+pub const TILE_GRID_RESOLUTION: usize = 32;
+
+/// Rendered map tile size in pixels.
+pub const TILE_SIZE_PX: u32 = 256;
+
+/// Lowest zoom level tiles are rendered for (larger tiles need too many samples).
+pub const MIN_TILE_ZOOM: u32 = 8;
+
+/// Highest zoom level tiles are rendered for.
+pub const MAX_TILE_ZOOM: u32 = 18;
+
+/// How long a fetched weather sample is reused across requests.
+const WEATHER_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Weather samples shared between overlapping grid and tile requests.
+static WEATHER_CACHE: LazyLock<Mutex<HashMap<SampleKey, (Instant, WeatherCurrent)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Weather lattice point and hour (as a Unix timestamp).
+type SampleKey = (i32, i32, i64);
+
+/// Geographic bounding box in degrees.
+#[derive(Debug, Clone, Copy)]
+pub struct BoundingBox {
+    pub min_lat: f64,
+    pub min_lon: f64,
+    pub max_lat: f64,
+    pub max_lon: f64,
+}
+
+/// Cell edges of a forecast grid.
+///
+/// Edges are not required to be evenly spaced, which lets map tiles follow
+/// the Web Mercator projection.
+#[derive(Debug, Clone)]
+pub struct GridLayout {
+    /// Row edges from north to south (`rows + 1` values).
+    pub lat_edges: Vec<f64>,
+    /// Column edges from west to east (`cols + 1` values).
+    pub lon_edges: Vec<f64>,
+}
+
+impl GridLayout {
+    /// Evenly spaced `resolution` x `resolution` grid over a bounding box.
+    pub fn for_bbox(bbox: &BoundingBox, resolution: usize) -> anyhow::Result<Self> {
+        if !(-90.0..=90.0).contains(&bbox.min_lat) || !(-90.0..=90.0).contains(&bbox.max_lat) {
+            anyhow::bail!("latitude must be between -90 and 90");
+        }
+        if !(-180.0..=180.0).contains(&bbox.min_lon) || !(-180.0..=180.0).contains(&bbox.max_lon) {
+            anyhow::bail!("longitude must be between -180 and 180");
+        }
+        if bbox.min_lat >= bbox.max_lat || bbox.min_lon >= bbox.max_lon {
+            anyhow::bail!("bounding box minimum must be below its maximum");
+        }
+        if resolution == 0 || resolution > MAX_GRID_RESOLUTION {
+            anyhow::bail!("resolution must be between 1 and {MAX_GRID_RESOLUTION}");
+        }
+
+        let layout = Self {
+            lat_edges: linspace(bbox.max_lat, bbox.min_lat, resolution),
+            lon_edges: linspace(bbox.min_lon, bbox.max_lon, resolution),
+        };
+        layout.check_sample_count()?;
+        Ok(layout)
+    }
+
+    /// Grid covering a Web Mercator map tile.
+    pub fn for_tile(zoom: u32, x: u32, y: u32) -> anyhow::Result<Self> {
+        if !(MIN_TILE_ZOOM..=MAX_TILE_ZOOM).contains(&zoom) {
+            anyhow::bail!("zoom must be between {MIN_TILE_ZOOM} and {MAX_TILE_ZOOM}");
+        }
+        let tiles = 1u32 << zoom;
+        if x >= tiles || y >= tiles {
+            anyhow::bail!("tile {x}/{y} is out of range for zoom {zoom}");
+        }
+
+        let n = TILE_GRID_RESOLUTION as f64;
+        let lat_edges = (0..=TILE_GRID_RESOLUTION)
+            .map(|i| tile_y_to_lat(zoom, y as f64 + i as f64 / n))
+            .collect();
+        let lon_edges = (0..=TILE_GRID_RESOLUTION)
+            .map(|i| tile_x_to_lon(zoom, x as f64 + i as f64 / n))
+            .collect();
+
+        let layout = Self { lat_edges, lon_edges };
+        layout.check_sample_count()?;
+        Ok(layout)
+    }
+
+    /// Number of rows in the grid.
+    pub fn rows(&self) -> usize {
+        self.lat_edges.len().saturating_sub(1)
+    }
+
+    /// Number of columns in the grid.
+    pub fn cols(&self) -> usize {
+        self.lon_edges.len().saturating_sub(1)
+    }
+
+    /// Center of a cell as (lat, lon).
+    fn cell_center(&self, row: usize, col: usize) -> (f64, f64) {
+        (
+            (self.lat_edges[row] + self.lat_edges[row + 1]) / 2.0,
+            (self.lon_edges[col] + self.lon_edges[col + 1]) / 2.0,
+        )
+    }
+
+    /// Weather lattice point for every cell, in row-major order.
+    fn cell_samples(&self) -> Vec<(i32, i32)> {
+        (0..self.rows())
+            .flat_map(|row| (0..self.cols()).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                let (lat, lon) = self.cell_center(row, col);
+                (snap_to_lattice(lat), snap_to_lattice(lon))
+            })
+            .collect()
+    }
+
+    fn check_sample_count(&self) -> anyhow::Result<()> {
+        let mut samples = self.cell_samples();
+        samples.sort_unstable();
+        samples.dedup();
+        if samples.len() > MAX_WEATHER_SAMPLES {
+            anyhow::bail!(
+                "area too large: needs {} weather samples (max {MAX_WEATHER_SAMPLES})",
+                samples.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Bite probabilities evaluated over a grid at one point in time.
+#[derive(Debug, Clone)]
+pub struct ForecastGrid {
+    pub layout: GridLayout,
+    pub time: DateTime<Utc>,
+    /// Row-major probabilities, `rows * cols` values.
+    pub probabilities: Vec<f64>,
+}
+
+/// Evaluate the bite model for every cell of a grid at the given time.
+///
+/// Weather is fetched once per lattice point and shared by all cells that
+/// snap to it, and is cached for a while so adjacent map tiles reuse it.
+pub async fn evaluate_grid(
+    http: &reqwest::Client,
+    layout: GridLayout,
+    time: DateTime<Utc>,
+) -> anyhow::Result<ForecastGrid> {
+    let hour = time
+        .duration_trunc(chrono::Duration::hours(1))
+        .context("invalid forecast time")?;
+    let cell_samples = layout.cell_samples();
+
+    let mut needed: Vec<(i32, i32)> = cell_samples.clone();
+    needed.sort_unstable();
+    needed.dedup();
+
+    let mut weather: HashMap<(i32, i32), WeatherCurrent> = HashMap::new();
+    {
+        let cache = WEATHER_CACHE.lock().expect("weather cache poisoned");
+        for &(lat_idx, lon_idx) in &needed {
+            if let Some((fetched_at, sample)) = cache.get(&(lat_idx, lon_idx, hour.timestamp())) {
+                if fetched_at.elapsed() < WEATHER_CACHE_TTL {
+                    weather.insert((lat_idx, lon_idx), sample.clone());
+                }
+            }
+        }
+    }
+
+    let mut fetches = JoinSet::new();
+    for &(lat_idx, lon_idx) in needed.iter().filter(|key| !weather.contains_key(key)) {
+        let http = http.clone();
+        fetches.spawn(async move {
+            let lat = lat_idx as f64 * WEATHER_SAMPLE_DEG;
+            let lon = lon_idx as f64 * WEATHER_SAMPLE_DEG;
+            let sample = fetch_hourly_weather(&http, lat, lon, hour).await;
+            ((lat_idx, lon_idx), sample)
+        });
+    }
+
+    let mut fetched = Vec::new();
+    while let Some(joined) = fetches.join_next().await {
+        let (key, sample) = joined.context("weather fetch task failed")?;
+        let sample = sample.context("weather fetch failed")?;
+        fetched.push((key, sample));
+    }
+
+    if !fetched.is_empty() {
+        let mut cache = WEATHER_CACHE.lock().expect("weather cache poisoned");
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < WEATHER_CACHE_TTL);
+        for ((lat_idx, lon_idx), sample) in fetched {
+            cache.insert((lat_idx, lon_idx, hour.timestamp()), (Instant::now(), sample.clone()));
+            weather.insert((lat_idx, lon_idx), sample);
+        }
+    }
+
+    let model = MODEL_REGISTRY.get().await;
+    let probabilities = cell_samples
+        .iter()
+        .enumerate()
+        .map(|(index, key)| {
+            let (lat, _) = layout.cell_center(index / layout.cols(), index % layout.cols());
+            model.predict(&features_from_weather(&weather[key], lat))
+        })
+        .collect();
+
+    Ok(ForecastGrid {
+        layout,
+        time: hour,
+        probabilities,
+    })
+}
+
+impl ForecastGrid {
+    /// Probability of a single cell.
+    pub fn probability(&self, row: usize, col: usize) -> f64 {
+        self.probabilities[row * self.layout.cols() + col]
+    }
+
+    /// Convert the grid into a GeoJSON feature collection of cell polygons.
+    pub fn to_geojson(&self) -> FeatureCollection {
+        let lat = &self.layout.lat_edges;
+        let lon = &self.layout.lon_edges;
+
+        let features = (0..self.layout.rows())
+            .flat_map(|row| (0..self.layout.cols()).map(move |col| (row, col)))
+            .map(|(row, col)| {
+                let ring = vec![
+                    vec![lon[col], lat[row]],
+                    vec![lon[col + 1], lat[row]],
+                    vec![lon[col + 1], lat[row + 1]],
+                    vec![lon[col], lat[row + 1]],
+                    vec![lon[col], lat[row]],
+                ];
+
+                let mut properties = JsonObject::new();
+                properties.insert("row".to_string(), row.into());
+                properties.insert("col".to_string(), col.into());
+                properties.insert("probability".to_string(), self.probability(row, col).into());
+
+                Feature {
+                    bbox: None,
+                    geometry: Some(Geometry::new(Value::Polygon(vec![ring]))),
+                    id: None,
+                    properties: Some(properties),
+                    foreign_members: None,
+                }
+            })
+            .collect();
+
+        let mut foreign_members = JsonObject::new();
+        foreign_members.insert("time".to_string(), self.time.to_rfc3339().into());
+
+        FeatureCollection {
+            bbox: Some(vec![lon[0], lat[lat.len() - 1], lon[lon.len() - 1], lat[0]]),
+            features,
+            foreign_members: Some(foreign_members),
+        }
+    }
+
+    /// Render the grid as a square RGBA PNG, one flat-colored block per cell.
+    pub fn render_png(&self, size_px: u32) -> anyhow::Result<Vec<u8>> {
+        let rows = self.layout.rows();
+        let cols = self.layout.cols();
+        let mut pixels = Vec::with_capacity((size_px * size_px * 4) as usize);
+        for py in 0..size_px as usize {
+            let row = py * rows / size_px as usize;
+            for px in 0..size_px as usize {
+                let col = px * cols / size_px as usize;
+                pixels.extend_from_slice(&probability_color(self.probability(row, col)));
+            }
+        }
+
+        let mut png_bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png_bytes, size_px, size_px);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().context("png header failed")?;
+        writer.write_image_data(&pixels).context("png encoding failed")?;
+        writer.finish().context("png encoding failed")?;
+
+        Ok(png_bytes)
+    }
+}
+
+/// Semi-transparent red → yellow → green ramp for a bite probability.
+pub fn probability_color(probability: f64) -> [u8; 4] {
+    let p = probability.clamp(0.0, 1.0);
+    let (r, g) = if p < 0.5 {
+        (1.0, p * 2.0)
+    } else {
+        (1.0 - (p - 0.5) * 2.0, 1.0)
+    };
+    [(r * 220.0) as u8, (g * 200.0) as u8, 40, 150]
+}
+
+/// `cells + 1` evenly spaced edges from `start` to `end`.
+fn linspace(start: f64, end: f64, cells: usize) -> Vec<f64> {
+    (0..=cells)
+        .map(|i| start + (end - start) * i as f64 / cells as f64)
+        .collect()
+}
+
+fn snap_to_lattice(degrees: f64) -> i32 {
+    (degrees / WEATHER_SAMPLE_DEG).round() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bbox(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> BoundingBox {
+        BoundingBox { min_lat, min_lon, max_lat, max_lon }
+    }
+
+    #[test]
+    fn test_bbox_layout_edges() {
+        let layout = GridLayout::for_bbox(&bbox(50.0, 30.0, 51.0, 31.0), 4).unwrap();
+        assert_eq!(layout.rows(), 4);
+        assert_eq!(layout.cols(), 4);
+        assert_eq!(layout.lat_edges[0], 51.0);
+        assert_eq!(layout.lat_edges[4], 50.0);
+        assert_eq!(layout.lon_edges[0], 30.0);
+        assert_eq!(layout.lon_edges[4], 31.0);
+    }
+
+    #[test]
+    fn test_bbox_layout_rejects_invalid_input() {
+        assert!(GridLayout::for_bbox(&bbox(51.0, 30.0, 50.0, 31.0), 4).is_err());
+        assert!(GridLayout::for_bbox(&bbox(50.0, 30.0, 95.0, 31.0), 4).is_err());
+        assert!(GridLayout::for_bbox(&bbox(50.0, 30.0, 51.0, 31.0), 0).is_err());
+        // 10° x 10° needs far more than MAX_WEATHER_SAMPLES lattice points
+        assert!(GridLayout::for_bbox(&bbox(40.0, 20.0, 50.0, 30.0), 40).is_err());
+    }
+
+    #[test]
+    fn test_nearby_cells_share_weather_samples() {
+        let layout = GridLayout::for_bbox(&bbox(50.0, 30.0, 50.5, 30.5), 20).unwrap();
+        let mut samples = layout.cell_samples();
+        assert_eq!(samples.len(), 400);
+        samples.sort_unstable();
+        samples.dedup();
+        assert!(samples.len() <= 9, "expected shared samples, got {}", samples.len());
+    }
+
+    #[test]
+    fn test_tile_layout_covers_tile_bounds() {
+        // Tile containing Kyiv at zoom 10
+        let layout = GridLayout::for_tile(10, 598, 345).unwrap();
+        assert_eq!(layout.rows(), TILE_GRID_RESOLUTION);
+        assert!(layout.lat_edges[0] > layout.lat_edges[TILE_GRID_RESOLUTION]);
+        assert!((layout.lon_edges[0] - tile_x_to_lon(10, 598.0)).abs() < 1e-9);
+        assert!(GridLayout::for_tile(3, 4, 2).is_err());
+        assert!(GridLayout::for_tile(10, 1024, 0).is_err());
+    }
+
+    #[test]
+    fn test_probability_color_ramp() {
+        assert_eq!(probability_color(0.0), [220, 0, 40, 150]);
+        assert_eq!(probability_color(1.0), [0, 200, 40, 150]);
+        assert_eq!(probability_color(2.0), probability_color(1.0));
+    }
+
+    #[test]
+    fn test_render_png_signature() {
+        let layout = GridLayout::for_bbox(&bbox(50.0, 30.0, 50.2, 30.2), 2).unwrap();
+        let grid = ForecastGrid {
+            layout,
+            time: Utc::now(),
+            probabilities: vec![0.1, 0.4, 0.6, 0.9],
+        };
+        let png = grid.render_png(16).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let geojson = grid.to_geojson();
+        assert_eq!(geojson.features.len(), 4);
+    }
+}
@@ -3,6 +3,8 @@ use serde::Deserialize;
 
 use fishing_shared::types::RegionInfo;
 
+use crate::config::upstream;
+
 #[derive(Debug, Deserialize)]
 struct NominatimAddress {
     country_code: Option<String>,
@@ -21,7 +23,8 @@ pub async fn detect_region(
     lon: f64,
 ) -> anyhow::Result<RegionInfo> {
     let url = format!(
-        "https://nominatim.openstreetmap.org/reverse?format=jsonv2&lat={lat}&lon={lon}",
+        "{}?format=jsonv2&lat={lat}&lon={lon}",
+        upstream().geocoding,
     );
 
     let resp = http
@@ -1,5 +1,17 @@
 pub mod http_client;
 pub mod weather;
 pub mod prediction;
+pub mod forecast_grid;
+pub mod bite_windows;
+pub mod trip_planner;
 pub mod geocoding;
 pub mod auth;
+pub mod environment;
+pub mod mailer;
+pub mod notifier;
+pub mod oidc;
+pub mod profile;
+pub mod favorites;
+pub mod alerts;
+pub mod admin;
+pub mod rate_limit;
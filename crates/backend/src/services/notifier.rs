@@ -0,0 +1,476 @@
+//! Push notifications to the users' browsers.
+//!
+//! The alert scheduler sends through the [`Notifier`] trait. With a VAPID
+//! key (`VAPID_PRIVATE_KEY`) messages go out as Web Push, encrypted as
+//! RFC 8291 describes; without one they are only logged.
+
+use std::fmt;
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes128Gcm, Nonce};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use hkdf::Hkdf;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::pkcs8::EncodePrivateKey;
+use p256::{PublicKey, SecretKey};
+use fishing_shared::types::{FieldError, PushSubscriptionKeys};
+use fishing_shared::validation::{codes, is_push_service_endpoint, Validator};
+use reqwest::{header, redirect, StatusCode, Url};
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::models::PushSubscriptionDb;
+
+const DEFAULT_VAPID_SUBJECT: &str = "mailto:noreply@localhost";
+
+/// How long a push service keeps an undelivered message.
+const PUSH_TTL_SECONDS: u32 = 12 * 60 * 60;
+
+/// Lifetime of the signed VAPID token; push services reject more than 24 hours.
+const VAPID_TOKEN_HOURS: i64 = 12;
+
+/// Record size announced in the encrypted message; one record holds it all.
+const RECORD_SIZE: u32 = 4096;
+
+/// What the service worker shows, sent as the JSON payload.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    /// Page opened when the notification is clicked.
+    pub url: Option<String>,
+    /// Buttons, already in the user's language.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<NotificationAction>,
+}
+
+/// A notification button; the service worker handles `open` and `close`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NotificationAction {
+    pub action: String,
+    pub title: String,
+}
+
+/// Outcome of sending one notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    Delivered,
+    /// The push service no longer knows the subscription; it should be deleted.
+    Gone,
+}
+
+/// Delivery backend for push notifications.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, subscription: &PushSubscriptionDb, notification: &Notification) -> Result<Delivery>;
+}
+
+/// Sends Web Push messages signed with the server's VAPID key.
+pub struct WebPushNotifier {
+    http: reqwest::Client,
+    key: VapidKey,
+    subject: String,
+}
+
+impl WebPushNotifier {
+    /// `http` must not follow redirects, or a push service could send the
+    /// request anywhere; see [`build_push_client`].
+    pub fn new(http: reqwest::Client, key: VapidKey, subject: &str) -> Self {
+        Self {
+            http,
+            key,
+            subject: subject.to_string(),
+        }
+    }
+}
+
+/// HTTP client for push services: no redirects, short timeouts.
+pub fn build_push_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .redirect(redirect::Policy::none())
+        .timeout(std::time::Duration::from_secs(10))
+        .connect_timeout(std::time::Duration::from_secs(5))
+        .use_rustls_tls()
+        .build()
+        .context("failed to build push client")
+}
+
+#[async_trait]
+impl Notifier for WebPushNotifier {
+    async fn notify(&self, subscription: &PushSubscriptionDb, notification: &Notification) -> Result<Delivery> {
+        // Subscriptions are checked when saved; rows from before that are not
+        anyhow::ensure!(
+            is_push_service_endpoint(&subscription.endpoint),
+            "Not a push service endpoint: {}",
+            subscription.endpoint
+        );
+        let payload = serde_json::to_vec(notification)?;
+        let body = encrypt(&subscription.p256dh, &subscription.auth, &payload)?;
+        let authorization = self.key.authorization(&subscription.endpoint, &self.subject, Utc::now())?;
+
+        let response = self
+            .http
+            .post(&subscription.endpoint)
+            .header(header::AUTHORIZATION, authorization)
+            .header(header::CONTENT_ENCODING, "aes128gcm")
+            .header(header::CONTENT_TYPE, "application/octet-stream")
+            .header("TTL", PUSH_TTL_SECONDS)
+            .body(body)
+            .send()
+            .await
+            .context("Push delivery failed")?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND | StatusCode::GONE => Ok(Delivery::Gone),
+            status if status.is_success() => Ok(Delivery::Delivered),
+            status => {
+                let detail = response.text().await.unwrap_or_default();
+                anyhow::bail!("Push service answered {}: {}", status, detail)
+            }
+        }
+    }
+}
+
+/// Logs notifications instead of sending them.
+pub struct LogNotifier;
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, subscription: &PushSubscriptionDb, notification: &Notification) -> Result<Delivery> {
+        tracing::info!(
+            "Notification not sent (no VAPID_PRIVATE_KEY) to {}: {} - {}",
+            subscription.endpoint,
+            notification.title,
+            notification.body
+        );
+        Ok(Delivery::Delivered)
+    }
+}
+
+/// Keeps notifications in memory; endpoints listed in `gone` answer as
+/// unsubscribed.
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryNotifier {
+    pub sent: std::sync::Mutex<Vec<(String, Notification)>>,
+    pub gone: Vec<String>,
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Notifier for MemoryNotifier {
+    async fn notify(&self, subscription: &PushSubscriptionDb, notification: &Notification) -> Result<Delivery> {
+        if self.gone.contains(&subscription.endpoint) {
+            return Ok(Delivery::Gone);
+        }
+        self.sent
+            .lock()
+            .unwrap()
+            .push((subscription.endpoint.clone(), notification.clone()));
+        Ok(Delivery::Delivered)
+    }
+}
+
+/// The server's P-256 key for VAPID (RFC 8292).
+#[derive(Clone)]
+pub struct VapidKey {
+    secret: SecretKey,
+}
+
+impl fmt::Debug for VapidKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VapidKey").field("public_key", &self.public_key()).finish()
+    }
+}
+
+impl VapidKey {
+    /// A new random key.
+    pub fn generate() -> Self {
+        Self {
+            secret: SecretKey::random(&mut rand::rngs::OsRng),
+        }
+    }
+
+    /// Key from the base64url private scalar, as `web-push generate-vapid-keys`
+    /// and [`VapidKey::private_key`] print it.
+    pub fn from_base64(private_key: &str) -> Result<Self> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(private_key.trim_end_matches('='))
+            .context("not base64url")?;
+        let secret = SecretKey::from_slice(&bytes).context("not a P-256 private key")?;
+        Ok(Self { secret })
+    }
+
+    /// Base64url private scalar.
+    pub fn private_key(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self.secret.to_bytes())
+    }
+
+    /// Base64url uncompressed public point, the browsers' `applicationServerKey`.
+    pub fn public_key(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self.secret.public_key().to_encoded_point(false).as_bytes())
+    }
+
+    /// `Authorization` header value for a message to `endpoint`.
+    fn authorization(&self, endpoint: &str, subject: &str, now: DateTime<Utc>) -> Result<String> {
+        #[derive(Serialize)]
+        struct Claims<'a> {
+            aud: String,
+            exp: i64,
+            sub: &'a str,
+        }
+
+        let audience = Url::parse(endpoint).context("Invalid push endpoint")?.origin();
+        let claims = Claims {
+            aud: audience.ascii_serialization(),
+            exp: (now + Duration::hours(VAPID_TOKEN_HOURS)).timestamp(),
+            sub: subject,
+        };
+        let der = self.secret.to_pkcs8_der().context("Cannot encode VAPID key")?;
+        let token = jsonwebtoken::encode(
+            &Header::new(Algorithm::ES256),
+            &claims,
+            &EncodingKey::from_ec_der(der.as_bytes()),
+        )?;
+
+        Ok(format!("vapid t={}, k={}", token, self.public_key()))
+    }
+}
+
+/// Web Push settings.
+#[derive(Debug, Clone)]
+pub struct PushConfig {
+    /// `VAPID_PRIVATE_KEY`; without it notifications are only logged.
+    pub vapid_key: Option<VapidKey>,
+    /// `VAPID_SUBJECT`: `mailto:` or `https:` contact for push services.
+    pub subject: String,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            vapid_key: None,
+            subject: DEFAULT_VAPID_SUBJECT.to_string(),
+        }
+    }
+}
+
+impl PushConfig {
+    /// Read `VAPID_PRIVATE_KEY` and `VAPID_SUBJECT`.
+    pub fn from_lookup(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let vapid_key = var("VAPID_PRIVATE_KEY")
+            .map(|key| VapidKey::from_base64(&key).context("Invalid VAPID_PRIVATE_KEY"))
+            .transpose()?;
+        let subject = var("VAPID_SUBJECT").unwrap_or_else(|| DEFAULT_VAPID_SUBJECT.to_string());
+        anyhow::ensure!(
+            subject.starts_with("mailto:") || subject.starts_with("https://"),
+            "VAPID_SUBJECT {:?} must be a mailto: or https: URL",
+            subject
+        );
+
+        Ok(Self { vapid_key, subject })
+    }
+}
+
+/// Pick the notifier: Web Push with a VAPID key, otherwise the log.
+pub fn from_config(config: &PushConfig) -> Result<Arc<dyn Notifier>> {
+    Ok(match &config.vapid_key {
+        Some(key) => Arc::new(WebPushNotifier::new(build_push_client()?, key.clone(), &config.subject)),
+        None => {
+            tracing::warn!("VAPID_PRIVATE_KEY not set, push notifications will only be logged");
+            Arc::new(LogNotifier)
+        }
+    })
+}
+
+/// Check that a subscription's keys decode, so they fail when subscribing
+/// rather than on every notification.
+pub fn check_keys(keys: &PushSubscriptionKeys) -> Result<(), Vec<FieldError>> {
+    let mut v = Validator::default();
+    if receiver_key(&keys.p256dh).is_err() {
+        v.error("keys.p256dh", codes::INVALID_FORMAT, "p256dh must be a base64url P-256 public key");
+    }
+    if auth_secret(&keys.auth).is_err() {
+        v.error("keys.auth", codes::INVALID_FORMAT, "auth must be a base64url 16-byte secret");
+    }
+    v.finish()
+}
+
+/// The browser's public key, as sent and decoded.
+fn receiver_key(p256dh: &str) -> Result<(Vec<u8>, PublicKey)> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(p256dh.trim_end_matches('='))
+        .context("p256dh is not base64url")?;
+    let key = PublicKey::from_sec1_bytes(&bytes).context("p256dh is not a P-256 key")?;
+    Ok((bytes, key))
+}
+
+fn auth_secret(auth: &str) -> Result<Vec<u8>> {
+    let secret = URL_SAFE_NO_PAD
+        .decode(auth.trim_end_matches('='))
+        .context("auth is not base64url")?;
+    anyhow::ensure!(secret.len() == 16, "auth secret must be 16 bytes");
+    Ok(secret)
+}
+
+/// Encrypt `payload` for a subscription (RFC 8291, `aes128gcm`).
+fn encrypt(p256dh: &str, auth: &str, payload: &[u8]) -> Result<Vec<u8>> {
+    let sender = SecretKey::random(&mut rand::rngs::OsRng);
+    let salt: [u8; 16] = rand::random();
+    encrypt_with(p256dh, auth, payload, &sender, salt)
+}
+
+fn encrypt_with(p256dh: &str, auth: &str, payload: &[u8], sender: &SecretKey, salt: [u8; 16]) -> Result<Vec<u8>> {
+    let (receiver_bytes, receiver) = receiver_key(p256dh)?;
+    let auth_secret = auth_secret(auth)?;
+    // Header, padding delimiter and tag share the record with the payload
+    anyhow::ensure!(payload.len() + 1 + 16 <= RECORD_SIZE as usize - 86, "payload too large for one record");
+
+    let sender_public = sender.public_key().to_encoded_point(false);
+    let shared = p256::ecdh::diffie_hellman(sender.to_nonzero_scalar(), receiver.as_affine());
+
+    let mut key_info = b"WebPush: info\0".to_vec();
+    key_info.extend_from_slice(&receiver_bytes);
+    key_info.extend_from_slice(sender_public.as_bytes());
+    let mut ikm = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&auth_secret), shared.raw_secret_bytes())
+        .expand(&key_info, &mut ikm)
+        .map_err(|_| anyhow::anyhow!("HKDF expand failed"))?;
+
+    let prk = Hkdf::<Sha256>::new(Some(&salt), &ikm);
+    let mut key = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    prk.expand(b"Content-Encoding: aes128gcm\0", &mut key)
+        .and_then(|_| prk.expand(b"Content-Encoding: nonce\0", &mut nonce))
+        .map_err(|_| anyhow::anyhow!("HKDF expand failed"))?;
+
+    // A single, last record: the payload followed by the 0x02 delimiter
+    let mut record = payload.to_vec();
+    record.push(2);
+    let ciphertext = Aes128Gcm::new(&key.into())
+        .encrypt(Nonce::from_slice(&nonce), record.as_slice())
+        .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+
+    let mut body = Vec::with_capacity(86 + ciphertext.len());
+    body.extend_from_slice(&salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(sender_public.as_bytes().len() as u8);
+    body.extend_from_slice(sender_public.as_bytes());
+    body.extend_from_slice(&ciphertext);
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(value: &str) -> Vec<u8> {
+        URL_SAFE_NO_PAD.decode(value).unwrap()
+    }
+
+    #[test]
+    fn test_encrypt_matches_rfc_8291_example() {
+        // RFC 8291, Appendix A
+        let sender = SecretKey::from_slice(&decode("yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw")).unwrap();
+        let salt: [u8; 16] = decode("DGv6ra1nlYgDCS1FRnbzlw").try_into().unwrap();
+
+        let body = encrypt_with(
+            "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4",
+            "BTBZMqHH6r4Tts7J_aSIgg",
+            b"When I grow up, I want to be a watermelon",
+            &sender,
+            salt,
+        )
+        .unwrap();
+
+        assert_eq!(
+            URL_SAFE_NO_PAD.encode(body),
+            "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN"
+        );
+    }
+
+    #[test]
+    fn test_vapid_authorization_is_signed_for_the_push_origin() {
+        let key = VapidKey::generate();
+        assert_eq!(VapidKey::from_base64(&key.private_key()).unwrap().public_key(), key.public_key());
+
+        let now = Utc::now();
+        let header = key
+            .authorization("https://push.example.com/send/abc?x=1", "mailto:ops@example.com", now)
+            .unwrap();
+        let (token, public_key) = header
+            .strip_prefix("vapid t=")
+            .and_then(|rest| rest.split_once(", k="))
+            .unwrap();
+        assert_eq!(public_key, key.public_key());
+
+        // Uncompressed point: 0x04, then x and y
+        let point = decode(public_key);
+        let decoding = jsonwebtoken::DecodingKey::from_ec_components(
+            &URL_SAFE_NO_PAD.encode(&point[1..33]),
+            &URL_SAFE_NO_PAD.encode(&point[33..]),
+        )
+        .unwrap();
+        let mut validation = jsonwebtoken::Validation::new(Algorithm::ES256);
+        validation.set_audience(&["https://push.example.com"]);
+        let claims = jsonwebtoken::decode::<serde_json::Value>(token, &decoding, &validation)
+            .unwrap()
+            .claims;
+        assert_eq!(claims["sub"], "mailto:ops@example.com");
+        assert_eq!(claims["exp"], (now + Duration::hours(VAPID_TOKEN_HOURS)).timestamp());
+    }
+
+    #[test]
+    fn test_push_config_validates_key_and_subject() {
+        let key = VapidKey::generate().private_key();
+        let config = PushConfig::from_lookup(|name| (name == "VAPID_PRIVATE_KEY").then(|| key.clone())).unwrap();
+        assert!(config.vapid_key.is_some());
+        assert_eq!(config.subject, DEFAULT_VAPID_SUBJECT);
+
+        assert!(PushConfig::from_lookup(|name| (name == "VAPID_PRIVATE_KEY").then(|| "nope".to_string())).is_err());
+        assert!(PushConfig::from_lookup(|name| (name == "VAPID_SUBJECT").then(|| "ops@example.com".to_string())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_only_push_services_are_contacted() {
+        let notifier = WebPushNotifier::new(build_push_client().unwrap(), VapidKey::generate(), DEFAULT_VAPID_SUBJECT);
+        let subscription = PushSubscriptionDb {
+            endpoint: "https://169.254.169.254/latest/meta-data".into(),
+            user_id: "user".into(),
+            p256dh: "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4".into(),
+            auth: "BTBZMqHH6r4Tts7J_aSIgg".into(),
+            created_at: Utc::now(),
+        };
+        let notification = Notification {
+            title: "Bite".into(),
+            body: "Now".into(),
+            url: None,
+            actions: Vec::new(),
+        };
+        let err = notifier.notify(&subscription, &notification).await.unwrap_err();
+        assert!(err.to_string().starts_with("Not a push service endpoint"), "{err}");
+    }
+
+    #[test]
+    fn test_subscription_keys_must_decode() {
+        let keys = |p256dh: &str, auth: &str| PushSubscriptionKeys {
+            p256dh: p256dh.into(),
+            auth: auth.into(),
+        };
+        let p256dh = "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4";
+        assert!(check_keys(&keys(p256dh, "BTBZMqHH6r4Tts7J_aSIgg")).is_ok());
+
+        let fields = |result: Result<(), Vec<FieldError>>| -> Vec<String> {
+            result.unwrap_err().into_iter().map(|error| error.field).collect()
+        };
+        // Truncated point, and a 15-byte secret
+        assert_eq!(fields(check_keys(&keys(&p256dh[..40], "BTBZMqHH6r4Tts7J_aSI"))), ["keys.p256dh", "keys.auth"]);
+        assert_eq!(fields(check_keys(&keys(p256dh, "not base64!"))), ["keys.auth"]);
+    }
+}
@@ -0,0 +1,733 @@
+//! OpenID Connect login: authorization code flow with PKCE.
+//!
+//! `authorize` returns the provider URL to send the browser to and records
+//! the pending login (`state`, `nonce`, PKCE verifier). The provider redirects
+//! back to the frontend, which posts `code` and `state` to `callback`; the ID
+//! token is verified against the provider's JWKS and the identity is mapped
+//! to a row in `users`. The result is the same token pair as password login.
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use sha2::{Digest, Sha256};
+use fishing_shared::validation::{Validate, Validator};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::models::{NewUser, NewUserIdentity, OidcLoginDb, UserDb};
+use crate::services::auth::{self, AuthResponse, Claims, ClientInfo};
+use crate::AppState;
+
+/// How long the user has to finish logging in at the provider.
+const LOGIN_TTL_MINUTES: i64 = 10;
+
+const DEFAULT_SCOPES: &str = "openid email profile";
+
+/// Signature algorithms accepted for ID tokens (never HMAC).
+const ID_TOKEN_ALGORITHMS: &[Algorithm] = &[
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+    Algorithm::ES256,
+    Algorithm::ES384,
+];
+
+/// A configured identity provider.
+#[derive(Debug, Clone)]
+pub struct OidcProvider {
+    /// Short id used in URLs and stored with linked identities.
+    pub id: String,
+    /// Display name for the login button.
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    /// Omit for public clients, which rely on PKCE alone.
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    pub scopes: String,
+}
+
+/// Identity providers enabled on this server.
+#[derive(Debug, Clone, Default)]
+pub struct OidcConfig {
+    providers: Vec<OidcProvider>,
+}
+
+impl OidcConfig {
+    pub fn new(providers: Vec<OidcProvider>) -> Self {
+        Self { providers }
+    }
+
+    /// Read providers from configuration variables.
+    ///
+    /// `OIDC_PROVIDERS` lists provider ids (e.g. `google,keycloak`); each one
+    /// needs `OIDC_<ID>_ISSUER` and `OIDC_<ID>_CLIENT_ID`, and may set
+    /// `_CLIENT_SECRET`, `_NAME`, `_REDIRECT_URI` and `_SCOPES`. Redirect URIs
+    /// default to `<app_base_url>/auth/callback/<id>`.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>, app_base_url: &str) -> Result<Self> {
+        let var = |name: &str| lookup(name).filter(|v| !v.trim().is_empty());
+        let Some(ids) = var("OIDC_PROVIDERS") else {
+            return Ok(Self::default());
+        };
+
+        let mut providers = Vec::new();
+        for id in ids.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+            anyhow::ensure!(
+                id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'),
+                "OIDC provider id {:?} must be lowercase letters, digits or '-'",
+                id
+            );
+            let prefix = format!("OIDC_{}_", id.to_ascii_uppercase().replace('-', "_"));
+            let required = |key: &str| {
+                var(&format!("{}{}", prefix, key))
+                    .with_context(|| format!("{}{} is not set", prefix, key))
+            };
+
+            providers.push(OidcProvider {
+                id: id.to_string(),
+                name: var(&format!("{}NAME", prefix)).unwrap_or_else(|| id.to_string()),
+                issuer: required("ISSUER")?.trim_end_matches('/').to_string(),
+                client_id: required("CLIENT_ID")?,
+                client_secret: var(&format!("{}CLIENT_SECRET", prefix)),
+                redirect_uri: var(&format!("{}REDIRECT_URI", prefix))
+                    .unwrap_or_else(|| format!("{}/auth/callback/{}", app_base_url, id)),
+                scopes: var(&format!("{}SCOPES", prefix)).unwrap_or_else(|| DEFAULT_SCOPES.to_string()),
+            });
+        }
+
+        Ok(Self { providers })
+    }
+
+    pub fn provider(&self, id: &str) -> Option<&OidcProvider> {
+        self.providers.iter().find(|p| p.id == id)
+    }
+
+    pub fn providers(&self) -> &[OidcProvider] {
+        &self.providers
+    }
+}
+
+/// Provider entry for the login screen
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProviderResponse {
+    pub id: String,
+    pub name: String,
+}
+
+/// Where to send the browser to log in
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuthorizeResponse {
+    pub authorization_url: String,
+    pub state: String,
+}
+
+/// Parameters the provider redirected back with
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CallbackRequest {
+    pub code: String,
+    pub state: String,
+}
+
+impl Validate for CallbackRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.required("code", &self.code).required("state", &self.state);
+    }
+}
+
+/// Identity linked to the current user
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IdentityResponse {
+    pub provider: String,
+    pub email: Option<String>,
+    pub created_at: String,
+}
+
+/// Subset of the provider's discovery document
+#[derive(Debug, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    email: Option<String>,
+    /// Some providers send `"true"` instead of `true`.
+    email_verified: Option<serde_json::Value>,
+    nonce: Option<String>,
+}
+
+impl IdTokenClaims {
+    fn email_verified(&self) -> bool {
+        match &self.email_verified {
+            Some(serde_json::Value::Bool(verified)) => *verified,
+            Some(serde_json::Value::String(verified)) => verified == "true",
+            _ => false,
+        }
+    }
+}
+
+/// Random URL-safe value for `state`, `nonce` and the PKCE verifier.
+fn random_urlsafe() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// S256 code challenge for a PKCE verifier.
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+async fn discover(http: &reqwest::Client, provider: &OidcProvider) -> Result<Discovery> {
+    let url = format!("{}/.well-known/openid-configuration", provider.issuer);
+    let discovery: Discovery = http
+        .get(&url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("Failed to fetch {}", url))?
+        .json()
+        .await
+        .context("Invalid OIDC discovery document")?;
+
+    anyhow::ensure!(
+        discovery.issuer.trim_end_matches('/') == provider.issuer,
+        "Discovery document is for issuer {}, expected {}",
+        discovery.issuer,
+        provider.issuer
+    );
+    Ok(discovery)
+}
+
+/// Start a login: record the pending state and build the provider URL
+pub async fn authorize(app: &AppState, provider: &OidcProvider) -> Result<AuthorizeResponse> {
+    let discovery = discover(&app.http, provider).await?;
+    let login = OidcLoginDb {
+        state: random_urlsafe(),
+        provider: provider.id.clone(),
+        code_verifier: random_urlsafe(),
+        nonce: random_urlsafe(),
+        redirect_uri: provider.redirect_uri.clone(),
+        expires_at: Utc::now() + Duration::minutes(LOGIN_TTL_MINUTES),
+    };
+    app.db.create_oidc_login(&login).await?;
+
+    let url = reqwest::Url::parse_with_params(
+        &discovery.authorization_endpoint,
+        &[
+            ("response_type", "code"),
+            ("client_id", provider.client_id.as_str()),
+            ("redirect_uri", login.redirect_uri.as_str()),
+            ("scope", provider.scopes.as_str()),
+            ("state", login.state.as_str()),
+            ("nonce", login.nonce.as_str()),
+            ("code_challenge", pkce_challenge(&login.code_verifier).as_str()),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .context("Invalid authorization endpoint")?;
+
+    Ok(AuthorizeResponse {
+        authorization_url: url.to_string(),
+        state: login.state,
+    })
+}
+
+/// Finish a login: redeem the code, verify the ID token and start a session
+///
+/// With `link_to` (a signed-in user) the identity is linked to that account
+/// instead of signing in whoever owns it.
+pub async fn callback(
+    app: &AppState,
+    provider: &OidcProvider,
+    request: CallbackRequest,
+    link_to: Option<&Claims>,
+    client: ClientInfo,
+) -> Result<AuthResponse> {
+    let login = app
+        .db
+        .take_oidc_login(&request.state)
+        .await?
+        .filter(|login| login.provider == provider.id && login.expires_at > Utc::now())
+        .ok_or_else(|| ApiError::Unauthorized("Unknown or expired login state".into()))?;
+
+    let discovery = discover(&app.http, provider).await.map_err(ApiError::Upstream)?;
+    // A rejected code or token is the caller's problem, not the provider's.
+    let id_token = exchange_code(&app.http, provider, &discovery, &login, &request.code)
+        .await
+        .map_err(|err| ApiError::Unauthorized(err.to_string()))?;
+    let claims = verify_id_token(&app.http, provider, &discovery, &id_token, &login.nonce)
+        .await
+        .map_err(|err| ApiError::Unauthorized(err.to_string()))?;
+
+    let user = resolve_user(app, provider, &claims, link_to).await?;
+    auth::start_session(app, user, client).await
+}
+
+async fn exchange_code(
+    http: &reqwest::Client,
+    provider: &OidcProvider,
+    discovery: &Discovery,
+    login: &OidcLoginDb,
+    code: &str,
+) -> Result<String> {
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", login.redirect_uri.as_str()),
+        ("client_id", provider.client_id.as_str()),
+        ("code_verifier", login.code_verifier.as_str()),
+    ];
+    if let Some(secret) = &provider.client_secret {
+        form.push(("client_secret", secret.as_str()));
+    }
+
+    let response = http
+        .post(&discovery.token_endpoint)
+        .form(&form)
+        .send()
+        .await
+        .context("Token request failed")?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Provider rejected the authorization code ({}): {}", status, body);
+    }
+
+    let tokens: TokenResponse = response.json().await.context("Invalid token response")?;
+    Ok(tokens.id_token)
+}
+
+async fn verify_id_token(
+    http: &reqwest::Client,
+    provider: &OidcProvider,
+    discovery: &Discovery,
+    id_token: &str,
+    nonce: &str,
+) -> Result<IdTokenClaims> {
+    let header = decode_header(id_token).context("Malformed ID token")?;
+    anyhow::ensure!(
+        ID_TOKEN_ALGORITHMS.contains(&header.alg),
+        "Unsupported ID token algorithm {:?}",
+        header.alg
+    );
+
+    let jwks: JwkSet = http
+        .get(&discovery.jwks_uri)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .context("Failed to fetch provider keys")?
+        .json()
+        .await
+        .context("Invalid JWKS")?;
+    let jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+    .ok_or_else(|| anyhow::anyhow!("No provider key matches the ID token"))?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_issuer(&[&discovery.issuer]);
+    validation.set_audience(&[&provider.client_id]);
+    let claims = decode::<IdTokenClaims>(id_token, &DecodingKey::from_jwk(jwk)?, &validation)
+        .context("Invalid ID token")?
+        .claims;
+
+    anyhow::ensure!(claims.nonce.as_deref() == Some(nonce), "ID token nonce mismatch");
+    Ok(claims)
+}
+
+/// Find or create the local user for a verified external identity
+async fn resolve_user(
+    app: &AppState,
+    provider: &OidcProvider,
+    claims: &IdTokenClaims,
+    link_to: Option<&Claims>,
+) -> Result<UserDb> {
+    let existing = app.db.find_user_identity(&provider.id, &claims.sub).await?;
+
+    let user_id = match (existing, link_to) {
+        (Some(identity), Some(current)) if identity.user_id != current.sub => {
+            return Err(ApiError::Conflict(format!("This {} account is linked to another user", provider.name)).into())
+        }
+        (Some(identity), _) => identity.user_id,
+        (None, Some(current)) => {
+            link(app, provider, claims, &current.sub).await?;
+            current.sub.clone()
+        }
+        (None, None) => {
+            let email = claims
+                .email
+                .as_deref()
+                .map(auth::normalize_email)
+                .ok_or_else(|| ApiError::Unauthorized(format!("{} did not share an email address", provider.name)))?;
+
+            // Only a provider-verified address may claim an existing account;
+            // otherwise anyone could sign up at the provider with a victim's email.
+            let user_id = match app.db.find_user_by_email(&email).await? {
+                Some(user) if claims.email_verified() => user.id,
+                Some(_) => {
+                    return Err(ApiError::Conflict(format!(
+                        "An account with this email already exists; sign in with your password and link {} from your profile",
+                        provider.name
+                    ))
+                    .into())
+                }
+                None => create_user(app, &email).await?,
+            };
+            link(app, provider, claims, &user_id).await?;
+            if claims.email_verified() {
+                app.db.mark_email_verified(&user_id).await?;
+            }
+            user_id
+        }
+    };
+
+    app.db
+        .find_user_by_id(&user_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("User not found".into()).into())
+}
+
+async fn link(app: &AppState, provider: &OidcProvider, claims: &IdTokenClaims, user_id: &str) -> Result<()> {
+    app.db
+        .link_user_identity(&NewUserIdentity {
+            provider: provider.id.clone(),
+            subject: claims.sub.clone(),
+            user_id: user_id.to_string(),
+            email: claims.email.clone(),
+        })
+        .await
+        .context("Failed to link identity")
+}
+
+/// New account without a password; one can be set later via password reset.
+async fn create_user(app: &AppState, email: &str) -> Result<String> {
+    let user_id = Uuid::new_v4().to_string();
+    app.db
+        .create_user(&NewUser {
+            id: user_id.clone(),
+            email: email.to_string(),
+            password_hash: String::new(),
+            country_code: "UA".to_string(),
+            language: "uk".to_string(),
+        })
+        .await
+        .context("Failed to create user")?;
+    Ok(user_id)
+}
+
+/// Identities linked to the token's user
+pub async fn list_identities(app: &AppState, claims: &Claims) -> Result<Vec<IdentityResponse>> {
+    let identities = app.db.list_user_identities(&claims.sub).await?;
+
+    Ok(identities
+        .into_iter()
+        .map(|identity| IdentityResponse {
+            provider: identity.provider,
+            email: identity.email,
+            created_at: identity.created_at.to_rfc3339(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use axum::{extract::State, http::StatusCode, routing::get, routing::post, Form, Json, Router};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+
+    const CLIENT_ID: &str = "fishing-app";
+
+    /// Login the mock issuer will hand out for an authorization code.
+    struct Grant {
+        code_challenge: String,
+        nonce: String,
+        redirect_uri: String,
+        sub: String,
+        email: String,
+        email_verified: bool,
+    }
+
+    #[derive(Clone)]
+    struct MockIssuer {
+        url: String,
+        grants: Arc<Mutex<HashMap<String, Grant>>>,
+    }
+
+    /// Start a local issuer with discovery, JWKS and token endpoints.
+    async fn mock_issuer() -> MockIssuer {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = MockIssuer {
+            url: format!("http://{}", listener.local_addr().unwrap()),
+            grants: Arc::default(),
+        };
+
+        let app = Router::new()
+            .route(
+                "/.well-known/openid-configuration",
+                get(|State(issuer): State<MockIssuer>| async move {
+                    Json(serde_json::json!({
+                        "issuer": issuer.url,
+                        "authorization_endpoint": format!("{}/authorize", issuer.url),
+                        "token_endpoint": format!("{}/token", issuer.url),
+                        "jwks_uri": format!("{}/jwks", issuer.url),
+                    }))
+                }),
+            )
+            .route(
+                "/jwks",
+                get(|| async {
+                    let jwks: serde_json::Value =
+                        serde_json::from_str(include_str!("../../testdata/oidc_test_jwks.json")).unwrap();
+                    Json(jwks)
+                }),
+            )
+            .route("/token", post(mock_token))
+            .with_state(issuer.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        issuer
+    }
+
+    async fn mock_token(
+        State(issuer): State<MockIssuer>,
+        Form(form): Form<HashMap<String, String>>,
+    ) -> Result<Json<serde_json::Value>, StatusCode> {
+        let grant = issuer
+            .grants
+            .lock()
+            .unwrap()
+            .remove(&form["code"])
+            .ok_or(StatusCode::BAD_REQUEST)?;
+        let verifier = form.get("code_verifier").ok_or(StatusCode::BAD_REQUEST)?;
+        if pkce_challenge(verifier) != grant.code_challenge
+            || form["redirect_uri"] != grant.redirect_uri
+            || form["client_id"] != CLIENT_ID
+        {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+
+        let now = Utc::now().timestamp();
+        let claims = serde_json::json!({
+            "iss": issuer.url,
+            "aud": CLIENT_ID,
+            "sub": grant.sub,
+            "email": grant.email,
+            "email_verified": grant.email_verified,
+            "nonce": grant.nonce,
+            "iat": now,
+            "exp": now + 300,
+        });
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some("test-key".to_string());
+        let key = EncodingKey::from_rsa_pem(include_bytes!("../../testdata/oidc_test_key.pem")).unwrap();
+        let id_token = encode(&header, &claims, &key).unwrap();
+
+        Ok(Json(serde_json::json!({ "access_token": "unused", "token_type": "Bearer", "id_token": id_token })))
+    }
+
+    impl MockIssuer {
+        /// Play the provider's login page: approve the login and return the
+        /// parameters it redirects back with.
+        fn approve(&self, authorization_url: &str, sub: &str, email: &str, email_verified: bool) -> CallbackRequest {
+            let url = reqwest::Url::parse(authorization_url).unwrap();
+            let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+            assert_eq!(params["code_challenge_method"], "S256");
+            assert_eq!(params["client_id"], CLIENT_ID);
+
+            let code = random_urlsafe();
+            self.grants.lock().unwrap().insert(
+                code.clone(),
+                Grant {
+                    code_challenge: params["code_challenge"].clone(),
+                    nonce: params["nonce"].clone(),
+                    redirect_uri: params["redirect_uri"].clone(),
+                    sub: sub.to_string(),
+                    email: email.to_string(),
+                    email_verified,
+                },
+            );
+            CallbackRequest {
+                code,
+                state: params["state"].clone(),
+            }
+        }
+    }
+
+    async fn test_state(issuer: &MockIssuer, dir: &tempfile::TempDir) -> (AppState, OidcProvider) {
+        auth::init_jwt_secret().unwrap();
+        let provider = OidcProvider {
+            id: "mock".to_string(),
+            name: "Mock".to_string(),
+            issuer: issuer.url.clone(),
+            client_id: CLIENT_ID.to_string(),
+            client_secret: None,
+            redirect_uri: "http://127.0.0.1:3001/auth/callback/mock".to_string(),
+            scopes: DEFAULT_SCOPES.to_string(),
+        };
+        let state = AppState {
+            http: reqwest::Client::builder().no_proxy().build().unwrap(),
+            oidc: Arc::new(OidcConfig::new(vec![provider.clone()])),
+            ..AppState::for_tests(dir).await
+        };
+        (state, provider)
+    }
+
+    #[tokio::test]
+    async fn test_login_creates_then_reuses_linked_user() {
+        let issuer = mock_issuer().await;
+        let dir = tempfile::tempdir().unwrap();
+        let (app, provider) = test_state(&issuer, &dir).await;
+
+        let start = authorize(&app, &provider).await.unwrap();
+        let redirect = issuer.approve(&start.authorization_url, "sub-1", "oidc@example.com", true);
+        let state = redirect.state.clone();
+        let first = callback(&app, &provider, redirect, None, ClientInfo::default()).await.unwrap();
+        assert_eq!(first.user.email, "oidc@example.com");
+        assert!(first.user.email_verified);
+        let claims = auth::validate_token(&first.token).unwrap();
+        assert_eq!(claims.sub, first.user.id);
+
+        // `state` is single use.
+        let replay = CallbackRequest { code: "whatever".to_string(), state };
+        assert!(callback(&app, &provider, replay, None, ClientInfo::default()).await.is_err());
+
+        // Same subject, same user, even if the email changed at the provider.
+        let start = authorize(&app, &provider).await.unwrap();
+        let redirect = issuer.approve(&start.authorization_url, "sub-1", "renamed@example.com", true);
+        let second = callback(&app, &provider, redirect, None, ClientInfo::default()).await.unwrap();
+        assert_eq!(second.user.id, first.user.id);
+
+        // The password login is not usable for an account without one.
+        let login = auth::LoginRequest { email: "oidc@example.com".to_string(), password: String::new() };
+        assert!(auth::login(&app, login, ClientInfo::default()).await.is_err());
+
+        let identities = list_identities(&app, &claims).await.unwrap();
+        assert_eq!(identities.len(), 1);
+        assert_eq!(identities[0].provider, "mock");
+    }
+
+    #[tokio::test]
+    async fn test_linking_rules() {
+        let issuer = mock_issuer().await;
+        let dir = tempfile::tempdir().unwrap();
+        let (app, provider) = test_state(&issuer, &dir).await;
+        let register = |email: &str| auth::RegisterRequest {
+            email: email.to_string(),
+            password: "s3cret-pass".to_string(),
+            country_code: None,
+            language: None,
+        };
+        let owner = auth::register(&app, register("owner@example.com"), ClientInfo::default()).await.unwrap();
+
+        // An unverified provider email cannot take over an existing account.
+        let start = authorize(&app, &provider).await.unwrap();
+        let redirect = issuer.approve(&start.authorization_url, "sub-a", "owner@example.com", false);
+        let err = callback(&app, &provider, redirect, None, ClientInfo::default()).await.unwrap_err();
+        assert_eq!(ApiError::from(err).code(), ErrorCode::Conflict);
+
+        // A verified one links to it.
+        let start = authorize(&app, &provider).await.unwrap();
+        let redirect = issuer.approve(&start.authorization_url, "sub-a", "owner@example.com", true);
+        let linked = callback(&app, &provider, redirect, None, ClientInfo::default()).await.unwrap();
+        assert_eq!(linked.user.id, owner.user.id);
+
+        // A signed-in user links an identity with a different email...
+        let other = auth::register(&app, register("other@example.com"), ClientInfo::default()).await.unwrap();
+        let other_claims = auth::validate_token(&other.token).unwrap();
+        let start = authorize(&app, &provider).await.unwrap();
+        let redirect = issuer.approve(&start.authorization_url, "sub-b", "someone@example.net", false);
+        let result = callback(&app, &provider, redirect, Some(&other_claims), ClientInfo::default()).await.unwrap();
+        assert_eq!(result.user.id, other.user.id);
+
+        // ...but cannot take one that belongs to someone else.
+        let start = authorize(&app, &provider).await.unwrap();
+        let redirect = issuer.approve(&start.authorization_url, "sub-a", "owner@example.com", true);
+        assert!(callback(&app, &provider, redirect, Some(&other_claims), ClientInfo::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_wrong_verifier_and_provider() {
+        let issuer = mock_issuer().await;
+        let dir = tempfile::tempdir().unwrap();
+        let (app, provider) = test_state(&issuer, &dir).await;
+
+        // The issuer only redeems the code for the matching PKCE verifier.
+        let start = authorize(&app, &provider).await.unwrap();
+        let redirect = issuer.approve(&start.authorization_url, "sub-1", "a@example.com", true);
+        issuer.grants.lock().unwrap().get_mut(&redirect.code).unwrap().code_challenge = pkce_challenge("other");
+        let err = callback(&app, &provider, redirect, None, ClientInfo::default()).await.unwrap_err();
+        assert_eq!(ApiError::from(err).code(), ErrorCode::Unauthorized);
+
+        // A state issued for one provider is not accepted by another.
+        let start = authorize(&app, &provider).await.unwrap();
+        let redirect = issuer.approve(&start.authorization_url, "sub-1", "a@example.com", true);
+        let impostor = OidcProvider { id: "other".to_string(), ..provider.clone() };
+        assert!(callback(&app, &impostor, redirect, None, ClientInfo::default()).await.is_err());
+    }
+
+    #[test]
+    fn test_config_from_lookup() {
+        let env: HashMap<&str, &str> = [
+            ("OIDC_PROVIDERS", "google, my-idp"),
+            ("OIDC_GOOGLE_ISSUER", "https://accounts.google.com/"),
+            ("OIDC_GOOGLE_CLIENT_ID", "google-client"),
+            ("OIDC_GOOGLE_CLIENT_SECRET", "google-secret"),
+            ("OIDC_GOOGLE_NAME", "Google"),
+            ("OIDC_MY_IDP_ISSUER", "https://idp.example.com/realms/anglers"),
+            ("OIDC_MY_IDP_CLIENT_ID", "fishing"),
+            ("OIDC_MY_IDP_REDIRECT_URI", "https://fish.example.com/cb"),
+        ]
+        .into_iter()
+        .collect();
+        let base = "https://fish.example.com";
+        let config = OidcConfig::from_lookup(|name| env.get(name).map(|v| v.to_string()), base).unwrap();
+
+        let google = config.provider("google").unwrap();
+        assert_eq!(google.issuer, "https://accounts.google.com");
+        assert_eq!(google.name, "Google");
+        assert_eq!(google.client_secret.as_deref(), Some("google-secret"));
+        assert_eq!(google.scopes, DEFAULT_SCOPES);
+        assert_eq!(google.redirect_uri, "https://fish.example.com/auth/callback/google");
+
+        let idp = config.provider("my-idp").unwrap();
+        assert_eq!(idp.name, "my-idp");
+        assert_eq!(idp.client_secret, None);
+        assert_eq!(idp.redirect_uri, "https://fish.example.com/cb");
+
+        let missing = OidcConfig::from_lookup(|name| (name == "OIDC_PROVIDERS").then(|| "google".to_string()), base);
+        assert!(missing.is_err());
+        assert!(OidcConfig::from_lookup(|_| None, base).unwrap().providers().is_empty());
+    }
+
+    #[test]
+    fn test_pkce_challenge() {
+        // base64url(SHA-256(verifier)) without padding, computed independently.
+        let challenge = pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gXk-kmNpQ");
+        assert_eq!(challenge, "JJxw-cMzrwGJrp8qjq2SusRYGvSngOZZhEd6deS3Uag");
+        assert_eq!(random_urlsafe().len(), 43);
+    }
+}
@@ -1,11 +1,14 @@
 use anyhow::Context;
-use chrono::{Datelike, Timelike};
+use chrono::{Datelike, Timelike, Utc};
 use fishing_ml_engine::{
-    create_features, FeatureImportance, ModelRegistry,
+    create_features, FeatureImportance, FishingFeatures, ModelRegistry,
     PredictionResult, TrainingSample,
 };
 use fishing_shared::{
-    types::{BaitRecommendation, ForecastFactors, ForecastResult},
+    types::{
+        BaitRecommendation, ForecastFactors, ForecastResult, ForecastSource, Language,
+        WeatherCurrent,
+    },
     utils::moon::moon_phase,
 };
 
@@ -13,36 +16,23 @@ use super::weather::fetch_current_weather;
 
 /// In-memory model registry for the application (lazily initialized)
 pub static MODEL_REGISTRY: std::sync::LazyLock<ModelRegistry> =
-    std::sync::LazyLock::new(|| ModelRegistry::new());
+    std::sync::LazyLock::new(ModelRegistry::new);
 
-/// Build a forecast using ML model and current weather.
+/// Build a forecast using ML model and current weather, worded in `language`.
 pub async fn build_forecast_ml(
     http: &reqwest::Client,
     lat: f64,
     lon: f64,
     fish: Option<&str>,
+    language: Language,
 ) -> anyhow::Result<ForecastResult> {
     let weather = fetch_current_weather(http, lat, lon)
         .await
         .context("weather fetch failed")?;
 
-    let hour = weather.time.hour() as u32;
-    let day_of_year = weather.time.ordinal() + 1;
+    let hour = weather.time.hour();
     let moon = moon_phase(weather.time);
-
-    let features = create_features(
-        weather.temperature_c,
-        weather.pressure_hpa,
-        weather.wind_speed_ms,
-        weather.wind_direction_deg,
-        weather.precipitation_mm,
-        hour,
-        day_of_year,
-        moon,
-        lat,
-        None, // Cloud cover not in current weather type
-        None, // Humidity not in current weather type
-    );
+    let features = features_from_weather(&weather, lat);
 
     let prediction = MODEL_REGISTRY.predict(&features).await;
 
@@ -69,14 +59,7 @@ pub async fn build_forecast_ml(
 
     let recommended_baits = generate_bait_recommendations(fish, probability);
 
-    let explanation = format!(
-        "ML model predicts {:.0}% bite probability based on current conditions: {:.1}°C, {} hPa pressure, {:.1} m/s wind, {} moon phase.",
-        probability * 100.0,
-        weather.temperature_c,
-        weather.pressure_hpa,
-        weather.wind_speed_ms,
-        if moon < 0.1 || moon > 0.9 { "new/full" } else { "quarter" }
-    );
+    let explanation = explain(language, probability, &weather, moon);
 
     Ok(ForecastResult {
         probability,
@@ -84,9 +67,11 @@ pub async fn build_forecast_ml(
         factors,
         explanation,
         recommended_baits,
-        best_time: get_best_time(hour),
+        best_time: BestTime::at(hour).message(language).to_string(),
         weather,
         moon_phase: moon,
+        source: ForecastSource::Model,
+        generated_at: Utc::now(),
     })
 }
 
@@ -100,26 +85,27 @@ pub async fn get_detailed_prediction(
         .await
         .context("weather fetch failed")?;
 
-    let hour = weather.time.hour() as u32;
-    let day_of_year = weather.time.ordinal() + 1;
-    let moon = moon_phase(weather.time);
+    let features = features_from_weather(&weather, lat);
 
-    let features = create_features(
+    let model = MODEL_REGISTRY.get().await;
+    Ok(model.predict_detailed(&features))
+}
+
+/// Build model features from a weather snapshot at the given latitude.
+pub fn features_from_weather(weather: &WeatherCurrent, lat: f64) -> FishingFeatures {
+    create_features(
         weather.temperature_c,
         weather.pressure_hpa,
         weather.wind_speed_ms,
         weather.wind_direction_deg,
         weather.precipitation_mm,
-        hour,
-        day_of_year,
-        moon,
+        weather.time.hour(),
+        weather.time.ordinal() + 1,
+        moon_phase(weather.time),
         lat,
-        None,
-        None,
-    );
-
-    let model = MODEL_REGISTRY.get().await;
-    Ok(model.predict_detailed(&features))
+        None, // Cloud cover not in current weather type
+        None, // Humidity not in current weather type
+    )
 }
 
 /// Initialize the ML model with default weights
@@ -184,6 +170,7 @@ pub async fn get_feature_importance() -> FeatureImportance {
 }
 
 /// Add user catch data for model improvement
+#[allow(dead_code)]
 pub async fn add_training_sample(sample: TrainingSample) {
     let mut model = MODEL_REGISTRY.get_mut().await;
     model.add_sample(sample);
@@ -195,10 +182,6 @@ pub async fn add_training_sample(sample: TrainingSample) {
 }
 
 // Scoring functions from original prediction.rs
-fn clamp01(value: f64) -> f64 {
-    value.clamp(0.0, 1.0)
-}
-
 fn score_pressure(pressure_hpa: f64) -> f64 {
     if (1000.0..=1020.0).contains(&pressure_hpa) {
         0.9
@@ -281,14 +264,113 @@ fn generate_bait_recommendations(fish: Option<&str>, probability: f64) -> Vec<Ba
     }
 }
 
-fn get_best_time(hour: u32) -> String {
-    if (5..=9).contains(&hour) {
-        "Now - Excellent morning bite!".to_string()
-    } else if (10..=15).contains(&hour) {
-        "Best time: Early morning or evening".to_string()
-    } else if (16..=21).contains(&hour) {
-        "Now - Good evening bite expected".to_string()
-    } else {
-        "Best time: Dawn or dusk (5-9 or 18-21)".to_string()
+/// Forecast explanation for people.
+fn explain(language: Language, probability: f64, weather: &WeatherCurrent, moon: f64) -> String {
+    let percent = probability * 100.0;
+    let temperature = weather.temperature_c;
+    let pressure = weather.pressure_hpa;
+    let wind = weather.wind_speed_ms;
+    let new_or_full = !(0.1..=0.9).contains(&moon);
+
+    match language {
+        Language::Uk => format!(
+            "Модель прогнозує ймовірність клювання {percent:.0}% за поточних умов: {temperature:.1}°C, тиск {pressure:.0} гПа, вітер {wind:.1} м/с, місяць — {}.",
+            if new_or_full { "молодик або повня" } else { "чверть" }
+        ),
+        Language::En => format!(
+            "ML model predicts {percent:.0}% bite probability based on current conditions: {temperature:.1}°C, {pressure:.0} hPa pressure, {wind:.1} m/s wind, {} moon phase.",
+            if new_or_full { "new/full" } else { "quarter" }
+        ),
+        Language::Pl => format!(
+            "Model przewiduje {percent:.0}% szans na branie w obecnych warunkach: {temperature:.1}°C, ciśnienie {pressure:.0} hPa, wiatr {wind:.1} m/s, księżyc: {}.",
+            if new_or_full { "nów lub pełnia" } else { "kwadra" }
+        ),
+        Language::De => format!(
+            "Das Modell sagt bei den aktuellen Bedingungen eine Beißwahrscheinlichkeit von {percent:.0}% voraus: {temperature:.1}°C, Luftdruck {pressure:.0} hPa, Wind {wind:.1} m/s, Mond: {}.",
+            if new_or_full { "Neu- oder Vollmond" } else { "Viertel" }
+        ),
+        Language::Fr => format!(
+            "Le modèle prévoit {percent:.0}% de probabilité de touche dans les conditions actuelles : {temperature:.1}°C, pression {pressure:.0} hPa, vent {wind:.1} m/s, lune : {}.",
+            if new_or_full { "nouvelle ou pleine" } else { "quartier" }
+        ),
+    }
+}
+
+/// Advice on when to fish, by local hour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BestTime {
+    MorningNow,
+    MorningOrEvening,
+    EveningNow,
+    DawnOrDusk,
+}
+
+impl BestTime {
+    fn at(hour: u32) -> Self {
+        match hour {
+            5..=9 => Self::MorningNow,
+            10..=15 => Self::MorningOrEvening,
+            16..=21 => Self::EveningNow,
+            _ => Self::DawnOrDusk,
+        }
+    }
+
+    fn message(self, language: Language) -> &'static str {
+        use Language::{De, En, Fr, Pl, Uk};
+
+        match (self, language) {
+            (Self::MorningNow, Uk) => "Зараз — чудове ранкове клювання!",
+            (Self::MorningNow, En) => "Now - Excellent morning bite!",
+            (Self::MorningNow, Pl) => "Teraz — świetne poranne branie!",
+            (Self::MorningNow, De) => "Jetzt – hervorragende Beißzeit am Morgen!",
+            (Self::MorningNow, Fr) => "Maintenant – excellente activité du matin !",
+
+            (Self::MorningOrEvening, Uk) => "Найкращий час: рано вранці або ввечері",
+            (Self::MorningOrEvening, En) => "Best time: Early morning or evening",
+            (Self::MorningOrEvening, Pl) => "Najlepsza pora: wczesny ranek lub wieczór",
+            (Self::MorningOrEvening, De) => "Beste Zeit: früher Morgen oder Abend",
+            (Self::MorningOrEvening, Fr) => "Meilleur moment : tôt le matin ou le soir",
+
+            (Self::EveningNow, Uk) => "Зараз — очікується добре вечірнє клювання",
+            (Self::EveningNow, En) => "Now - Good evening bite expected",
+            (Self::EveningNow, Pl) => "Teraz — spodziewane dobre wieczorne branie",
+            (Self::EveningNow, De) => "Jetzt – gute Beißzeit am Abend erwartet",
+            (Self::EveningNow, Fr) => "Maintenant – bonne activité attendue en soirée",
+
+            (Self::DawnOrDusk, Uk) => "Найкращий час: світанок або сутінки (5–9 або 18–21)",
+            (Self::DawnOrDusk, En) => "Best time: Dawn or dusk (5-9 or 18-21)",
+            (Self::DawnOrDusk, Pl) => "Najlepsza pora: świt lub zmierzch (5–9 lub 18–21)",
+            (Self::DawnOrDusk, De) => "Beste Zeit: Morgen- oder Abenddämmerung (5–9 oder 18–21 Uhr)",
+            (Self::DawnOrDusk, Fr) => "Meilleur moment : aube ou crépuscule (5 h–9 h ou 18 h–21 h)",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forecast_text_follows_language() {
+        let weather = WeatherCurrent {
+            temperature_c: 14.3,
+            pressure_hpa: 1016.4,
+            wind_speed_ms: 3.0,
+            wind_gust_ms: None,
+            wind_direction_deg: None,
+            precipitation_mm: None,
+            time: Utc::now(),
+        };
+
+        assert_eq!(
+            explain(Language::En, 0.62, &weather, 0.5),
+            "ML model predicts 62% bite probability based on current conditions: 14.3°C, 1016 hPa pressure, 3.0 m/s wind, quarter moon phase."
+        );
+        assert!(explain(Language::Uk, 0.62, &weather, 0.95).contains("ймовірність клювання 62%"));
+        assert!(explain(Language::Uk, 0.62, &weather, 0.95).ends_with("молодик або повня."));
+
+        assert_eq!(BestTime::at(6), BestTime::MorningNow);
+        assert_eq!(BestTime::at(23), BestTime::DawnOrDusk);
+        assert_eq!(BestTime::at(18).message(Language::Pl), "Teraz — spodziewane dobre wieczorne branie");
     }
 }
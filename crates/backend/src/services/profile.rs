@@ -0,0 +1,118 @@
+//! Profile and preferences of the signed-in user.
+
+use anyhow::Result;
+use fishing_shared::types::{
+    CatchPrivacy, FieldError, Language, LengthUnit, PressureUnit, ProfileUpdate, TemperatureUnit,
+    UnitPreferences, UserProfile, WeightUnit, WindSpeedUnit,
+};
+use fishing_shared::validation::{codes, Validate, Validator};
+
+use crate::models::{UserDb, UserPreferencesDb};
+use crate::AppState;
+
+/// Profile of a user, or `None` if the account no longer exists
+pub async fn get_profile(state: &AppState, user_id: &str) -> Result<Option<UserProfile>> {
+    let user = state.db.find_user_by_id(user_id).await?;
+    Ok(user.map(profile_from_db))
+}
+
+/// Check an update before applying it, including that favorite species
+/// exist; returns every rejected field.
+pub async fn validate_update(state: &AppState, update: &ProfileUpdate) -> Result<Vec<FieldError>> {
+    let mut v = Validator::default();
+    update.validate(&mut v);
+
+    for id in update.favorite_species.iter().flatten() {
+        if !state.db.fish_species_exists(id).await? {
+            v.error("favorite_species", codes::UNKNOWN, format!("Unknown fish species: {}", id));
+        }
+    }
+
+    Ok(v.finish().err().unwrap_or_default())
+}
+
+/// Apply a validated update; returns `None` if the user does not exist
+pub async fn update_profile(
+    state: &AppState,
+    user_id: &str,
+    update: ProfileUpdate,
+) -> Result<Option<UserProfile>> {
+    let Some(user) = state.db.find_user_by_id(user_id).await? else {
+        return Ok(None);
+    };
+    let mut profile = profile_from_db(user);
+
+    if let Some(code) = update.country_code {
+        profile.country_code = code.to_ascii_uppercase();
+    }
+    if let Some(language) = update.language {
+        profile.language = language;
+    }
+    if let Some(units) = update.units {
+        profile.units = units;
+    }
+    if let Some(region) = update.home_region {
+        let region = region.to_ascii_uppercase();
+        profile.home_region = (!region.is_empty()).then_some(region);
+    }
+    if let Some(species) = update.favorite_species {
+        let mut unique = Vec::with_capacity(species.len());
+        for id in species {
+            if !unique.contains(&id) {
+                unique.push(id);
+            }
+        }
+        profile.favorite_species = unique;
+    }
+    if let Some(privacy) = update.default_privacy {
+        profile.default_privacy = privacy;
+    }
+
+    if !state.db.update_user_preferences(user_id, &preferences_to_db(&profile)?).await? {
+        return Ok(None);
+    }
+    Ok(Some(profile))
+}
+
+/// Values that do not parse fall back to the defaults.
+fn profile_from_db(user: UserDb) -> UserProfile {
+    let stored = user.preferences;
+    let defaults = UnitPreferences::default();
+
+    UserProfile {
+        id: user.id,
+        email: user.email,
+        email_verified: user.email_verified_at.is_some(),
+        country_code: stored.country_code.unwrap_or_else(|| "UA".to_string()),
+        language: stored
+            .language
+            .as_deref()
+            .and_then(Language::from_code)
+            .unwrap_or(Language::Uk),
+        units: UnitPreferences {
+            length: LengthUnit::parse(&stored.length_unit).unwrap_or(defaults.length),
+            weight: WeightUnit::parse(&stored.weight_unit).unwrap_or(defaults.weight),
+            temperature: TemperatureUnit::parse(&stored.temperature_unit).unwrap_or(defaults.temperature),
+            pressure: PressureUnit::parse(&stored.pressure_unit).unwrap_or(defaults.pressure),
+            wind: WindSpeedUnit::parse(&stored.wind_speed_unit).unwrap_or(defaults.wind),
+        },
+        home_region: stored.home_region,
+        favorite_species: serde_json::from_str(&stored.favorite_species).unwrap_or_default(),
+        default_privacy: CatchPrivacy::parse(&stored.default_privacy).unwrap_or_default(),
+    }
+}
+
+fn preferences_to_db(profile: &UserProfile) -> Result<UserPreferencesDb> {
+    Ok(UserPreferencesDb {
+        country_code: Some(profile.country_code.clone()),
+        language: Some(profile.language.code().to_string()),
+        length_unit: profile.units.length.as_str().to_string(),
+        weight_unit: profile.units.weight.as_str().to_string(),
+        temperature_unit: profile.units.temperature.as_str().to_string(),
+        pressure_unit: profile.units.pressure.as_str().to_string(),
+        wind_speed_unit: profile.units.wind.as_str().to_string(),
+        home_region: profile.home_region.clone(),
+        favorite_species: serde_json::to_string(&profile.favorite_species)?,
+        default_privacy: profile.default_privacy.as_str().to_string(),
+    })
+}
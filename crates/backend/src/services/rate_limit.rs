@@ -0,0 +1,530 @@
+//! Request rate limiting and login lockout.
+//!
+//! [`RateLimitLayer`] keeps a token bucket per client for each route group:
+//! requests with a valid access token are counted per user, anonymous ones
+//! per IP address. Quotas come from `RATE_LIMIT_<GROUP>_IP` and
+//! `RATE_LIMIT_<GROUP>_USER` (`100/min`, `10/s`, `1000/h` or `off`).
+//!
+//! [`LoginLockout`] blocks an account after repeated failed logins, for a
+//! period that doubles with every further failure.
+//!
+//! State is kept in memory, so limits apply per server process.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context as _, Result};
+use axum::{
+    extract::{ConnectInfo, Request},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use tower::{Layer, Service};
+
+use crate::error::ApiError;
+use crate::services::auth;
+
+/// Buckets kept at most. Idle ones are dropped first, then the least
+/// recently used.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Failed logins allowed before an account is locked.
+const FREE_LOGIN_ATTEMPTS: u32 = 5;
+
+/// Lockout after the first failure past the free attempts; doubles after that.
+const BASE_LOCKOUT: Duration = Duration::from_secs(30);
+
+/// Longest lockout.
+const MAX_LOCKOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Failures older than this are forgotten.
+const FAILURE_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// `requests` per `period`, allowing bursts of up to `requests`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quota {
+    pub requests: u32,
+    pub period: Duration,
+}
+
+impl Quota {
+    pub const fn per_minute(requests: u32) -> Self {
+        Self {
+            requests,
+            period: Duration::from_secs(60),
+        }
+    }
+
+    /// Parse `N/s`, `N/min` or `N/h`; `off` disables the limit.
+    pub fn parse(value: &str) -> Result<Option<Self>> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("off") {
+            return Ok(None);
+        }
+
+        let (requests, unit) = value
+            .split_once('/')
+            .with_context(|| format!("invalid rate limit '{}', expected e.g. 100/min", value))?;
+        let requests: u32 = requests
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid request count in '{}'", value))?;
+        let period = match unit.trim() {
+            "s" | "sec" => Duration::from_secs(1),
+            "m" | "min" => Duration::from_secs(60),
+            "h" | "hour" => Duration::from_secs(60 * 60),
+            other => bail!("invalid rate limit period '{}' in '{}'", other, value),
+        };
+        if requests == 0 {
+            bail!("rate limit '{}' allows no requests; use 'off' to disable", value);
+        }
+
+        Ok(Some(Self { requests, period }))
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        f64::from(self.requests) / self.period.as_secs_f64()
+    }
+}
+
+/// Routes sharing a quota, matched by path prefix.
+#[derive(Debug, Clone)]
+pub struct RouteGroup {
+    pub name: &'static str,
+    pub prefix: &'static str,
+    pub per_ip: Option<Quota>,
+    pub per_user: Option<Quota>,
+}
+
+/// Quotas per route group and how client addresses are determined.
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Checked in order; the last group should match every path.
+    pub groups: Vec<RouteGroup>,
+    /// Take the client address from `X-Forwarded-For`/`X-Real-IP`. Only
+    /// enable behind a proxy that sets them, otherwise clients can pick
+    /// their own bucket.
+    pub trust_proxy_headers: bool,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        let group = |name, prefix, per_ip, per_user| RouteGroup {
+            name,
+            prefix,
+            per_ip: Some(Quota::per_minute(per_ip)),
+            per_user: Some(Quota::per_minute(per_user)),
+        };
+
+        Self {
+            groups: vec![
+                group("auth", "/api/v1/auth/", 20, 60),
+                group("admin", "/api/v1/admin/", 60, 120),
+                // Map tiles come in bursts of a dozen or more.
+                group("forecast", "/api/v1/forecast", 300, 300),
+                group("default", "", 100, 100),
+            ],
+            trust_proxy_headers: false,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Defaults overridden by `RATE_LIMIT_<GROUP>_IP`, `RATE_LIMIT_<GROUP>_USER`
+    /// and `RATE_LIMIT_TRUST_PROXY`.
+    pub fn from_lookup(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let mut config = Self::default();
+
+        for group in &mut config.groups {
+            let name = group.name.to_uppercase();
+            if let Some(value) = var(&format!("RATE_LIMIT_{}_IP", name)) {
+                group.per_ip = Quota::parse(&value)?;
+            }
+            if let Some(value) = var(&format!("RATE_LIMIT_{}_USER", name)) {
+                group.per_user = Quota::parse(&value)?;
+            }
+        }
+        if let Some(value) = var("RATE_LIMIT_TRUST_PROXY") {
+            config.trust_proxy_headers = matches!(value.as_str(), "1" | "true" | "yes");
+        }
+
+        Ok(config)
+    }
+
+    fn group_for(&self, path: &str) -> Option<(usize, &RouteGroup)> {
+        self.groups
+            .iter()
+            .enumerate()
+            .find(|(_, group)| path.starts_with(group.prefix))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token buckets for every client seen recently.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<(usize, String), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token for the request; `Err` holds how long to wait.
+    fn check_request(&self, request: &Request) -> Result<(), Duration> {
+        let Some((index, group)) = self.config.group_for(request.uri().path()) else {
+            return Ok(());
+        };
+
+        let user = bearer_token(request)
+            .and_then(|token| auth::validate_token(token).ok())
+            .map(|claims| claims.sub);
+        let (key, quota) = match user {
+            Some(user_id) => (format!("user:{}", user_id), group.per_user),
+            None => (format!("ip:{}", self.client_ip(request)), group.per_ip),
+        };
+
+        match quota {
+            Some(quota) => self.take((index, key), &quota, Instant::now()),
+            None => Ok(()),
+        }
+    }
+
+    fn take(&self, key: (usize, String), quota: &Quota, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&key) {
+            // A bucket idle for a whole period is full again, so forgetting it
+            // changes nothing.
+            buckets.retain(|(index, _), bucket| {
+                let period = self.config.groups[*index]
+                    .per_ip
+                    .into_iter()
+                    .chain(self.config.groups[*index].per_user)
+                    .map(|q| q.period)
+                    .max()
+                    .unwrap_or_default();
+                now.duration_since(bucket.updated) < period
+            });
+            // Still full of active clients: make room by forgetting the one
+            // seen longest ago, so memory stays bounded whatever the traffic.
+            if buckets.len() >= MAX_TRACKED_CLIENTS {
+                let oldest = buckets.iter().min_by_key(|(_, bucket)| bucket.updated).map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    buckets.remove(&oldest);
+                }
+            }
+        }
+
+        let capacity = f64::from(quota.requests);
+        let rate = quota.tokens_per_second();
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+
+    fn client_ip(&self, request: &Request) -> String {
+        let headers = request.headers();
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.split(',').next())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+
+        let forwarded = if self.config.trust_proxy_headers {
+            header("x-forwarded-for").or_else(|| header("x-real-ip"))
+        } else {
+            None
+        };
+
+        forwarded
+            .or_else(|| {
+                request
+                    .extensions()
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map(|ConnectInfo(addr)| addr.ip().to_string())
+            })
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+}
+
+fn bearer_token(request: &Request) -> Option<&str> {
+    request
+        .headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+/// Tower layer applying a [`RateLimiter`] to every request.
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitLayer {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            limiter: Arc::new(RateLimiter::new(config)),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+/// Service produced by [`RateLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> Service<Request> for RateLimitService<S>
+where
+    S: Service<Request, Response = Response>,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        match self.limiter.check_request(&request) {
+            Ok(()) => Box::pin(self.inner.call(request)),
+            Err(retry_after) => {
+                let response = ApiError::RateLimited { retry_after }.into_response();
+                Box::pin(std::future::ready(Ok(response)))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LoginFailures {
+    count: u32,
+    last_failure: Instant,
+    locked_until: Option<Instant>,
+}
+
+/// Failed login tracking, keyed by normalized email.
+#[derive(Debug, Default)]
+pub struct LoginLockout {
+    failures: Mutex<HashMap<String, LoginFailures>>,
+}
+
+impl LoginLockout {
+    /// Time left until `key` may try again, if it is locked.
+    pub fn locked_for(&self, key: &str) -> Option<Duration> {
+        self.locked_for_at(key, Instant::now())
+    }
+
+    pub fn record_failure(&self, key: &str) {
+        self.record_failure_at(key, Instant::now());
+    }
+
+    pub fn record_success(&self, key: &str) {
+        self.lock().remove(key);
+    }
+
+    fn locked_for_at(&self, key: &str, now: Instant) -> Option<Duration> {
+        self.lock()
+            .get(key)
+            .and_then(|f| f.locked_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    fn record_failure_at(&self, key: &str, now: Instant) {
+        let mut failures = self.lock();
+        failures.retain(|_, f| now.duration_since(f.last_failure) < FAILURE_WINDOW);
+
+        let entry = failures.entry(key.to_string()).or_insert(LoginFailures {
+            count: 0,
+            last_failure: now,
+            locked_until: None,
+        });
+        entry.count += 1;
+        entry.last_failure = now;
+
+        if let Some(excess) = entry.count.checked_sub(FREE_LOGIN_ATTEMPTS) {
+            let lockout = BASE_LOCKOUT
+                .checked_mul(1 << excess.min(16))
+                .map_or(MAX_LOCKOUT, |d| d.min(MAX_LOCKOUT));
+            entry.locked_until = Some(now + lockout);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, LoginFailures>> {
+        self.failures.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_parse() {
+        assert_eq!(Quota::parse("100/min").unwrap(), Some(Quota::per_minute(100)));
+        assert_eq!(
+            Quota::parse("5/s").unwrap(),
+            Some(Quota { requests: 5, period: Duration::from_secs(1) })
+        );
+        assert_eq!(Quota::parse("off").unwrap(), None);
+        assert!(Quota::parse("0/min").is_err());
+        assert!(Quota::parse("100").is_err());
+        assert!(Quota::parse("100/day").is_err());
+
+        let config = RateLimitConfig::from_lookup(|name| match name {
+            "RATE_LIMIT_AUTH_IP" => Some("5/min".to_string()),
+            "RATE_LIMIT_DEFAULT_USER" => Some("off".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        let (_, auth) = config.group_for("/api/v1/auth/login").unwrap();
+        assert_eq!(auth.per_ip, Some(Quota::per_minute(5)));
+        let (_, other) = config.group_for("/api/v1/catches").unwrap();
+        assert_eq!((other.name, other.per_user), ("default", None));
+    }
+
+    #[test]
+    fn test_token_bucket_refills() {
+        let limiter = RateLimiter::new(RateLimitConfig::default());
+        let quota = Quota::per_minute(2);
+        let key = || (0, "ip:203.0.113.7".to_string());
+        let start = Instant::now();
+
+        assert!(limiter.take(key(), &quota, start).is_ok());
+        assert!(limiter.take(key(), &quota, start).is_ok());
+        let wait = limiter.take(key(), &quota, start).unwrap_err();
+        assert_eq!(wait.as_secs(), 30, "one token every 30 s");
+        assert!(limiter.take((0, "ip:198.51.100.1".to_string()), &quota, start).is_ok(), "per client");
+
+        assert!(limiter.take(key(), &quota, start + Duration::from_secs(29)).is_err());
+        assert!(limiter.take(key(), &quota, start + Duration::from_secs(31)).is_ok());
+    }
+
+    #[test]
+    fn test_tracked_clients_are_capped() {
+        let limiter = RateLimiter::new(RateLimitConfig::default());
+        let quota = Quota::per_minute(2);
+        let key = |i: usize| (0, format!("ip:10.0.{}.{}", i / 256, i % 256));
+        let start = Instant::now();
+
+        // Every client stays active, so none can be dropped as idle
+        for i in 0..MAX_TRACKED_CLIENTS {
+            assert!(limiter.take(key(i), &quota, start + Duration::from_micros(i as u64)).is_ok());
+        }
+        let later = start + Duration::from_secs(1);
+        assert!(limiter.take(key(MAX_TRACKED_CLIENTS), &quota, later).is_ok());
+
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), MAX_TRACKED_CLIENTS);
+        assert!(!buckets.contains_key(&key(0)), "least recently seen client evicted");
+        assert!(buckets.contains_key(&key(1)));
+    }
+
+    #[tokio::test]
+    async fn test_layer_rejects_with_retry_after() {
+        use axum::{body::Body, http::StatusCode, routing::get, Router};
+        use tower::ServiceExt;
+
+        let mut config = RateLimitConfig::default();
+        config.groups[0].per_ip = Some(Quota::per_minute(2));
+        config.trust_proxy_headers = true;
+        let app = Router::new()
+            .route("/api/v1/auth/me", get(|| async { "ok" }))
+            .route("/api/v1/health", get(|| async { "ok" }))
+            .layer(RateLimitLayer::new(config));
+        let request = |uri: &str, ip: &str| {
+            axum::http::Request::get(uri)
+                .header("x-forwarded-for", ip)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for _ in 0..2 {
+            let response = app.clone().oneshot(request("/api/v1/auth/me", "203.0.113.7")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = app.clone().oneshot(request("/api/v1/auth/me", "203.0.113.7")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "30");
+
+        let other_ip = app.clone().oneshot(request("/api/v1/auth/me", "198.51.100.1")).await.unwrap();
+        assert_eq!(other_ip.status(), StatusCode::OK);
+        let other_group = app.clone().oneshot(request("/api/v1/health", "203.0.113.7")).await.unwrap();
+        assert_eq!(other_group.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_login_lockout_grows_exponentially() {
+        let lockout = LoginLockout::default();
+        let start = Instant::now();
+        for _ in 0..FREE_LOGIN_ATTEMPTS - 1 {
+            lockout.record_failure_at("angler@example.com", start);
+        }
+        assert!(lockout.locked_for_at("angler@example.com", start).is_none());
+
+        lockout.record_failure_at("angler@example.com", start);
+        assert_eq!(lockout.locked_for_at("angler@example.com", start).unwrap(), BASE_LOCKOUT);
+        assert!(lockout.locked_for_at("other@example.com", start).is_none());
+
+        let later = start + BASE_LOCKOUT;
+        assert!(lockout.locked_for_at("angler@example.com", later).is_none());
+        lockout.record_failure_at("angler@example.com", later);
+        assert_eq!(lockout.locked_for_at("angler@example.com", later).unwrap(), BASE_LOCKOUT * 2);
+
+        for _ in 0..20 {
+            lockout.record_failure_at("angler@example.com", later);
+        }
+        assert_eq!(lockout.locked_for_at("angler@example.com", later).unwrap(), MAX_LOCKOUT);
+
+        lockout.record_success("angler@example.com");
+        assert!(lockout.locked_for_at("angler@example.com", later).is_none());
+    }
+}
@@ -0,0 +1,187 @@
+//! Trip planning: the best spot/time windows across several candidate spots.
+//!
+//! The bite model runs on each spot's hourly forecast; its good bite windows
+//! (see [`bite_windows::good_windows`]) are ranked together, with a penalty
+//! for the distance the user would travel to get there.
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use fishing_shared::types::{
+    HourlyProbability, TripOrigin, TripPlan, TripPlanRequest, TripSpotForecast, TripWindow,
+};
+use fishing_shared::utils::geo::distance_km;
+use tokio::task::JoinSet;
+
+use super::bite_windows::{self, hourly_probabilities};
+
+/// Probability a window loses per 100 km between the origin and the spot.
+pub const DISTANCE_PENALTY_PER_100_KM: f64 = 0.05;
+
+/// Most windows returned, best first.
+pub const MAX_TRIP_WINDOWS: usize = 20;
+
+/// Forecast every spot of `request` from `now` on and rank their windows.
+///
+/// Spots the weather service has no forecast for are kept without hours;
+/// fails only when none of them has one.
+pub async fn plan_trip(
+    http: &reqwest::Client,
+    request: &TripPlanRequest,
+    now: DateTime<Utc>,
+) -> anyhow::Result<TripPlan> {
+    let first = request
+        .from
+        .max(now)
+        .duration_trunc(Duration::hours(1))
+        .context("invalid trip start")?;
+    // Hours starting before `to`
+    let count = ((request.to - first).num_minutes() + 59) / 60;
+    anyhow::ensure!(count > 0, "trip ends before {}", first);
+
+    let mut fetches = JoinSet::new();
+    for (index, spot) in request.spots.iter().enumerate() {
+        let http = http.clone();
+        let (lat, lon) = (spot.lat, spot.lon);
+        fetches.spawn(async move { (index, hourly_probabilities(&http, lat, lon, first, count).await) });
+    }
+
+    let mut forecasts = vec![Vec::new(); request.spots.len()];
+    let mut last_error = None;
+    while let Some(joined) = fetches.join_next().await {
+        let (index, hours) = joined.context("trip forecast task failed")?;
+        match hours {
+            Ok(hours) => forecasts[index] = hours,
+            Err(err) => {
+                tracing::warn!("Trip forecast for spot {} failed: {:#}", index, err);
+                last_error = Some(err);
+            }
+        }
+    }
+    if forecasts.iter().all(Vec::is_empty) {
+        return Err(last_error.unwrap_or_else(|| anyhow::anyhow!("no hourly weather for any spot")));
+    }
+
+    Ok(rank(request, forecasts))
+}
+
+/// Combine hourly probabilities per spot (in request order) into a plan.
+pub fn rank(request: &TripPlanRequest, forecasts: Vec<Vec<(DateTime<Utc>, f64)>>) -> TripPlan {
+    let distance = |origin: &TripOrigin, lat: f64, lon: f64| distance_km(origin.lat, origin.lon, lat, lon);
+
+    let mut windows = Vec::new();
+    let mut spots = Vec::with_capacity(request.spots.len());
+    for (index, (spot, hours)) in request.spots.iter().zip(forecasts).enumerate() {
+        let distance_km = request.origin.as_ref().map(|origin| distance(origin, spot.lat, spot.lon));
+        let penalty = distance_km.unwrap_or_default() / 100.0 * DISTANCE_PENALTY_PER_100_KM;
+
+        windows.extend(bite_windows::good_windows(&hours).into_iter().map(|window| TripWindow {
+            spot: index,
+            start: window.start,
+            end: window.end,
+            peak_probability: window.peak_probability,
+            score: window.peak_probability - penalty,
+        }));
+        spots.push(TripSpotForecast {
+            name: spot.name.clone(),
+            lat: spot.lat,
+            lon: spot.lon,
+            distance_km,
+            hours: hours
+                .into_iter()
+                .map(|(time, probability)| HourlyProbability { time, probability })
+                .collect(),
+        });
+    }
+
+    // Best score first; of equal ones the longer, then the earlier window
+    windows.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| (b.end - b.start).cmp(&(a.end - a.start)))
+            .then_with(|| a.start.cmp(&b.start))
+    });
+    windows.truncate(MAX_TRIP_WINDOWS);
+
+    TripPlan {
+        windows,
+        spots,
+        generated_at: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use fishing_shared::types::TripSpot;
+
+    fn hours(start: DateTime<Utc>, probabilities: &[f64]) -> Vec<(DateTime<Utc>, f64)> {
+        probabilities
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| (start + Duration::hours(i as i64), p))
+            .collect()
+    }
+
+    fn spot(name: &str, lat: f64, lon: f64) -> TripSpot {
+        TripSpot {
+            name: name.into(),
+            lat,
+            lon,
+        }
+    }
+
+    #[test]
+    fn test_windows_are_ranked_across_spots_with_travel_penalty() {
+        let start = Utc.with_ymd_and_hms(2026, 6, 1, 4, 0, 0).unwrap();
+        let request = TripPlanRequest {
+            // Near Kyiv, and about 470 km away near Lviv
+            spots: vec![spot("Dnipro", 50.45, 30.52), spot("Lake", 49.84, 24.03), spot("Offline", 48.0, 30.0)],
+            from: start,
+            to: start + Duration::hours(6),
+            origin: Some(TripOrigin { lat: 50.45, lon: 30.52 }),
+        };
+        let forecasts = vec![
+            hours(start, &[0.5, 0.8, 0.7, 0.3, 0.65, 0.2]),
+            hours(start, &[0.9, 0.9, 0.4, 0.5, 0.5, 0.5]),
+            Vec::new(),
+        ];
+
+        let plan = rank(&request, forecasts);
+
+        let order: Vec<_> = plan.windows.iter().map(|w| (w.spot, w.start - start)).collect();
+        assert_eq!(
+            order,
+            [(0, Duration::hours(1)), (1, Duration::hours(0)), (0, Duration::hours(4))],
+            "0.9 far away scores below 0.8 close by"
+        );
+        let far = &plan.windows[1];
+        assert_eq!((far.end - far.start, far.peak_probability), (Duration::hours(2), 0.9));
+        assert!((far.score - (0.9 - 0.05 * plan.spots[1].distance_km.unwrap() / 100.0)).abs() < 1e-9);
+
+        assert_eq!(plan.spots.len(), 3);
+        assert_eq!(plan.spots[0].distance_km, Some(0.0));
+        assert!((plan.spots[1].distance_km.unwrap() - 470.0).abs() < 10.0);
+        assert_eq!(plan.spots[0].hours.len(), 6);
+        assert!(plan.spots[2].hours.is_empty());
+    }
+
+    #[test]
+    fn test_without_origin_distance_is_ignored() {
+        let start = Utc.with_ymd_and_hms(2026, 6, 1, 4, 0, 0).unwrap();
+        let request = TripPlanRequest {
+            spots: vec![spot("Near", 50.45, 30.52), spot("Far", 40.0, 20.0)],
+            from: start,
+            to: start + Duration::hours(3),
+            origin: None,
+        };
+
+        let plan = rank(&request, vec![hours(start, &[0.7, 0.2, 0.7]), hours(start, &[0.2, 0.7, 0.7])]);
+
+        // Equal peaks: the longer window wins, then the earlier one
+        let order: Vec<_> = plan.windows.iter().map(|w| (w.spot, w.start - start)).collect();
+        assert_eq!(order, [(1, Duration::hours(1)), (0, Duration::hours(0)), (0, Duration::hours(2))]);
+        assert!(plan.windows.iter().all(|w| w.score == w.peak_probability));
+        assert!(plan.spots.iter().all(|s| s.distance_km.is_none()));
+    }
+}
@@ -4,6 +4,8 @@ use serde::Deserialize;
 
 use fishing_shared::types::WeatherCurrent;
 
+use crate::config::upstream;
+
 // ========== Open-Meteo Structures ==========
 #[derive(Debug, Deserialize)]
 struct OpenMeteoCurrent {
@@ -21,6 +23,22 @@ struct OpenMeteoResponse {
     current: OpenMeteoCurrent,
 }
 
+#[derive(Debug, Deserialize)]
+struct OpenMeteoHourly {
+    time: Vec<String>,
+    temperature_2m: Vec<Option<f64>>,
+    pressure_msl: Vec<Option<f64>>,
+    wind_speed_10m: Vec<Option<f64>>,
+    wind_direction_10m: Vec<Option<f64>>,
+    wind_gusts_10m: Vec<Option<f64>>,
+    precipitation: Vec<Option<f64>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoHourlyResponse {
+    hourly: OpenMeteoHourly,
+}
+
 /// Fetch current weather from Open-Meteo (безкоштовний).
 pub async fn fetch_current_weather(
     http: &reqwest::Client,
@@ -28,8 +46,8 @@ pub async fn fetch_current_weather(
     lon: f64,
 ) -> anyhow::Result<WeatherCurrent> {
     let url = format!(
-        "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current=temperature_2m,pressure_msl,wind_speed_10m,wind_direction_10m,wind_gusts_10m,precipitation&timezone=UTC",
-        lat, lon
+        "{}?latitude={}&longitude={}&current=temperature_2m,pressure_msl,wind_speed_10m,wind_direction_10m,wind_gusts_10m,precipitation&timezone=UTC",
+        upstream().weather_forecast, lat, lon
     );
 
     tracing::debug!("Fetching weather from Open-Meteo: {}", url);
@@ -47,14 +65,7 @@ pub async fn fetch_current_weather(
 
     tracing::debug!("Open-Meteo response: {:?}", resp);
 
-    // Parse time - Open-Meteo returns simplified format like "2026-02-07T16:45"
-    let time = if let Ok(parsed) = DateTime::parse_from_rfc3339(&format!("{}:00+00:00", resp.current.time)) {
-        parsed.with_timezone(&Utc)
-    } else if let Ok(parsed) = chrono::NaiveDateTime::parse_from_str(&resp.current.time, "%Y-%m-%dT%H:%M") {
-        DateTime::<Utc>::from_naive_utc_and_offset(parsed, Utc)
-    } else {
-        anyhow::bail!("invalid time format: {}", resp.current.time)
-    };
+    let time = parse_open_meteo_time(&resp.current.time)?;
 
     Ok(WeatherCurrent {
         temperature_c: resp.current.temperature_2m,
@@ -66,3 +77,106 @@ pub async fn fetch_current_weather(
         time,
     })
 }
+
+/// How far back the forecast API serves past hours; older data comes from
+/// the archive API.
+const FORECAST_PAST_DAYS: i64 = 90;
+
+const HOURLY_VARIABLES: &str =
+    "temperature_2m,pressure_msl,wind_speed_10m,wind_direction_10m,wind_gusts_10m,precipitation";
+
+/// Fetch the hourly weather for the hour containing `time` from Open-Meteo.
+pub async fn fetch_hourly_weather(
+    http: &reqwest::Client,
+    lat: f64,
+    lon: f64,
+    time: DateTime<Utc>,
+) -> anyhow::Result<WeatherCurrent> {
+    fetch_weather_series(http, lat, lon, time, time)
+        .await?
+        .pop()
+        .ok_or_else(|| anyhow::anyhow!("open-meteo returned no hourly data for {}", time))
+}
+
+/// Fetch hourly weather between `start` and `end` (inclusive, whole hours).
+///
+/// Recent hours come from the forecast API, older ones from the historical
+/// archive. Hours with missing core values are skipped.
+pub async fn fetch_weather_series(
+    http: &reqwest::Client,
+    lat: f64,
+    lon: f64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> anyhow::Result<Vec<WeatherCurrent>> {
+    let first_hour = start.format("%Y-%m-%dT%H:00").to_string();
+    let last_hour = end.format("%Y-%m-%dT%H:00").to_string();
+
+    let url = if Utc::now() - start <= chrono::Duration::days(FORECAST_PAST_DAYS) {
+        format!(
+            "{}?latitude={}&longitude={}&hourly={HOURLY_VARIABLES}&wind_speed_unit=ms&start_hour={first_hour}&end_hour={last_hour}&timezone=UTC",
+            upstream().weather_forecast, lat, lon
+        )
+    } else {
+        format!(
+            "{}?latitude={}&longitude={}&hourly={HOURLY_VARIABLES}&wind_speed_unit=ms&start_date={}&end_date={}&timezone=UTC",
+            upstream().weather_archive,
+            lat,
+            lon,
+            start.format("%Y-%m-%d"),
+            end.format("%Y-%m-%d")
+        )
+    };
+
+    tracing::debug!("Fetching hourly weather from Open-Meteo: {}", url);
+
+    let resp = http
+        .get(&url)
+        .send()
+        .await
+        .context("open-meteo request failed")?
+        .error_for_status()
+        .context("open-meteo error status")?
+        .json::<OpenMeteoHourlyResponse>()
+        .await
+        .context("open-meteo json parse failed")?;
+
+    let hourly = resp.hourly;
+    let mut series = Vec::with_capacity(hourly.time.len());
+    for (index, time_str) in hourly.time.iter().enumerate() {
+        let value = |values: &[Option<f64>]| values.get(index).copied().flatten();
+        if *time_str < first_hour || *time_str > last_hour {
+            continue;
+        }
+        let (Some(temperature_c), Some(pressure_hpa), Some(wind_speed_ms)) = (
+            value(&hourly.temperature_2m),
+            value(&hourly.pressure_msl),
+            value(&hourly.wind_speed_10m),
+        ) else {
+            continue;
+        };
+
+        series.push(WeatherCurrent {
+            temperature_c,
+            pressure_hpa,
+            wind_speed_ms,
+            wind_gust_ms: value(&hourly.wind_gusts_10m),
+            wind_direction_deg: value(&hourly.wind_direction_10m),
+            precipitation_mm: value(&hourly.precipitation),
+            time: parse_open_meteo_time(time_str)?,
+        });
+    }
+
+    Ok(series)
+}
+
+/// Parse Open-Meteo's simplified timestamp format like "2026-02-07T16:45".
+fn parse_open_meteo_time(value: &str) -> anyhow::Result<DateTime<Utc>> {
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(&format!("{}:00+00:00", value)) {
+        Ok(parsed.with_timezone(&Utc))
+    } else if let Ok(parsed) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M") {
+        Ok(DateTime::<Utc>::from_naive_utc_and_offset(parsed, Utc))
+    } else {
+        anyhow::bail!("invalid time format: {}", value)
+    }
+}
@@ -1,7 +1,6 @@
 // Fishing Forecast Service Worker
 const CACHE_NAME = 'fishing-forecast-v1';
 const STATIC_CACHE = 'static-v1';
-const API_CACHE = 'api-v1';
 
 // Resources to cache immediately
 const STATIC_RESOURCES = [
@@ -11,13 +10,6 @@ const STATIC_RESOURCES = [
   '/images/fishing-icon.svg'
 ];
 
-// API endpoints to cache
-const API_ENDPOINTS = [
-  '/api/v1/forecast',
-  '/api/v1/fish',
-  '/api/v1/regulations'
-];
-
 // Install event - cache static resources
 self.addEventListener('install', (event) => {
   console.log('🔧 Service Worker installing...');
@@ -40,7 +32,7 @@ self.addEventListener('activate', (event) => {
         return Promise.all(
           cacheNames
             .filter((name) => {
-              return name !== STATIC_CACHE && name !== API_CACHE;
+              return name !== STATIC_CACHE;
             })
             .map((name) => {
               console.log('🗑️ Deleting old cache:', name);
@@ -61,10 +53,17 @@ self.addEventListener('fetch', (event) => {
   if (url.origin !== location.origin) {
     return;
   }
+
+  // Only reads can be answered from the cache; catches sent while offline
+  // are queued by the app itself and replayed with their ids
+  if (request.method !== 'GET') {
+    return;
+  }
   
-  // Handle API requests
-  if (url.pathname.startsWith('/api/v1/')) {
-    event.respondWith(handleApiRequest(request));
+  // API responses are never cached here: they may belong to the signed-in
+  // user, and the app keeps its own offline copies (forecasts marked as
+  // cached, fish species)
+  if (url.pathname.startsWith('/api/')) {
     return;
   }
   
@@ -72,40 +71,6 @@ self.addEventListener('fetch', (event) => {
   event.respondWith(handleStaticRequest(request));
 });
 
-// Handle API requests with network-first strategy
-async function handleApiRequest(request) {
-  try {
-    const response = await fetch(request);
-    
-    // Cache successful responses
-    if (response.status === 200) {
-      const cache = await caches.open(API_CACHE);
-      cache.put(request, response.clone());
-    }
-    
-    return response;
-  } catch (error) {
-    // Try cache on network failure
-    const cachedResponse = await caches.match(request);
-    if (cachedResponse) {
-      return cachedResponse;
-    }
-    
-    // Return offline response
-    return new Response(
-      JSON.stringify({
-        error: 'offline',
-        message: 'Немає з\'єднання з інтернетом',
-        cached_at: null
-      }),
-      {
-        status: 503,
-        headers: { 'Content-Type': 'application/json' }
-      }
-    );
-  }
-}
-
 // Handle static requests with cache-first strategy
 async function handleStaticRequest(request) {
   const cachedResponse = await caches.match(request);
@@ -144,46 +109,6 @@ async function handleStaticRequest(request) {
   }
 }
 
-// Handle background sync for offline catches
-self.addEventListener('sync', (event) => {
-  if (event.tag === 'sync-catches') {
-    event.waitUntil(syncCatches());
-  }
-});
-
-async function syncCatches() {
-  try {
-    const cache = await caches.open('pending-catches');
-    const requests = await cache.keys();
-    
-    for (const request of requests) {
-      const response = await cache.match(request);
-      const data = await response.json();
-      
-      const syncResponse = await fetch('/api/v1/catches', {
-        method: 'POST',
-        headers: {
-          'Content-Type': 'application/json',
-          'Authorization': `Bearer ${data.token}`
-        },
-        body: JSON.stringify(data.catch)
-      });
-      
-      if (syncResponse.ok) {
-        await cache.delete(request);
-      }
-    }
-    
-    // Notify clients
-    const clients = await self.clients.matchAll();
-    clients.forEach(client => {
-      client.postMessage({ type: 'SYNCE_COMPLETE' });
-    });
-  } catch (error) {
-    console.error('Sync failed:', error);
-  }
-}
-
 // Handle push notifications
 self.addEventListener('push', (event) => {
   if (!event.data) return;
@@ -192,16 +117,14 @@ self.addEventListener('push', (event) => {
   
   const options = {
     body: data.body,
-    icon: '/icons/icon-192.png',
-    badge: '/icons/icon-192.png',
+    icon: '/images/fishing-icon.svg',
+    badge: '/images/fishing-icon.svg',
     vibrate: [100, 50, 100],
     data: {
       url: data.url || '/'
     },
-    actions: [
-      { action: 'open', title: 'Відкрити' },
-      { action: 'close', title: 'Закрити' }
-    ]
+    // Titles come in the user's language
+    actions: data.actions || []
   };
   
   event.waitUntil(
@@ -240,20 +163,4 @@ self.addEventListener('message', (event) => {
   if (event.data.type === 'SKIP_WAITING') {
     self.skipWaiting();
   }
-  
-  if (event.data.type === 'CACHE_CATCH') {
-    cachePendingCatch(event.data.catch, event.data.token);
-  }
 });
-
-async function cachePendingCatch(catchData, token) {
-  const cache = await caches.open('pending-catches');
-  const request = await cache.put(
-    new Request('/api/v1/catches', {
-      method: 'POST',
-      headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify({ catch: catchData, token })
-    }),
-    new Response(JSON.stringify({ catch: catchData, token }))
-  );
-}
@@ -2,15 +2,16 @@ use dioxus::prelude::*;
 use wasm_bindgen::prelude::*;
 use crate::components::MapView;
 use crate::components::ForecastPanel;
-use crate::components::CatchForm;
-use crate::services::ApiClient;
-use crate::services::api_client::{ForecastResponse, CatchRecord};
+use crate::components::{AccountMenu, CatchForm, CatchOutcome, FavoritesPanel, TripPlanner, UnitSettings};
+use crate::services::{api_client, offline, preferences, session, ApiClient};
+use crate::state::app_state::UserPreferences;
+use crate::utils::formatters::format_weight;
+use fishing_shared::types::{ForecastResult, Language, UnitPreferences};
+use futures_util::StreamExt;
+use rust_i18n::t;
 
 #[wasm_bindgen]
 extern "C" {
-    #[wasm_bindgen(js_namespace = window)]
-    fn updateUserMarker(lat: f64, lon: f64, heading: f64, wind_direction: f64);
-    
     #[wasm_bindgen(js_namespace = console)]
     fn log(s: &str);
 }
@@ -21,8 +22,37 @@ extern "C" {
 #[component]
 pub fn App() -> Element {
     // API client
-    let api_client = ApiClient::default();
-    let api_client_signal = use_signal(|| api_client.clone());
+    let api_client = api_client::configured_client();
+
+    // User preferences, shared with components; synced with the profile
+    // when a login session is stored
+    let mut user_preferences = use_context_provider(|| Signal::new(preferences::load_local()));
+    let language = user_preferences.read().language;
+    let lang = language.code();
+
+    // Server messages follow the chosen language
+    let mut api_client_signal = use_signal(|| api_client.with_language(language));
+    use_effect(move || {
+        let language = user_preferences.read().language;
+        let localized = api_client_signal.peek().with_language(language);
+        api_client_signal.set(localized);
+        set_document_language(language);
+    });
+    {
+        let api_client = api_client.clone();
+        use_hook(move || {
+            if session::load().is_none() {
+                return;
+            }
+            spawn(async move {
+                let synced = session::send(&api_client, |api| async move { preferences::sync_after_login(&api).await });
+                match synced.await {
+                    Ok(synced) => user_preferences.set(synced),
+                    Err(err) => log::warn!("Preference sync failed: {}", err),
+                }
+            });
+        });
+    }
     
     // Selected location on map
     let mut selected_location = use_signal::<Option<(f64, f64)>>(|| {
@@ -31,18 +61,60 @@ pub fn App() -> Element {
     });
     
     // Forecast data and loading state
-    let forecast = use_signal::<Option<ForecastResponse>>(|| None);
+    let forecast = use_signal::<Option<ForecastResult>>(|| None);
     let is_loading = use_signal(|| false);
     let error = use_signal::<Option<String>>(|| None);
-    let user_location = use_signal::<Option<(f64, f64)>>(|| None);
+    let mut user_location = use_signal::<Option<(f64, f64)>>(|| None);
+
+    // Pin dropped with a long press on the map
+    let mut dropped_pin = use_signal::<Option<(f64, f64)>>(|| None);
     
-    // Catch form modal state
+    // Catch form modal state; the catch is logged at the user's position
+    // or at the dropped pin
     let mut show_catch_form = use_signal(|| false);
+    let mut catch_location = use_signal::<Option<(f64, f64)>>(|| None);
     let success_message = use_signal::<Option<String>>(|| None);
+
+    // Catches waiting on this device, sent on start and whenever the
+    // browser reports the connection is back
+    let mut sync_status = use_signal(offline::status);
+    let mut online = use_signal(offline::is_online);
+    let sync = {
+        let mut success_message = success_message;
+        use_coroutine(move |mut requests: UnboundedReceiver<()>| async move {
+            while requests.next().await.is_some() {
+                online.set(offline::is_online());
+                if !online() {
+                    continue;
+                }
+                let api_client = api_client_signal.peek().clone();
+                let synced = offline::flush(&api_client).await;
+                sync_status.set(offline::status());
+                if !synced.is_empty() {
+                    let lang = user_preferences.peek().language.code();
+                    success_message.set(Some(t!("sync.done", locale = lang, count = synced.len()).into_owned()));
+                }
+            }
+        })
+    };
+    use_hook(move || {
+        watch_connection(sync.tx());
+        sync.send(());
+    });
     
     // Show forecast panel state
     let mut show_forecast_panel = use_signal(|| false);
     
+    // Saved spots with their bite windows
+    let mut show_favorites = use_signal(|| false);
+
+    // Spots compared for a trip
+    let mut show_trip = use_signal(|| false);
+
+    // Bite probability overlay on the map
+    let mut show_bite_layer = use_signal(|| false);
+    let bite_layer_url = api_client.forecast_tiles_url();
+    
     // Restore location from localStorage on mount
     {
         let mut initialized = use_signal(|| false);
@@ -79,7 +151,7 @@ pub fn App() -> Element {
     
     // Handle location selection from map
     let on_location_selected = {
-        to_owned![selected_location, show_forecast_panel, forecast, is_loading, error, api_client, user_location];
+        to_owned![selected_location, show_forecast_panel, forecast, is_loading, error];
         move |(lat, lon): (f64, f64)| {
             let mut selected_location = selected_location;
             let mut show_forecast_panel = show_forecast_panel;
@@ -87,41 +159,96 @@ pub fn App() -> Element {
             show_forecast_panel.set(true);
 
             spawn_forecast(
-                api_client.clone(),
+                api_client_signal.peek().clone(),
                 lat,
                 lon,
                 forecast,
                 is_loading,
                 error,
-                user_location,
             );
         }
     };
+
+    // A long press drops a pin and logs a catch there
+    let on_pin_dropped = move |at: (f64, f64)| {
+        dropped_pin.set(Some(at));
+        catch_location.set(Some(at));
+        show_catch_form.set(true);
+    };
     
     // Handle catch form close
     let on_catch_form_close = move |_| {
         show_catch_form.set(false);
+        dropped_pin.set(None);
     };
     
     // Handle catch submission
     let on_catch_submit = {
         to_owned![show_catch_form, success_message];
-        move |catch: CatchRecord| {
+        move |outcome: CatchOutcome| {
             show_catch_form.set(false);
-            
-            let weight_text = if let Some(w) = catch.weight_kg {
-                format!(" ({} кг)", w)
-            } else {
-                String::new()
+            dropped_pin.set(None);
+
+            let language = user_preferences.peek().language;
+            let weight_unit = user_preferences.peek().units.weight;
+            let lang = language.code();
+            let message = match outcome {
+                CatchOutcome::Saved(catch) => {
+                    // The server is reachable again; send anything queued
+                    sync.send(());
+                    match catch.weight_kg {
+                        Some(w) => t!(
+                            "catch.saved_with_weight",
+                            locale = lang,
+                            fish = catch.fish_species,
+                            weight = format_weight(w, weight_unit, language)
+                        ),
+                        None => t!("catch.saved", locale = lang, fish = catch.fish_species),
+                    }
+                }
+                CatchOutcome::Queued(catch) => {
+                    sync_status.set(offline::status());
+                    t!("catch.queued", locale = lang, fish = catch.fish_species)
+                }
             };
-            
-            success_message.set(Some(format!(
-                "✅ Улов успішно зареєстровано: {}{}",
-                catch.fish_species, weight_text
-            )));
+            success_message.set(Some(message.into_owned()));
         }
     };
     
+    // Save a new language on this device and, when signed in, in the profile
+    let on_language_change = {
+        let api_client = api_client.clone();
+        move |event: Event<FormData>| {
+            let Some(language) = Language::from_code(&event.value()) else {
+                return;
+            };
+            user_preferences.write().language = language;
+            save_preferences(&api_client, user_preferences.peek().clone());
+        }
+    };
+
+    // Same for the display units
+    let on_units_change = {
+        let api_client = api_client.clone();
+        move |units: UnitPreferences| {
+            user_preferences.write().units = units;
+            save_preferences(&api_client, user_preferences.peek().clone());
+        }
+    };
+
+    // Panels listing the user's own data reopen with the new account
+    let on_signed_in = move |synced: UserPreferences| {
+        user_preferences.set(synced);
+        show_favorites.set(false);
+        show_trip.set(false);
+        // Queued catches go out as the user now
+        sync.send(());
+    };
+    let on_signed_out = move |_| {
+        show_favorites.set(false);
+        show_trip.set(false);
+    };
+
     rsx! {
         div {
             class: "fixed inset-0 bg-white text-slate-900 flex flex-col w-screen h-screen",
@@ -132,13 +259,84 @@ pub fn App() -> Element {
                 class: "bg-gradient-to-r from-blue-600 to-blue-700 text-white px-4 py-2 shadow-md z-10 flex-shrink-0",
                 div {
                     class: "flex items-center justify-between",
-                    h1 { class: "text-lg font-bold", "🎣 Прогноз Клювання" }
+                    h1 { class: "text-lg font-bold", {t!("app.title", locale = lang)} }
                     div { class: "flex items-center gap-2",
+                        if !online() {
+                            span {
+                                class: "bg-slate-800 bg-opacity-40 px-2 py-1 rounded text-xs",
+                                title: t!("sync.offline_hint", locale = lang).into_owned(),
+                                {t!("sync.offline", locale = lang)}
+                            }
+                        }
+                        if sync_status().pending > 0 {
+                            button {
+                                class: "bg-amber-500 hover:bg-amber-600 px-2 py-1 rounded text-xs",
+                                title: t!("sync.pending_hint", locale = lang).into_owned(),
+                                onclick: move |_| sync.send(()),
+                                {t!("sync.pending", locale = lang, count = sync_status().pending)}
+                            }
+                        }
+                        if sync_status().rejected > 0 {
+                            button {
+                                class: "bg-red-500 hover:bg-red-600 px-2 py-1 rounded text-xs",
+                                title: t!("sync.rejected_hint", locale = lang).into_owned(),
+                                onclick: move |_| {
+                                    offline::discard_rejected();
+                                    sync_status.set(offline::status());
+                                },
+                                {t!("sync.rejected", locale = lang, count = sync_status().rejected)}
+                            }
+                        }
+                        button {
+                            class: "text-white hover:bg-blue-800 px-3 py-1 rounded text-sm",
+                            title: t!("app.bite_layer", locale = lang).into_owned(),
+                            onclick: move |_| show_bite_layer.set(!show_bite_layer()),
+                            if show_bite_layer() {
+                                {t!("app.bite_layer_hide", locale = lang)}
+                            } else {
+                                {t!("app.bite_layer_show", locale = lang)}
+                            }
+                        }
+                        button {
+                            class: "text-white hover:bg-blue-800 px-3 py-1 rounded text-sm",
+                            onclick: move |_| show_favorites.set(!show_favorites()),
+                            {t!("app.favorites", locale = lang)}
+                        }
+                        button {
+                            class: "text-white hover:bg-blue-800 px-3 py-1 rounded text-sm",
+                            onclick: move |_| show_trip.set(!show_trip()),
+                            {t!("app.trip", locale = lang)}
+                        }
                         if selected_location().is_some() {
                             button {
                                 class: "text-white hover:bg-blue-800 px-3 py-1 rounded text-sm",
                                 onclick: move |_| show_forecast_panel.set(!show_forecast_panel()),
-                                if show_forecast_panel() { "▼ Сховати" } else { "▲ Показати" }
+                                if show_forecast_panel() {
+                                    {t!("app.panel_hide", locale = lang)}
+                                } else {
+                                    {t!("app.panel_show", locale = lang)}
+                                }
+                            }
+                        }
+                        AccountMenu {
+                            api_client: api_client_signal,
+                            on_signed_in: on_signed_in,
+                            on_signed_out: on_signed_out,
+                        }
+                        UnitSettings {
+                            units: user_preferences().units,
+                            on_change: on_units_change,
+                        }
+                        select {
+                            class: "bg-blue-800 text-white text-sm rounded px-1 py-1",
+                            title: t!("app.language", locale = lang).into_owned(),
+                            onchange: on_language_change,
+                            for option_language in Language::ALL {
+                                option {
+                                    value: option_language.code(),
+                                    selected: option_language == language,
+                                    {option_language.native_name()}
+                                }
                             }
                         }
                     }
@@ -151,8 +349,15 @@ pub fn App() -> Element {
                 style: "flex: 1; position: relative; overflow: hidden; width: 100%;",
                 
                 MapView {
-                    on_location_selected: on_location_selected.clone(),
+                    api_client: api_client_signal,
+                    on_location_selected: on_location_selected,
+                    on_pin_dropped: on_pin_dropped,
+                    on_user_located: move |at| user_location.set(Some(at)),
                     selected_location: selected_location(),
+                    pin: dropped_pin(),
+                    user_location: user_location(),
+                    wind_direction: forecast().and_then(|result| result.weather.wind_direction_deg),
+                    forecast_overlay: show_bite_layer().then(|| bite_layer_url.clone()),
                 }
             }
             
@@ -172,6 +377,33 @@ pub fn App() -> Element {
                 }
             }
             
+            // Favorite spots, over the right of the map
+            if show_favorites() {
+                div {
+                    class: "absolute top-16 right-4 w-80 max-w-[calc(100vw-2rem)] max-h-[70vh] overflow-y-auto z-30",
+                    FavoritesPanel {
+                        api_client: api_client_signal,
+                        selected_location: selected_location(),
+                        on_select: on_location_selected,
+                        on_close: move |_| show_favorites.set(false),
+                    }
+                }
+            }
+            
+            // Trip planner, wide enough to compare spots side by side
+            if show_trip() {
+                div {
+                    class: "absolute top-16 inset-x-4 md:left-auto md:w-[40rem] max-h-[80vh] overflow-y-auto z-30",
+                    TripPlanner {
+                        api_client: api_client_signal,
+                        selected_location: selected_location(),
+                        user_location: user_location(),
+                        on_select: on_location_selected,
+                        on_close: move |_| show_trip.set(false),
+                    }
+                }
+            }
+            
             // Success message notification
             if let Some(msg) = success_message() {
                 div {
@@ -183,8 +415,11 @@ pub fn App() -> Element {
             // Floating Action Button for adding catch
             button {
                 class: "fixed bottom-6 right-6 bg-blue-600 hover:bg-blue-700 text-white rounded-full w-16 h-16 shadow-lg flex items-center justify-center z-40 transition-all hover:scale-110 active:scale-95 p-3",
-                onclick: move |_| show_catch_form.set(true),
-                title: "Зареєструвати улов",
+                onclick: move |_| {
+                    catch_location.set(user_location());
+                    show_catch_form.set(true);
+                },
+                title: t!("app.add_catch", locale = lang).into_owned(),
                 dangerous_inner_html: r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 90 110" width="100%" height="100%" style="display: block;">
                     <g>
                         <circle cx="30" cy="22" r="11" fill="white"/>
@@ -211,7 +446,7 @@ pub fn App() -> Element {
                         
                         CatchForm {
                             api_client: api_client_signal,
-                            user_location: user_location,
+                            location: catch_location,
                             on_close: on_catch_form_close,
                             on_submit: on_catch_submit,
                         }
@@ -222,6 +457,46 @@ pub fn App() -> Element {
     }
 }
 
+/// Save preferences on this device and, when signed in, in the profile.
+fn save_preferences(api_client: &ApiClient, updated: UserPreferences) {
+    let api_client = api_client.clone();
+    spawn(async move {
+        if let Err(err) = preferences::save(&api_client, &updated).await {
+            log::warn!("Saving preferences failed: {}", err);
+        }
+    });
+}
+
+/// Ask for a sync whenever the browser goes online or offline.
+fn watch_connection(sync: UnboundedSender<()>) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    for event in ["online", "offline"] {
+        let sync = sync.clone();
+        let listener = Closure::<dyn Fn()>::new(move || {
+            let _ = sync.unbounded_send(());
+        });
+        if window
+            .add_event_listener_with_callback(event, listener.as_ref().unchecked_ref())
+            .is_ok()
+        {
+            // Listens for the lifetime of the page
+            listener.forget();
+        }
+    }
+}
+
+/// Keep `<html lang>` in step with the UI, for screen readers and hyphenation.
+fn set_document_language(language: Language) {
+    let root = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.document_element());
+    if let Some(root) = root {
+        let _ = root.set_attribute("lang", language.code());
+    }
+}
+
 /// Parse coordinates from "lat,lon" format
 fn parse_coordinates(data: &str) -> Option<(f64, f64)> {
     let parts: Vec<&str> = data.split(',').collect();
@@ -237,10 +512,9 @@ fn spawn_forecast(
     api_client: ApiClient,
     lat: f64,
     lon: f64,
-    forecast: Signal<Option<ForecastResponse>>,
+    forecast: Signal<Option<ForecastResult>>,
     is_loading: Signal<bool>,
     error: Signal<Option<String>>,
-    user_location: Signal<Option<(f64, f64)>>,
 ) {
     let mut is_loading = is_loading;
     let mut error = error;
@@ -250,19 +524,14 @@ fn spawn_forecast(
     error.set(None);
 
     spawn(async move {
-        match api_client.get_forecast(lat, lon, None).await {
+        match crate::services::forecast::get_forecast(&api_client, lat, lon, None).await {
             Ok(result) => {
-                let wind_dir = result.weather.wind_direction;
-
-                // Update user marker with wind direction if we have user location
-                if let Some((ulat, ulon)) = *user_location.read() {
-                    updateUserMarker(ulat, ulon, 0.0, wind_dir);
-                }
-
                 forecast.set(Some(result));
                 is_loading.set(false);
             }
             Err(e) => {
+                // Never leave the previous spot's forecast on screen
+                forecast.set(None);
                 error.set(Some(e.to_string()));
                 is_loading.set(false);
             }
@@ -0,0 +1,143 @@
+use dioxus::prelude::*;
+use fishing_shared::types::RegisterRequest;
+use rust_i18n::t;
+
+use crate::i18n::use_language;
+use crate::services::api_client::ApiClient;
+use crate::services::{preferences, session};
+use crate::state::app_state::UserPreferences;
+
+/// Header menu to sign in, create an account or sign out
+#[component]
+pub fn AccountMenu(
+    api_client: Signal<ApiClient>,
+    // Preferences pulled from the profile after signing in
+    on_signed_in: EventHandler<UserPreferences>,
+    on_signed_out: EventHandler<()>,
+) -> Element {
+    let language = use_language();
+    let lang = language.code();
+    let mut is_open = use_signal(|| false);
+    let mut signed_in = use_signal(session::load);
+    let mut email = use_signal(String::new);
+    let mut password = use_signal(String::new);
+    let mut is_loading = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    // Both buttons end the same way: a stored session and synced preferences
+    let mut submit = move |create_account: bool| {
+        if email.read().trim().is_empty() || password.read().is_empty() {
+            error.set(Some(t!("account.missing", locale = lang).into_owned()));
+            return;
+        }
+        let api = api_client.peek().clone();
+        let (address, secret) = (email.read().trim().to_string(), password.read().clone());
+        is_loading.set(true);
+        error.set(None);
+        spawn(async move {
+            let result = if create_account {
+                let request = RegisterRequest {
+                    email: address,
+                    password: secret,
+                    country_code: None,
+                    language: Some(language.code().to_string()),
+                };
+                session::register(&api, &request).await
+            } else {
+                session::login(&api, &address, &secret).await
+            };
+            let synced = match result {
+                Ok((_, preferences)) => Some(preferences),
+                // Signed in, but the profile could not be read; local preferences stay
+                Err(err) if session::load().is_some() => {
+                    log::warn!("Preference sync failed: {}", err);
+                    Some(preferences::load_local())
+                }
+                Err(err) => {
+                    error.set(Some(t!("account.failed", locale = lang, error = err).into_owned()));
+                    None
+                }
+            };
+            if let Some(preferences) = synced {
+                password.set(String::new());
+                is_open.set(false);
+                on_signed_in.call(preferences);
+            }
+            signed_in.set(session::load());
+            is_loading.set(false);
+        });
+    };
+
+    let handle_sign_out = move |_| {
+        let api = api_client.peek().clone();
+        spawn(async move {
+            session::logout(&api).await;
+            signed_in.set(None);
+            is_open.set(false);
+            on_signed_out.call(());
+        });
+    };
+
+    let input_class = "w-full px-2 py-1 border border-slate-300 rounded text-sm";
+
+    rsx! {
+        div { class: "relative",
+            button {
+                class: "text-white hover:bg-blue-800 px-2 py-1 rounded text-sm",
+                title: t!("account.title", locale = lang).into_owned(),
+                onclick: move |_| {
+                    // A refused refresh may have ended the session meanwhile
+                    signed_in.set(session::load());
+                    is_open.set(!is_open());
+                },
+                if signed_in.read().is_some() { "👤" } else { {t!("account.sign_in", locale = lang)} }
+            }
+            if is_open() {
+                div { class: "absolute right-0 mt-2 w-64 bg-white text-slate-800 rounded-lg shadow-lg p-3 space-y-2 z-50",
+                    if let Some(current) = signed_in.read().as_ref() {
+                        p { class: "text-sm break-all", {t!("account.signed_in_as", locale = lang, email = current.email)} }
+                        button {
+                            class: "w-full text-sm border border-slate-300 py-1 rounded hover:bg-slate-50",
+                            onclick: handle_sign_out,
+                            {t!("account.sign_out", locale = lang)}
+                        }
+                    } else {
+                        if let Some(err) = error.read().as_ref() {
+                            p { class: "text-sm text-red-700", "{err}" }
+                        }
+                        input {
+                            class: input_class,
+                            r#type: "email",
+                            autocomplete: "email",
+                            placeholder: t!("account.email", locale = lang).into_owned(),
+                            value: "{email}",
+                            oninput: move |e| email.set(e.value()),
+                        }
+                        input {
+                            class: input_class,
+                            r#type: "password",
+                            autocomplete: "current-password",
+                            placeholder: t!("account.password", locale = lang).into_owned(),
+                            value: "{password}",
+                            oninput: move |e| password.set(e.value()),
+                        }
+                        div { class: "flex gap-2",
+                            button {
+                                class: "flex-1 text-sm bg-blue-600 hover:bg-blue-700 text-white py-1 rounded disabled:opacity-50",
+                                disabled: is_loading(),
+                                onclick: move |_| submit(false),
+                                {t!("account.sign_in", locale = lang)}
+                            }
+                            button {
+                                class: "flex-1 text-sm border border-blue-600 text-blue-700 py-1 rounded hover:bg-blue-50 disabled:opacity-50",
+                                disabled: is_loading(),
+                                onclick: move |_| submit(true),
+                                {t!("account.register", locale = lang)}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,21 +1,34 @@
 use dioxus::prelude::*;
-use crate::services::api_client::{ApiClient, CatchRecord};
+use crate::services::api_client::{ApiClient, CatchRecord, NewCatchRecord};
+use crate::services::{offline, session};
+use crate::utils::formatters::{parse_length, parse_weight, UnitLabel};
+use crate::i18n::{use_language, use_units};
+use crate::state::app_state::UserPreferences;
 use chrono::Utc;
+use fishing_shared::types::FishSpecies;
+use rust_i18n::t;
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct FishSpecies {
-    pub id: String,
-    pub name: String,
-    pub scientific_name: String,
+/// What became of a submitted catch.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatchOutcome {
+    /// The server stored it.
+    Saved(CatchRecord),
+    /// The server could not be reached; kept on this device until it can.
+    Queued(NewCatchRecord),
 }
 
 #[component]
 pub fn CatchForm(
     api_client: Signal<ApiClient>,
-    user_location: Signal<Option<(f64, f64)>>,
+    // The user's position or a pin dropped on the map
+    location: Signal<Option<(f64, f64)>>,
     on_close: EventHandler<()>,
-    on_submit: EventHandler<CatchRecord>,
+    on_submit: EventHandler<CatchOutcome>,
 ) -> Element {
+    let language = use_language();
+    let lang = language.code();
+    let units = use_units();
+    let preferences = use_context::<Signal<UserPreferences>>();
     let mut fish_species = use_signal(|| Vec::<FishSpecies>::new());
     let mut selected_fish = use_signal(|| String::new());
     let mut weight = use_signal(|| String::new());
@@ -29,61 +42,59 @@ pub fn CatchForm(
     // Load fish species on mount
     use_effect(move || {
         spawn(async move {
-            match api_client.read().get_fish_species("UA", "uk").await {
-                Ok(species_list) => {
-                    let species: Vec<FishSpecies> = species_list
-                        .into_iter()
-                        .map(|s| FishSpecies {
-                            id: s.id,
-                            name: s.name,
-                            scientific_name: s.scientific_name.unwrap_or_default(),
-                        })
-                        .collect();
+            let api = api_client.read().clone();
+            // Species of the home country, or all of them without one
+            let country = preferences.read().home_country().map(str::to_string);
+            match offline::fish_species(&api, country.as_deref(), lang).await {
+                Ok(species) => {
                     fish_species.set(species);
                 }
                 Err(e) => {
-                    error.set(Some(format!("Failed to load fish species: {}", e)));
+                    error.set(Some(t!("catch_form.species_failed", locale = lang, error = e).into_owned()));
                 }
             }
         });
     });
 
     let handle_submit = move |_| {
-        let location = match *user_location.read() {
+        let location = match *location.read() {
             Some((lat, lon)) => (lat, lon),
             None => {
-                error.set(Some("Location not available. Please enable geolocation.".to_string()));
+                error.set(Some(t!("catch_form.no_location", locale = lang).into_owned()));
                 return;
             }
         };
 
         let fish = selected_fish.read().clone();
         if fish.is_empty() {
-            error.set(Some("Please select a fish species".to_string()));
+            error.set(Some(t!("catch_form.no_species", locale = lang).into_owned()));
             return;
         }
 
-        let weight_val = weight.read().parse::<f64>().ok();
-        let length_val = length.read().parse::<f64>().ok();
+        // Stored in kg and cm whatever the user typed them in
+        let weight_val = parse_weight(&weight.read(), units.weight);
+        let length_val = parse_length(&length.read(), units.length);
         let bait_val = bait.read().clone();
         
         if bait_val.is_empty() {
-            error.set(Some("Please enter the bait used".to_string()));
+            error.set(Some(t!("catch_form.no_bait", locale = lang).into_owned()));
             return;
         }
 
         is_loading.set(true);
 
-        let catch_record = CatchRecord {
-            id: String::new(), // Will be set by backend
-            user_id: "default_user".to_string(), // TODO: Replace with actual user ID after auth
+        let catch_record = NewCatchRecord {
+            id: Some(offline::new_catch_id()),
+            // Set by the server from the token; empty for anonymous catches
+            user_id: String::new(),
             lat: location.0,
             lon: location.1,
-            caught_at: Utc::now().to_rfc3339(),
+            caught_at: Utc::now(),
             fish_species: fish,
             weight_kg: weight_val,
             length_cm: length_val,
             bait_used: Some(bait_val),
+            bite_intensity: Some(*bite_intensity.read()),
             weather_temp: None, // TODO: Add from forecast
             weather_pressure: None, // TODO: Add from forecast
             moon_phase: None, // TODO: Add from forecast
@@ -93,22 +104,30 @@ pub fn CatchForm(
                 Some(notes.read().clone())
             },
             photo_url: None,
+            // The profile's default privacy
+            privacy: None,
         };
 
         spawn(async move {
-            match api_client.read().save_catch(&catch_record).await {
-                Ok(_) => {
-                    on_submit.call(catch_record.clone());
-                    is_loading.set(false);
-                }
-                Err(e) => {
-                    error.set(Some(format!("Failed to save catch: {}", e)));
-                    is_loading.set(false);
+            let api = api_client.read().clone();
+            // Saved for the signed-in user, anonymously otherwise
+            let catch = &catch_record;
+            let saved = session::send(&api, |api| async move { api.save_catch(catch).await }).await;
+            match saved {
+                Ok(saved) => on_submit.call(CatchOutcome::Saved(saved)),
+                Err(e) if offline::is_retryable(&e) => {
+                    offline::enqueue(catch_record.clone());
+                    on_submit.call(CatchOutcome::Queued(catch_record));
                 }
+                Err(e) => error.set(Some(t!("catch_form.save_failed", locale = lang, error = e).into_owned())),
             }
+            is_loading.set(false);
         });
     };
 
+    let bait_placeholder = t!("catch_form.bait_placeholder", locale = lang);
+    let notes_placeholder = t!("catch_form.notes_placeholder", locale = lang);
+
     rsx! {
         div {
             class: "bg-white rounded-lg shadow-xl max-w-md w-full",
@@ -118,7 +137,7 @@ pub fn CatchForm(
                 class: "flex justify-between items-center p-4 border-b",
                 h2 {
                     class: "text-xl font-bold text-gray-800",
-                    "🎣 Зареєструвати улов"
+                    {t!("catch_form.title", locale = lang)}
                 }
                 button {
                     class: "text-gray-500 hover:text-gray-700 text-2xl",
@@ -143,7 +162,7 @@ pub fn CatchForm(
                     div {
                         label {
                             class: "block text-sm font-medium text-gray-700 mb-1",
-                            "Вид риби *"
+                            {t!("catch_form.species", locale = lang)}
                         }
                         select {
                             class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500",
@@ -152,7 +171,7 @@ pub fn CatchForm(
                             
                             option {
                                 value: "",
-                                "Виберіть вид..."
+                                {t!("catch_form.choose_species", locale = lang)}
                             }
                             for species in fish_species.read().iter() {
                                 option {
@@ -167,7 +186,7 @@ pub fn CatchForm(
                     div {
                         label {
                             class: "block text-sm font-medium text-gray-700 mb-1",
-                            "Вага (кг)"
+                            {t!("catch_form.weight", locale = lang, unit = units.weight.label(language))}
                         }
                         input {
                             r#type: "number",
@@ -183,7 +202,7 @@ pub fn CatchForm(
                     div {
                         label {
                             class: "block text-sm font-medium text-gray-700 mb-1",
-                            "Довжина (см)"
+                            {t!("catch_form.length", locale = lang, unit = units.length.label(language))}
                         }
                         input {
                             r#type: "number",
@@ -199,12 +218,12 @@ pub fn CatchForm(
                     div {
                         label {
                             class: "block text-sm font-medium text-gray-700 mb-1",
-                            "Наживка *"
+                            {t!("catch_form.bait", locale = lang)}
                         }
                         input {
                             r#type: "text",
                             class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500",
-                            placeholder: "Черв'як, кукурудза...",
+                            placeholder: "{bait_placeholder}",
                             value: "{bait}",
                             oninput: move |e| bait.set(e.value()),
                         }
@@ -214,7 +233,7 @@ pub fn CatchForm(
                     div {
                         label {
                             class: "block text-sm font-medium text-gray-700 mb-1",
-                            "Інтенсивність клювання: {bite_intensity}"
+                            {t!("catch_form.intensity", locale = lang, value = bite_intensity)}
                         }
                         div {
                             class: "flex items-center space-x-2",
@@ -248,22 +267,22 @@ pub fn CatchForm(
                     div {
                         label {
                             class: "block text-sm font-medium text-gray-700 mb-1",
-                            "Нотатки"
+                            {t!("catch_form.notes", locale = lang)}
                         }
                         textarea {
                             class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500",
                             rows: "3",
-                            placeholder: "Додаткова інформація...",
+                            placeholder: "{notes_placeholder}",
                             value: "{notes}",
                             oninput: move |e| notes.set(e.value()),
                         }
                     }
 
                     // Location info
-                    if let Some((lat, lon)) = *user_location.read() {
+                    if let Some((lat, lon)) = *location.read() {
                         div {
                             class: "text-sm text-gray-600 bg-gray-50 p-2 rounded",
-                            "📍 Локація: {lat:.4}°, {lon:.4}°"
+                            {t!("catch_form.location", locale = lang, lat = format!("{lat:.4}"), lon = format!("{lon:.4}"))}
                         }
                     }
 
@@ -275,15 +294,15 @@ pub fn CatchForm(
                             disabled: *is_loading.read(),
                             onclick: handle_submit,
                             if *is_loading.read() {
-                                "Збереження..."
+                                {t!("catch_form.saving", locale = lang)}
                             } else {
-                                "💾 Зберегти улов"
+                                {t!("catch_form.save", locale = lang)}
                             }
                         }
                         button {
                             class: "px-4 py-2 border border-gray-300 rounded-md hover:bg-gray-50",
                             onclick: move |_| on_close.call(()),
-                            "Скасувати"
+                            {t!("catch_form.cancel", locale = lang)}
                         }
                     }
             }
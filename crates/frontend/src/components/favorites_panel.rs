@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+
+use chrono::{FixedOffset, Utc};
+use dioxus::prelude::*;
+use fishing_shared::types::{AlertRule, BiteWindows, FavoriteSpot, FavoriteSpotInput, FishSpecies};
+use rust_i18n::t;
+
+use crate::components::spot_alerts::SpotAlerts;
+use crate::i18n::use_language;
+use crate::services::api_client::ApiClient;
+use crate::services::{offline, push, session};
+use crate::state::app_state::UserPreferences;
+use crate::utils::formatters::{browser_offset, format_window};
+
+/// Saved spots of the signed-in user with the bite windows at each.
+#[component]
+pub fn FavoritesPanel(
+    api_client: Signal<ApiClient>,
+    // Place saved by the form
+    selected_location: Option<(f64, f64)>,
+    on_select: EventHandler<(f64, f64)>,
+    on_close: EventHandler<()>,
+) -> Element {
+    let language = use_language();
+    let lang = language.code();
+    let preferences = use_context::<Signal<UserPreferences>>();
+    let mut spots = use_signal(|| None::<Vec<FavoriteSpot>>);
+    // Bite windows by spot id; `None` when the forecast failed
+    let mut windows = use_signal(HashMap::<String, Option<BiteWindows>>::new);
+    let mut fish_species = use_signal(Vec::<FishSpecies>::new);
+    let mut alerts = use_signal(Vec::<AlertRule>::new);
+    // Spot whose alert rules are shown
+    let mut alerts_open = use_signal(|| None::<String>);
+    // Whether this browser receives alerts; `None` until known or when unsupported
+    let mut push_enabled = use_signal(|| None::<bool>);
+    let mut name = use_signal(String::new);
+    let mut selected_fish = use_signal(String::new);
+    let mut notes = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+    let mut reload = use_signal(|| 0u32);
+    let mut reload_alerts = use_signal(|| 0u32);
+
+    // Favorites belong to an account; nothing to show without a session
+    let signed_in = move || session::load().map(|_| api_client.peek().clone());
+
+    use_effect(move || {
+        reload.read();
+        let Some(api) = signed_in() else {
+            return;
+        };
+        spawn(async move {
+            match session::send(&api, |api| async move { api.get_favorites().await }).await {
+                Ok(loaded) => {
+                    spots.set(Some(loaded.clone()));
+                    for spot in loaded {
+                        let found = api.get_bite_windows(spot.lat, spot.lon).await;
+                        if let Err(err) = &found {
+                            log::warn!("Bite windows for {} failed: {}", spot.id, err);
+                        }
+                        windows.write().insert(spot.id, found.ok());
+                    }
+                }
+                Err(err) => error.set(Some(t!("favorites.failed", locale = lang, error = err).into_owned())),
+            }
+        });
+    });
+
+    use_effect(move || {
+        spawn(async move {
+            let api = api_client.read().clone();
+            let country = preferences.read().home_country().map(str::to_string);
+            match offline::fish_species(&api, country.as_deref(), lang).await {
+                Ok(species) => fish_species.set(species),
+                Err(err) => log::warn!("Loading fish species failed: {}", err),
+            }
+        });
+    });
+
+    use_effect(move || {
+        reload_alerts.read();
+        let Some(api) = signed_in() else {
+            return;
+        };
+        spawn(async move {
+            match session::send(&api, |api| async move { api.get_alerts().await }).await {
+                Ok(loaded) => alerts.set(loaded),
+                Err(err) => log::warn!("Loading alerts failed: {}", err),
+            }
+        });
+    });
+
+    use_effect(move || {
+        if push::is_supported() {
+            spawn(async move { push_enabled.set(Some(push::is_subscribed().await)) });
+        }
+    });
+
+    let handle_push = move |_| {
+        let Some(api) = signed_in() else {
+            return;
+        };
+        let enable = *push_enabled.read() != Some(true);
+        spawn(async move {
+            let result = if enable { push::subscribe(&api).await } else { push::unsubscribe(&api).await };
+            match result {
+                Ok(()) => {
+                    push_enabled.set(Some(enable));
+                    error.set(None);
+                }
+                Err(err) => error.set(Some(t!("alerts.push_failed", locale = lang, error = err).into_owned())),
+            }
+        });
+    };
+
+    let handle_save = move |_| {
+        let Some((lat, lon)) = selected_location else {
+            error.set(Some(t!("favorites.pick_first", locale = lang).into_owned()));
+            return;
+        };
+        if name.read().trim().is_empty() {
+            error.set(Some(t!("favorites.name_required", locale = lang).into_owned()));
+            return;
+        }
+        let Some(api) = signed_in() else {
+            return;
+        };
+        let input = FavoriteSpotInput {
+            name: name.read().trim().to_string(),
+            lat,
+            lon,
+            water_body_id: None,
+            target_species: Some(selected_fish.read().clone()).filter(|id| !id.is_empty()).into_iter().collect(),
+            notes: Some(notes.read().trim().to_string()).filter(|text| !text.is_empty()),
+        };
+        spawn(async move {
+            let input = &input;
+            match session::send(&api, |api| async move { api.create_favorite(input).await }).await {
+                Ok(_) => {
+                    name.set(String::new());
+                    selected_fish.set(String::new());
+                    notes.set(String::new());
+                    error.set(None);
+                    reload += 1;
+                }
+                Err(err) => error.set(Some(err.to_string())),
+            }
+        });
+    };
+
+    let handle_delete = move |id: String| {
+        let Some(api) = signed_in() else {
+            return;
+        };
+        spawn(async move {
+            let spot_id = &id;
+            match session::send(&api, |api| async move { api.delete_favorite(spot_id).await }).await {
+                Ok(()) => {
+                    if let Some(spots) = spots.write().as_mut() {
+                        spots.retain(|spot| spot.id != id);
+                    }
+                    windows.write().remove(&id);
+                    alerts.write().retain(|rule| rule.favorite_spot_id != id);
+                }
+                Err(err) => error.set(Some(err.to_string())),
+            }
+        });
+    };
+
+    let now = Utc::now();
+    let offset = browser_offset();
+    let species_name = move |id: &str| {
+        fish_species
+            .read()
+            .iter()
+            .find(|species| species.id == id)
+            .map_or_else(|| id.to_string(), |species| species.name.clone())
+    };
+    let name_placeholder = t!("favorites.name", locale = lang);
+    let notes_placeholder = t!("favorites.notes", locale = lang);
+
+    rsx! {
+        div {
+            class: "bg-white rounded-lg shadow-xl w-full",
+
+            div {
+                class: "flex justify-between items-center p-4 border-b",
+                h2 {
+                    class: "text-xl font-bold text-gray-800",
+                    {t!("favorites.title", locale = lang)}
+                }
+                button {
+                    class: "text-gray-500 hover:text-gray-700 text-2xl",
+                    onclick: move |_| on_close.call(()),
+                    "×"
+                }
+            }
+
+            div {
+                class: "p-4 space-y-4",
+
+                if session::load().is_none() {
+                    p { class: "text-sm text-gray-600", {t!("favorites.sign_in", locale = lang)} }
+                } else {
+                    if let Some(err) = error.read().as_ref() {
+                        div {
+                            class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded",
+                            "{err}"
+                        }
+                    }
+
+                    if let Some(enabled) = *push_enabled.read() {
+                        button {
+                            class: "w-full text-sm border border-blue-600 text-blue-700 py-1 px-3 rounded-md hover:bg-blue-50",
+                            onclick: handle_push,
+                            if enabled {
+                                {t!("alerts.disable_push", locale = lang)}
+                            } else {
+                                {t!("alerts.enable_push", locale = lang)}
+                            }
+                        }
+                    }
+
+                    match spots.read().as_ref() {
+                        None => rsx! {
+                            p { class: "text-sm text-gray-500", {t!("favorites.loading", locale = lang)} }
+                        },
+                        Some(list) if list.is_empty() => rsx! {
+                            p { class: "text-sm text-gray-500", {t!("favorites.empty", locale = lang)} }
+                        },
+                        Some(list) => rsx! {
+                            ul {
+                                class: "divide-y divide-gray-200",
+                                for spot in list.iter().cloned() {
+                                    li {
+                                        key: "{spot.id}",
+                                        class: "py-2",
+                                        div {
+                                            class: "flex justify-between items-start gap-2",
+                                            div {
+                                                class: "flex-1 cursor-pointer",
+                                                title: t!("favorites.show", locale = lang).into_owned(),
+                                                onclick: move |_| on_select.call((spot.lat, spot.lon)),
+                                                div { class: "font-semibold text-gray-800", "{spot.name}" }
+                                                if !spot.target_species.is_empty() {
+                                                    div {
+                                                        class: "text-xs text-gray-500",
+                                                        {spot.target_species.iter().map(|id| species_name(id)).collect::<Vec<_>>().join(", ")}
+                                                    }
+                                                }
+                                                {bite_summary(windows.read().get(&spot.id), now, offset, language)}
+                                            }
+                                            div {
+                                                class: "flex flex-col items-end gap-1",
+                                                button {
+                                                    class: "text-xs text-blue-600 hover:underline",
+                                                    title: t!("alerts.title", locale = lang).into_owned(),
+                                                    onclick: {
+                                                        let id = spot.id.clone();
+                                                        move |_| {
+                                                            let open = alerts_open.read().as_deref() == Some(id.as_str());
+                                                            alerts_open.set((!open).then(|| id.clone()));
+                                                        }
+                                                    },
+                                                    {format!("🔔 {}", alerts.read().iter().filter(|rule| rule.favorite_spot_id == spot.id).count())}
+                                                }
+                                                button {
+                                                    class: "text-xs text-red-600 hover:underline",
+                                                    onclick: {
+                                                        let id = spot.id.clone();
+                                                        move |_| handle_delete(id.clone())
+                                                    },
+                                                    {t!("favorites.delete", locale = lang)}
+                                                }
+                                            }
+                                        }
+                                        if alerts_open.read().as_deref() == Some(spot.id.as_str()) {
+                                            SpotAlerts {
+                                                key: "alerts-{spot.id}",
+                                                api_client,
+                                                spot_id: spot.id.clone(),
+                                                rules: alerts.read().iter().filter(|rule| rule.favorite_spot_id == spot.id).cloned().collect::<Vec<_>>(),
+                                                fish_species: fish_species.read().clone(),
+                                                utc_offset_minutes: offset.local_minus_utc() / 60,
+                                                on_changed: move |_| reload_alerts += 1,
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        },
+                    }
+
+                    div {
+                        class: "space-y-2 border-t pt-4",
+                        input {
+                            r#type: "text",
+                            class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500",
+                            placeholder: "{name_placeholder}",
+                            value: "{name}",
+                            oninput: move |e| name.set(e.value()),
+                        }
+                        select {
+                            class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500",
+                            title: t!("favorites.species", locale = lang).into_owned(),
+                            value: "{selected_fish}",
+                            onchange: move |e| selected_fish.set(e.value()),
+                            option { value: "", {t!("favorites.any_species", locale = lang)} }
+                            for species in fish_species.read().iter() {
+                                option { value: "{species.id}", "{species.name}" }
+                            }
+                        }
+                        input {
+                            r#type: "text",
+                            class: "w-full px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500",
+                            placeholder: "{notes_placeholder}",
+                            value: "{notes}",
+                            oninput: move |e| notes.set(e.value()),
+                        }
+                        button {
+                            class: "w-full bg-blue-600 text-white py-2 px-4 rounded-md hover:bg-blue-700",
+                            onclick: handle_save,
+                            {t!("favorites.save", locale = lang)}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Probability now, the window under way and the next best one.
+fn bite_summary(
+    windows: Option<&Option<BiteWindows>>,
+    now: chrono::DateTime<Utc>,
+    offset: FixedOffset,
+    language: fishing_shared::types::Language,
+) -> Element {
+    let lang = language.code();
+    let windows = match windows {
+        None => return rsx! { div { class: "text-xs text-gray-400", {t!("favorites.loading", locale = lang)} } },
+        Some(None) => return rsx! { div { class: "text-xs text-gray-400", {t!("favorites.windows_failed", locale = lang)} } },
+        Some(Some(windows)) => windows,
+    };
+    let percent = |probability: f64| (probability * 100.0).round();
+    rsx! {
+        div {
+            class: "text-xs text-gray-700 mt-1 space-y-0.5",
+            div { {t!("favorites.now", locale = lang, percent = percent(windows.probability_now))} }
+            if let Some(current) = &windows.current {
+                div {
+                    class: "text-green-700 font-semibold",
+                    {t!("favorites.biting_until", locale = lang, time = current.end.with_timezone(&offset).format("%H:%M"))}
+                }
+            }
+            match &windows.next_best {
+                Some(next) => rsx! {
+                    div {
+                        {t!(
+                            "favorites.next_best",
+                            locale = lang,
+                            window = format_window(next.start, next.end, now, offset, language),
+                            percent = percent(next.peak_probability)
+                        )}
+                    }
+                },
+                None if windows.current.is_none() => rsx! {
+                    div { class: "text-gray-500", {t!("favorites.no_window", locale = lang)} }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
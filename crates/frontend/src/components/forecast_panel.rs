@@ -1,12 +1,17 @@
+use chrono::Utc;
 use dioxus::prelude::*;
-use crate::services::api_client::ForecastResponse;
+use fishing_shared::types::{ForecastResult, ForecastSource};
+use rust_i18n::t;
+
+use crate::i18n::{use_language, use_units};
+use crate::utils::formatters::{bait_name, format_age, format_pressure, format_temperature, format_wind_speed};
 
 /// Component props for forecast panel
 #[derive(Props, Clone, PartialEq)]
 pub struct ForecastPanelProps {
     /// Forecast data to display
     #[props(default)]
-    forecast: Option<ForecastResponse>,
+    forecast: Option<ForecastResult>,
     
     /// Whether forecast is loading
     #[props(default = true)]
@@ -19,32 +24,50 @@ pub struct ForecastPanelProps {
 
 /// Display forecast data content
 #[component]
-fn ForecastContent(forecast: ForecastResponse) -> Element {
-    let temp = forecast.weather.temperature;
-    let pressure = forecast.weather.pressure_msl;
-    let wind = forecast.weather.wind_speed;
-    let wind_dir = forecast.weather.wind_direction;
-    let humidity = forecast.weather.humidity;
-    let water_temp = forecast.weather.water_temperature;
-    let wave_height = forecast.weather.wave_height;
-    let visibility = forecast.weather.visibility;
-    let sunrise = forecast.weather.sunrise.clone();
-    let sunset = forecast.weather.sunset.clone();
+fn ForecastContent(forecast: ForecastResult) -> Element {
+    let language = use_language();
+    let lang = language.code();
+    let units = use_units();
+    let temp = format_temperature(forecast.weather.temperature_c, units.temperature);
+    let pressure = format_pressure(forecast.weather.pressure_hpa, units.pressure, language);
+    let wind = format_wind_speed(forecast.weather.wind_speed_ms, units.wind, language);
+    let gust = forecast
+        .weather
+        .wind_gust_ms
+        .map(|g| format_wind_speed(g, units.wind, language))
+        .unwrap_or_else(|| "—".to_string());
+    let precipitation = forecast
+        .weather
+        .precipitation_mm
+        .map(|p| format!("{p:.1} {}", t!("units.mm", locale = lang)))
+        .unwrap_or_else(|| "—".to_string());
     let moon_phase = forecast.moon_phase;
     let prob = forecast.probability * 100.0;
     let best_time = forecast.best_time.clone();
+
+    // Provenance: anything but the server's model is flagged
+    let (source_key, source_class) = match forecast.source {
+        ForecastSource::Model => ("forecast.source.model", "bg-blue-50 text-blue-700"),
+        ForecastSource::ClientHeuristic => ("forecast.source.heuristic", "bg-amber-50 text-amber-800"),
+        ForecastSource::Cached => ("forecast.source.cached", "bg-amber-50 text-amber-800"),
+        ForecastSource::Offline => ("forecast.source.offline", "bg-red-50 text-red-700"),
+    };
+    let source_label = t!(source_key, locale = lang);
+    let age = format_age(forecast.generated_at, Utc::now(), language);
     
     // Wind direction as compass
-    let wind_dir_text = match wind_dir {
-        d if d >= 337.5 || d < 22.5 => "Пн",
-        d if d >= 22.5 && d < 67.5 => "ПнСх",
-        d if d >= 67.5 && d < 112.5 => "Сх",
-        d if d >= 112.5 && d < 157.5 => "ПдСх",
-        d if d >= 157.5 && d < 202.5 => "Пд",
-        d if d >= 202.5 && d < 247.5 => "ПдЗх",
-        d if d >= 247.5 && d < 292.5 => "Зх",
-        _ => "ПнЗх",
+    let wind_dir_key = match forecast.weather.wind_direction_deg {
+        None => None,
+        Some(d) if d >= 337.5 || d < 22.5 => Some("compass.n"),
+        Some(d) if d >= 22.5 && d < 67.5 => Some("compass.ne"),
+        Some(d) if d >= 67.5 && d < 112.5 => Some("compass.e"),
+        Some(d) if d >= 112.5 && d < 157.5 => Some("compass.se"),
+        Some(d) if d >= 157.5 && d < 202.5 => Some("compass.s"),
+        Some(d) if d >= 202.5 && d < 247.5 => Some("compass.sw"),
+        Some(d) if d >= 247.5 && d < 292.5 => Some("compass.w"),
+        _ => Some("compass.nw"),
     };
+    let wind_dir_text = wind_dir_key.map(|key| t!(key, locale = lang)).unwrap_or_default();
     
     // Moon phase emoji
     let moon_emoji = match moon_phase {
@@ -63,44 +86,50 @@ fn ForecastContent(forecast: ForecastResponse) -> Element {
     let has_bait3 = forecast.recommended_baits.len() > 2;
     
     let bait1_name = if has_bait1 { 
-        forecast.recommended_baits[0].name.clone()
+        bait_name(&forecast.recommended_baits[0].name, language)
     } else { 
         String::new() 
     };
     let bait1_eff = if has_bait1 { 
-        forecast.recommended_baits[0].effectiveness * 100.0 
+        forecast.recommended_baits[0].score * 100.0 
     } else { 
         0.0 
     };
     
     let bait2_name = if has_bait2 { 
-        forecast.recommended_baits[1].name.clone() 
+        bait_name(&forecast.recommended_baits[1].name, language) 
     } else { 
         String::new() 
     };
     let bait2_eff = if has_bait2 { 
-        forecast.recommended_baits[1].effectiveness * 100.0 
+        forecast.recommended_baits[1].score * 100.0 
     } else { 
         0.0 
     };
     
     let bait3_name = if has_bait3 { 
-        forecast.recommended_baits[2].name.clone() 
+        bait_name(&forecast.recommended_baits[2].name, language) 
     } else { 
         String::new() 
     };
     let bait3_eff = if has_bait3 { 
-        forecast.recommended_baits[2].effectiveness * 100.0 
+        forecast.recommended_baits[2].score * 100.0 
     } else { 
         0.0 
     };
     
     rsx! {
         div { class: "space-y-6",
+            // Where the forecast came from and how old it is
+            div { class: "flex justify-between items-center text-xs px-3 py-2 rounded-lg {source_class}",
+                span { "{source_label}" }
+                span { "🕒 {age}" }
+            }
+
             // Main forecast
             div { class: "flex items-center justify-between bg-gradient-to-r from-blue-50 to-cyan-50 p-4 rounded-lg",
                 div {
-                    h3 { class: "text-sm text-slate-600 mb-1", "🎣 Прогноз клювання" }
+                    h3 { class: "text-sm text-slate-600 mb-1", {t!("forecast.bite", locale = lang)} }
                     p { class: "text-4xl font-bold text-blue-600", "{prob:.0}%" }
                     p { class: "text-xs text-slate-500 mt-1", "⏰ {best_time}" }
                 }
@@ -111,63 +140,42 @@ fn ForecastContent(forecast: ForecastResponse) -> Element {
             div { class: "bg-white rounded-lg p-4 shadow-sm",
                 h4 { class: "text-sm font-semibold text-slate-700 mb-3 flex items-center gap-2",
                     span { "🌤️" }
-                    "Погодні умови"
+                    {t!("forecast.weather", locale = lang)}
                 }
                 div { class: "grid grid-cols-2 gap-3 text-sm",
                     div { class: "flex items-center gap-2",
                         span { class: "text-lg", "🌡️" }
                         div {
-                            p { class: "text-xs text-slate-500", "Температура повітря" }
-                            p { class: "font-semibold", "{temp:.1}°C" }
-                        }
-                    }
-                    div { class: "flex items-center gap-2",
-                        span { class: "text-lg", "💧" }
-                        div {
-                            p { class: "text-xs text-slate-500", "Температура води" }
-                            p { class: "font-semibold text-blue-600", "{water_temp:.1}°C" }
+                            p { class: "text-xs text-slate-500", {t!("forecast.air_temperature", locale = lang)} }
+                            p { class: "font-semibold", "{temp}" }
                         }
                     }
                     div { class: "flex items-center gap-2",
                         span { class: "text-lg", "🌬️" }
                         div {
-                            p { class: "text-xs text-slate-500", "Вітер" }
-                            p { class: "font-semibold", "{wind:.1} м/с {wind_dir_text}" }
+                            p { class: "text-xs text-slate-500", {t!("forecast.wind", locale = lang)} }
+                            p { class: "font-semibold", "{wind} {wind_dir_text}" }
                         }
                     }
                     div { class: "flex items-center gap-2",
                         span { class: "text-lg", "💨" }
                         div {
-                            p { class: "text-xs text-slate-500", "Тиск" }
-                            p { class: "font-semibold", "{pressure:.0} гПа" }
-                        }
-                    }
-                    div { class: "flex items-center gap-2",
-                        span { class: "text-lg", "💦" }
-                        div {
-                            p { class: "text-xs text-slate-500", "Вологість" }
-                            p { class: "font-semibold", "{humidity:.0}%" }
-                        }
-                    }
-                    div { class: "flex items-center gap-2",
-                        span { class: "text-lg", "🌊" }
-                        div {
-                            p { class: "text-xs text-slate-500", "Висота хвиль" }
-                            p { class: "font-semibold", "{wave_height:.1} м" }
+                            p { class: "text-xs text-slate-500", {t!("forecast.pressure", locale = lang)} }
+                            p { class: "font-semibold", "{pressure}" }
                         }
                     }
                     div { class: "flex items-center gap-2",
-                        span { class: "text-lg", "👁️" }
+                        span { class: "text-lg", "🌪️" }
                         div {
-                            p { class: "text-xs text-slate-500", "Видимість" }
-                            p { class: "font-semibold", "{visibility:.1} км" }
+                            p { class: "text-xs text-slate-500", {t!("forecast.gusts", locale = lang)} }
+                            p { class: "font-semibold", "{gust}" }
                         }
                     }
                     div { class: "flex items-center gap-2",
-                        span { class: "text-lg", "☀️" }
+                        span { class: "text-lg", "🌧️" }
                         div {
-                            p { class: "text-xs text-slate-500", "Світло" }
-                            p { class: "font-semibold text-xs", "↑{sunrise} ↓{sunset}" }
+                            p { class: "text-xs text-slate-500", {t!("forecast.precipitation", locale = lang)} }
+                            p { class: "font-semibold", "{precipitation}" }
                         }
                     }
                 }
@@ -177,7 +185,7 @@ fn ForecastContent(forecast: ForecastResponse) -> Element {
             div { class: "bg-white rounded-lg p-4 shadow-sm",
                 h4 { class: "text-sm font-semibold text-slate-700 mb-3 flex items-center gap-2",
                     span { "🪱" }
-                    "Рекомендовані приманки"
+                    {t!("forecast.baits", locale = lang)}
                 }
                 div { class: "space-y-2",
                     if has_bait1 {
@@ -219,6 +227,7 @@ fn ForecastContent(forecast: ForecastResponse) -> Element {
 /// Simple version that shows forecast data when available.
 #[component]
 pub fn ForecastPanel(props: ForecastPanelProps) -> Element {
+    let lang = use_language().code();
     let mut is_open = use_signal(|| true);
     
     rsx! {
@@ -235,7 +244,7 @@ pub fn ForecastPanel(props: ForecastPanelProps) -> Element {
                     div {
                         class: "flex items-center gap-2",
                         span { class: "text-2xl", "🎣" }
-                        h2 { class: "text-lg font-bold text-slate-800", "Прогноз клювання" }
+                        h2 { class: "text-lg font-bold text-slate-800", {t!("forecast.title", locale = lang)} }
                     }
                     
                     button {
@@ -254,14 +263,14 @@ pub fn ForecastPanel(props: ForecastPanelProps) -> Element {
                     if props.is_loading {
                         div {
                             class: "text-center py-8",
-                            p { class: "text-slate-500 animate-pulse", "⏳ Завантаження прогнозу..." }
+                            p { class: "text-slate-500 animate-pulse", {t!("forecast.loading", locale = lang)} }
                         }
                     }
                     
                     if let Some(err) = &props.error {
                         div {
                             class: "bg-red-50 border border-red-200 p-3 rounded-lg",
-                            p { class: "text-red-700 text-sm", "❌ Помилка: {err}" }
+                            p { class: "text-red-700 text-sm", {t!("forecast.error", locale = lang, error = err)} }
                         }
                     }
                 }
@@ -1,96 +1,188 @@
+use std::rc::Rc;
+
 use dioxus::prelude::*;
-use wasm_bindgen::prelude::*;
-
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = window)]
-    fn initLeafletMap(container_id: &str, lat: f64, lon: f64, zoom: i32);
-    
-    #[wasm_bindgen(js_namespace = window)]
-    fn updateLeafletMarker(lat: f64, lon: f64);
-    
-    #[wasm_bindgen(js_namespace = window)]
-    fn updateUserMarker(lat: f64, lon: f64, heading: f64, wind_direction: f64);
-    
-    #[wasm_bindgen(js_namespace = window)]
-    fn setLeafletClickHandler(callback: &Closure<dyn Fn(f64, f64)>);
-}
+use fishing_shared::types::{CatchRecord, WaterBody};
+use futures_util::StreamExt;
+use rust_i18n::t;
+
+use crate::i18n::use_language;
+use crate::services::map_service::{escape_html, FishingMap, LayerLabels, MapEvent, MapPoint, ViewArea};
+use crate::services::ApiClient;
+use crate::state::app_state::UserPreferences;
+use crate::utils::formatters::{format_length, format_weight};
 
 /// Component props for the map view
 #[derive(Props, Clone, PartialEq)]
 pub struct MapViewProps {
+    /// Client for water bodies and catches around the view
+    pub api_client: Signal<ApiClient>,
+
     /// Callback when user clicks on map
     pub on_location_selected: EventHandler<(f64, f64)>,
-    
+
+    /// Callback when user long-presses the map
+    pub on_pin_dropped: EventHandler<(f64, f64)>,
+
+    /// Callback when the browser reports the user's position
+    pub on_user_located: EventHandler<(f64, f64)>,
+
     /// Selected location (lat, lon) for the forecast
     #[props(default)]
     pub selected_location: Option<(f64, f64)>,
+
+    /// Pin dropped by a long press
+    #[props(default)]
+    pub pin: Option<(f64, f64)>,
+
+    /// The user's position, once known
+    #[props(default)]
+    pub user_location: Option<(f64, f64)>,
+
+    /// Wind direction (degrees, where it blows from) shown at the user's position
+    #[props(default)]
+    pub wind_direction: Option<f64>,
+
+    /// Tile URL template for the bite probability overlay (hidden when `None`)
+    #[props(default)]
+    pub forecast_overlay: Option<String>,
 }
 
 /// Interactive map component with Leaflet integration
-/// 
-/// Displays an interactive OpenStreetMap with water bodies and marker for selected location.
-/// Allows user to click on map to select location for forecast.
+///
+/// Displays street, topographic and satellite maps with water bodies and
+/// catches around the view. A click selects the forecast location, a long
+/// press drops a pin.
 #[component]
 pub fn MapView(props: MapViewProps) -> Element {
-    let on_click = props.on_location_selected.clone();
-    let mut map_initialized = use_signal(|| false);
-    let _user_location = use_signal(|| (50.45, 30.52)); // Kyiv default
-    
-    // Initialize map on first render
+    let lang = use_language().code();
+    let preferences = use_context::<Signal<UserPreferences>>();
+    let api_client = props.api_client;
+    let on_location_selected = props.on_location_selected;
+    let on_pin_dropped = props.on_pin_dropped;
+    let on_user_located = props.on_user_located;
+    let mut map = use_signal(|| None::<Rc<FishingMap>>);
+
+    let events = use_coroutine(move |mut events: UnboundedReceiver<MapEvent>| async move {
+        // Area whose markers are on the map
+        let mut loaded: Option<ViewArea> = None;
+        while let Some(event) = events.next().await {
+            match event {
+                MapEvent::Click(lat, lon) => on_location_selected.call((lat, lon)),
+                MapEvent::LongPress(lat, lon) => on_pin_dropped.call((lat, lon)),
+                MapEvent::Located(lat, lon) => on_user_located.call((lat, lon)),
+                MapEvent::Moved(view) => {
+                    if let Some(map) = map.peek().as_ref() {
+                        map.recluster();
+                    }
+                    if !view.is_close_enough() || loaded.is_some_and(|area| area.covers(&view)) {
+                        continue;
+                    }
+                    let area = view.fetch_area();
+                    let api_client = api_client.peek().clone();
+                    let water_bodies = api_client.get_water_bodies(area.lat, area.lon, area.radius_km).await;
+                    let catches = api_client.get_nearby_catches(area.lat, area.lon, area.radius_km).await;
+
+                    let Some(map) = map.peek().clone() else {
+                        continue;
+                    };
+                    let preferences = preferences.peek().clone();
+                    match (water_bodies, catches) {
+                        (Ok(water_bodies), Ok(catches)) => {
+                            map.show_water_bodies(&water_bodies.iter().map(water_body_point).collect::<Vec<_>>());
+                            map.show_catches(catches.iter().map(|catch| catch_point(catch, &preferences)).collect());
+                            loaded = Some(area);
+                        }
+                        (Err(err), _) | (_, Err(err)) => log::warn!("Loading map markers failed: {}", err),
+                    }
+                }
+            }
+        }
+    });
+
+    // Create the map once its container is on the page; dropping it with
+    // the component removes it
     use_effect(move || {
-        if !*map_initialized.read() {
-            let init_lat = 49.0;
-            let init_lon = 31.0;
-            let init_zoom = 6;
-            
-            web_sys::console::log_1(&"🗺️ Rust: Starting map initialization".into());
-            
-            // Initialize Leaflet map
-            initLeafletMap("leaflet-map", init_lat, init_lon, init_zoom);
-            
-            // Setup click handler
-            let callback = Closure::wrap(Box::new(move |lat: f64, lon: f64| {
-                on_click.call((lat, lon));
-            }) as Box<dyn Fn(f64, f64)>);
-            
-            setLeafletClickHandler(&callback);
-            callback.forget(); // Keep callback alive
-            
-            web_sys::console::log_1(&"✅ Rust: Map initialized".into());
-            
-            *map_initialized.write() = true;
-            
-            // Let JavaScript handle geolocation and user marker
-            // It will call updateUserMarker automatically
+        if map.peek().is_some() {
+            return;
         }
+        let tx = events.tx();
+        let created = FishingMap::new("leaflet-map", move |event| {
+            let _ = tx.unbounded_send(event);
+        });
+        events.send(MapEvent::Moved(created.view()));
+        created.locate();
+        map.set(Some(Rc::new(created)));
     });
-    
-    // Update marker when location changes
+
+    // Layer names follow the language
     use_effect(move || {
-        if let Some((lat, lon)) = props.selected_location {
-            if *map_initialized.read() {
-                updateLeafletMarker(lat, lon);
-            }
+        let lang = preferences.read().language.code();
+        if let Some(map) = map.read().as_ref() {
+            map.set_layer_labels(&LayerLabels {
+                street: t!("map.layers.street", locale = lang).into_owned(),
+                topo: t!("map.layers.topo", locale = lang).into_owned(),
+                satellite: t!("map.layers.satellite", locale = lang).into_owned(),
+                water_bodies: t!("map.layers.water", locale = lang).into_owned(),
+                catches: t!("map.layers.catches", locale = lang).into_owned(),
+            });
         }
     });
-    
+
+    let selected_location = props.selected_location;
+    use_effect(use_reactive!(|selected_location| {
+        let lang = preferences.peek().language.code();
+        if let Some(map) = map.read().as_ref() {
+            map.set_selected(selected_location, &escape_html(&t!("map.selected", locale = lang)));
+        }
+    }));
+
+    let pin = props.pin;
+    use_effect(use_reactive!(|pin| {
+        let lang = preferences.peek().language.code();
+        if let Some(map) = map.read().as_ref() {
+            map.set_pin(pin, &escape_html(&t!("map.pin", locale = lang)));
+        }
+    }));
+
+    let user_location = props.user_location;
+    let wind_direction = props.wind_direction;
+    use_effect(use_reactive!(|user_location, wind_direction| {
+        let lang = preferences.peek().language.code();
+        if let Some(map) = map.read().as_ref() {
+            map.set_user(user_location, &escape_html(&t!("map.you_are_here", locale = lang)));
+            let degrees = wind_direction.unwrap_or_default().round();
+            map.set_wind(
+                user_location,
+                wind_direction,
+                &escape_html(&t!("map.wind_from", locale = lang, degrees = degrees)),
+            );
+        }
+    }));
+
+    // Show or hide the bite probability overlay
+    let forecast_overlay = props.forecast_overlay.clone();
+    use_effect(use_reactive!(|forecast_overlay| {
+        if let Some(map) = map.read().as_ref() {
+            map.set_forecast_overlay(forecast_overlay.as_deref());
+        }
+    }));
+
     rsx! {
         div {
             class: "w-full h-full rounded-lg shadow-md relative",
             style: "width: 100%; height: 100%; position: relative;",
-            
-            // Інформаційна панель
+
+            // Інформаційна панель, right of Leaflet's zoom buttons
             div {
-                class: "absolute top-2 left-2 bg-white bg-opacity-95 px-3 py-2 rounded-lg shadow-lg z-[1000] border border-blue-200",
-                style: "position: absolute; top: 8px; left: 8px; z-index: 1000;",
+                class: "absolute top-2 left-14 bg-white bg-opacity-95 px-3 py-2 rounded-lg shadow-lg z-[1000] border border-blue-200",
+                style: "position: absolute; top: 8px; left: 56px; z-index: 1000;",
                 div {
                     class: "font-bold text-sm text-blue-700",
-                    "🗺️ Карта лову"
+                    {t!("map.title", locale = lang)}
                 }
                 div {
                     class: "text-xs text-gray-600 mt-1",
-                    "Клікніть на карту для вибору локації"
+                    {t!("map.hint", locale = lang)}
                 }
                 {props.selected_location.map(|(lat, lon)| rsx! {
                     div {
@@ -99,7 +191,7 @@ pub fn MapView(props: MapViewProps) -> Element {
                     }
                 })}
             }
-            
+
             // Leaflet map container - fullscreen
             div {
                 id: "leaflet-map",
@@ -109,3 +201,39 @@ pub fn MapView(props: MapViewProps) -> Element {
         }
     }
 }
+
+fn water_body_point(water_body: &WaterBody) -> MapPoint {
+    let mut popup_html = format!("<b>{}</b>", escape_html(&water_body.name));
+    if let Some(water_type) = &water_body.water_type {
+        popup_html.push_str(&format!("<br><i>{}</i>", escape_html(water_type)));
+    }
+    if let Some(description) = &water_body.description {
+        popup_html.push_str(&format!("<br>{}", escape_html(description)));
+    }
+    MapPoint {
+        lat: water_body.location_lat,
+        lon: water_body.location_lon,
+        popup_html,
+    }
+}
+
+fn catch_point(catch: &CatchRecord, preferences: &UserPreferences) -> MapPoint {
+    let language = preferences.language;
+    let mut details = Vec::new();
+    if let Some(weight) = catch.weight_kg {
+        details.push(format_weight(weight, preferences.units.weight, language));
+    }
+    if let Some(length) = catch.length_cm {
+        details.push(format_length(length, preferences.units.length, language));
+    }
+    let mut popup_html = format!("<b>🐟 {}</b>", escape_html(&catch.fish_species));
+    if !details.is_empty() {
+        popup_html.push_str(&format!("<br>{}", escape_html(&details.join(", "))));
+    }
+    popup_html.push_str(&format!("<br>{}", catch.caught_at.format("%Y-%m-%d")));
+    MapPoint {
+        lat: catch.lat,
+        lon: catch.lon,
+        popup_html,
+    }
+}
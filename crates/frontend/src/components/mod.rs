@@ -2,7 +2,16 @@
 pub mod map;
 pub mod forecast_panel;
 pub mod catch_form;
+pub mod unit_settings;
+pub mod favorites_panel;
+pub mod spot_alerts;
+pub mod trip_planner;
+pub mod account_menu;
 
 pub use map::MapView;
 pub use forecast_panel::ForecastPanel;
-pub use catch_form::CatchForm;
+pub use catch_form::{CatchForm, CatchOutcome};
+pub use unit_settings::UnitSettings;
+pub use favorites_panel::FavoritesPanel;
+pub use trip_planner::TripPlanner;
+pub use account_menu::AccountMenu;
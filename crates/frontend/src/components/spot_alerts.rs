@@ -0,0 +1,228 @@
+use dioxus::prelude::*;
+use fishing_shared::types::{AlertRule, AlertRuleInput, FishSpecies, PressureTrend};
+use rust_i18n::t;
+
+use crate::i18n::use_language;
+use crate::services::api_client::ApiClient;
+use crate::services::session;
+
+/// Bite alert rules of one favorite spot, with a form to add another.
+#[component]
+pub fn SpotAlerts(
+    api_client: Signal<ApiClient>,
+    spot_id: String,
+    rules: Vec<AlertRule>,
+    fish_species: Vec<FishSpecies>,
+    // Minutes east of UTC the hours are entered in
+    utc_offset_minutes: i32,
+    on_changed: EventHandler<()>,
+) -> Element {
+    let language = use_language();
+    let lang = language.code();
+    let mut species = use_signal(String::new);
+    let mut percent = use_signal(|| 60u8);
+    let mut start_hour = use_signal(|| 5u8);
+    let mut end_hour = use_signal(|| 10u8);
+    let mut trend = use_signal(String::new);
+    let mut error = use_signal(|| None::<String>);
+
+    let signed_in = move || session::load().map(|_| api_client.peek().clone());
+
+    let spot = spot_id.clone();
+    let handle_add = move |_| {
+        let Some(api) = signed_in() else {
+            return;
+        };
+        let input = AlertRuleInput {
+            favorite_spot_id: spot.clone(),
+            species: Some(species.read().clone()).filter(|id| !id.is_empty()),
+            min_probability: f64::from(*percent.read()) / 100.0,
+            start_hour: *start_hour.read(),
+            end_hour: *end_hour.read(),
+            utc_offset_minutes,
+            pressure_trend: PressureTrend::parse(&trend.read()),
+            enabled: true,
+        };
+        spawn(async move {
+            let input = &input;
+            match session::send(&api, |api| async move { api.create_alert(input).await }).await {
+                Ok(_) => {
+                    error.set(None);
+                    on_changed.call(());
+                }
+                Err(err) => error.set(Some(err.to_string())),
+            }
+        });
+    };
+
+    let handle_toggle = move |rule: AlertRule| {
+        let Some(api) = signed_in() else {
+            return;
+        };
+        let input = AlertRuleInput {
+            favorite_spot_id: rule.favorite_spot_id,
+            species: rule.species,
+            min_probability: rule.min_probability,
+            start_hour: rule.start_hour,
+            end_hour: rule.end_hour,
+            utc_offset_minutes: rule.utc_offset_minutes,
+            pressure_trend: rule.pressure_trend,
+            enabled: !rule.enabled,
+        };
+        spawn(async move {
+            let (id, input) = (&rule.id, &input);
+            match session::send(&api, |api| async move { api.update_alert(id, input).await }).await {
+                Ok(_) => on_changed.call(()),
+                Err(err) => error.set(Some(err.to_string())),
+            }
+        });
+    };
+
+    let handle_delete = move |id: String| {
+        let Some(api) = signed_in() else {
+            return;
+        };
+        spawn(async move {
+            let id = &id;
+            match session::send(&api, |api| async move { api.delete_alert(id).await }).await {
+                Ok(()) => on_changed.call(()),
+                Err(err) => error.set(Some(err.to_string())),
+            }
+        });
+    };
+
+    let species_name = |id: &str| {
+        fish_species
+            .iter()
+            .find(|species| species.id == id)
+            .map_or_else(|| id.to_string(), |species| species.name.clone())
+    };
+    let select_class = "px-1 py-1 border border-gray-300 rounded text-xs";
+
+    rsx! {
+        div {
+            class: "mt-2 p-2 bg-gray-50 rounded space-y-2 text-xs",
+
+            if let Some(err) = error.read().as_ref() {
+                div { class: "text-red-700", "{err}" }
+            }
+
+            for rule in rules.iter().cloned() {
+                div {
+                    key: "{rule.id}",
+                    class: "flex justify-between items-center gap-2",
+                    span {
+                        class: if rule.enabled { "text-gray-800" } else { "text-gray-400 line-through" },
+                        {rule_summary(&rule, rule.species.as_deref().map(species_name), lang)}
+                    }
+                    div {
+                        class: "flex gap-2 shrink-0",
+                        button {
+                            class: "text-blue-600 hover:underline",
+                            onclick: {
+                                let rule = rule.clone();
+                                move |_| handle_toggle(rule.clone())
+                            },
+                            if rule.enabled { {t!("alerts.pause", locale = lang)} } else { {t!("alerts.resume", locale = lang)} }
+                        }
+                        button {
+                            class: "text-red-600 hover:underline",
+                            onclick: move |_| handle_delete(rule.id.clone()),
+                            {t!("alerts.delete", locale = lang)}
+                        }
+                    }
+                }
+            }
+
+            div {
+                class: "flex flex-wrap items-center gap-1",
+                select {
+                    class: select_class,
+                    title: t!("alerts.species", locale = lang).into_owned(),
+                    value: "{species}",
+                    onchange: move |e| species.set(e.value()),
+                    option { value: "", {t!("favorites.any_species", locale = lang)} }
+                    for fish in fish_species.iter() {
+                        option { value: "{fish.id}", "{fish.name}" }
+                    }
+                }
+                label { "≥" }
+                input {
+                    r#type: "number",
+                    class: "{select_class} w-14",
+                    title: t!("alerts.threshold", locale = lang).into_owned(),
+                    min: "1",
+                    max: "100",
+                    value: "{percent}",
+                    oninput: move |e| {
+                        if let Ok(value) = e.value().parse::<u8>() {
+                            percent.set(value.clamp(1, 100));
+                        }
+                    },
+                }
+                label { "%" }
+                select {
+                    class: select_class,
+                    title: t!("alerts.from", locale = lang).into_owned(),
+                    value: "{start_hour}",
+                    onchange: move |e| start_hour.set(e.value().parse().unwrap_or(0)),
+                    for hour in 0..24u8 {
+                        option { value: "{hour}", "{hour:02}:00" }
+                    }
+                }
+                label { "–" }
+                select {
+                    class: select_class,
+                    title: t!("alerts.until", locale = lang).into_owned(),
+                    value: "{end_hour}",
+                    onchange: move |e| end_hour.set(e.value().parse().unwrap_or(0)),
+                    for hour in 0..24u8 {
+                        option { value: "{hour}", "{hour:02}:00" }
+                    }
+                }
+                select {
+                    class: select_class,
+                    title: t!("alerts.pressure", locale = lang).into_owned(),
+                    value: "{trend}",
+                    onchange: move |e| trend.set(e.value()),
+                    option { value: "", {t!("alerts.any_pressure", locale = lang)} }
+                    for option_trend in [PressureTrend::Falling, PressureTrend::Steady, PressureTrend::Rising] {
+                        option { value: "{option_trend.as_str()}", {trend_label(option_trend, lang)} }
+                    }
+                }
+                button {
+                    class: "bg-blue-600 text-white px-2 py-1 rounded hover:bg-blue-700",
+                    onclick: handle_add,
+                    {t!("alerts.add", locale = lang)}
+                }
+            }
+        }
+    }
+}
+
+/// One line describing when a rule fires, e.g. "Pike ≥ 70%, 05:00–10:00, pressure falling".
+fn rule_summary(rule: &AlertRule, species: Option<String>, lang: &str) -> String {
+    let mut parts = vec![format!(
+        "{} ≥ {}%",
+        species.unwrap_or_else(|| t!("favorites.any_species", locale = lang).into_owned()),
+        (rule.min_probability * 100.0).round()
+    )];
+    parts.push(if rule.start_hour == rule.end_hour {
+        t!("alerts.all_day", locale = lang).into_owned()
+    } else {
+        format!("{:02}:00–{:02}:00", rule.start_hour, rule.end_hour)
+    });
+    if let Some(trend) = rule.pressure_trend {
+        parts.push(trend_label(trend, lang));
+    }
+    parts.join(", ")
+}
+
+fn trend_label(trend: PressureTrend, lang: &str) -> String {
+    match trend {
+        PressureTrend::Rising => t!("alerts.rising", locale = lang),
+        PressureTrend::Steady => t!("alerts.steady", locale = lang),
+        PressureTrend::Falling => t!("alerts.falling", locale = lang),
+    }
+    .into_owned()
+}
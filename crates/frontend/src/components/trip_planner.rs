@@ -0,0 +1,342 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, Utc};
+use dioxus::prelude::*;
+use fishing_shared::types::{FavoriteSpot, HourlyProbability, TripOrigin, TripPlan, TripPlanRequest, TripSpot};
+use fishing_shared::validation::{MAX_TRIP_DAYS, MAX_TRIP_SPOTS};
+use rust_i18n::t;
+
+use crate::i18n::use_language;
+use crate::services::api_client::ApiClient;
+use crate::services::session;
+use crate::state::app_state::UserPreferences;
+use crate::utils::formatters::{browser_offset, format_distance, format_window};
+
+/// Probability from which an hour is highlighted, as in the bite windows.
+const GOOD_BITE_PROBABILITY: f64 = 0.6;
+
+/// Compare candidate spots over a few days and rank their best bite windows.
+#[component]
+pub fn TripPlanner(
+    api_client: Signal<ApiClient>,
+    // Place picked on the map, offered as a candidate
+    selected_location: Option<(f64, f64)>,
+    // Where the user is, to weigh travel distance
+    user_location: Option<(f64, f64)>,
+    on_select: EventHandler<(f64, f64)>,
+    on_close: EventHandler<()>,
+) -> Element {
+    let language = use_language();
+    let lang = language.code();
+    let preferences = use_context::<Signal<UserPreferences>>();
+    let mut favorites = use_signal(Vec::<FavoriteSpot>::new);
+    // Ids of the favorites to compare
+    let mut chosen = use_signal(Vec::<String>::new);
+    let mut include_selected = use_signal(|| selected_location.is_some());
+    let mut start_day = use_signal(|| 0i64);
+    let mut days = use_signal(|| 3i64);
+    let mut plan = use_signal(|| None::<TripPlan>);
+    let mut is_loading = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    use_effect(move || {
+        if session::load().is_none() {
+            return;
+        }
+        let api = api_client.peek().clone();
+        spawn(async move {
+            match session::send(&api, |api| async move { api.get_favorites().await }).await {
+                Ok(loaded) => {
+                    chosen.set(loaded.iter().take(MAX_TRIP_SPOTS).map(|spot| spot.id.clone()).collect());
+                    favorites.set(loaded);
+                }
+                Err(err) => log::warn!("Loading favorites for the trip planner failed: {}", err),
+            }
+        });
+    });
+
+    let offset = browser_offset();
+    let now = Utc::now();
+    let today = now.with_timezone(&offset).date_naive();
+
+    let handle_compare = move |_| {
+        let mut spots: Vec<TripSpot> = favorites
+            .read()
+            .iter()
+            .filter(|spot| chosen.read().contains(&spot.id))
+            .map(|spot| TripSpot { name: spot.name.clone(), lat: spot.lat, lon: spot.lon })
+            .collect();
+        if let (true, Some((lat, lon))) = (*include_selected.read(), selected_location) {
+            spots.insert(0, TripSpot { name: t!("trip.selected_place", locale = lang).into_owned(), lat, lon });
+        }
+        if spots.is_empty() {
+            error.set(Some(t!("trip.pick_spots", locale = lang).into_owned()));
+            return;
+        }
+        spots.truncate(MAX_TRIP_SPOTS);
+
+        let now = Utc::now();
+        let from = local_midnight(today + Duration::days(*start_day.read()), offset);
+        let to = (from + Duration::days(*days.read())).min(now + Duration::days(MAX_TRIP_DAYS));
+        let request = TripPlanRequest {
+            spots,
+            from,
+            to,
+            origin: user_location.map(|(lat, lon)| TripOrigin { lat, lon }),
+        };
+        let api = api_client.read().clone();
+        is_loading.set(true);
+        error.set(None);
+        spawn(async move {
+            match api.plan_trip(&request).await {
+                Ok(result) => plan.set(Some(result)),
+                Err(err) => error.set(Some(t!("trip.failed", locale = lang, error = err).into_owned())),
+            }
+            is_loading.set(false);
+        });
+    };
+
+    let length_unit = preferences.read().units.length;
+    let percent = |probability: f64| (probability * 100.0).round();
+    let select_class = "px-2 py-1 border border-gray-300 rounded-md text-sm";
+
+    rsx! {
+        div {
+            class: "bg-white rounded-lg shadow-xl w-full",
+
+            div {
+                class: "flex justify-between items-center p-4 border-b",
+                h2 {
+                    class: "text-xl font-bold text-gray-800",
+                    {t!("trip.title", locale = lang)}
+                }
+                button {
+                    class: "text-gray-500 hover:text-gray-700 text-2xl",
+                    onclick: move |_| on_close.call(()),
+                    "×"
+                }
+            }
+
+            div {
+                class: "p-4 space-y-4",
+
+                div {
+                    class: "space-y-1",
+                    h3 { class: "text-sm font-semibold text-gray-700", {t!("trip.spots", locale = lang)} }
+                    if selected_location.is_some() {
+                        label {
+                            class: "flex items-center gap-2 text-sm",
+                            input {
+                                r#type: "checkbox",
+                                checked: include_selected(),
+                                onchange: move |e| include_selected.set(e.checked()),
+                            }
+                            {t!("trip.selected_place", locale = lang)}
+                        }
+                    }
+                    for spot in favorites.read().iter().cloned() {
+                        label {
+                            key: "{spot.id}",
+                            class: "flex items-center gap-2 text-sm",
+                            input {
+                                r#type: "checkbox",
+                                checked: chosen.read().contains(&spot.id),
+                                onchange: move |e| {
+                                    let mut ids = chosen.write();
+                                    ids.retain(|id| id != &spot.id);
+                                    if e.checked() {
+                                        ids.push(spot.id.clone());
+                                    }
+                                },
+                            }
+                            "{spot.name}"
+                        }
+                    }
+                    if selected_location.is_none() && favorites.read().is_empty() {
+                        p { class: "text-sm text-gray-500", {t!("trip.no_spots", locale = lang)} }
+                    }
+                    if user_location.is_none() {
+                        p { class: "text-xs text-gray-500", {t!("trip.no_origin", locale = lang)} }
+                    }
+                }
+
+                div {
+                    class: "flex flex-wrap items-center gap-2 text-sm",
+                    label { {t!("trip.from", locale = lang)} }
+                    select {
+                        class: select_class,
+                        value: "{start_day}",
+                        onchange: move |e| start_day.set(e.value().parse().unwrap_or(0)),
+                        for day in 0..MAX_TRIP_DAYS {
+                            option { value: "{day}", {day_label(today + Duration::days(day), today, lang)} }
+                        }
+                    }
+                    label { {t!("trip.days", locale = lang)} }
+                    select {
+                        class: select_class,
+                        value: "{days}",
+                        onchange: move |e| days.set(e.value().parse().unwrap_or(1)),
+                        for count in 1..=MAX_TRIP_DAYS {
+                            option { value: "{count}", "{count}" }
+                        }
+                    }
+                    button {
+                        class: "bg-blue-600 text-white py-1 px-4 rounded-md hover:bg-blue-700 disabled:opacity-50",
+                        disabled: is_loading(),
+                        onclick: handle_compare,
+                        if is_loading() {
+                            {t!("trip.comparing", locale = lang)}
+                        } else {
+                            {t!("trip.compare", locale = lang)}
+                        }
+                    }
+                }
+
+                if let Some(err) = error.read().as_ref() {
+                    div {
+                        class: "bg-red-100 border border-red-400 text-red-700 px-4 py-3 rounded",
+                        "{err}"
+                    }
+                }
+
+                if let Some(plan) = plan.read().as_ref() {
+                    div {
+                        class: "space-y-1",
+                        h3 { class: "text-sm font-semibold text-gray-700", {t!("trip.best_windows", locale = lang)} }
+                        if plan.windows.is_empty() {
+                            p { class: "text-sm text-gray-500", {t!("trip.no_window", locale = lang)} }
+                        }
+                        ol {
+                            class: "space-y-1 text-sm",
+                            for (rank, window) in plan.windows.iter().take(10).enumerate() {
+                                li {
+                                    key: "{window.spot}-{window.start}",
+                                    class: "flex justify-between gap-2 p-2 rounded hover:bg-blue-50 cursor-pointer",
+                                    title: t!("favorites.show", locale = lang).into_owned(),
+                                    onclick: {
+                                        let spot = &plan.spots[window.spot];
+                                        let at = (spot.lat, spot.lon);
+                                        move |_| on_select.call(at)
+                                    },
+                                    span {
+                                        span { class: "font-semibold", "{rank + 1}. {plan.spots[window.spot].name}" }
+                                        " · {format_window(window.start, window.end, now, offset, language)}"
+                                    }
+                                    span {
+                                        class: "text-green-700 font-semibold whitespace-nowrap",
+                                        "{percent(window.peak_probability)}%"
+                                        if let Some(km) = plan.spots[window.spot].distance_km {
+                                            span { class: "text-gray-500 font-normal", " · {format_distance(km, length_unit, language)}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    {comparison(plan, offset, today, language, length_unit)}
+                }
+            }
+        }
+    }
+}
+
+/// Best hour per local day at each spot, spots side by side.
+fn comparison(
+    plan: &TripPlan,
+    offset: FixedOffset,
+    today: NaiveDate,
+    language: fishing_shared::types::Language,
+    length_unit: fishing_shared::types::LengthUnit,
+) -> Element {
+    let lang = language.code();
+    // Per day, the best (probability, hour) of each spot
+    let mut by_day: BTreeMap<NaiveDate, Vec<Option<HourlyProbability>>> = BTreeMap::new();
+    for (index, spot) in plan.spots.iter().enumerate() {
+        for hour in &spot.hours {
+            let day = hour.time.with_timezone(&offset).date_naive();
+            let row = by_day.entry(day).or_insert_with(|| vec![None; plan.spots.len()]);
+            if row[index].is_none_or(|best| hour.probability > best.probability) {
+                row[index] = Some(*hour);
+            }
+        }
+    }
+
+    // Cells as (probability, local time, best of the day)
+    let rows = by_day.into_iter().map(|(day, row)| {
+        let top = row.iter().flatten().map(|hour| hour.probability).fold(f64::MIN, f64::max);
+        let cells: Vec<_> = row
+            .into_iter()
+            .map(|cell| {
+                cell.map(|hour| {
+                    let time = hour.time.with_timezone(&offset).format("%H:%M").to_string();
+                    (hour.probability, time, hour.probability == top)
+                })
+            })
+            .collect();
+        (day, cells)
+    });
+
+    rsx! {
+        div {
+            class: "overflow-x-auto",
+            h3 { class: "text-sm font-semibold text-gray-700 mb-1", {t!("trip.side_by_side", locale = lang)} }
+            table {
+                class: "min-w-full text-xs border-collapse",
+                thead {
+                    tr {
+                        th { class: "p-1 text-left" }
+                        for spot in plan.spots.iter() {
+                            th {
+                                class: "p-1 text-left font-semibold text-gray-700",
+                                div { "{spot.name}" }
+                                if let Some(km) = spot.distance_km {
+                                    div { class: "font-normal text-gray-500", {format_distance(km, length_unit, language)} }
+                                }
+                            }
+                        }
+                    }
+                }
+                tbody {
+                    for (day, cells) in rows {
+                        tr {
+                            key: "{day}",
+                            class: "border-t",
+                            td { class: "p-1 text-gray-600 whitespace-nowrap", {day_label(day, today, lang)} }
+                            for cell in cells {
+                                match cell {
+                                    Some((probability, time, top)) => rsx! {
+                                        td {
+                                            class: if probability >= GOOD_BITE_PROBABILITY { "p-1 bg-green-100" } else { "p-1" },
+                                            span {
+                                                class: if top { "font-bold" } else { "" },
+                                                "{(probability * 100.0).round()}%"
+                                            }
+                                            span { class: "text-gray-500", " {time}" }
+                                        }
+                                    },
+                                    None => rsx! { td { class: "p-1 text-gray-400", "—" } },
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// "today", "tomorrow" or the date.
+fn day_label(day: NaiveDate, today: NaiveDate, lang: &str) -> String {
+    match (day - today).num_days() {
+        0 => t!("time.today", locale = lang).into_owned(),
+        1 => t!("time.tomorrow", locale = lang).into_owned(),
+        _ => day.format("%d.%m").to_string(),
+    }
+}
+
+/// Start of a local day, in UTC.
+fn local_midnight(day: NaiveDate, offset: FixedOffset) -> DateTime<Utc> {
+    let midnight = day.and_hms_opt(0, 0, 0).expect("midnight exists");
+    (midnight - Duration::seconds(i64::from(offset.local_minus_utc()))).and_utc()
+}
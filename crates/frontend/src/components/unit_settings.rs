@@ -0,0 +1,85 @@
+use dioxus::prelude::*;
+use fishing_shared::types::{LengthUnit, PressureUnit, TemperatureUnit, UnitPreferences, WeightUnit, WindSpeedUnit};
+use rust_i18n::t;
+
+use crate::i18n::use_language;
+use crate::utils::formatters::UnitLabel;
+
+/// Header menu for the units measurements are shown and entered in
+#[component]
+pub fn UnitSettings(units: UnitPreferences, on_change: EventHandler<UnitPreferences>) -> Element {
+    let language = use_language();
+    let lang = language.code();
+    let mut is_open = use_signal(|| false);
+
+    // One select per measurement; `apply` returns the preferences with the picked code
+    let row = |label_key: &'static str,
+               options: Vec<(&'static str, String)>,
+               selected: &'static str,
+               apply: fn(&mut UnitPreferences, &str)| {
+        let units = units.clone();
+        rsx! {
+            label { class: "flex items-center justify-between gap-3 text-sm",
+                span { {t!(label_key, locale = lang)} }
+                select {
+                    class: "border border-slate-300 rounded px-1 py-0.5",
+                    onchange: move |event: Event<FormData>| {
+                        let mut updated = units.clone();
+                        apply(&mut updated, &event.value());
+                        on_change.call(updated);
+                    },
+                    for (code, label) in options {
+                        option { value: code, selected: code == selected, "{label}" }
+                    }
+                }
+            }
+        }
+    };
+
+    rsx! {
+        div { class: "relative",
+            button {
+                class: "text-white hover:bg-blue-800 px-2 py-1 rounded text-sm",
+                title: t!("settings.units", locale = lang).into_owned(),
+                onclick: move |_| is_open.set(!is_open()),
+                "📏"
+            }
+            if is_open() {
+                div { class: "absolute right-0 mt-2 w-56 bg-white text-slate-800 rounded-lg shadow-lg p-3 space-y-2 z-50",
+                    {row(
+                        "settings.length",
+                        [LengthUnit::Cm, LengthUnit::Inches].map(|u| (u.as_str(), u.label(language))).into(),
+                        units.length.as_str(),
+                        |units, code| units.length = LengthUnit::parse(code).unwrap_or(units.length),
+                    )}
+                    {row(
+                        "settings.weight",
+                        [WeightUnit::Kg, WeightUnit::Lb].map(|u| (u.as_str(), u.label(language))).into(),
+                        units.weight.as_str(),
+                        |units, code| units.weight = WeightUnit::parse(code).unwrap_or(units.weight),
+                    )}
+                    {row(
+                        "settings.temperature",
+                        [TemperatureUnit::C, TemperatureUnit::F].map(|u| (u.as_str(), format!("°{}", u.as_str()))).into(),
+                        units.temperature.as_str(),
+                        |units, code| units.temperature = TemperatureUnit::parse(code).unwrap_or(units.temperature),
+                    )}
+                    {row(
+                        "settings.pressure",
+                        [PressureUnit::Hpa, PressureUnit::MmHg, PressureUnit::InHg].map(|u| (u.as_str(), u.label(language))).into(),
+                        units.pressure.as_str(),
+                        |units, code| units.pressure = PressureUnit::parse(code).unwrap_or(units.pressure),
+                    )}
+                    {row(
+                        "settings.wind",
+                        [WindSpeedUnit::Ms, WindSpeedUnit::Kmh, WindSpeedUnit::Knots, WindSpeedUnit::Beaufort]
+                            .map(|u| (u.as_str(), u.label(language)))
+                            .into(),
+                        units.wind.as_str(),
+                        |units, code| units.wind = WindSpeedUnit::parse(code).unwrap_or(units.wind),
+                    )}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! UI translations and display units.
+//!
+//! One catalog per supported language lives in `locales/<code>.yml`. Text is
+//! looked up with `t!(key, locale = ...)` in the language from the user's
+//! preferences, so changing it re-renders every component that asked. The
+//! same goes for the units measurements are shown and entered in.
+
+use dioxus::prelude::*;
+use fishing_shared::types::{Language, UnitPreferences};
+
+use crate::state::app_state::UserPreferences;
+
+/// Language the user picked; the calling component re-renders when it changes.
+pub fn use_language() -> Language {
+    use_context::<Signal<UserPreferences>>().read().language
+}
+
+/// Units the user picked; the calling component re-renders when they change.
+pub fn use_units() -> UnitPreferences {
+    use_context::<Signal<UserPreferences>>().read().units.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use fishing_shared::constants::languages::SUPPORTED_LANGUAGES;
+    use rust_i18n::t;
+
+    /// Dotted keys of a catalog, sorted.
+    fn keys(code: &str) -> Vec<String> {
+        fn walk(prefix: &str, value: &serde_yaml::Value, out: &mut Vec<String>) {
+            match value {
+                serde_yaml::Value::Mapping(map) => {
+                    for (key, value) in map {
+                        let key = key.as_str().unwrap();
+                        let path = if prefix.is_empty() { key.to_string() } else { format!("{prefix}.{key}") };
+                        walk(&path, value, out);
+                    }
+                }
+                _ => out.push(prefix.to_string()),
+            }
+        }
+
+        let path = format!("{}/locales/{code}.yml", env!("CARGO_MANIFEST_DIR"));
+        let catalog: serde_yaml::Value = serde_yaml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let mut out = Vec::new();
+        walk("", &catalog, &mut out);
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn test_every_language_has_every_key() {
+        let english = keys("en");
+        for (code, _) in SUPPORTED_LANGUAGES {
+            assert_eq!(keys(code), english, "{code}.yml and en.yml differ");
+        }
+    }
+
+    #[test]
+    fn test_lookup_interpolates() {
+        assert_eq!(t!("sync.pending", locale = "uk", count = 3), "⏳ В черзі: 3");
+        assert_eq!(t!("sync.pending", locale = "de", count = 3), "⏳ In Warteschlange: 3");
+    }
+}
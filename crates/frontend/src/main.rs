@@ -1,9 +1,13 @@
 mod app;
 mod components;
+mod i18n;
 mod services;
 mod state;
 mod utils;
 
+// Catalogs in `locales/`; keys missing from a language fall back to English
+rust_i18n::i18n!("locales", fallback = "en");
+
 fn main() {
     dioxus::launch(app::App);
 }
@@ -0,0 +1,301 @@
+//! Forecasts for the map, labelled with where they came from.
+//!
+//! The server's model forecast is preferred. While the server cannot be
+//! reached the app falls back to a rough estimate from Open-Meteo weather,
+//! then to the last model forecast saved near the spot; `source` says which
+//! one the user is looking at. Made-up data is only available in builds with
+//! the `mock-forecast` feature.
+
+use chrono::{DateTime, Duration, Utc};
+use fishing_shared::types::{
+    BaitRecommendation, ForecastFactors, ForecastResult, ForecastSource, Language, WeatherCurrent,
+};
+use fishing_shared::utils::geo::distance_km;
+use gloo_storage::{LocalStorage, Storage};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+
+use crate::services::api_client::{ApiClient, ApiError};
+
+const CACHE_KEY: &str = "forecast_cache";
+
+/// Forecasts kept for offline use, newest first.
+const MAX_CACHED_FORECASTS: usize = 20;
+
+/// How far from the requested spot a cached forecast may have been made.
+const CACHE_RADIUS_KM: f64 = 5.0;
+
+/// Oldest cached forecast still shown.
+const MAX_CACHE_AGE_HOURS: i64 = 24;
+
+/// Model forecast saved for a spot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedForecast {
+    lat: f64,
+    lon: f64,
+    forecast: ForecastResult,
+}
+
+/// Forecast for coordinates, from the best source available.
+///
+/// Fails with the server's error when there is nothing else to show.
+pub async fn get_forecast(
+    api: &ApiClient,
+    lat: f64,
+    lon: f64,
+    fish: Option<&str>,
+) -> Result<ForecastResult, ApiError> {
+    let err = match api.get_forecast(lat, lon, fish).await {
+        Ok(forecast) => {
+            let mut cache = load_cache();
+            remember(&mut cache, lat, lon, &forecast);
+            if let Err(err) = LocalStorage::set(CACHE_KEY, &cache) {
+                log::warn!("Failed to cache forecast: {}", err);
+            }
+            return Ok(forecast);
+        }
+        Err(err) => err,
+    };
+    log::warn!("Backend forecast failed: {}", err);
+
+    match get_real_weather(lat, lon, api.language().unwrap_or_default()).await {
+        Ok(forecast) => return Ok(forecast),
+        Err(err) => log::warn!("Weather estimate failed: {}", err),
+    }
+
+    if let Some(forecast) = find_cached(&load_cache(), lat, lon, Utc::now()) {
+        return Ok(forecast);
+    }
+
+    #[cfg(feature = "mock-forecast")]
+    {
+        let _ = err;
+        Ok(mock_forecast(lat, lon))
+    }
+    #[cfg(not(feature = "mock-forecast"))]
+    Err(err)
+}
+
+fn load_cache() -> Vec<CachedForecast> {
+    LocalStorage::get(CACHE_KEY).unwrap_or_default()
+}
+
+/// Put a fresh forecast first, replacing one for the same spot.
+fn remember(cache: &mut Vec<CachedForecast>, lat: f64, lon: f64, forecast: &ForecastResult) {
+    cache.retain(|entry| distance_km(entry.lat, entry.lon, lat, lon) > 0.1);
+    cache.insert(
+        0,
+        CachedForecast {
+            lat,
+            lon,
+            forecast: forecast.clone(),
+        },
+    );
+    cache.truncate(MAX_CACHED_FORECASTS);
+}
+
+/// Closest recent cached forecast, labelled as cached.
+fn find_cached(cache: &[CachedForecast], lat: f64, lon: f64, now: DateTime<Utc>) -> Option<ForecastResult> {
+    cache
+        .iter()
+        .filter(|entry| now - entry.forecast.generated_at <= Duration::hours(MAX_CACHE_AGE_HOURS))
+        .map(|entry| (distance_km(entry.lat, entry.lon, lat, lon), entry))
+        .filter(|(distance, _)| *distance <= CACHE_RADIUS_KM)
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, entry)| ForecastResult {
+            source: ForecastSource::Cached,
+            ..entry.forecast.clone()
+        })
+}
+
+fn bait(name: &str, score: f64) -> BaitRecommendation {
+    BaitRecommendation {
+        name: name.to_string(),
+        score,
+    }
+}
+
+/// Made-up forecast for working on the UI without a server.
+#[cfg(feature = "mock-forecast")]
+fn mock_forecast(lat: f64, lon: f64) -> ForecastResult {
+    ForecastResult {
+        probability: 0.75,
+        confidence: 0.82,
+        factors: ForecastFactors {
+            pressure_score: 0.65,
+            temperature_score: 0.72,
+            time_of_day_score: 0.88,
+            wind_score: 0.58,
+            moon_score: 0.71,
+            other_score: 0.0,
+        },
+        explanation: format!("Mock forecast for location {:.4}°, {:.4}°. Testing offline mode - mock data returned.", lat, lon),
+        recommended_baits: vec![
+            bait("Earthworm", 0.85),
+            bait("Maggot", 0.78),
+            bait("Bread", 0.65),
+        ],
+        best_time: "14:30 - 16:45".to_string(),
+        weather: WeatherCurrent {
+            temperature_c: 1.5,
+            pressure_hpa: 1020.0,
+            wind_speed_ms: 12.3,
+            wind_gust_ms: None,
+            wind_direction_deg: Some(240.0),
+            precipitation_mm: None,
+            time: Utc::now(),
+        },
+        moon_phase: 0.65,
+        source: ForecastSource::Offline,
+        generated_at: Utc::now(),
+    }
+}
+
+/// Get real weather data from Open-Meteo API (free, no API key needed)
+/// and score it with a few fixed thresholds.
+async fn get_real_weather(lat: f64, lon: f64, language: Language) -> Result<ForecastResult, ApiError> {
+    let lang = language.code();
+    let url = format!(
+        "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current=temperature_2m,wind_speed_10m,wind_gusts_10m,wind_direction_10m,pressure_msl,precipitation&wind_speed_unit=ms&timezone=auto",
+        lat, lon
+    );
+
+    let response = gloo_net::http::Request::get(&url)
+        .send()
+        .await
+        .map_err(|e| ApiError::Network(format!("Failed to fetch weather: {}", e)))?;
+
+    let text = response
+        .text()
+        .await
+        .map_err(|e| ApiError::Network(format!("Failed to read response: {}", e)))?;
+
+    let weather_data: serde_json::Value = serde_json::from_str(&text)
+        .map_err(|e| ApiError::Decode(format!("Failed to parse weather JSON: {}", e)))?;
+
+    // A forecast from defaults would pass for real data; refuse instead
+    let current = &weather_data["current"];
+    let (Some(temp), Some(wind_speed), Some(pressure)) = (
+        current["temperature_2m"].as_f64(),
+        current["wind_speed_10m"].as_f64(),
+        current["pressure_msl"].as_f64(),
+    ) else {
+        return Err(ApiError::Decode("Weather response lacks current conditions".to_string()));
+    };
+
+    // Calculate bite probability based on weather
+    let pressure_factor = if pressure > 1015.0 && pressure < 1025.0 { 0.9 } else { 0.5 };
+    let temp_factor = if temp > 0.0 && temp < 5.0 { 0.8 } else { 0.4 };
+    let wind_factor = if wind_speed > 1.5 && wind_speed < 4.0 { 0.8 } else { 0.5 };
+    let probability = (pressure_factor + temp_factor + wind_factor) / 3.0 * 0.85;
+
+    Ok(ForecastResult {
+        probability,
+        confidence: 0.3,
+        factors: ForecastFactors {
+            pressure_score: pressure_factor,
+            temperature_score: temp_factor,
+            time_of_day_score: 0.5,
+            wind_score: wind_factor,
+            moon_score: 0.5,
+            other_score: 0.0,
+        },
+        explanation: t!(
+            "forecast.estimate",
+            locale = lang,
+            lat = format!("{lat:.4}"),
+            lon = format!("{lon:.4}"),
+            temperature = temp as i32,
+            wind = wind_speed as i32,
+            pressure = pressure as i32
+        )
+        .into_owned(),
+        recommended_baits: vec![
+            bait("bloodworm", 0.88),
+            bait("worm", 0.82),
+            bait("bread", 0.65),
+        ],
+        best_time: "—".to_string(),
+        weather: WeatherCurrent {
+            temperature_c: temp,
+            pressure_hpa: pressure,
+            wind_speed_ms: wind_speed,
+            wind_gust_ms: current["wind_gusts_10m"].as_f64(),
+            wind_direction_deg: current["wind_direction_10m"].as_f64(),
+            precipitation_mm: current["precipitation"].as_f64(),
+            time: Utc::now(),
+        },
+        moon_phase: fishing_shared::utils::moon::moon_phase(Utc::now()),
+        source: ForecastSource::ClientHeuristic,
+        generated_at: Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forecast(generated_at: DateTime<Utc>) -> ForecastResult {
+        ForecastResult {
+            probability: 0.6,
+            confidence: 0.6,
+            factors: ForecastFactors {
+                pressure_score: 0.5,
+                temperature_score: 0.5,
+                time_of_day_score: 0.5,
+                wind_score: 0.5,
+                moon_score: 0.5,
+                other_score: 0.5,
+            },
+            explanation: String::new(),
+            recommended_baits: Vec::new(),
+            best_time: String::new(),
+            weather: WeatherCurrent {
+                temperature_c: 10.0,
+                pressure_hpa: 1013.0,
+                wind_speed_ms: 2.0,
+                wind_gust_ms: None,
+                wind_direction_deg: None,
+                precipitation_mm: None,
+                time: generated_at,
+            },
+            moon_phase: 0.5,
+            source: ForecastSource::Model,
+            generated_at,
+        }
+    }
+
+    #[test]
+    fn test_cached_forecast_is_labelled_and_nearby() {
+        let now = Utc::now();
+        let mut cache = Vec::new();
+        remember(&mut cache, 50.45, 30.52, &forecast(now - Duration::hours(2)));
+        remember(&mut cache, 49.84, 24.03, &forecast(now));
+
+        let cached = find_cached(&cache, 50.46, 30.53, now).unwrap();
+        assert_eq!(cached.source, ForecastSource::Cached);
+        assert_eq!(cached.generated_at, now - Duration::hours(2));
+
+        assert!(find_cached(&cache, 46.48, 30.72, now).is_none());
+    }
+
+    #[test]
+    fn test_stale_forecasts_are_not_served() {
+        let now = Utc::now();
+        let mut cache = Vec::new();
+        remember(&mut cache, 50.45, 30.52, &forecast(now - Duration::hours(30)));
+
+        assert!(find_cached(&cache, 50.45, 30.52, now).is_none());
+    }
+
+    #[test]
+    fn test_same_spot_is_replaced() {
+        let now = Utc::now();
+        let mut cache = Vec::new();
+        remember(&mut cache, 50.45, 30.52, &forecast(now - Duration::hours(1)));
+        remember(&mut cache, 50.45, 30.52, &forecast(now));
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache[0].forecast.generated_at, now);
+    }
+}
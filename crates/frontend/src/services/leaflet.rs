@@ -0,0 +1,151 @@
+//! Bindings to the parts of Leaflet the map uses.
+//!
+//! Leaflet itself is loaded from the CDN in `index.html` as the global `L`.
+//! Options are plain objects built with [`options`].
+
+use js_sys::{Array, Function, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    /// `L.Map`
+    #[derive(Clone)]
+    pub type Map;
+
+    #[wasm_bindgen(js_namespace = L, js_name = map)]
+    pub fn map(container_id: &str, options: &Object) -> Map;
+
+    #[wasm_bindgen(method, js_name = setView)]
+    pub fn set_view(this: &Map, center: &Array, zoom: f64) -> Map;
+
+    #[wasm_bindgen(method, js_name = panTo)]
+    pub fn pan_to(this: &Map, center: &Array) -> Map;
+
+    #[wasm_bindgen(method, js_name = getZoom)]
+    pub fn get_zoom(this: &Map) -> f64;
+
+    #[wasm_bindgen(method, js_name = getCenter)]
+    pub fn get_center(this: &Map) -> LatLng;
+
+    #[wasm_bindgen(method, js_name = getBounds)]
+    pub fn get_bounds(this: &Map) -> LatLngBounds;
+
+    #[wasm_bindgen(method, js_name = addLayer)]
+    pub fn add_layer(this: &Map, layer: &Layer) -> Map;
+
+    #[wasm_bindgen(method, js_name = removeLayer)]
+    pub fn remove_layer(this: &Map, layer: &Layer) -> Map;
+
+    #[wasm_bindgen(method, js_name = invalidateSize)]
+    pub fn invalidate_size(this: &Map) -> Map;
+
+    /// Ask the browser for the position; answered with `locationfound` or `locationerror`.
+    #[wasm_bindgen(method)]
+    pub fn locate(this: &Map, options: &Object) -> Map;
+
+    #[wasm_bindgen(method)]
+    pub fn on(this: &Map, event: &str, handler: &Function) -> Map;
+
+    #[wasm_bindgen(method)]
+    pub fn remove(this: &Map);
+
+    /// `L.Layer`: tile layers, markers, groups
+    #[derive(Clone)]
+    pub type Layer;
+
+    #[wasm_bindgen(method, js_name = bindPopup)]
+    pub fn bind_popup(this: &Layer, html: &str) -> Layer;
+
+    #[wasm_bindgen(method, js_name = openPopup)]
+    pub fn open_popup(this: &Layer) -> Layer;
+
+    #[wasm_bindgen(method)]
+    pub fn on(this: &Layer, event: &str, handler: &Function) -> Layer;
+
+    #[wasm_bindgen(extends = Layer)]
+    #[derive(Clone)]
+    pub type TileLayer;
+
+    #[wasm_bindgen(js_namespace = L, js_name = tileLayer)]
+    pub fn tile_layer(url_template: &str, options: &Object) -> TileLayer;
+
+    #[wasm_bindgen(method, js_name = setUrl)]
+    pub fn set_url(this: &TileLayer, url_template: &str) -> TileLayer;
+
+    #[wasm_bindgen(extends = Layer)]
+    #[derive(Clone)]
+    pub type LayerGroup;
+
+    #[wasm_bindgen(js_namespace = L, js_name = layerGroup)]
+    pub fn layer_group() -> LayerGroup;
+
+    #[wasm_bindgen(method, js_name = addLayer)]
+    pub fn add_layer(this: &LayerGroup, layer: &Layer) -> LayerGroup;
+
+    #[wasm_bindgen(method, js_name = clearLayers)]
+    pub fn clear_layers(this: &LayerGroup) -> LayerGroup;
+
+    #[wasm_bindgen(extends = Layer)]
+    pub type Marker;
+
+    #[wasm_bindgen(js_namespace = L, js_name = marker)]
+    pub fn marker(position: &Array, options: &Object) -> Marker;
+
+    #[wasm_bindgen(js_namespace = L, js_name = circleMarker)]
+    pub fn circle_marker(position: &Array, options: &Object) -> Marker;
+
+    #[wasm_bindgen(method, js_name = setLatLng)]
+    pub fn set_lat_lng(this: &Marker, position: &Array) -> Marker;
+
+    /// `L.DivIcon`
+    pub type Icon;
+
+    #[wasm_bindgen(js_namespace = L, js_name = divIcon)]
+    pub fn div_icon(options: &Object) -> Icon;
+
+    /// `L.Control`
+    pub type Control;
+
+    /// Layer switcher: one radio button per base layer, a checkbox per overlay
+    #[wasm_bindgen(js_namespace = ["L", "control"], js_name = layers)]
+    pub fn layers_control(base_layers: &Object, overlays: &Object, options: &Object) -> Control;
+
+    #[wasm_bindgen(method, js_name = addTo)]
+    pub fn add_to(this: &Control, map: &Map) -> Control;
+
+    #[wasm_bindgen(method)]
+    pub fn remove(this: &Control) -> Control;
+
+    pub type LatLng;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn lat(this: &LatLng) -> f64;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn lng(this: &LatLng) -> f64;
+
+    pub type LatLngBounds;
+
+    #[wasm_bindgen(method, js_name = getNorthEast)]
+    pub fn get_north_east(this: &LatLngBounds) -> LatLng;
+
+    /// Event passed to map and layer handlers
+    pub type LeafletEvent;
+
+    #[wasm_bindgen(method, getter)]
+    pub fn latlng(this: &LeafletEvent) -> LatLng;
+}
+
+/// `[lat, lon]`, the position form every binding takes.
+pub fn position(lat: f64, lon: f64) -> Array {
+    Array::of2(&lat.into(), &lon.into())
+}
+
+/// Options object from key/value pairs.
+pub fn options(entries: &[(&str, JsValue)]) -> Object {
+    let object = Object::new();
+    for (key, value) in entries {
+        let _ = Reflect::set(&object, &(*key).into(), value);
+    }
+    object
+}
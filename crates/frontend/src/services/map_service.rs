@@ -1,150 +1,543 @@
-use wasm_bindgen::prelude::*;
-use web_sys::HtmlElement;
-use std::rc::Rc;
+//! The fishing map: Leaflet driven from Rust.
+//!
+//! Base layers (streets, topographic, satellite) and overlays (water bodies,
+//! catches) are switched with Leaflet's layer control. Catches are grouped
+//! into clusters on a pixel grid, recomputed whenever the view changes, so a
+//! busy lake shows one numbered marker instead of a pile.
+
 use std::cell::RefCell;
+use std::f64::consts::PI;
+
+use fishing_shared::utils::geo::distance_km;
+use fishing_shared::validation::MAX_RADIUS_KM;
+use js_sys::Object;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::services::leaflet::{self, options, position, LeafletEvent};
+
+/// Where the map opens before the user is located.
+pub const DEFAULT_CENTER: (f64, f64) = (49.0, 31.0);
+pub const DEFAULT_ZOOM: f64 = 6.0;
+
+/// Side of a clustering cell in screen pixels.
+const CLUSTER_CELL_PX: f64 = 60.0;
+
+/// From this zoom on every catch gets its own marker.
+const CLUSTER_MAX_ZOOM: f64 = 16.0;
+
+/// Water bodies and catches are only loaded for views this close.
+const MAX_VIEW_RADIUS_KM: f64 = MAX_RADIUS_KM / 2.0;
+
+/// What the user did on the map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MapEvent {
+    Click(f64, f64),
+    /// Long press on touch screens, right click with a mouse
+    LongPress(f64, f64),
+    /// The view settled after panning or zooming
+    Moved(ViewArea),
+    /// The browser reported the user's position
+    Located(f64, f64),
+}
+
+/// Circle around the visible part of the map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewArea {
+    pub lat: f64,
+    pub lon: f64,
+    pub radius_km: f64,
+}
 
-/// Initialize MapLibre GL map with click handlers
-/// Returns Ok on success, Err with message on failure
-pub async fn init_map(
-    container_id: &str,
-    on_click: Box<dyn Fn(f64, f64)>,
-) -> Result<(), String> {
-    // Get window and document
-    let window = web_sys::window().ok_or("No window object")?;
-    let document = window.document().ok_or("No document object")?;
-    
-    // Get container element
-    let container = document
-        .get_element_by_id(container_id)
-        .ok_or(format!("Element with id {} not found", container_id))?;
-    
-    // Initialize simple map using HTML canvas or div-based approach
-    // For now, we'll use a simple approach with data attributes
-    // In production, you'd load MapLibre GL via CDN or bundler
-    
-    init_simple_map(&container, on_click).await
-}
-
-/// Initialize a simple map with basic tile layer
-/// This is a simplified version without MapLibre GL library
-/// In production, you'd include MapLibre GL and use its API
-async fn init_simple_map(
-    container: &web_sys::Element,
-    on_click: Box<dyn Fn(f64, f64)>,
-) -> Result<(), String> {
-    // Cast to HtmlElement
-    let html_element = container
-        .dyn_ref::<HtmlElement>()
-        .ok_or("Container is not an HTML element")?;
-    
-    // Set up basic map styling
-    html_element.set_attribute("data-map-initialized", "true")
-        .map_err(|_| "Failed to set attribute".to_string())?;
-    
-    // Set innerHTML to show a basic map placeholder with grid
-    let map_html = r#"
-        <div style="width: 100%; height: 100%; position: relative; background: linear-gradient(45deg, #e8f4f8 25%, transparent 25%, transparent 75%, #e8f4f8 75%, #e8f4f8), 
-                    linear-gradient(45deg, #e8f4f8 25%, transparent 25%, transparent 75%, #e8f4f8 75%, #e8f4f8);
-                    background-size: 40px 40px; background-position: 0 0, 20px 20px; background-color: #f0f8ff;">
-            <div style="position: absolute; top: 10px; left: 10px; background: white; padding: 10px; border-radius: 5px; font-size: 12px; z-index: 10;">
-                <div style="margin-bottom: 5px;"><strong>Клік на карті</strong></div>
-                <div style="font-size: 11px; color: #666;">Натисніть для вибору локації</div>
-            </div>
-            <div style="position: absolute; bottom: 10px; left: 10px; background: white; padding: 8px; border-radius: 5px; font-size: 11px; z-index: 10;">
-                © OpenStreetMap
-            </div>
-        </div>
-    "#;
-    
-    container.set_inner_html(map_html);
-    
-    // Add click handler to container
-    add_map_click_handler(container, on_click).await?;
-    
-    Ok(())
-}
-
-/// Add click event listener to map container
-async fn add_map_click_handler(
-    container: &web_sys::Element,
-    on_click: Box<dyn Fn(f64, f64)>,
-) -> Result<(), String> {
-    use wasm_bindgen::prelude::*;
-    use web_sys::MouseEvent;
-    
-    let container_copy = container.clone();
-    let on_click_rc = Rc::new(RefCell::new(on_click));
-    let on_click_copy = on_click_rc.clone();
-    
-    // Create closure for click handler
-    let on_click_closure = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
-        // Get click position relative to container
-        if let Some(html_elem) = container_copy.dyn_ref::<HtmlElement>() {
-            let rect = html_elem.get_bounding_client_rect();
-            
-            let x = (event.client_x() as f64 - rect.left()) / rect.width();
-            let y = (event.client_y() as f64 - rect.top()) / rect.height();
-            
-            // Convert pixel position to lat/lon
-            // This is a simple mercator projection approximation
-            // Centered on Europe/Africa region
-            let lat = 20.0 + (1.0 - y) * 40.0;  // Range: -20 to 60
-            let lon = -20.0 + x * 80.0;          // Range: -20 to 60
-            
-            let on_click_fn = on_click_copy.borrow();
-            on_click_fn(lat, lon);
+impl ViewArea {
+    /// Whether markers are worth loading at this scale.
+    pub fn is_close_enough(&self) -> bool {
+        self.radius_km <= MAX_VIEW_RADIUS_KM
+    }
+
+    /// Area to load: twice the view, so short pans need no new request.
+    pub fn fetch_area(self) -> ViewArea {
+        ViewArea {
+            radius_km: (self.radius_km * 2.0).min(MAX_RADIUS_KM),
+            ..self
         }
-    }) as Box<dyn FnMut(MouseEvent)>);
-    
-    // Add event listener
-    container
-        .add_event_listener_with_callback("click", on_click_closure.as_ref().unchecked_ref())
-        .map_err(|_| "Failed to add event listener".to_string())?;
-    
-    // Leak the closure to keep it alive
-    on_click_closure.forget();
-    
-    Ok(())
-}
-
-/// Update marker position on map
-pub fn update_marker(lat: f64, lon: f64) -> Result<(), String> {
-    let window = web_sys::window().ok_or("No window")?;
-    let document = window.document().ok_or("No document")?;
-    
-    let container = document
-        .get_element_by_id("map-container")
-        .ok_or("Map container not found")?;
-    
-    // Get or create marker element
-    let marker = if let Some(existing) = document.get_element_by_id("map-marker") {
-        existing
-    } else {
-        let marker = document
-            .create_element("div")
-            .map_err(|_| "Failed to create marker")?;
-        marker.set_id("map-marker");
-        container
-            .append_child(&marker)
-            .map_err(|_| "Failed to append marker")?;
-        marker
+    }
+
+    /// Whether `other` lies entirely inside this area.
+    pub fn covers(&self, other: &ViewArea) -> bool {
+        distance_km(self.lat, self.lon, other.lat, other.lon) + other.radius_km <= self.radius_km
+    }
+}
+
+/// A point shown on an overlay, with its popup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapPoint {
+    pub lat: f64,
+    pub lon: f64,
+    pub popup_html: String,
+}
+
+/// Points close together on screen at some zoom.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    pub lat: f64,
+    pub lon: f64,
+    /// Indexes into the clustered points, in their order
+    pub members: Vec<usize>,
+}
+
+/// Group points that fall into the same grid cell at `zoom`.
+pub fn cluster(points: &[(f64, f64)], zoom: f64) -> Vec<Cluster> {
+    let mut clusters: Vec<(i64, i64, Cluster)> = Vec::new();
+    for (index, &(lat, lon)) in points.iter().enumerate() {
+        let (x, y) = project(lat, lon, zoom);
+        let cell = if zoom >= CLUSTER_MAX_ZOOM {
+            // One cell per point
+            (index as i64, -1)
+        } else {
+            ((x / CLUSTER_CELL_PX).floor() as i64, (y / CLUSTER_CELL_PX).floor() as i64)
+        };
+        match clusters.iter_mut().find(|(cx, cy, _)| (*cx, *cy) == cell) {
+            Some((_, _, existing)) => existing.members.push(index),
+            None => clusters.push((cell.0, cell.1, Cluster { lat, lon, members: vec![index] })),
+        }
+    }
+
+    clusters
+        .into_iter()
+        .map(|(_, _, mut cluster)| {
+            let count = cluster.members.len() as f64;
+            cluster.lat = cluster.members.iter().map(|&i| points[i].0).sum::<f64>() / count;
+            cluster.lon = cluster.members.iter().map(|&i| points[i].1).sum::<f64>() / count;
+            cluster
+        })
+        .collect()
+}
+
+/// Web Mercator pixel coordinates at `zoom`, as Leaflet places tiles.
+fn project(lat: f64, lon: f64, zoom: f64) -> (f64, f64) {
+    let size = 256.0 * 2f64.powf(zoom);
+    let lat = lat.clamp(-85.05, 85.05).to_radians();
+    let x = (lon + 180.0) / 360.0 * size;
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * size;
+    (x, y)
+}
+
+/// Text safe to put inside popup HTML.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Names in the layer switcher, in the user's language.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerLabels {
+    pub street: String,
+    pub topo: String,
+    pub satellite: String,
+    pub water_bodies: String,
+    pub catches: String,
+}
+
+const ANGLER_ICON: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 90 110" width="45" height="55" style="display: block;">
+  <circle cx="30" cy="22" r="11" fill="#F5B8A0"/>
+  <path d="M 20 32 Q 20 30, 22 28 L 38 28 Q 40 30, 40 32 L 40 55 Q 40 58, 37 58 L 23 58 Q 20 58, 20 55 Z" fill="#5BA3E0"/>
+  <path d="M 22 32 Q 18 35, 17 42 Q 16 45, 18 47 L 25 50 Q 26 49, 25 47 L 22 38 Z" fill="#F5B8A0"/>
+  <path d="M 38 32 Q 42 35, 45 42 L 48 45 Q 49 46, 48 47 L 41 50 Q 40 49, 41 47 L 38 38 Z" fill="#F5B8A0"/>
+  <rect x="20" y="56" width="20" height="38" rx="3" fill="#5C6B87"/>
+  <path d="M 22 94 L 22 102 Q 22 104, 24 104 L 28 104 Q 30 104, 30 102 L 30 94 Z" fill="#5C6B87"/>
+  <path d="M 30 94 L 30 102 Q 30 104, 32 104 L 36 104 Q 38 104, 38 102 L 38 94 Z" fill="#5C6B87"/>
+  <line x1="48" y1="45" x2="80" y2="8" stroke="#7FB5D9" stroke-width="2.5" stroke-linecap="round"/>
+  <line x1="80" y1="8" x2="85" y2="35" stroke="#999" stroke-width="0.8" opacity="0.7"/>
+</svg>"##;
+
+const USER_ICON: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 90 110" width="45" height="55" style="display: block;">
+  <circle cx="30" cy="22" r="11" fill="#F5B8A0"/>
+  <path d="M 20 32 Q 20 30, 22 28 L 38 28 Q 40 30, 40 32 L 40 55 Q 40 58, 37 58 L 23 58 Q 20 58, 20 55 Z" fill="#E94444"/>
+  <path d="M 22 32 Q 18 35, 17 42 Q 16 45, 18 47 L 25 50 Q 26 49, 25 47 L 22 38 Z" fill="#F5B8A0"/>
+  <path d="M 38 32 Q 42 35, 43 42 Q 44 45, 42 47 L 35 50 Q 34 49, 35 47 L 38 38 Z" fill="#F5B8A0"/>
+  <rect x="20" y="56" width="20" height="38" rx="3" fill="#5C6B87"/>
+  <path d="M 22 94 L 22 102 Q 22 104, 24 104 L 28 104 Q 30 104, 30 102 L 30 94 Z" fill="#5C6B87"/>
+  <path d="M 30 94 L 30 102 Q 30 104, 32 104 L 36 104 Q 38 104, 38 102 L 38 94 Z" fill="#5C6B87"/>
+</svg>"##;
+
+fn svg_icon(html: &str) -> leaflet::Icon {
+    leaflet::div_icon(&options(&[
+        ("className", "map-svg-marker".into()),
+        ("html", html.into()),
+        ("iconSize", position(45.0, 55.0).into()),
+        ("iconAnchor", position(30.0, 104.0).into()),
+        ("popupAnchor", position(0.0, -104.0).into()),
+    ]))
+}
+
+/// Arrow pointing where a wind from `from_deg` blows.
+fn wind_icon(from_deg: f64) -> leaflet::Icon {
+    let html = format!(
+        r##"<svg viewBox="0 0 60 60" width="60" height="60" style="transform: rotate({}deg);">
+  <path class="wind-arrow" d="M 30 6 L 40 24 L 33 24 L 33 54 L 27 54 L 27 24 L 20 24 Z" fill="white" stroke="#2563eb" stroke-width="2"/>
+</svg>"##,
+        (from_deg + 180.0) % 360.0
+    );
+    leaflet::div_icon(&options(&[
+        ("className", "wind-marker".into()),
+        ("html", html.into()),
+        ("iconSize", position(60.0, 60.0).into()),
+        ("iconAnchor", position(30.0, 30.0).into()),
+    ]))
+}
+
+fn cluster_icon(count: usize) -> leaflet::Icon {
+    let size = match count {
+        0..=9 => 34.0,
+        10..=99 => 42.0,
+        _ => 50.0,
     };
-    
-    // Position marker based on lat/lon
-    let x_percent = ((lon + 20.0) / 80.0) * 100.0;
-    let y_percent = ((60.0 - lat) / 40.0) * 100.0;
-    
-    if let Some(html_elem) = marker.dyn_ref::<HtmlElement>() {
-        html_elem.set_attribute("style", 
-            &format!(
-                "position: absolute; left: {}%; top: {}%; width: 24px; height: 24px; \
-                 transform: translate(-50%, -50%); z-index: 20; \
-                 background: #ff4444; border: 3px solid white; border-radius: 50%; \
-                 box-shadow: 0 2px 8px rgba(0,0,0,0.3); cursor: pointer;",
-                x_percent, y_percent
-            ))
-            .map_err(|_| "Failed to set marker style".to_string())?;
-    }
-    
-    Ok(())
+    leaflet::div_icon(&options(&[
+        ("className", "catch-cluster".into()),
+        ("html", format!("<span>{count}</span>").into()),
+        ("iconSize", position(size, size).into()),
+    ]))
+}
+
+/// A Leaflet map with the app's layers, removed from the page when dropped.
+pub struct FishingMap {
+    map: leaflet::Map,
+    base_layers: [leaflet::TileLayer; 3],
+    water_bodies: leaflet::LayerGroup,
+    catches: leaflet::LayerGroup,
+    catch_points: RefCell<Vec<MapPoint>>,
+    forecast_overlay: RefCell<Option<leaflet::TileLayer>>,
+    selected: RefCell<Option<leaflet::Marker>>,
+    pin: RefCell<Option<leaflet::Marker>>,
+    user: RefCell<Option<leaflet::Marker>>,
+    wind: RefCell<Option<leaflet::Marker>>,
+    layer_control: RefCell<Option<leaflet::Control>>,
+    /// Zooms into a clicked cluster; shared by all cluster markers
+    zoom_into_cluster: Closure<dyn FnMut(LeafletEvent)>,
+    _handlers: Vec<Closure<dyn FnMut(LeafletEvent)>>,
+}
+
+impl FishingMap {
+    /// Create the map in the element `container_id` and report what the
+    /// user does through `on_event`.
+    pub fn new(container_id: &str, on_event: impl Fn(MapEvent) + 'static) -> Self {
+        let map = leaflet::map(
+            container_id,
+            &options(&[
+                ("attributionControl", true.into()),
+                // Long press fires `contextmenu` on iOS too
+                ("tapHold", true.into()),
+            ]),
+        );
+        map.set_view(&position(DEFAULT_CENTER.0, DEFAULT_CENTER.1), DEFAULT_ZOOM);
+
+        let base_layers = [
+            leaflet::tile_layer(
+                "https://{s}.tile.openstreetmap.org/{z}/{x}/{y}.png",
+                &options(&[
+                    ("attribution", "© <a href=\"https://www.openstreetmap.org/copyright\">OpenStreetMap</a> contributors".into()),
+                    ("maxZoom", 19.into()),
+                ]),
+            ),
+            leaflet::tile_layer(
+                "https://{s}.tile.opentopomap.org/{z}/{x}/{y}.png",
+                &options(&[
+                    ("attribution", "© OpenStreetMap contributors, SRTM | © <a href=\"https://opentopomap.org\">OpenTopoMap</a>".into()),
+                    ("maxZoom", 17.into()),
+                ]),
+            ),
+            leaflet::tile_layer(
+                "https://server.arcgisonline.com/ArcGIS/rest/services/World_Imagery/MapServer/tile/{z}/{y}/{x}",
+                &options(&[
+                    ("attribution", "Tiles © Esri".into()),
+                    ("maxZoom", 19.into()),
+                ]),
+            ),
+        ];
+        map.add_layer(&base_layers[0]);
+
+        let water_bodies = leaflet::layer_group();
+        let catches = leaflet::layer_group();
+        map.add_layer(&water_bodies);
+        map.add_layer(&catches);
+
+        let on_event = std::rc::Rc::new(on_event);
+        let mut handlers = Vec::new();
+        for (name, make) in [
+            ("click", MapEvent::Click as fn(f64, f64) -> MapEvent),
+            ("contextmenu", MapEvent::LongPress),
+            ("locationfound", MapEvent::Located),
+        ] {
+            let on_event = on_event.clone();
+            let handler = Closure::<dyn FnMut(LeafletEvent)>::new(move |event: LeafletEvent| {
+                let at = event.latlng();
+                on_event(make(at.lat(), at.lng()));
+            });
+            map.on(name, handler.as_ref().unchecked_ref());
+            handlers.push(handler);
+        }
+        {
+            let view = map.clone();
+            let on_event = on_event.clone();
+            let handler = Closure::<dyn FnMut(LeafletEvent)>::new(move |_| on_event(MapEvent::Moved(view_area(&view))));
+            map.on("moveend", handler.as_ref().unchecked_ref());
+            handlers.push(handler);
+        }
+
+        let zoom_into_cluster = {
+            let map = map.clone();
+            Closure::<dyn FnMut(LeafletEvent)>::new(move |event: LeafletEvent| {
+                let at = event.latlng();
+                map.set_view(&position(at.lat(), at.lng()), (map.get_zoom() + 2.0).min(CLUSTER_MAX_ZOOM));
+            })
+        };
+
+        // The container may have been laid out after Leaflet measured it
+        map.invalidate_size();
+
+        Self {
+            map,
+            base_layers,
+            water_bodies,
+            catches,
+            catch_points: RefCell::default(),
+            forecast_overlay: RefCell::default(),
+            selected: RefCell::default(),
+            pin: RefCell::default(),
+            user: RefCell::default(),
+            wind: RefCell::default(),
+            layer_control: RefCell::default(),
+            zoom_into_cluster,
+            _handlers: handlers,
+        }
+    }
+
+    /// Ask the browser where the user is and move there; answered with
+    /// [`MapEvent::Located`].
+    pub fn locate(&self) {
+        self.map.locate(&options(&[
+            ("setView", true.into()),
+            ("maxZoom", 12.into()),
+            ("enableHighAccuracy", true.into()),
+        ]));
+    }
+
+    /// (Re)build the layer switcher with names in the current language.
+    pub fn set_layer_labels(&self, labels: &LayerLabels) {
+        if let Some(control) = self.layer_control.take() {
+            control.remove();
+        }
+        let base = options(&[
+            (labels.street.as_str(), self.base_layers[0].clone().into()),
+            (labels.topo.as_str(), self.base_layers[1].clone().into()),
+            (labels.satellite.as_str(), self.base_layers[2].clone().into()),
+        ]);
+        let overlays = options(&[
+            (labels.water_bodies.as_str(), self.water_bodies.clone().into()),
+            (labels.catches.as_str(), self.catches.clone().into()),
+        ]);
+        let control = leaflet::layers_control(&base, &overlays, &Object::new());
+        control.add_to(&self.map);
+        self.layer_control.replace(Some(control));
+    }
+
+    /// Current view, for deciding what to load.
+    pub fn view(&self) -> ViewArea {
+        view_area(&self.map)
+    }
+
+    /// Replace the water body markers.
+    pub fn show_water_bodies(&self, points: &[MapPoint]) {
+        self.water_bodies.clear_layers();
+        for point in points {
+            let marker = leaflet::circle_marker(
+                &position(point.lat, point.lon),
+                &options(&[
+                    ("radius", 8.into()),
+                    ("color", "#1d4ed8".into()),
+                    ("fillColor", "#60a5fa".into()),
+                    ("fillOpacity", 0.7.into()),
+                    ("weight", 2.into()),
+                ]),
+            );
+            marker.bind_popup(&point.popup_html);
+            self.water_bodies.add_layer(&marker);
+        }
+    }
+
+    /// Replace the catch markers.
+    pub fn show_catches(&self, points: Vec<MapPoint>) {
+        self.catch_points.replace(points);
+        self.recluster();
+    }
+
+    /// Regroup catch markers for the current zoom.
+    pub fn recluster(&self) {
+        self.catches.clear_layers();
+        let points = self.catch_points.borrow();
+        let positions: Vec<(f64, f64)> = points.iter().map(|point| (point.lat, point.lon)).collect();
+        for group in cluster(&positions, self.map.get_zoom()) {
+            if let [only] = group.members[..] {
+                let point = &points[only];
+                let marker = leaflet::circle_marker(
+                    &position(point.lat, point.lon),
+                    &options(&[
+                        ("radius", 7.into()),
+                        ("color", "#c2410c".into()),
+                        ("fillColor", "#fb923c".into()),
+                        ("fillOpacity", 0.9.into()),
+                        ("weight", 2.into()),
+                    ]),
+                );
+                marker.bind_popup(&point.popup_html);
+                self.catches.add_layer(&marker);
+            } else {
+                let marker = leaflet::marker(
+                    &position(group.lat, group.lon),
+                    &options(&[("icon", cluster_icon(group.members.len()).into())]),
+                );
+                marker.on("click", self.zoom_into_cluster.as_ref().unchecked_ref());
+                self.catches.add_layer(&marker);
+            }
+        }
+    }
+
+    /// Show or hide the bite probability tiles.
+    pub fn set_forecast_overlay(&self, url_template: Option<&str>) {
+        let mut overlay = self.forecast_overlay.borrow_mut();
+        match (url_template, overlay.as_ref()) {
+            (Some(url), Some(layer)) => {
+                layer.set_url(url);
+            }
+            (Some(url), None) => {
+                let layer = leaflet::tile_layer(
+                    url,
+                    &options(&[
+                        ("opacity", 0.6.into()),
+                        ("minZoom", 8.into()),
+                        ("maxZoom", 18.into()),
+                        ("attribution", "Bite forecast © Open-Meteo".into()),
+                    ]),
+                );
+                self.map.add_layer(&layer);
+                *overlay = Some(layer);
+            }
+            (None, Some(layer)) => {
+                self.map.remove_layer(layer);
+                *overlay = None;
+            }
+            (None, None) => {}
+        }
+    }
+
+    /// Mark the place the forecast is for.
+    pub fn set_selected(&self, at: Option<(f64, f64)>, popup_html: &str) {
+        self.replace_marker(&self.selected, at, || svg_icon(ANGLER_ICON).into(), popup_html);
+        if let Some((lat, lon)) = at {
+            self.map.pan_to(&position(lat, lon));
+        }
+    }
+
+    /// Mark a dropped pin.
+    pub fn set_pin(&self, at: Option<(f64, f64)>, popup_html: &str) {
+        self.replace_marker(&self.pin, at, JsValue::undefined, popup_html);
+    }
+
+    /// Mark the user's position.
+    pub fn set_user(&self, at: Option<(f64, f64)>, popup_html: &str) {
+        self.replace_marker(&self.user, at, || svg_icon(USER_ICON).into(), popup_html);
+    }
+
+    /// Show which way the wind blows at `at`.
+    pub fn set_wind(&self, at: Option<(f64, f64)>, from_deg: Option<f64>, popup_html: &str) {
+        let at = at.filter(|_| from_deg.is_some());
+        self.replace_marker(&self.wind, at, || wind_icon(from_deg.unwrap_or_default()).into(), popup_html);
+    }
+
+    fn replace_marker(
+        &self,
+        slot: &RefCell<Option<leaflet::Marker>>,
+        at: Option<(f64, f64)>,
+        icon: impl FnOnce() -> JsValue,
+        popup_html: &str,
+    ) {
+        if let Some(old) = slot.take() {
+            self.map.remove_layer(&old);
+        }
+        let Some((lat, lon)) = at else {
+            return;
+        };
+        let icon = icon();
+        let marker_options = if icon.is_undefined() {
+            Object::new()
+        } else {
+            options(&[("icon", icon)])
+        };
+        let marker = leaflet::marker(&position(lat, lon), &marker_options);
+        self.map.add_layer(&marker);
+        marker.bind_popup(popup_html);
+        slot.replace(Some(marker));
+    }
+}
+
+impl Drop for FishingMap {
+    fn drop(&mut self) {
+        self.map.remove();
+    }
+}
+
+fn view_area(map: &leaflet::Map) -> ViewArea {
+    let center = map.get_center();
+    let corner = map.get_bounds().get_north_east();
+    ViewArea {
+        lat: center.lat(),
+        lon: center.lng(),
+        radius_km: distance_km(center.lat(), center.lng(), corner.lat(), corner.lng()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearby_catches_share_a_cluster_until_zoomed_in() {
+        let points = [(50.4500, 30.5200), (50.4510, 30.5210), (50.4490, 30.5195), (49.8400, 24.0300)];
+
+        let far = cluster(&points, 8.0);
+        assert_eq!(far.len(), 2);
+        assert_eq!(far[0].members, [0, 1, 2]);
+        assert!((far[0].lat - 50.45).abs() < 1e-3, "cluster sits at the members' mean");
+        assert_eq!(far[1].members, [3]);
+
+        let close = cluster(&points, CLUSTER_MAX_ZOOM);
+        assert_eq!(close.len(), 4);
+    }
+
+    #[test]
+    fn test_loaded_area_covers_small_pans() {
+        let view = ViewArea { lat: 50.45, lon: 30.52, radius_km: 10.0 };
+        let fetched = view.fetch_area();
+        assert_eq!(fetched.radius_km, 20.0);
+        assert!(fetched.covers(&view));
+        assert!(fetched.covers(&ViewArea { lat: 50.50, ..view }));
+        assert!(!fetched.covers(&ViewArea { lat: 50.60, ..view }));
+        assert!(!fetched.covers(&ViewArea { radius_km: 25.0, ..view }));
+
+        assert!(view.is_close_enough());
+        assert!(!ViewArea { radius_km: 300.0, ..view }.is_close_enough());
+        assert_eq!(ViewArea { radius_km: 80.0, ..view }.fetch_area().radius_km, MAX_RADIUS_KM);
+    }
+
+    #[test]
+    fn test_popup_text_is_escaped() {
+        assert_eq!(
+            escape_html(r#"<img src=x onerror="alert('1')"> & Co"#),
+            "&lt;img src=x onerror=&quot;alert(&#39;1&#39;)&quot;&gt; &amp; Co"
+        );
+    }
 }
@@ -1,8 +1,12 @@
 /// Services for API communication and state management.
 pub mod api_client;
+pub mod forecast;
 pub mod geolocation;
+pub mod leaflet;
 pub mod map_service;
+pub mod offline;
+pub mod preferences;
+pub mod push;
+pub mod session;
 
 pub use api_client::ApiClient;
-// Unused map service functions - kept for future use
-// pub use map_service::{init_map, update_marker};
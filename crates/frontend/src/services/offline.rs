@@ -0,0 +1,241 @@
+//! Offline support: catches wait in local storage until the server takes
+//! them, and the species list stays available without a connection.
+//!
+//! Every queued catch carries the UUID it was created with, so sending it
+//! again after a lost response cannot store it twice: the server answers a
+//! replay with the catch it already has.
+
+use std::cell::Cell;
+
+use fishing_shared::types::FishSpecies;
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::services::api_client::{ApiClient, ApiError, CatchRecord, NewCatchRecord};
+use crate::services::session;
+
+const QUEUE_KEY: &str = "pending_catches";
+const SPECIES_KEY: &str = "fish_species_cache";
+
+thread_local! {
+    /// Set while a flush runs, so the `online` event and a new catch do not
+    /// send the same queue twice.
+    static FLUSHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Catch waiting to be sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingCatch {
+    pub catch: NewCatchRecord,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    /// The server refused the catch itself; sending it again will not help.
+    #[serde(default)]
+    pub rejected: bool,
+}
+
+/// What the sync chip shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncStatus {
+    /// Catches that will be sent when the server is reachable.
+    pub pending: usize,
+    /// Catches the server refused; kept until the user discards them.
+    pub rejected: usize,
+}
+
+/// Give a new catch its idempotency key.
+pub fn new_catch_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Queue a catch; it must already have its id.
+pub fn enqueue(catch: NewCatchRecord) {
+    let mut queue = load_queue();
+    queue.push(PendingCatch {
+        catch,
+        attempts: 0,
+        last_error: None,
+        rejected: false,
+    });
+    save_queue(&queue);
+}
+
+/// Current state of the queue.
+pub fn status() -> SyncStatus {
+    status_of(&load_queue())
+}
+
+/// Drop the catches the server refused.
+pub fn discard_rejected() {
+    let mut queue = load_queue();
+    queue.retain(|entry| !entry.rejected);
+    save_queue(&queue);
+}
+
+/// Whether the browser believes it has a connection.
+pub fn is_online() -> bool {
+    web_sys::window().is_none_or(|window| window.navigator().on_line())
+}
+
+/// Send queued catches, oldest first, and return the ones saved.
+///
+/// Stops at the first failure that a later retry could fix, such as a lost
+/// connection or a busy server, so the order of catches is kept.
+pub async fn flush(api: &ApiClient) -> Vec<CatchRecord> {
+    if FLUSHING.with(|flag| flag.replace(true)) {
+        return Vec::new();
+    }
+
+    let mut synced = Vec::new();
+    while let Some(next) = next_pending(&load_queue()) {
+        // Saved for the signed-in user, anonymously otherwise
+        let catch = &next.catch;
+        let result = session::send(api, |api| async move { api.save_catch(catch).await }).await;
+        // Reload: a catch may have been queued while this one was in flight
+        let mut queue = load_queue();
+        let keep_going = record_attempt(&mut queue, &next, result.as_ref().map(|_| ()));
+        save_queue(&queue);
+
+        match result {
+            Ok(saved) => synced.push(saved),
+            Err(err) => log::warn!("Catch sync failed: {}", err),
+        }
+        if !keep_going {
+            break;
+        }
+    }
+
+    FLUSHING.with(|flag| flag.set(false));
+    synced
+}
+
+/// Species for the catch form, from the last successful load when offline.
+pub async fn fish_species(api: &ApiClient, country: Option<&str>, language: &str) -> Result<Vec<FishSpecies>, ApiError> {
+    match api.get_fish_species(country, language).await {
+        Ok(species) => {
+            if let Err(err) = LocalStorage::set(SPECIES_KEY, &species) {
+                log::warn!("Failed to cache fish species: {}", err);
+            }
+            Ok(species)
+        }
+        Err(err) => match LocalStorage::get::<Vec<FishSpecies>>(SPECIES_KEY) {
+            Ok(cached) if !cached.is_empty() => Ok(cached),
+            _ => Err(err),
+        },
+    }
+}
+
+fn load_queue() -> Vec<PendingCatch> {
+    LocalStorage::get(QUEUE_KEY).unwrap_or_default()
+}
+
+fn save_queue(queue: &[PendingCatch]) {
+    if let Err(err) = LocalStorage::set(QUEUE_KEY, queue) {
+        log::error!("Failed to store pending catches: {}", err);
+    }
+}
+
+fn status_of(queue: &[PendingCatch]) -> SyncStatus {
+    let rejected = queue.iter().filter(|entry| entry.rejected).count();
+    SyncStatus {
+        pending: queue.len() - rejected,
+        rejected,
+    }
+}
+
+fn next_pending(queue: &[PendingCatch]) -> Option<PendingCatch> {
+    queue.iter().find(|entry| !entry.rejected).cloned()
+}
+
+/// Whether a failed send may succeed later without changes to the catch.
+///
+/// A rejected token is one of these: the catch waits for the next sign-in.
+pub fn is_retryable(err: &ApiError) -> bool {
+    match err {
+        ApiError::Network(_) => true,
+        other => matches!(other.status(), Some(401 | 408 | 429 | 500..)),
+    }
+}
+
+/// Update the queue after sending `sent`; returns whether to send the next.
+fn record_attempt(queue: &mut Vec<PendingCatch>, sent: &PendingCatch, result: Result<(), &ApiError>) -> bool {
+    let Some(index) = queue.iter().position(|entry| entry.catch.id == sent.catch.id) else {
+        return true;
+    };
+    let Err(err) = result else {
+        queue.remove(index);
+        return true;
+    };
+
+    let entry = &mut queue[index];
+    entry.attempts += 1;
+    entry.last_error = Some(err.to_string());
+    entry.rejected = !is_retryable(err);
+    entry.rejected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(id: &str) -> PendingCatch {
+        PendingCatch {
+            catch: NewCatchRecord {
+                id: Some(id.to_string()),
+                user_id: "angler-1".to_string(),
+                lat: 50.45,
+                lon: 30.52,
+                caught_at: "2026-05-14T05:30:00Z".parse().unwrap(),
+                fish_species: "pike".to_string(),
+                weight_kg: None,
+                length_cm: None,
+                bait_used: None,
+                bite_intensity: None,
+                weather_temp: None,
+                weather_pressure: None,
+                moon_phase: None,
+                notes: None,
+                photo_url: None,
+                privacy: None,
+            },
+            attempts: 0,
+            last_error: None,
+            rejected: false,
+        }
+    }
+
+    #[test]
+    fn test_saved_catch_leaves_the_queue() {
+        let mut queue = vec![pending("a"), pending("b")];
+        let sent = next_pending(&queue).unwrap();
+
+        assert!(record_attempt(&mut queue, &sent, Ok(())));
+        assert_eq!(queue, vec![pending("b")]);
+    }
+
+    #[test]
+    fn test_connection_errors_are_retried_later() {
+        let mut queue = vec![pending("a"), pending("b")];
+        let sent = next_pending(&queue).unwrap();
+
+        let errors = [ApiError::Network("offline".into()), ApiError::Status(503), ApiError::Status(429), ApiError::Status(401)];
+        for err in errors {
+            assert!(!record_attempt(&mut queue, &sent, Err(&err)), "{err:?} stops the flush");
+        }
+        assert_eq!(queue[0].attempts, 4);
+        assert!(!queue[0].rejected);
+        assert_eq!(status_of(&queue), SyncStatus { pending: 2, rejected: 0 });
+    }
+
+    #[test]
+    fn test_refused_catch_is_kept_aside() {
+        let mut queue = vec![pending("a"), pending("b")];
+        let sent = next_pending(&queue).unwrap();
+
+        assert!(record_attempt(&mut queue, &sent, Err(&ApiError::Status(400))));
+        assert!(queue[0].rejected);
+        assert_eq!(queue[0].last_error.as_deref(), Some("HTTP 400"));
+        assert_eq!(next_pending(&queue).unwrap().catch.id.as_deref(), Some("b"));
+        assert_eq!(status_of(&queue), SyncStatus { pending: 1, rejected: 1 });
+    }
+}
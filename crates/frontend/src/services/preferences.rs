@@ -0,0 +1,39 @@
+//! User preferences kept in localStorage and synced with the server profile.
+
+use gloo_storage::{LocalStorage, Storage};
+
+use crate::services::api_client::{ApiClient, ApiError};
+use crate::services::session;
+use crate::state::app_state::UserPreferences;
+
+const PREFERENCES_KEY: &str = "user_preferences";
+
+/// Preferences saved on this device, or the defaults.
+pub fn load_local() -> UserPreferences {
+    LocalStorage::get(PREFERENCES_KEY).unwrap_or_default()
+}
+
+fn save_local(preferences: &UserPreferences) {
+    if let Err(err) = LocalStorage::set(PREFERENCES_KEY, preferences) {
+        log::warn!("Failed to store preferences: {}", err);
+    }
+}
+
+/// Pull the profile after login; server values replace the local ones.
+pub async fn sync_after_login(api: &ApiClient) -> Result<UserPreferences, ApiError> {
+    let profile = api.get_profile().await?;
+    let mut preferences = load_local();
+    preferences.apply_profile(&profile);
+    save_local(&preferences);
+    Ok(preferences)
+}
+
+/// Save a change on this device and, when signed in, in the profile.
+pub async fn save(api: &ApiClient, preferences: &UserPreferences) -> Result<(), ApiError> {
+    save_local(preferences);
+    if session::load().is_some() {
+        let update = &preferences.to_update();
+        session::send(api, |api| async move { api.update_profile(update).await }).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Web Push subscription of this browser, for bite alerts.
+//!
+//! Subscribes through the service worker registered in `index.html`, which
+//! also shows the notifications (`assets/sw.js`).
+
+use fishing_shared::types::PushSubscription;
+use js_sys::{Object, Promise, Reflect, JSON};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::services::api_client::ApiClient;
+use crate::services::session;
+
+/// Whether the browser can receive push notifications at all.
+pub fn is_supported() -> bool {
+    let Some(window) = web_sys::window() else {
+        return false;
+    };
+    let has = |target: &JsValue, name: &str| Reflect::has(target, &name.into()).unwrap_or(false);
+    has(&window.navigator(), "serviceWorker") && has(&window, "PushManager")
+}
+
+/// Ask for permission and send this browser's subscription to the server.
+pub async fn subscribe(api: &ApiClient) -> Result<(), String> {
+    let key = api.get_push_public_key().await.map_err(|err| err.to_string())?;
+    let push_manager = push_manager().await?;
+
+    let options = Object::new();
+    Reflect::set(&options, &"userVisibleOnly".into(), &JsValue::TRUE).map_err(js_error)?;
+    Reflect::set(&options, &"applicationServerKey".into(), &key.public_key.into()).map_err(js_error)?;
+    let subscription = call_promise(&push_manager, "subscribe", &[options.into()]).await?;
+
+    let subscription = &to_subscription(&subscription)?;
+    session::send(api, |api| async move { api.subscribe_push(subscription).await })
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// Cancel this browser's subscription here and on the server.
+pub async fn unsubscribe(api: &ApiClient) -> Result<(), String> {
+    let push_manager = push_manager().await?;
+    let subscription = call_promise(&push_manager, "getSubscription", &[]).await?;
+    if subscription.is_null() {
+        return Ok(());
+    }
+
+    let endpoint = to_subscription(&subscription)?.endpoint;
+    call_promise(&subscription, "unsubscribe", &[]).await?;
+    let endpoint = &endpoint;
+    session::send(api, |api| async move { api.unsubscribe_push(endpoint).await })
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// Whether this browser currently has a push subscription.
+pub async fn is_subscribed() -> bool {
+    match push_manager().await {
+        Ok(push_manager) => call_promise(&push_manager, "getSubscription", &[])
+            .await
+            .is_ok_and(|subscription| !subscription.is_null()),
+        Err(_) => false,
+    }
+}
+
+/// `PushManager` of the active service worker registration.
+async fn push_manager() -> Result<JsValue, String> {
+    let window = web_sys::window().ok_or("no window")?;
+    let container = Reflect::get(&window.navigator(), &"serviceWorker".into()).map_err(js_error)?;
+    let ready = Reflect::get(&container, &"ready".into()).map_err(js_error)?;
+    let registration = JsFuture::from(Promise::from(ready)).await.map_err(js_error)?;
+    Reflect::get(&registration, &"pushManager".into()).map_err(js_error)
+}
+
+/// Call the method `name` of `target` and await the promise it returns.
+async fn call_promise(target: &JsValue, name: &str, args: &[JsValue]) -> Result<JsValue, String> {
+    let method: js_sys::Function = Reflect::get(target, &name.into()).map_err(js_error)?.dyn_into().map_err(js_error)?;
+    let promise = method.apply(target, &args.iter().collect()).map_err(js_error)?;
+    JsFuture::from(Promise::from(promise)).await.map_err(js_error)
+}
+
+/// The API's view of a browser `PushSubscription`, via its `toJSON()`.
+fn to_subscription(subscription: &JsValue) -> Result<PushSubscription, String> {
+    let json = JSON::stringify(subscription).map_err(js_error)?;
+    serde_json::from_str(&String::from(json)).map_err(|err| err.to_string())
+}
+
+fn js_error(value: JsValue) -> String {
+    value.as_string().unwrap_or_else(|| format!("{:?}", value))
+}
@@ -0,0 +1,153 @@
+//! Login session stored in localStorage.
+
+use std::cell::RefCell;
+use std::future::Future;
+
+use fishing_shared::types::{AuthResponse, RegisterRequest};
+use futures_util::future::{FutureExt, LocalBoxFuture, Shared};
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::services::api_client::{ApiClient, ApiError};
+use crate::services::preferences;
+use crate::state::app_state::UserPreferences;
+
+const SESSION_KEY: &str = "auth_session";
+
+type Refresh = Shared<LocalBoxFuture<'static, Result<AuthSession, ApiError>>>;
+
+thread_local! {
+    /// Refresh in flight. Requests that fail together wait for the same one:
+    /// a refresh token works once, and presenting it twice revokes the session.
+    static REFRESHING: RefCell<Option<Refresh>> = const { RefCell::new(None) };
+}
+
+/// Tokens kept between visits
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuthSession {
+    pub token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+    /// Account shown in the header
+    #[serde(default)]
+    pub email: String,
+}
+
+impl From<AuthResponse> for AuthSession {
+    fn from(response: AuthResponse) -> Self {
+        Self {
+            token: response.token,
+            refresh_token: response.refresh_token,
+            expires_in: response.expires_in,
+            email: response.user.email,
+        }
+    }
+}
+
+/// Session saved by the last login, if any.
+pub fn load() -> Option<AuthSession> {
+    LocalStorage::get(SESSION_KEY).ok()
+}
+
+/// Run `call` as the signed-in user, or anonymously without a session.
+///
+/// Access tokens are short-lived: when the server answers 401, e.g. with
+/// `token_expired`, the session is refreshed once and `call` retried. A
+/// refused refresh token ends the session.
+pub async fn send<T, F, Fut>(api: &ApiClient, call: F) -> Result<T, ApiError>
+where
+    F: Fn(ApiClient) -> Fut,
+    Fut: Future<Output = Result<T, ApiError>>,
+{
+    let Some(stored) = load() else {
+        return call(api.clone()).await;
+    };
+    match call(api.with_token(&stored.token)).await {
+        Err(err) if err.status() == Some(401) => {
+            let refreshed = refresh(api, &stored).await?;
+            call(api.with_token(&refreshed.token)).await
+        }
+        result => result,
+    }
+}
+
+/// New tokens for a session whose access token was rejected.
+async fn refresh(api: &ApiClient, expired: &AuthSession) -> Result<AuthSession, ApiError> {
+    // Another request may have refreshed it meanwhile
+    if let Some(current) = load().filter(|current| current.token != expired.token) {
+        return Ok(current);
+    }
+
+    let pending = REFRESHING.with(|slot| {
+        slot.borrow_mut()
+            .get_or_insert_with(|| {
+                let api = api.clone();
+                let refresh_token = expired.refresh_token.clone();
+                async move {
+                    let result = api.refresh(&refresh_token).await.map(AuthSession::from);
+                    match &result {
+                        Ok(session) => store(session),
+                        Err(err) if err.status() == Some(401) => clear(),
+                        Err(err) => log::warn!("Session refresh failed: {}", err),
+                    }
+                    REFRESHING.with(|slot| slot.borrow_mut().take());
+                    result
+                }
+                .boxed_local()
+                .shared()
+            })
+            .clone()
+    });
+    pending.await
+}
+
+/// Forget the session, e.g. after logout or a rejected token.
+pub fn clear() {
+    LocalStorage::delete(SESSION_KEY);
+}
+
+/// Log in, remember the session and pull the user's preferences.
+///
+/// Returns a client authenticated as the user.
+pub async fn login(
+    api: &ApiClient,
+    email: &str,
+    password: &str,
+) -> Result<(ApiClient, UserPreferences), ApiError> {
+    start(api, api.login(email, password).await?).await
+}
+
+/// Create an account and sign in to it, as [`login`] does.
+pub async fn register(
+    api: &ApiClient,
+    request: &RegisterRequest,
+) -> Result<(ApiClient, UserPreferences), ApiError> {
+    start(api, api.register(request).await?).await
+}
+
+/// End the session on the server and forget it here.
+///
+/// The session is forgotten even when the server cannot be reached.
+pub async fn logout(api: &ApiClient) {
+    if let Some(stored) = load() {
+        if let Err(err) = api.logout(&stored.refresh_token).await {
+            log::warn!("Logout failed: {}", err);
+        }
+    }
+    clear();
+}
+
+async fn start(api: &ApiClient, response: AuthResponse) -> Result<(ApiClient, UserPreferences), ApiError> {
+    let session = AuthSession::from(response);
+    store(&session);
+
+    let authenticated = api.with_token(&session.token);
+    let preferences = preferences::sync_after_login(&authenticated).await?;
+    Ok((authenticated, preferences))
+}
+
+fn store(session: &AuthSession) {
+    if let Err(err) = LocalStorage::set(SESSION_KEY, session) {
+        log::warn!("Failed to store session: {}", err);
+    }
+}
@@ -1,7 +1,9 @@
+use fishing_shared::types::{CatchPrivacy, Language, ProfileUpdate, UnitPreferences, UserProfile};
 use serde::{Deserialize, Serialize};
 
 /// Global application state
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[allow(dead_code)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AppState {
     /// Currently selected location (lat, lon)
     pub selected_location: Option<(f64, f64)>,
@@ -11,57 +13,111 @@ pub struct AppState {
     
     /// User preferences
     pub preferences: UserPreferences,
-    
-    /// Whether offline mode is active
-    pub offline_mode: bool,
-    
-    /// Pending actions to sync when online
-    pub pending_syncs: Vec<String>,
 }
 
 /// User preferences
-#[derive(Clone, Debug, Serialize, Deserialize)]
+///
+/// Everything except `dark_mode` is stored in the user's profile on the
+/// server and synced after login (see `services::preferences`).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UserPreferences {
     /// Preferred language (uk, en, pl, de, fr)
-    pub language: String,
-    
-    /// Length unit (cm or in)
-    pub length_unit: String,
-    
-    /// Weight unit (kg or lb)
-    pub weight_unit: String,
-    
-    /// Temperature unit (C or F)
-    pub temperature_unit: String,
-    
-    /// Pressure unit (mmHg, hPa, inHg)
-    pub pressure_unit: String,
-    
-    /// Dark mode enabled
+    pub language: Language,
+
+    /// Length, weight, temperature and pressure units
+    pub units: UnitPreferences,
+
+    /// Region the app opens on, e.g. `UA-32`
+    pub home_region: Option<String>,
+
+    /// Favorite fish species ids, most important first
+    pub favorite_species: Vec<String>,
+
+    /// Privacy applied to new catches
+    pub default_privacy: CatchPrivacy,
+
+    /// Dark mode enabled (this device only)
     pub dark_mode: bool,
 }
 
-impl Default for AppState {
-    fn default() -> Self {
-        Self {
-            selected_location: None,
-            current_forecast: None,
-            preferences: UserPreferences::default(),
-            offline_mode: false,
-            pending_syncs: Vec::new(),
+impl UserPreferences {
+    /// Take the server-side values from a profile, keeping local-only ones.
+    pub fn apply_profile(&mut self, profile: &UserProfile) {
+        self.language = profile.language;
+        self.units = profile.units.clone();
+        self.home_region = profile.home_region.clone();
+        self.favorite_species = profile.favorite_species.clone();
+        self.default_privacy = profile.default_privacy;
+    }
+
+    /// Country of the home region, e.g. `UA` for `UA-32`.
+    pub fn home_country(&self) -> Option<&str> {
+        self.home_region
+            .as_deref()
+            .and_then(|region| region.split('-').next())
+            .filter(|country| !country.is_empty())
+    }
+
+    /// Update that stores these preferences in the profile.
+    pub fn to_update(&self) -> ProfileUpdate {
+        ProfileUpdate {
+            language: Some(self.language),
+            units: Some(self.units.clone()),
+            home_region: Some(self.home_region.clone().unwrap_or_default()),
+            favorite_species: Some(self.favorite_species.clone()),
+            default_privacy: Some(self.default_privacy),
+            ..ProfileUpdate::default()
         }
     }
 }
 
-impl Default for UserPreferences {
-    fn default() -> Self {
-        Self {
-            language: "uk".to_string(),
-            length_unit: "cm".to_string(),
-            weight_unit: "kg".to_string(),
-            temperature_unit: "C".to_string(),
-            pressure_unit: "hPa".to_string(),
-            dark_mode: false,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fishing_shared::types::{PressureUnit, WeightUnit};
+
+    fn profile() -> UserProfile {
+        UserProfile {
+            id: "user-1".to_string(),
+            email: "angler@example.com".to_string(),
+            email_verified: true,
+            country_code: "PL".to_string(),
+            language: Language::Pl,
+            units: UnitPreferences {
+                weight: WeightUnit::Lb,
+                pressure: PressureUnit::MmHg,
+                ..UnitPreferences::default()
+            },
+            home_region: Some("PL-MZ".to_string()),
+            favorite_species: vec!["zander".to_string()],
+            default_privacy: CatchPrivacy::Public,
         }
     }
+
+    #[test]
+    fn test_apply_profile_keeps_local_settings() {
+        let mut preferences = UserPreferences { dark_mode: true, ..UserPreferences::default() };
+        preferences.apply_profile(&profile());
+
+        assert_eq!(preferences.language, Language::Pl);
+        assert_eq!(preferences.units.weight, WeightUnit::Lb);
+        assert_eq!(preferences.home_region.as_deref(), Some("PL-MZ"));
+        assert_eq!(preferences.home_country(), Some("PL"));
+        assert!(preferences.dark_mode);
+    }
+
+    #[test]
+    fn test_update_round_trips_through_profile() {
+        let mut preferences = UserPreferences::default();
+        preferences.apply_profile(&profile());
+        let update = preferences.to_update();
+
+        assert_eq!(update.country_code, None, "country is not a UI preference");
+        assert_eq!(update.units, Some(profile().units));
+        assert_eq!(update.favorite_species, Some(vec!["zander".to_string()]));
+
+        preferences.home_region = None;
+        assert_eq!(preferences.to_update().home_region.as_deref(), Some(""), "clears the region");
+    }
 }
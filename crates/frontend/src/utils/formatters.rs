@@ -1,78 +1,250 @@
-/// Format utilities for display
+//! Format utilities for display
+
+use chrono::{DateTime, FixedOffset, Utc};
+use fishing_shared::types::{
+    celsius_to_fahrenheit, cm_to_inches, hpa_to_inhg, hpa_to_mmhg, inches_to_cm, kg_to_lb, km_to_miles,
+    lb_to_kg, ms_to_beaufort, ms_to_kmh, ms_to_knots, Language, LengthUnit, PressureUnit, TemperatureUnit, WeightUnit,
+    WindSpeedUnit,
+};
+use rust_i18n::t;
+
+/// Localized symbol of a unit, e.g. "кг" for kilograms in Ukrainian
+pub trait UnitLabel {
+    fn label(&self, language: Language) -> String;
+}
+
+macro_rules! unit_labels {
+    ($unit:ident { $($variant:ident => $key:literal),+ $(,)? }) => {
+        impl UnitLabel for $unit {
+            fn label(&self, language: Language) -> String {
+                let key = match self {
+                    $($unit::$variant => $key,)+
+                };
+                t!(key, locale = language.code()).into_owned()
+            }
+        }
+    };
+}
+
+unit_labels!(LengthUnit { Cm => "units.cm", Inches => "units.in" });
+unit_labels!(WeightUnit { Kg => "units.kg", Lb => "units.lb" });
+unit_labels!(PressureUnit { Hpa => "units.hpa", MmHg => "units.mmhg", InHg => "units.inhg" });
+unit_labels!(WindSpeedUnit { Ms => "units.ms", Kmh => "units.kmh", Knots => "units.kn", Beaufort => "units.bft" });
 
 /// Format temperature based on unit preference
-pub fn format_temperature(celsius: f64, unit: &str) -> String {
-    if unit == "F" {
-        let fahrenheit = (celsius * 9.0 / 5.0) + 32.0;
-        format!("{:.1}°F", fahrenheit)
-    } else {
-        format!("{:.1}°C", celsius)
+pub fn format_temperature(celsius: f64, unit: TemperatureUnit) -> String {
+    match unit {
+        TemperatureUnit::F => format!("{:.1}°F", celsius_to_fahrenheit(celsius)),
+        TemperatureUnit::C => format!("{:.1}°C", celsius),
     }
 }
 
 /// Format length based on unit preference
-pub fn format_length(cm: f64, unit: &str) -> String {
-    if unit == "in" {
-        let inches = cm / 2.54;
-        format!("{:.1} in", inches)
-    } else {
-        format!("{:.1} cm", cm)
-    }
+pub fn format_length(cm: f64, unit: LengthUnit, language: Language) -> String {
+    let value = match unit {
+        LengthUnit::Inches => cm_to_inches(cm),
+        LengthUnit::Cm => cm,
+    };
+    format!("{:.1} {}", value, unit.label(language))
 }
 
 /// Format weight based on unit preference
-pub fn format_weight(kg: f64, unit: &str) -> String {
-    if unit == "lb" {
-        let lb = kg * 2.20462;
-        format!("{:.2} lb", lb)
-    } else {
-        format!("{:.2} kg", kg)
-    }
+pub fn format_weight(kg: f64, unit: WeightUnit, language: Language) -> String {
+    let value = match unit {
+        WeightUnit::Lb => kg_to_lb(kg),
+        WeightUnit::Kg => kg,
+    };
+    format!("{:.2} {}", value, unit.label(language))
 }
 
 /// Format pressure based on unit preference
-pub fn format_pressure(hpa: f64, unit: &str) -> String {
+pub fn format_pressure(hpa: f64, unit: PressureUnit, language: Language) -> String {
     match unit {
-        "mmHg" => {
-            let mmhg = hpa * 0.75006;
-            format!("{:.0} mmHg", mmhg)
-        }
-        "inHg" => {
-            let inhg = hpa * 0.02953;
-            format!("{:.2} inHg", inhg)
-        }
-        _ => format!("{:.0} hPa", hpa),
+        PressureUnit::MmHg => format!("{:.0} {}", hpa_to_mmhg(hpa), unit.label(language)),
+        PressureUnit::InHg => format!("{:.2} {}", hpa_to_inhg(hpa), unit.label(language)),
+        PressureUnit::Hpa => format!("{:.0} {}", hpa, unit.label(language)),
     }
 }
 
+/// Format wind speed based on unit preference
+pub fn format_wind_speed(ms: f64, unit: WindSpeedUnit, language: Language) -> String {
+    match unit {
+        WindSpeedUnit::Ms => format!("{:.1} {}", ms, unit.label(language)),
+        WindSpeedUnit::Kmh => format!("{:.0} {}", ms_to_kmh(ms), unit.label(language)),
+        WindSpeedUnit::Knots => format!("{:.0} {}", ms_to_knots(ms), unit.label(language)),
+        WindSpeedUnit::Beaufort => format!("{} {}", ms_to_beaufort(ms), unit.label(language)),
+    }
+}
+
+/// Number typed by the user, accepting a decimal comma
+fn parse_number(input: &str) -> Option<f64> {
+    input.trim().replace(',', ".").parse().ok().filter(|value: &f64| value.is_finite())
+}
+
+/// Weight typed in `unit`, in kilograms
+pub fn parse_weight(input: &str, unit: WeightUnit) -> Option<f64> {
+    parse_number(input).map(|value| match unit {
+        WeightUnit::Lb => lb_to_kg(value),
+        WeightUnit::Kg => value,
+    })
+}
+
+/// Length typed in `unit`, in centimeters
+pub fn parse_length(input: &str, unit: LengthUnit) -> Option<f64> {
+    parse_number(input).map(|value| match unit {
+        LengthUnit::Inches => inches_to_cm(value),
+        LengthUnit::Cm => value,
+    })
+}
+
+/// How long ago `time` was, e.g. "5 хв тому"
+pub fn format_age(time: DateTime<Utc>, now: DateTime<Utc>, language: Language) -> String {
+    let locale = language.code();
+    let minutes = (now - time).num_minutes();
+    match minutes {
+        m if m < 1 => t!("age.just_now", locale = locale),
+        m if m < 60 => t!("age.minutes", locale = locale, count = m),
+        m if m < 60 * 24 => t!("age.hours", locale = locale, count = m / 60),
+        m => t!("age.days", locale = locale, count = m / (60 * 24)),
+    }
+    .into_owned()
+}
+
+/// A time span in the browser's time zone, e.g. "tomorrow 05:00–07:00"
+pub fn format_window(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    now: DateTime<Utc>,
+    offset: FixedOffset,
+    language: Language,
+) -> String {
+    let locale = language.code();
+    let (start, end, today) = (start.with_timezone(&offset), end.with_timezone(&offset), now.with_timezone(&offset).date_naive());
+    let day = match (start.date_naive() - today).num_days() {
+        0 => t!("time.today", locale = locale).into_owned(),
+        1 => t!("time.tomorrow", locale = locale).into_owned(),
+        _ => start.format("%d.%m").to_string(),
+    };
+    format!("{} {}–{}", day, start.format("%H:%M"), end.format("%H:%M"))
+}
+
+/// Travel distance, in miles for users who measure lengths in inches
+pub fn format_distance(km: f64, unit: LengthUnit, language: Language) -> String {
+    let locale = language.code();
+    match unit {
+        LengthUnit::Inches => format!("{:.0} {}", km_to_miles(km), t!("units.mi", locale = locale)),
+        LengthUnit::Cm => format!("{:.0} {}", km, t!("units.km", locale = locale)),
+    }
+}
+
+/// Name of a recommended bait; the server sends ids such as `dead bait`
+pub fn bait_name(id: &str, language: Language) -> String {
+    let key = format!("bait.{}", id.replace(' ', "_"));
+    let name = t!(&key, locale = language.code());
+    // Unknown ids come back as the key
+    if name == key {
+        id.to_string()
+    } else {
+        name.into_owned()
+    }
+}
+
+/// The browser's UTC offset; chrono's `Local` does not know it on wasm.
+pub fn browser_offset() -> FixedOffset {
+    // Minutes to add to local time to get UTC
+    let minutes = js_sys::Date::new_0().get_timezone_offset();
+    FixedOffset::west_opt((minutes * 60.0) as i32).unwrap_or_else(|| FixedOffset::east_opt(0).unwrap())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_format_temperature_celsius() {
-        assert_eq!(format_temperature(20.0, "C"), "20.0°C");
+        assert_eq!(format_temperature(20.0, TemperatureUnit::C), "20.0°C");
     }
 
     #[test]
     fn test_format_temperature_fahrenheit() {
-        let f = format_temperature(0.0, "F");
-        assert!(f.starts_with("32.0"));
+        assert_eq!(format_temperature(0.0, TemperatureUnit::F), "32.0°F");
     }
 
     #[test]
-    fn test_format_length_cm() {
-        assert_eq!(format_length(10.0, "cm"), "10.0 cm");
+    fn test_format_length() {
+        assert_eq!(format_length(10.0, LengthUnit::Cm, Language::En), "10.0 cm");
+        assert_eq!(format_length(25.4, LengthUnit::Inches, Language::En), "10.0 in");
+        assert_eq!(format_length(10.0, LengthUnit::Cm, Language::Uk), "10.0 см");
     }
 
     #[test]
-    fn test_format_length_inches() {
-        let inches = format_length(25.4, "in");
-        assert!(inches.starts_with("10."));
+    fn test_format_weight() {
+        assert_eq!(format_weight(1.0, WeightUnit::Kg, Language::En), "1.00 kg");
+        assert_eq!(format_weight(1.0, WeightUnit::Lb, Language::En), "2.20 lb");
     }
 
     #[test]
-    fn test_format_weight_kg() {
-        assert_eq!(format_weight(1.0, "kg"), "1.00 kg");
+    fn test_format_pressure() {
+        assert_eq!(format_pressure(1013.0, PressureUnit::Hpa, Language::En), "1013 hPa");
+        assert_eq!(format_pressure(1013.0, PressureUnit::MmHg, Language::En), "760 mmHg");
+        assert_eq!(format_pressure(1013.0, PressureUnit::InHg, Language::En), "29.91 inHg");
+    }
+
+    #[test]
+    fn test_format_wind_speed() {
+        assert_eq!(format_wind_speed(5.0, WindSpeedUnit::Ms, Language::En), "5.0 m/s");
+        assert_eq!(format_wind_speed(5.0, WindSpeedUnit::Kmh, Language::En), "18 km/h");
+        assert_eq!(format_wind_speed(5.0, WindSpeedUnit::Knots, Language::En), "10 kn");
+        assert_eq!(format_wind_speed(5.0, WindSpeedUnit::Beaufort, Language::En), "3 Bft");
+        assert_eq!(format_wind_speed(0.1, WindSpeedUnit::Beaufort, Language::En), "0 Bft");
+        assert_eq!(format_wind_speed(40.0, WindSpeedUnit::Beaufort, Language::En), "12 Bft");
+    }
+
+    #[test]
+    fn test_format_distance() {
+        assert_eq!(format_distance(12.4, LengthUnit::Cm, Language::En), "12 km");
+        assert_eq!(format_distance(16.1, LengthUnit::Inches, Language::En), "10 mi");
+        assert_eq!(format_distance(12.4, LengthUnit::Cm, Language::Uk), "12 км");
+    }
+
+    #[test]
+    fn test_bait_name() {
+        assert_eq!(bait_name("dead bait", Language::En), "Dead bait");
+        assert_eq!(bait_name("maggot", Language::Uk), "Опариш");
+        assert_eq!(bait_name("jig", Language::De), "jig");
+    }
+
+    #[test]
+    fn test_entered_values_are_stored_metric() {
+        assert_eq!(parse_weight("1,5", WeightUnit::Kg), Some(1.5));
+        assert!((parse_weight("2.2046", WeightUnit::Lb).unwrap() - 1.0).abs() < 1e-3);
+        assert_eq!(parse_length("10", LengthUnit::Inches), Some(25.4));
+        assert_eq!(parse_length(" 30 ", LengthUnit::Cm), Some(30.0));
+        assert_eq!(parse_weight("heavy", WeightUnit::Kg), None);
+        assert_eq!(parse_weight("", WeightUnit::Kg), None);
+    }
+
+    #[test]
+    fn test_format_age() {
+        let now = Utc::now();
+        assert_eq!(format_age(now, now, Language::Uk), "щойно");
+        assert_eq!(format_age(now - chrono::Duration::minutes(5), now, Language::Uk), "5 хв тому");
+        assert_eq!(format_age(now - chrono::Duration::minutes(150), now, Language::Uk), "2 год тому");
+        assert_eq!(format_age(now - chrono::Duration::days(3), now, Language::Uk), "3 дн тому");
+        assert_eq!(format_age(now - chrono::Duration::minutes(5), now, Language::En), "5 min ago");
+        assert_eq!(format_age(now - chrono::Duration::days(3), now, Language::Fr), "il y a 3 j");
+    }
+
+    #[test]
+    fn test_format_window_in_local_time() {
+        use chrono::TimeZone;
+        let kyiv = FixedOffset::east_opt(3 * 3600).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 5, 14, 19, 0, 0).unwrap();
+        let hour = chrono::Duration::hours(1);
+
+        assert_eq!(format_window(now, now + hour * 2, now, kyiv, Language::En), "today 22:00–00:00");
+        // 22:00 UTC is already the next day in Kyiv
+        assert_eq!(format_window(now + hour * 3, now + hour * 5, now, kyiv, Language::Uk), "завтра 01:00–03:00");
+        assert_eq!(format_window(now + hour * 40, now + hour * 41, now, kyiv, Language::En), "16.05 14:00–15:00");
     }
 }
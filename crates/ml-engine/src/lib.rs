@@ -66,6 +66,7 @@ struct ModelWeights {
     evening_weight: f64,
     /// Moon coefficient
     moon_weight: f64,
+    #[allow(dead_code)]
     moon_phase_optimal: f64,
     /// Season coefficient
     season_weight: f64,
@@ -110,7 +111,10 @@ impl GradientBoostingModel {
                 season_weight: 0.05,
                 rain_penalty: 0.15,
                 cloud_penalty: 0.05,
-                bias: 0.35,
+                // The factors add up to at most 0.83; at 0.35 even a storm
+                // scored above 0.6. This puts conditions half as good as
+                // the best near 0.5.
+                bias: -0.4,
             },
             n_iterations: 100,
             learning_rate: 0.1,
@@ -305,6 +309,7 @@ pub struct ModelStats {
 
 /// Feature importance scores
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct FeatureImportance {
     pub temperature: f64,
     pub pressure: f64,
@@ -387,6 +392,7 @@ impl ModelRegistry {
 
 /// Prediction result with explanation
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct PredictionResult {
     pub probability: f64,
     pub confidence: f64,
@@ -396,6 +402,7 @@ pub struct PredictionResult {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct FactorScore {
     pub name: String,
     pub score: f64,
@@ -403,6 +410,7 @@ pub struct FactorScore {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum PredictionRecommendation {
     Excellent,
     Good,
@@ -414,10 +422,11 @@ pub enum PredictionRecommendation {
 impl From<f64> for PredictionRecommendation {
     fn from(prob: f64) -> Self {
         match prob {
-            p if p >= 0.8 => Self::Excellent,
-            p if p >= 0.6 => Self::Good,
+            // The model scores between about 0.15 and 0.78
+            p if p >= 0.7 => Self::Excellent,
+            p if p >= 0.55 => Self::Good,
             p if p >= 0.4 => Self::Moderate,
-            p if p >= 0.2 => Self::Poor,
+            p if p >= 0.3 => Self::Poor,
             _ => Self::Avoid,
         }
     }
@@ -509,6 +518,7 @@ impl GradientBoostingModel {
 
 /// Create features from weather and time data
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn create_features(
     temperature_c: f64,
     pressure_hpa: f64,
@@ -544,12 +554,9 @@ pub fn create_features(
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_prediction_basic() {
-        let model = GradientBoostingModel::new();
-        
-        // Good conditions for fishing
-        let features = FishingFeatures {
+    /// Mild, steady weather on a spring morning at full moon.
+    fn calm() -> FishingFeatures {
+        FishingFeatures {
             temperature_c: 18.0,
             pressure_hpa: 1013.0,
             wind_speed_ms: 4.0,
@@ -564,18 +571,12 @@ mod tests {
             time_category: 1,
             cloud_cover: Some(0.3),
             humidity: Some(60.0),
-        };
-        
-        let prob = model.predict(&features);
-        assert!(prob > 0.5, "Good conditions should have high probability: {}", prob);
+        }
     }
 
-    #[test]
-    fn test_prediction_bad_conditions() {
-        let model = GradientBoostingModel::new();
-        
-        // Poor conditions for fishing
-        let features = FishingFeatures {
+    /// A cold midday gale with heavy rain and falling pressure.
+    fn storm() -> FishingFeatures {
+        FishingFeatures {
             temperature_c: 5.0,
             pressure_hpa: 980.0,
             wind_speed_ms: 15.0,
@@ -590,12 +591,34 @@ mod tests {
             time_category: 2,
             cloud_cover: Some(0.9),
             humidity: Some(90.0),
-        };
-        
-        let prob = model.predict(&features);
+        }
+    }
+
+    #[test]
+    fn test_prediction_basic() {
+        let prob = GradientBoostingModel::new().predict(&calm());
+        assert!(prob > 0.5, "Good conditions should have high probability: {}", prob);
+    }
+
+    #[test]
+    fn test_prediction_bad_conditions() {
+        let prob = GradientBoostingModel::new().predict(&storm());
         assert!(prob < 0.5, "Bad conditions should have low probability: {}", prob);
     }
 
+    /// Pins the scores the bias produces; thresholds such as the
+    /// recommendation levels and alert defaults are tuned to this range.
+    #[test]
+    fn test_calm_and_storm_scores() {
+        let model = GradientBoostingModel::new();
+        let calm = model.predict(&calm());
+        let storm = model.predict(&storm());
+        assert!((calm - 0.771).abs() < 0.001, "calm: {}", calm);
+        assert!((storm - 0.280).abs() < 0.001, "storm: {}", storm);
+        assert!(matches!(PredictionRecommendation::from(calm), PredictionRecommendation::Excellent));
+        assert!(matches!(PredictionRecommendation::from(storm), PredictionRecommendation::Avoid));
+    }
+
     #[test]
     fn test_create_features() {
         let features = create_features(
@@ -1,3 +1,4 @@
 pub mod constants;
 pub mod types;
 pub mod utils;
+pub mod validation;
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::PressureTrend;
+
+/// When to be notified about a good bite at one of the user's favorite spots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AlertRule {
+    pub id: String,
+    pub favorite_spot_id: String,
+    /// Fish species id named in the alert, if any. It does not change when
+    /// the alert fires: the forecast is the same for every species.
+    pub species: Option<String>,
+    /// Bite probability (0–1) an hour must reach.
+    pub min_probability: f64,
+    /// First local hour of the day (0–23) worth an alert.
+    pub start_hour: u8,
+    /// Local hour the window ends before; a smaller value than `start_hour`
+    /// wraps past midnight, the same value means the whole day.
+    pub end_hour: u8,
+    /// Offset of the user's local time from UTC, in minutes east.
+    pub utc_offset_minutes: i32,
+    /// Required pressure change over the preceding three hours, if any.
+    pub pressure_trend: Option<PressureTrend>,
+    pub enabled: bool,
+    /// When the rule last sent a notification.
+    pub last_notified_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Fields of an alert rule, sent to create or replace one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct AlertRuleInput {
+    pub favorite_spot_id: String,
+    #[serde(default)]
+    pub species: Option<String>,
+    pub min_probability: f64,
+    #[serde(default)]
+    pub start_hour: u8,
+    #[serde(default)]
+    pub end_hour: u8,
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+    #[serde(default)]
+    pub pressure_trend: Option<PressureTrend>,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// A browser's Web Push subscription, as `PushSubscription.toJSON()` gives it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PushSubscription {
+    /// Push service URL messages are posted to.
+    pub endpoint: String,
+    pub keys: PushSubscriptionKeys,
+}
+
+/// Keys the browser encrypts push messages with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PushSubscriptionKeys {
+    /// Base64url P-256 public key of the browser.
+    pub p256dh: String,
+    /// Base64url authentication secret.
+    pub auth: String,
+}
+
+/// Server key browsers subscribe with (`applicationServerKey`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PushPublicKey {
+    /// Base64url uncompressed P-256 point of the VAPID key.
+    pub public_key: String,
+}
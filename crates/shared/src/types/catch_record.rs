@@ -1,8 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-/// User catch log entry.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::{CatchPrivacy, EnvironmentSnapshot};
+
+/// User catch log entry, as returned by the catches API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CatchRecord {
     pub id: String,
     pub user_id: String,
@@ -12,7 +15,48 @@ pub struct CatchRecord {
     pub fish_species: String,
     pub weight_kg: Option<f64>,
     pub length_cm: Option<f64>,
-    pub bait_used: String,
+    pub bait_used: Option<String>,
+    /// Subjective bite activity, 1 (dead) to 5 (frenzy).
+    pub bite_intensity: Option<u8>,
+    pub weather_temp: Option<f64>,
+    pub weather_pressure: Option<f64>,
+    pub moon_phase: Option<f64>,
+    pub notes: Option<String>,
+    pub photo_url: Option<String>,
+    /// Conditions looked up by the server when the catch was saved.
+    pub environment: Option<EnvironmentSnapshot>,
+    /// Public catches are listed in nearby catches.
+    pub privacy: CatchPrivacy,
+}
+
+/// New catch payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NewCatchRecord {
+    /// UUID chosen by the client, so a catch sent again (e.g. replayed
+    /// after a lost connection) is stored once. Assigned by the server when
+    /// left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Ignored: the server saves the catch for the signed-in user, or
+    /// anonymously without a token.
+    #[serde(default)]
+    pub user_id: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub caught_at: DateTime<Utc>,
+    pub fish_species: String,
+    pub weight_kg: Option<f64>,
+    pub length_cm: Option<f64>,
+    pub bait_used: Option<String>,
     pub bite_intensity: Option<u8>,
+    pub weather_temp: Option<f64>,
+    pub weather_pressure: Option<f64>,
+    pub moon_phase: Option<f64>,
     pub notes: Option<String>,
+    pub photo_url: Option<String>,
+    /// Who can see the catch; the owner's default privacy when left out.
+    /// Anonymous catches are always public.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<CatchPrivacy>,
 }
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Direction of the barometric pressure change over the preceding hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum PressureTrend {
+    Rising,
+    Steady,
+    Falling,
+}
+
+impl PressureTrend {
+    /// Change (hPa over 3 hours) below which pressure counts as steady.
+    pub const STEADY_THRESHOLD_HPA: f64 = 1.0;
+
+    /// Classify a 3-hour pressure change in hPa.
+    pub fn from_change(change_hpa: f64) -> Self {
+        if change_hpa >= Self::STEADY_THRESHOLD_HPA {
+            Self::Rising
+        } else if change_hpa <= -Self::STEADY_THRESHOLD_HPA {
+            Self::Falling
+        } else {
+            Self::Steady
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rising => "rising",
+            Self::Steady => "steady",
+            Self::Falling => "falling",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "rising" => Some(Self::Rising),
+            "steady" => Some(Self::Steady),
+            "falling" => Some(Self::Falling),
+            _ => None,
+        }
+    }
+}
+
+/// Conditions at the time and place of a catch, captured by the server.
+///
+/// Astronomical values are always present; weather values are `None` when
+/// no observation could be fetched for that hour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct EnvironmentSnapshot {
+    /// Hour of the weather observation used.
+    pub weather_time: Option<DateTime<Utc>>,
+    pub temperature_c: Option<f64>,
+    pub pressure_hpa: Option<f64>,
+    /// Pressure change over the 3 hours before the catch.
+    pub pressure_change_3h_hpa: Option<f64>,
+    pub pressure_trend: Option<PressureTrend>,
+    pub wind_speed_ms: Option<f64>,
+    pub wind_gust_ms: Option<f64>,
+    pub wind_direction_deg: Option<f64>,
+    pub precipitation_mm: Option<f64>,
+    /// 0.0 = new moon, 0.5 = full moon.
+    pub moon_phase: f64,
+    /// Illuminated fraction, 0.0-1.0.
+    pub moon_illumination: f64,
+    pub sun_elevation_deg: f64,
+    pub sun_azimuth_deg: f64,
+}
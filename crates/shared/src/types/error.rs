@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// Body of every API error response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApiErrorBody {
+    /// Message for people, in the language negotiated from `Accept-Language`.
+    pub error: String,
+    /// Stable machine-readable code, e.g. `invalid_credentials`.
+    pub code: String,
+    /// Untranslated specifics, e.g. which field was rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Also sent as the `X-Request-Id` header; quote it when reporting problems.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Seconds to wait before retrying, for `429` responses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
+    /// Rejected fields, for `validation_failed`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+}
+
+/// A payload field that failed validation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FieldError {
+    /// Field name as sent, e.g. `lat` or `favorite_species`.
+    pub field: String,
+    /// Stable rule name, e.g. `out_of_range`.
+    pub code: String,
+    /// English explanation.
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A place the user saved to check its forecast again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FavoriteSpot {
+    pub id: String,
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// Water body the spot is on, if it is a known one.
+    pub water_body_id: Option<String>,
+    /// Fish species ids the user goes there for.
+    pub target_species: Vec<String>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Fields of a favorite spot, sent to create or replace one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FavoriteSpotInput {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    #[serde(default)]
+    pub water_body_id: Option<String>,
+    #[serde(default)]
+    pub target_species: Vec<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Fish species with its name in the requested language.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FishSpecies {
+    /// Slug such as `pike`.
+    pub id: String,
+    pub name: String,
+    pub scientific_name: String,
+}
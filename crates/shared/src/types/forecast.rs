@@ -1,9 +1,11 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::weather::WeatherCurrent;
 
 /// Scoring factors used to explain the forecast.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ForecastFactors {
     pub pressure_score: f64,
     pub temperature_score: f64,
@@ -14,14 +16,32 @@ pub struct ForecastFactors {
 }
 
 /// Recommended bait with a relative score.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct BaitRecommendation {
     pub name: String,
     pub score: f64,
 }
 
+/// Where a forecast came from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum ForecastSource {
+    /// The server's model on current weather.
+    #[default]
+    Model,
+    /// The app's rough estimate from weather it fetched itself.
+    ClientHeuristic,
+    /// An earlier model forecast kept on the device.
+    Cached,
+    /// Made up on the device without any data; development builds only.
+    Offline,
+}
+
 /// Forecast response returned by the API.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ForecastResult {
     pub probability: f64,
     pub confidence: f64,
@@ -31,4 +51,34 @@ pub struct ForecastResult {
     pub best_time: String,
     pub weather: WeatherCurrent,
     pub moon_phase: f64,
+    #[serde(default)]
+    pub source: ForecastSource,
+    /// When the forecast was computed.
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Consecutive hours with a good chance of a bite.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BiteWindow {
+    /// Start of the first hour.
+    pub start: DateTime<Utc>,
+    /// End of the last hour.
+    pub end: DateTime<Utc>,
+    /// Highest hourly probability in the window.
+    pub peak_probability: f64,
+}
+
+/// When the bite is good at one place over the coming hours.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BiteWindows {
+    /// Probability for the current hour.
+    pub probability_now: f64,
+    /// The window under way, if the bite is good right now.
+    pub current: Option<BiteWindow>,
+    /// The best window still to come, if any.
+    pub next_best: Option<BiteWindow>,
+    /// When the windows were computed.
+    pub generated_at: DateTime<Utc>,
 }
@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
 
 /// Supported UI languages.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
 pub enum Language {
+    #[default]
     Uk,
     En,
     Pl,
@@ -11,6 +14,9 @@ pub enum Language {
 }
 
 impl Language {
+    /// Every supported language, in the order of `SUPPORTED_LANGUAGES`.
+    pub const ALL: [Language; 5] = [Language::Uk, Language::En, Language::Pl, Language::De, Language::Fr];
+
     /// Return the ISO language code.
     pub fn code(&self) -> &'static str {
         match self {
@@ -21,4 +27,27 @@ impl Language {
             Language::Fr => "fr",
         }
     }
+
+    /// Parse an ISO language code.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "uk" => Some(Language::Uk),
+            "en" => Some(Language::En),
+            "pl" => Some(Language::Pl),
+            "de" => Some(Language::De),
+            "fr" => Some(Language::Fr),
+            _ => None,
+        }
+    }
+
+    /// Name of the language in itself, for language pickers.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::Uk => "Українська",
+            Language::En => "English",
+            Language::Pl => "Polski",
+            Language::De => "Deutsch",
+            Language::Fr => "Français",
+        }
+    }
 }
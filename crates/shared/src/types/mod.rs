@@ -1,15 +1,33 @@
 pub mod weather;
 pub mod forecast;
 pub mod catch_record;
+pub mod environment;
 pub mod water_body;
 pub mod region;
 pub mod language;
 pub mod units;
+pub mod profile;
+pub mod error;
+pub mod auth;
+pub mod fish;
+pub mod regulation;
+pub mod favorite;
+pub mod alert;
+pub mod trip;
 
 pub use weather::*;
 pub use forecast::*;
 pub use catch_record::*;
+pub use environment::*;
 pub use water_body::*;
 pub use region::*;
 pub use language::*;
 pub use units::*;
+pub use profile::*;
+pub use error::*;
+pub use auth::*;
+pub use fish::*;
+pub use regulation::*;
+pub use favorite::*;
+pub use alert::*;
+pub use trip::*;
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Language, UnitPreferences};
+
+/// Who can see a catch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum CatchPrivacy {
+    /// Only the angler.
+    #[default]
+    Private,
+    /// Anyone, e.g. in nearby catches.
+    Public,
+}
+
+impl CatchPrivacy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Private => "private",
+            Self::Public => "public",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "private" => Some(Self::Private),
+            "public" => Some(Self::Public),
+            _ => None,
+        }
+    }
+}
+
+/// Account profile and preferences, as returned by the profile API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UserProfile {
+    pub id: String,
+    pub email: String,
+    pub email_verified: bool,
+    pub country_code: String,
+    pub language: Language,
+    pub units: UnitPreferences,
+    /// Region code the app opens on, e.g. `UA-32`.
+    pub home_region: Option<String>,
+    /// Fish species ids, most important first.
+    pub favorite_species: Vec<String>,
+    /// Privacy applied to new catches.
+    pub default_privacy: CatchPrivacy,
+}
+
+/// Partial profile update; fields left out stay unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProfileUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub units: Option<UnitPreferences>,
+    /// An empty string clears the home region.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favorite_species: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_privacy: Option<CatchPrivacy>,
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 /// Country information returned by region detection.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RegionInfo {
     pub country_code: String,
     pub country_name: String,
@@ -0,0 +1,35 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Fishing rule for a region, optionally limited to one species.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Regulation {
+    pub id: String,
+    pub region_code: String,
+    pub fish_species: Option<String>,
+    pub min_size_cm: Option<f64>,
+    pub max_catch_per_day: Option<i32>,
+    pub season_start: Option<String>,
+    pub season_end: Option<String>,
+    pub restrictions: Option<String>,
+}
+
+/// Catch to check against the regulations of a region.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ValidationRequest {
+    pub country_code: String,
+    pub fish_species: Option<String>,
+    pub size_cm: Option<f64>,
+    pub date: Option<NaiveDate>,
+}
+
+/// Whether a catch may be kept, and why not.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ValidationResult {
+    pub allowed: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
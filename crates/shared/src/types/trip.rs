@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Candidate spots and the time to plan a fishing trip for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TripPlanRequest {
+    pub spots: Vec<TripSpot>,
+    /// Start of the trip; earlier hours than the current one are skipped.
+    pub from: DateTime<Utc>,
+    /// End of the trip, exclusive.
+    pub to: DateTime<Utc>,
+    /// Where the user sets off from, to weigh travel distance.
+    #[serde(default)]
+    pub origin: Option<TripOrigin>,
+}
+
+/// A place considered for the trip.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TripSpot {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Starting point of the trip.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TripOrigin {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Best spot/time windows of a trip and each spot's forecast to compare them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TripPlan {
+    /// Good bite windows at all spots, best first.
+    pub windows: Vec<TripWindow>,
+    /// Forecast per spot, in request order.
+    pub spots: Vec<TripSpotForecast>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// A run of good bite hours at one spot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TripWindow {
+    /// Index of the spot in `spots`.
+    pub spot: usize,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub peak_probability: f64,
+    /// Peak probability less the travel penalty; windows are ordered by it.
+    pub score: f64,
+}
+
+/// Hourly bite forecast at one candidate spot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TripSpotForecast {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    /// Distance from the origin, if one was given.
+    pub distance_km: Option<f64>,
+    /// Model probability per hour; empty when the weather was unavailable.
+    pub hours: Vec<HourlyProbability>,
+}
+
+/// Bite probability (0–1) for the hour starting at `time`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct HourlyProbability {
+    pub time: DateTime<Utc>,
+    pub probability: f64,
+}
@@ -1,41 +1,102 @@
 use serde::{Deserialize, Serialize};
 
+/// `as_str`/`parse` for a unit enum, using the same codes as its serde form.
+macro_rules! unit_codes {
+    ($unit:ident { $($variant:ident => $code:literal),+ $(,)? }) => {
+        impl $unit {
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $code,)+
+                }
+            }
+
+            pub fn parse(value: &str) -> Option<Self> {
+                match value {
+                    $($code => Some(Self::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
 /// Length units used in the UI and API.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum LengthUnit {
+    #[serde(rename = "cm")]
     Cm,
+    #[serde(rename = "in")]
     Inches,
 }
 
+unit_codes!(LengthUnit { Cm => "cm", Inches => "in" });
+
 /// Weight units used in the UI and API.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum WeightUnit {
+    #[serde(rename = "kg")]
     Kg,
+    #[serde(rename = "lb")]
     Lb,
 }
 
+unit_codes!(WeightUnit { Kg => "kg", Lb => "lb" });
+
 /// Temperature units used in the UI and API.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum TemperatureUnit {
     C,
     F,
 }
 
+unit_codes!(TemperatureUnit { C => "C", F => "F" });
+
 /// Pressure units used in the UI and API.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub enum PressureUnit {
+    #[serde(rename = "hPa")]
     Hpa,
+    #[serde(rename = "mmHg")]
     MmHg,
+    #[serde(rename = "inHg")]
     InHg,
 }
 
+unit_codes!(PressureUnit { Hpa => "hPa", MmHg => "mmHg", InHg => "inHg" });
+
+/// Wind speed units used in the UI and API.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub enum WindSpeedUnit {
+    #[default]
+    #[serde(rename = "m/s")]
+    Ms,
+    #[serde(rename = "km/h")]
+    Kmh,
+    #[serde(rename = "kn")]
+    Knots,
+    /// Beaufort force, 0 to 12
+    #[serde(rename = "Bft")]
+    Beaufort,
+}
+
+unit_codes!(WindSpeedUnit { Ms => "m/s", Kmh => "km/h", Knots => "kn", Beaufort => "Bft" });
+
 /// User preferences for units.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UnitPreferences {
     pub length: LengthUnit,
     pub weight: WeightUnit,
     pub temperature: TemperatureUnit,
     pub pressure: PressureUnit,
+    /// Absent in profiles saved before wind units existed
+    #[serde(default)]
+    pub wind: WindSpeedUnit,
 }
 
 impl Default for UnitPreferences {
@@ -45,6 +106,7 @@ impl Default for UnitPreferences {
             weight: WeightUnit::Kg,
             temperature: TemperatureUnit::C,
             pressure: PressureUnit::Hpa,
+            wind: WindSpeedUnit::Ms,
         }
     }
 }
@@ -59,6 +121,11 @@ pub fn inches_to_cm(inches: f64) -> f64 {
     inches * 2.54
 }
 
+/// Convert kilometers to miles.
+pub fn km_to_miles(km: f64) -> f64 {
+    km / 1.609_344
+}
+
 /// Convert kilograms to pounds.
 pub fn kg_to_lb(kg: f64) -> f64 {
     kg * 2.204_622_621_8
@@ -93,3 +160,70 @@ pub fn hpa_to_mmhg(hpa: f64) -> f64 {
 pub fn mmhg_to_inhg(mmhg: f64) -> f64 {
     mmhg / 25.4
 }
+
+/// Convert hectopascals to inches of mercury.
+pub fn hpa_to_inhg(hpa: f64) -> f64 {
+    mmhg_to_inhg(hpa_to_mmhg(hpa))
+}
+
+/// Convert meters per second to kilometers per hour.
+pub fn ms_to_kmh(ms: f64) -> f64 {
+    ms * 3.6
+}
+
+/// Convert meters per second to knots.
+pub fn ms_to_knots(ms: f64) -> f64 {
+    ms * 3600.0 / 1852.0
+}
+
+/// Upper wind speed (m/s) of each Beaufort force below 12.
+const BEAUFORT_LIMITS_MS: [f64; 12] = [0.3, 1.6, 3.4, 5.5, 8.0, 10.8, 13.9, 17.2, 20.8, 24.5, 28.5, 32.7];
+
+/// Beaufort force for a wind speed in meters per second.
+pub fn ms_to_beaufort(ms: f64) -> u8 {
+    BEAUFORT_LIMITS_MS.iter().take_while(|limit| ms >= **limit).count() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn test_beaufort_limits_start_the_next_force() {
+        assert_eq!(ms_to_beaufort(0.0), 0);
+        assert_eq!(ms_to_beaufort(0.29), 0);
+        assert_eq!(ms_to_beaufort(0.3), 1);
+        assert_eq!(ms_to_beaufort(5.49), 3);
+        assert_eq!(ms_to_beaufort(5.5), 4);
+        assert_eq!(ms_to_beaufort(32.69), 11);
+        assert_eq!(ms_to_beaufort(32.7), 12);
+        assert_eq!(ms_to_beaufort(70.0), 12, "force 12 has no upper limit");
+        for (force, limit) in BEAUFORT_LIMITS_MS.iter().enumerate() {
+            assert_eq!(ms_to_beaufort(*limit) as usize, force + 1);
+        }
+    }
+
+    #[test]
+    fn test_weight_conversions() {
+        assert_close(lb_to_kg(1.0), 0.453_592_37);
+        assert_close(kg_to_lb(0.453_592_37), 1.0);
+        assert_close(lb_to_kg(0.0), 0.0);
+        for kg in [0.05, 2.45, 130.0] {
+            assert_close(lb_to_kg(kg_to_lb(kg)), kg);
+        }
+    }
+
+    #[test]
+    fn test_length_conversions() {
+        assert_close(inches_to_cm(1.0), 2.54);
+        assert_close(cm_to_inches(30.48), 12.0);
+        assert_close(cm_to_inches(0.0), 0.0);
+        for cm in [0.5, 61.5, 250.0] {
+            assert_close(inches_to_cm(cm_to_inches(cm)), cm);
+        }
+    }
+}
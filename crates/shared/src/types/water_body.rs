@@ -1,12 +1,15 @@
 use serde::{Deserialize, Serialize};
 
 /// Water body representation returned by the API.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct WaterBody {
     pub id: String,
     pub name: String,
-    pub water_type: String,
-    pub lat: f64,
-    pub lon: f64,
-    pub area_sqm: Option<f64>,
+    pub description: Option<String>,
+    pub location_lat: f64,
+    pub location_lon: f64,
+    /// E.g. `lake`, `river` or `reservoir`.
+    pub water_type: Option<String>,
+    pub country_code: Option<String>,
 }
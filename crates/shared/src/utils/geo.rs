@@ -24,6 +24,17 @@ pub fn distance_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
     r * c
 }
 
+/// Longitude of the western edge of a (fractional) Web Mercator tile column.
+pub fn tile_x_to_lon(zoom: u32, x: f64) -> f64 {
+    x / 2f64.powi(zoom as i32) * 360.0 - 180.0
+}
+
+/// Latitude of the northern edge of a (fractional) Web Mercator tile row.
+pub fn tile_y_to_lat(zoom: u32, y: f64) -> f64 {
+    let n = std::f64::consts::PI - std::f64::consts::TAU * y / 2f64.powi(zoom as i32);
+    n.sinh().atan().to_degrees()
+}
+
 /// Convert a point to WKT string.
 pub fn point_to_wkt(lat: f64, lon: f64) -> String {
     format!("POINT({} {})", lon, lat)
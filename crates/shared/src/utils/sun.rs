@@ -17,3 +17,47 @@ pub fn estimate_sun_times(_lat: f64, _lon: f64, date: DateTime<Utc>) -> SunTimes
         sunset_utc: date,
     }
 }
+
+/// Position of the sun in the sky.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunPosition {
+    /// Degrees above the horizon (negative at night).
+    pub elevation_deg: f64,
+    /// Degrees clockwise from true north.
+    pub azimuth_deg: f64,
+}
+
+/// Compute the sun's position for a location and time.
+///
+/// Uses the low-precision solar coordinates from the Astronomical Almanac,
+/// accurate to about a degree between 1950 and 2050.
+pub fn sun_position(lat: f64, lon: f64, time: DateTime<Utc>) -> SunPosition {
+    let days = time.timestamp() as f64 / 86_400.0 + 2_440_587.5 - 2_451_545.0;
+
+    let mean_longitude = (280.460 + 0.985_647_4 * days).rem_euclid(360.0);
+    let mean_anomaly = (357.528 + 0.985_600_3 * days).rem_euclid(360.0).to_radians();
+    let ecliptic_longitude = (mean_longitude
+        + 1.915 * mean_anomaly.sin()
+        + 0.020 * (2.0 * mean_anomaly).sin())
+    .to_radians();
+    let obliquity = (23.439 - 0.000_000_4 * days).to_radians();
+
+    let right_ascension =
+        (obliquity.cos() * ecliptic_longitude.sin()).atan2(ecliptic_longitude.cos());
+    let declination = (obliquity.sin() * ecliptic_longitude.sin()).asin();
+
+    let sidereal_hours = (18.697_374_558 + 24.065_709_824_419_08 * days).rem_euclid(24.0);
+    let hour_angle = (sidereal_hours * 15.0 + lon).to_radians() - right_ascension;
+
+    let lat = lat.to_radians();
+    let elevation = (lat.sin() * declination.sin()
+        + lat.cos() * declination.cos() * hour_angle.cos())
+    .asin();
+    let azimuth = (-hour_angle.sin())
+        .atan2(declination.tan() * lat.cos() - lat.sin() * hour_angle.cos());
+
+    SunPosition {
+        elevation_deg: elevation.to_degrees(),
+        azimuth_deg: azimuth.to_degrees().rem_euclid(360.0),
+    }
+}
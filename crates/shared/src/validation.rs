@@ -0,0 +1,574 @@
+//! Declarative payload validation.
+//!
+//! A payload lists its rules in [`Validate::validate`], one call per field;
+//! [`Validator`] collects every failure so clients can show them next to
+//! the offending inputs. Checks that need the database (e.g. whether a
+//! species exists) are added by the backend with [`Validator::error`].
+
+use std::fmt::Display;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::types::{
+    AlertRuleInput, FavoriteSpotInput, FieldError, Language, LoginRequest, NewCatchRecord, ProfileUpdate,
+    PushSubscription, RefreshRequest, RegisterRequest, TripPlanRequest, ValidationRequest,
+};
+use crate::utils::geo::{clamp_lat, clamp_lon};
+
+/// How far in the future a timestamp may be, to allow for clock skew.
+pub const MAX_CLOCK_SKEW_MINUTES: i64 = 5;
+
+/// Longest free-text field, such as catch notes.
+pub const MAX_TEXT_LEN: usize = 2000;
+
+/// Longest short text field, such as a bait name or species id.
+pub const MAX_NAME_LEN: usize = 100;
+
+/// Longest accepted region code (ISO 3166-2 codes are at most 6 characters).
+pub const MAX_REGION_CODE_LEN: usize = 10;
+
+/// Upper bound on `favorite_species` and a favorite spot's `target_species`.
+pub const MAX_FAVORITE_SPECIES: usize = 20;
+
+/// Largest offset of local time from UTC, in minutes (UTC+14).
+pub const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// Longest accepted Web Push endpoint URL.
+pub const MAX_URL_LEN: usize = 2048;
+
+/// Hosts of the browsers' push services (Chrome, Firefox, Safari).
+pub const PUSH_SERVICE_HOSTS: [&str; 3] = ["fcm.googleapis.com", "updates.push.services.mozilla.com", "web.push.apple.com"];
+
+/// Domain of the Windows push service (Edge), whose hosts vary by region.
+pub const WNS_DOMAIN: &str = ".notify.windows.com";
+
+/// Most spots compared in one trip plan.
+pub const MAX_TRIP_SPOTS: usize = 10;
+
+/// How far ahead a trip may end; the weather forecast reaches about a week.
+pub const MAX_TRIP_DAYS: i64 = 7;
+
+/// Largest search radius around a point.
+pub const MAX_RADIUS_KM: f64 = 100.0;
+
+/// Shortest accepted password.
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+/// Longest accepted password in bytes; bcrypt ignores anything past 72.
+pub const MAX_PASSWORD_LEN: usize = 72;
+
+/// Whether `endpoint` is an `https` URL on a known push service, on the
+/// default port and without credentials. The server posts to these URLs,
+/// so anything else could point it at internal hosts.
+pub fn is_push_service_endpoint(endpoint: &str) -> bool {
+    let Some(rest) = endpoint.strip_prefix("https://") else {
+        return false;
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.strip_suffix(":443").unwrap_or(authority).to_ascii_lowercase();
+    if host.contains([':', '@']) {
+        return false;
+    }
+    PUSH_SERVICE_HOSTS.contains(&host.as_str())
+        || host.strip_suffix(WNS_DOMAIN).is_some_and(|name| !name.is_empty() && !name.contains('.'))
+}
+
+/// Stable rule names used as [`FieldError::code`].
+pub mod codes {
+    pub const REQUIRED: &str = "required";
+    pub const OUT_OF_RANGE: &str = "out_of_range";
+    pub const NOT_POSITIVE: &str = "not_positive";
+    pub const TOO_LONG: &str = "too_long";
+    pub const TOO_SHORT: &str = "too_short";
+    pub const IN_FUTURE: &str = "in_future";
+    pub const INVALID_FORMAT: &str = "invalid_format";
+    pub const UNKNOWN: &str = "unknown";
+}
+
+/// A payload with validation rules.
+pub trait Validate {
+    fn validate(&self, v: &mut Validator);
+
+    /// Run the rules; `Err` holds every failed field.
+    fn check(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::default();
+        self.validate(&mut v);
+        v.finish()
+    }
+}
+
+/// Collects field errors.
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    /// Record a failure.
+    pub fn error(&mut self, field: &str, code: &str, message: impl Into<String>) -> &mut Self {
+        self.errors.push(FieldError::new(field, code, message));
+        self
+    }
+
+    pub fn finish(self) -> Result<(), Vec<FieldError>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    /// A latitude `clamp_lat` would leave unchanged.
+    pub fn latitude(&mut self, field: &str, lat: f64) -> &mut Self {
+        if !lat.is_finite() || clamp_lat(lat) != lat {
+            self.error(field, codes::OUT_OF_RANGE, format!("{} must be between -90 and 90", field));
+        }
+        self
+    }
+
+    /// A longitude `clamp_lon` would leave unchanged.
+    pub fn longitude(&mut self, field: &str, lon: f64) -> &mut Self {
+        if !lon.is_finite() || clamp_lon(lon) != lon {
+            self.error(field, codes::OUT_OF_RANGE, format!("{} must be between -180 and 180", field));
+        }
+        self
+    }
+
+    /// A measure greater than zero, if given.
+    pub fn positive(&mut self, field: &str, value: Option<f64>) -> &mut Self {
+        if value.is_some_and(|v| !v.is_finite() || v <= 0.0) {
+            self.error(field, codes::NOT_POSITIVE, format!("{} must be greater than 0", field));
+        }
+        self
+    }
+
+    /// A search radius greater than zero and at most [`MAX_RADIUS_KM`].
+    pub fn radius_km(&mut self, field: &str, radius: f64) -> &mut Self {
+        if !(radius.is_finite() && radius > 0.0 && radius <= MAX_RADIUS_KM) {
+            self.error(
+                field,
+                codes::OUT_OF_RANGE,
+                format!("{} must be greater than 0 and at most {}", field, MAX_RADIUS_KM),
+            );
+        }
+        self
+    }
+
+    /// A value within `min..=max`, if given.
+    pub fn range<T: PartialOrd + Display>(&mut self, field: &str, value: Option<T>, min: T, max: T) -> &mut Self {
+        if value.is_some_and(|v| !(min <= v && v <= max)) {
+            self.error(
+                field,
+                codes::OUT_OF_RANGE,
+                format!("{} must be between {} and {}", field, min, max),
+            );
+        }
+        self
+    }
+
+    /// Text with something other than whitespace.
+    pub fn required(&mut self, field: &str, value: &str) -> &mut Self {
+        if value.trim().is_empty() {
+            self.error(field, codes::REQUIRED, format!("{} must not be empty", field));
+        }
+        self
+    }
+
+    /// Text of at most `max` characters, if given.
+    pub fn max_len(&mut self, field: &str, value: Option<&str>, max: usize) -> &mut Self {
+        if value.is_some_and(|v| v.chars().count() > max) {
+            self.error(field, codes::TOO_LONG, format!("{} must be at most {} characters", field, max));
+        }
+        self
+    }
+
+    /// Text of at most `max` bytes once UTF-8 encoded.
+    pub fn max_bytes(&mut self, field: &str, value: &str, max: usize) -> &mut Self {
+        if value.len() > max {
+            self.error(field, codes::TOO_LONG, format!("{} must be at most {} bytes", field, max));
+        }
+        self
+    }
+
+    /// Text of at least `min` characters.
+    pub fn min_len(&mut self, field: &str, value: &str, min: usize) -> &mut Self {
+        if value.chars().count() < min {
+            self.error(field, codes::TOO_SHORT, format!("{} must be at least {} characters", field, min));
+        }
+        self
+    }
+
+    /// A moment that has already happened, give or take clock skew.
+    pub fn not_in_future(&mut self, field: &str, at: DateTime<Utc>) -> &mut Self {
+        if at > Utc::now() + Duration::minutes(MAX_CLOCK_SKEW_MINUTES) {
+            self.error(field, codes::IN_FUTURE, format!("{} must not be in the future", field));
+        }
+        self
+    }
+
+    /// A two-letter ISO 3166-1 code, if given.
+    pub fn country_code(&mut self, field: &str, code: Option<&str>) -> &mut Self {
+        if code.is_some_and(|c| c.len() != 2 || !c.chars().all(|c| c.is_ascii_alphabetic())) {
+            self.error(
+                field,
+                codes::INVALID_FORMAT,
+                format!("{} must be a two-letter ISO 3166-1 code", field),
+            );
+        }
+        self
+    }
+
+    /// A region code of letters, digits and `-`, if given.
+    pub fn region_code(&mut self, field: &str, code: Option<&str>) -> &mut Self {
+        let valid = |c: &str| {
+            c.len() <= MAX_REGION_CODE_LEN && c.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        };
+        if code.is_some_and(|c| !valid(c)) {
+            self.error(
+                field,
+                codes::INVALID_FORMAT,
+                format!(
+                    "{} must be a region code of at most {} letters, digits or '-'",
+                    field, MAX_REGION_CODE_LEN
+                ),
+            );
+        }
+        self
+    }
+
+    /// A UUID in its hyphenated form, if given.
+    pub fn uuid(&mut self, field: &str, value: Option<&str>) -> &mut Self {
+        let valid = |v: &str| {
+            let groups: Vec<&str> = v.split('-').collect();
+            groups.iter().map(|g| g.len()).eq([8, 4, 4, 4, 12])
+                && groups.iter().all(|g| g.chars().all(|c| c.is_ascii_hexdigit()))
+        };
+        if value.is_some_and(|v| !valid(v)) {
+            self.error(field, codes::INVALID_FORMAT, format!("{} must be a UUID", field));
+        }
+        self
+    }
+
+    /// Something shaped like an email address.
+    pub fn email(&mut self, field: &str, value: &str) -> &mut Self {
+        let valid = value.split_once('@').is_some_and(|(local, domain)| {
+            !local.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
+        }) && !value.chars().any(char::is_whitespace);
+        if !valid {
+            self.error(field, codes::INVALID_FORMAT, format!("{} must be an email address", field));
+        }
+        self
+    }
+}
+
+impl Validate for NewCatchRecord {
+    fn validate(&self, v: &mut Validator) {
+        v.uuid("id", self.id.as_deref())
+            .latitude("lat", self.lat)
+            .longitude("lon", self.lon)
+            .not_in_future("caught_at", self.caught_at)
+            .required("fish_species", &self.fish_species)
+            .max_len("fish_species", Some(&self.fish_species), MAX_NAME_LEN)
+            .positive("weight_kg", self.weight_kg)
+            .positive("length_cm", self.length_cm)
+            .max_len("bait_used", self.bait_used.as_deref(), MAX_NAME_LEN)
+            .range("bite_intensity", self.bite_intensity, 1, 5)
+            .range("moon_phase", self.moon_phase, 0.0, 1.0)
+            .positive("weather_pressure", self.weather_pressure)
+            .max_len("notes", self.notes.as_deref(), MAX_TEXT_LEN)
+            .max_len("photo_url", self.photo_url.as_deref(), MAX_TEXT_LEN);
+    }
+}
+
+impl Validate for ProfileUpdate {
+    fn validate(&self, v: &mut Validator) {
+        v.country_code("country_code", self.country_code.as_deref())
+            .region_code("home_region", self.home_region.as_deref());
+        if let Some(species) = &self.favorite_species {
+            if species.len() > MAX_FAVORITE_SPECIES {
+                v.error(
+                    "favorite_species",
+                    codes::TOO_LONG,
+                    format!("favorite_species allows at most {} entries", MAX_FAVORITE_SPECIES),
+                );
+            }
+        }
+    }
+}
+
+impl Validate for FavoriteSpotInput {
+    fn validate(&self, v: &mut Validator) {
+        v.required("name", self.name.trim())
+            .max_len("name", Some(&self.name), MAX_NAME_LEN)
+            .latitude("lat", self.lat)
+            .longitude("lon", self.lon)
+            .max_len("water_body_id", self.water_body_id.as_deref(), MAX_NAME_LEN)
+            .max_len("notes", self.notes.as_deref(), MAX_TEXT_LEN);
+        if self.target_species.len() > MAX_FAVORITE_SPECIES {
+            v.error(
+                "target_species",
+                codes::TOO_LONG,
+                format!("target_species allows at most {} entries", MAX_FAVORITE_SPECIES),
+            );
+        }
+    }
+}
+
+impl Validate for AlertRuleInput {
+    fn validate(&self, v: &mut Validator) {
+        v.required("favorite_spot_id", &self.favorite_spot_id)
+            .max_len("favorite_spot_id", Some(&self.favorite_spot_id), MAX_NAME_LEN)
+            .max_len("species", self.species.as_deref(), MAX_NAME_LEN)
+            .range("min_probability", Some(self.min_probability), 0.0, 1.0)
+            .range("start_hour", Some(self.start_hour), 0, 23)
+            .range("end_hour", Some(self.end_hour), 0, 23)
+            .range(
+                "utc_offset_minutes",
+                Some(self.utc_offset_minutes),
+                -MAX_UTC_OFFSET_MINUTES,
+                MAX_UTC_OFFSET_MINUTES,
+            );
+    }
+}
+
+impl Validate for PushSubscription {
+    fn validate(&self, v: &mut Validator) {
+        v.required("endpoint", &self.endpoint)
+            .max_len("endpoint", Some(&self.endpoint), MAX_URL_LEN)
+            .required("keys.p256dh", &self.keys.p256dh)
+            .max_len("keys.p256dh", Some(&self.keys.p256dh), MAX_NAME_LEN)
+            .required("keys.auth", &self.keys.auth)
+            .max_len("keys.auth", Some(&self.keys.auth), MAX_NAME_LEN);
+        if !self.endpoint.is_empty() && !is_push_service_endpoint(&self.endpoint) {
+            v.error(
+                "endpoint",
+                codes::INVALID_FORMAT,
+                "endpoint must be an https URL of a browser push service",
+            );
+        }
+    }
+}
+
+impl Validate for TripPlanRequest {
+    fn validate(&self, v: &mut Validator) {
+        if self.spots.is_empty() {
+            v.error("spots", codes::REQUIRED, "spots must not be empty");
+        } else if self.spots.len() > MAX_TRIP_SPOTS {
+            v.error("spots", codes::TOO_LONG, format!("spots allows at most {} entries", MAX_TRIP_SPOTS));
+        }
+        for (i, spot) in self.spots.iter().enumerate() {
+            v.max_len(&format!("spots[{}].name", i), Some(&spot.name), MAX_NAME_LEN)
+                .latitude(&format!("spots[{}].lat", i), spot.lat)
+                .longitude(&format!("spots[{}].lon", i), spot.lon);
+        }
+        if let Some(origin) = &self.origin {
+            v.latitude("origin.lat", origin.lat).longitude("origin.lon", origin.lon);
+        }
+
+        let now = Utc::now();
+        if self.to <= self.from {
+            v.error("to", codes::OUT_OF_RANGE, "to must be after from");
+        } else if self.to <= now {
+            v.error("to", codes::OUT_OF_RANGE, "to must be in the future");
+        } else if self.to > now + Duration::days(MAX_TRIP_DAYS) {
+            v.error(
+                "to",
+                codes::OUT_OF_RANGE,
+                format!("to must be at most {} days ahead", MAX_TRIP_DAYS),
+            );
+        }
+    }
+}
+
+impl Validate for LoginRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.required("email", &self.email).required("password", &self.password);
+    }
+}
+
+impl Validate for RegisterRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.email("email", self.email.trim())
+            .min_len("password", &self.password, MIN_PASSWORD_LEN)
+            .max_bytes("password", &self.password, MAX_PASSWORD_LEN)
+            .country_code("country_code", self.country_code.as_deref());
+        if self.language.as_deref().is_some_and(|code| Language::from_code(code).is_none()) {
+            v.error("language", codes::UNKNOWN, "language must be one of: uk, en, pl, de, fr");
+        }
+    }
+}
+
+impl Validate for RefreshRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.required("refresh_token", &self.refresh_token);
+    }
+}
+
+impl Validate for ValidationRequest {
+    fn validate(&self, v: &mut Validator) {
+        v.required("country_code", &self.country_code)
+            .region_code("country_code", Some(&self.country_code))
+            .positive("size_cm", self.size_cm);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PushSubscriptionKeys, TripOrigin, TripSpot};
+
+    /// `(field, code)` of every failed rule.
+    fn failures(payload: &impl Validate) -> Vec<(String, String)> {
+        payload
+            .check()
+            .err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|e| (e.field, e.code))
+            .collect()
+    }
+
+    fn failed(field: &str, code: &str) -> Vec<(String, String)> {
+        vec![(field.to_string(), code.to_string())]
+    }
+
+    #[test]
+    fn test_coordinates_include_their_limits() {
+        for (lat, lon) in [(90.0, 180.0), (-90.0, -180.0), (0.0, 0.0)] {
+            let mut v = Validator::default();
+            v.latitude("lat", lat).longitude("lon", lon);
+            assert!(v.finish().is_ok(), "{lat}, {lon}");
+        }
+        for (lat, lon) in [(90.000_1, 180.000_1), (-90.000_1, -180.000_1), (f64::NAN, f64::INFINITY)] {
+            let mut v = Validator::default();
+            v.latitude("lat", lat).longitude("lon", lon);
+            let errors = v.finish().unwrap_err();
+            assert_eq!(errors.len(), 2, "{lat}, {lon}");
+            assert!(errors.iter().all(|e| e.code == codes::OUT_OF_RANGE));
+        }
+    }
+
+    #[test]
+    fn test_password_length_counts_bytes() {
+        let register = |password: String| RegisterRequest {
+            email: "angler@example.com".to_string(),
+            password,
+            country_code: None,
+            language: None,
+        };
+        assert!(failures(&register("a".repeat(MAX_PASSWORD_LEN))).is_empty());
+        assert_eq!(failures(&register("a".repeat(MAX_PASSWORD_LEN + 1))), failed("password", codes::TOO_LONG));
+        // 40 characters, but 80 bytes: bcrypt would drop the last 4 letters
+        assert_eq!(failures(&register("ї".repeat(40))), failed("password", codes::TOO_LONG));
+    }
+
+    #[test]
+    fn test_timestamps_allow_clock_skew_only() {
+        let check = |at| {
+            let mut v = Validator::default();
+            v.not_in_future("caught_at", at);
+            v.finish()
+        };
+        assert!(check(Utc::now()).is_ok());
+        assert!(check(Utc::now() + Duration::minutes(MAX_CLOCK_SKEW_MINUTES - 1)).is_ok());
+        let err = check(Utc::now() + Duration::minutes(MAX_CLOCK_SKEW_MINUTES + 1)).unwrap_err();
+        assert_eq!(err[0].code, codes::IN_FUTURE);
+    }
+
+    #[test]
+    fn test_alert_window_bounds() {
+        let rule = AlertRuleInput {
+            favorite_spot_id: "spot".to_string(),
+            species: None,
+            min_probability: 1.0,
+            start_hour: 23,
+            end_hour: 0,
+            utc_offset_minutes: MAX_UTC_OFFSET_MINUTES,
+            pressure_trend: None,
+            enabled: true,
+        };
+        assert!(failures(&rule).is_empty(), "an overnight window is valid");
+        let west = AlertRuleInput { min_probability: 0.0, utc_offset_minutes: -MAX_UTC_OFFSET_MINUTES, ..rule.clone() };
+        assert!(failures(&west).is_empty());
+
+        let late = AlertRuleInput { end_hour: 24, ..rule.clone() };
+        assert_eq!(failures(&late), failed("end_hour", codes::OUT_OF_RANGE));
+        let far = AlertRuleInput { utc_offset_minutes: MAX_UTC_OFFSET_MINUTES + 1, ..rule.clone() };
+        assert_eq!(failures(&far), failed("utc_offset_minutes", codes::OUT_OF_RANGE));
+        let certain = AlertRuleInput { min_probability: 1.01, ..rule.clone() };
+        assert_eq!(failures(&certain), failed("min_probability", codes::OUT_OF_RANGE));
+        let nowhere = AlertRuleInput { favorite_spot_id: " ".to_string(), ..rule };
+        assert_eq!(failures(&nowhere), failed("favorite_spot_id", codes::REQUIRED));
+    }
+
+    #[test]
+    fn test_trip_range_bounds() {
+        let spot = TripSpot { name: "Dam".to_string(), lat: 50.45, lon: 30.52 };
+        let now = Utc::now();
+        let trip = TripPlanRequest {
+            spots: vec![spot.clone(); MAX_TRIP_SPOTS],
+            from: now,
+            to: now + Duration::days(MAX_TRIP_DAYS) - Duration::minutes(1),
+            origin: Some(TripOrigin { lat: -90.0, lon: 180.0 }),
+        };
+        assert!(failures(&trip).is_empty());
+
+        let crowded = TripPlanRequest { spots: vec![spot; MAX_TRIP_SPOTS + 1], ..trip.clone() };
+        assert_eq!(failures(&crowded), failed("spots", codes::TOO_LONG));
+        let empty = TripPlanRequest { spots: Vec::new(), ..trip.clone() };
+        assert_eq!(failures(&empty), failed("spots", codes::REQUIRED));
+        let instant = TripPlanRequest { to: trip.from, ..trip.clone() };
+        assert_eq!(failures(&instant), failed("to", codes::OUT_OF_RANGE));
+        let past = TripPlanRequest { from: now - Duration::days(2), to: now - Duration::days(1), ..trip.clone() };
+        assert_eq!(failures(&past), failed("to", codes::OUT_OF_RANGE));
+        let long = TripPlanRequest { to: now + Duration::days(MAX_TRIP_DAYS) + Duration::minutes(1), ..trip.clone() };
+        assert_eq!(failures(&long), failed("to", codes::OUT_OF_RANGE));
+        let lost = TripPlanRequest { origin: Some(TripOrigin { lat: 91.0, lon: 0.0 }), ..trip };
+        assert_eq!(failures(&lost), failed("origin.lat", codes::OUT_OF_RANGE));
+    }
+
+    #[test]
+    fn test_push_service_endpoints() {
+        for endpoint in [
+            "https://fcm.googleapis.com/fcm/send/abc",
+            "https://updates.push.services.mozilla.com/wpush/v2/abc",
+            "https://web.push.apple.com:443/abc",
+            "https://WEB.PUSH.APPLE.COM/abc",
+            "https://wns2-db5p.notify.windows.com/w/?token=abc",
+        ] {
+            assert!(is_push_service_endpoint(endpoint), "{endpoint}");
+        }
+        for endpoint in [
+            "http://fcm.googleapis.com/fcm/send/abc",
+            "https://fcm.googleapis.com:8443/fcm/send/abc",
+            "https://user@fcm.googleapis.com/fcm/send/abc",
+            "https://fcm.googleapis.com@127.0.0.1/abc",
+            "https://fcm.googleapis.com.example.com/abc",
+            "https://notify.windows.com/abc",
+            "https://.notify.windows.com/abc",
+            "https://a.b.notify.windows.com/abc",
+            "https://127.0.0.1/abc",
+            "https://[::1]/abc",
+            "",
+        ] {
+            assert!(!is_push_service_endpoint(endpoint), "{endpoint}");
+        }
+    }
+
+    #[test]
+    fn test_push_subscription_rules() {
+        let subscription = PushSubscription {
+            endpoint: "https://fcm.googleapis.com/fcm/send/abc".to_string(),
+            keys: PushSubscriptionKeys { p256dh: "key".to_string(), auth: "secret".to_string() },
+        };
+        assert!(failures(&subscription).is_empty());
+
+        let internal = PushSubscription { endpoint: "https://10.0.0.1/push".to_string(), ..subscription.clone() };
+        assert_eq!(failures(&internal), failed("endpoint", codes::INVALID_FORMAT));
+        let missing = PushSubscription { endpoint: String::new(), ..subscription.clone() };
+        assert_eq!(failures(&missing), failed("endpoint", codes::REQUIRED));
+        let long = format!("https://fcm.googleapis.com/{}", "a".repeat(MAX_URL_LEN));
+        let long = PushSubscription { endpoint: long, ..subscription };
+        assert_eq!(failures(&long), failed("endpoint", codes::TOO_LONG));
+    }
+}